@@ -75,6 +75,7 @@ impl Termination for RdapTestError {
 
                 // RDAP Server Errors
                 RdapClientError::Response(_) => 60,
+                RdapClientError::InvalidPagingLink(_) => 61,
                 RdapClientError::ParsingError(_) => 62,
                 RdapClientError::Json(_) => 63,
 
@@ -79,6 +79,7 @@ impl RdapCliError {
 
                 // RDAP Server Errors
                 RdapClientError::Response(_) => 60,
+                RdapClientError::InvalidPagingLink(_) => 61,
                 RdapClientError::ParsingError(_) => 62,
                 RdapClientError::Json(_) => 63,
 
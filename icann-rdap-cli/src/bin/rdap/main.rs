@@ -16,8 +16,11 @@ use {
 
 use {
     clap::{ArgGroup, Parser, ValueEnum},
-    icann_rdap_client::rdap::QueryType,
-    icann_rdap_common::VERSION,
+    icann_rdap_client::rdap::{QueryType, ReverseSearch},
+    icann_rdap_common::{
+        search_types::{RelatedEntityProperty, RirSearchRelation},
+        VERSION,
+    },
     query::OutputType,
     tokio::{join, task::spawn_blocking},
 };
@@ -82,6 +85,25 @@ struct Cli {
     )]
     query_type: Option<QtypeArg>,
 
+    /// Reverse search property.
+    ///
+    /// The property of the related entity to match with the query value
+    /// when using a reverse search query type (RFC 9536).
+    #[arg(
+        long,
+        required = false,
+        value_enum,
+        default_value_t = ReverseSearchPropertyArg::Fn,
+    )]
+    reverse_search_property: ReverseSearchPropertyArg,
+
+    /// Reverse search role.
+    ///
+    /// Limits a reverse search query type to related entities with this role,
+    /// such as "registrant" or "abuse".
+    #[arg(long, required = false)]
+    reverse_search_role: Option<String>,
+
     /// Get an RDAP server's help information.
     ///
     /// Ask for a server's help information.
@@ -352,6 +374,39 @@ enum QtypeArg {
     /// Nameserver IP Address Search
     NsIp,
 
+    /// Domain Reverse Search
+    DomainReverse,
+
+    /// Nameserver Reverse Search
+    NsReverse,
+
+    /// Entity Reverse Search
+    EntityReverse,
+
+    /// IP Network Handle Search
+    IpHandle,
+
+    /// IP Network Name Search
+    IpName,
+
+    /// Autonomous System Number Handle Search
+    AutnumHandle,
+
+    /// Autonomous System Number Name Search
+    AutnumName,
+
+    /// Less Specific IP Network Search (rdap-up)
+    IpUp,
+
+    /// More Specific IP Networks Search (rdap-down)
+    IpDown,
+
+    /// Least Specific IP Network Search (rdap-top)
+    IpTop,
+
+    /// Most Specific IP Networks Search (rdap-bottom)
+    IpBottom,
+
     /// RDAP URL
     Url,
 }
@@ -450,6 +505,19 @@ enum PagerType {
     Auto,
 }
 
+/// Represents the related entity properties of reverse searches.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum ReverseSearchPropertyArg {
+    /// The full name of the entity.
+    Fn,
+
+    /// The handle of the entity.
+    Handle,
+
+    /// The email address of the entity.
+    Email,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum TldLookupArg {
     /// Use IANA for TLD lookups.
@@ -715,11 +783,41 @@ fn query_type_from_cli(cli: &Cli) -> Result<QueryType, RdapCliError> {
         QtypeArg::DomainNsIp => QueryType::domain_ns_ip_search(&query_value)?,
         QtypeArg::NsName => QueryType::NameserverNameSearch(query_value),
         QtypeArg::NsIp => QueryType::ns_ip_search(&query_value)?,
+        QtypeArg::DomainReverse => QueryType::DomainReverseSearch(reverse_search(cli, query_value)),
+        QtypeArg::NsReverse => QueryType::NameserverReverseSearch(reverse_search(cli, query_value)),
+        QtypeArg::EntityReverse => QueryType::EntityReverseSearch(reverse_search(cli, query_value)),
+        QtypeArg::IpHandle => QueryType::NetworkHandleSearch(query_value),
+        QtypeArg::IpName => QueryType::NetworkNameSearch(query_value),
+        QtypeArg::AutnumHandle => QueryType::AutnumHandleSearch(query_value),
+        QtypeArg::AutnumName => QueryType::AutnumNameSearch(query_value),
+        QtypeArg::IpUp => QueryType::network_relation_search(RirSearchRelation::Up, &query_value)?,
+        QtypeArg::IpDown => {
+            QueryType::network_relation_search(RirSearchRelation::Down, &query_value)?
+        }
+        QtypeArg::IpTop => {
+            QueryType::network_relation_search(RirSearchRelation::Top, &query_value)?
+        }
+        QtypeArg::IpBottom => {
+            QueryType::network_relation_search(RirSearchRelation::Bottom, &query_value)?
+        }
         QtypeArg::Url => QueryType::Url(query_value),
     };
     Ok(q)
 }
 
+fn reverse_search(cli: &Cli, query_value: String) -> ReverseSearch {
+    let property = match cli.reverse_search_property {
+        ReverseSearchPropertyArg::Fn => RelatedEntityProperty::FullName,
+        ReverseSearchPropertyArg::Handle => RelatedEntityProperty::Handle,
+        ReverseSearchPropertyArg::Email => RelatedEntityProperty::Email,
+    };
+    ReverseSearch::builder()
+        .property(property)
+        .value(query_value)
+        .and_role(cli.reverse_search_role.clone())
+        .build()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Cli;
@@ -266,7 +266,9 @@ impl TestRun {
                 | RdapClientError::BootstrapUnavailable
                 | RdapClientError::BootstrapError(_)
                 | RdapClientError::IanaResponse(_) => RunOutcome::InternalError,
-                RdapClientError::Response(_) => RunOutcome::RdapDataError,
+                RdapClientError::Response(_) | RdapClientError::InvalidPagingLink(_) => {
+                    RunOutcome::RdapDataError
+                }
                 RdapClientError::Json(_) => RunOutcome::JsonError,
                 RdapClientError::ParsingError(e) => {
                     let status_code = e.http_data.status_code();
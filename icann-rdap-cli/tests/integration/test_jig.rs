@@ -1,14 +1,15 @@
 use {
     assert_cmd::Command,
     icann_rdap_srv::{
-        config::ListenConfig,
+        config::{CacheConfig, ContactFormat, ListenConfig, RateLimitConfig, RedactionConfig},
+        metrics::Metrics,
         server::{AppState, Listener},
         storage::{
             mem::{config::MemConfig, ops::Mem},
             CommonConfig,
         },
     },
-    std::time::Duration,
+    std::{sync::Arc, time::Duration},
     test_dir::{DirBuilder, FileType, TestDir},
 };
 
@@ -49,6 +50,11 @@ impl TestJig {
         let app_state = AppState {
             storage: mem.clone(),
             bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
         };
         let _ = tracing_subscriber::fmt().try_init();
         let listener = Listener::listen(&ListenConfig::default())
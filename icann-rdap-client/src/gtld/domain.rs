@@ -195,7 +195,11 @@ mod tests {
 
     use {
         super::GtldParams,
-        icann_rdap_common::{prelude::ToResponse, response::Domain},
+        icann_rdap_common::{
+            contact::{Contact, Email},
+            prelude::ToResponse,
+            response::{Domain, Entity},
+        },
     };
 
     use {
@@ -255,4 +259,32 @@ mod tests {
         let output = process_gtld_file("src/test_files/home.moscow.json").unwrap();
         assert_eq!(output, expected_output);
     }
+
+    #[test]
+    fn GIVEN_registrant_with_jscard_only_WHEN_to_gtld_whois_THEN_contact_is_rendered() {
+        // GIVEN
+        let contact = Contact::builder()
+            .full_name("Joe User")
+            .emails(vec![Email::builder().email("joe@example.com").build()])
+            .build();
+        let mut registrant = Entity::builder().handle("foo").role("registrant").build();
+        registrant.jscard = Some(contact.to_jscard());
+        let domain = Domain::builder()
+            .ldh_name("foo.example")
+            .entity(registrant)
+            .build();
+        let rdap_response = domain.clone().to_response();
+        let mut gtld_params = GtldParams {
+            root: &rdap_response,
+            parent_type: TypeId::of::<Domain>(),
+            label: "".to_string(),
+        };
+
+        // WHEN
+        let actual = domain.to_gtld_whois(&mut gtld_params);
+
+        // THEN
+        assert!(actual.contains("Registrant Name: Joe User\n"));
+        assert!(actual.contains("Registrant Email: joe@example.com\n"));
+    }
 }
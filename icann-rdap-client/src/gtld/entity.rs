@@ -16,8 +16,13 @@ impl ToGtldWhois for Option<Vec<Entity>> {
                 for role in entity.roles() {
                     match role.as_str() {
                         "registrar" => {
-                            if let Some(vcard_array) = &entity.vcard_array {
-                                let role_info = extract_role_info(role, vcard_array, params);
+                            if let Some(contact) = entity.contact() {
+                                let role_info = extract_role_info(
+                                    role,
+                                    contact,
+                                    entity.vcard_array.as_deref(),
+                                    params,
+                                );
                                 // Now use role_info to append to formatted_data
                                 if !role_info.name.is_empty() {
                                     front_formatted_data +=
@@ -51,8 +56,13 @@ impl ToGtldWhois for Option<Vec<Entity>> {
                             append_abuse_contact_info(entity, &mut front_formatted_data);
                         }
                         "technical" | "administrative" | "registrant" => {
-                            if let Some(vcard_array) = &entity.vcard_array {
-                                let role_info = extract_role_info(role, vcard_array, params);
+                            if let Some(contact) = entity.contact() {
+                                let role_info = extract_role_info(
+                                    role,
+                                    contact,
+                                    entity.vcard_array.as_deref(),
+                                    params,
+                                );
                                 // Now use role_info to append to formatted_data
                                 if !role_info.name.is_empty() {
                                     formatted_data +=
@@ -139,13 +149,10 @@ fn format_address_with_label(
 
 fn extract_role_info(
     role: &str,
-    vcard_array: &[serde_json::Value],
+    contact: Contact,
+    vcard_array: Option<&[serde_json::Value]>,
     params: &mut GtldParams,
 ) -> RoleInfo {
-    let contact = match Contact::from_vcard(vcard_array) {
-        Some(contact) => contact,
-        None => return RoleInfo::default(),
-    };
     let mut adr = String::new();
     let label = match role {
         "registrar" => "Registrar",
@@ -163,7 +170,7 @@ fn extract_role_info(
         .unwrap_or_default();
 
     // TODO this is a workout to get the address out of the contact. Replace this when from_vcard is fixed
-    for vcard in vcard_array.iter() {
+    for vcard in vcard_array.into_iter().flatten() {
         if let Some(properties) = vcard.as_array() {
             for property in properties {
                 if let Some(property) = property.as_array() {
@@ -176,6 +183,15 @@ fn extract_role_info(
             }
         }
     }
+    if vcard_array.is_none() {
+        if let Some(postal_address) = contact
+            .postal_addresses
+            .as_ref()
+            .and_then(|addresses| addresses.first())
+        {
+            adr = postal_address.to_gtld_whois(params).to_string();
+        }
+    }
 
     let email = contact
         .emails
@@ -227,30 +243,28 @@ fn append_abuse_contact_info(entity: &Entity, front_formatted_data: &mut String)
         for entity in entities {
             for role in entity.roles() {
                 if role.as_str() == "abuse" {
-                    if let Some(vcard_array) = &entity.vcard_array {
-                        if let Some(contact) = Contact::from_vcard(vcard_array) {
-                            // Emails
-                            if let Some(emails) = &contact.emails {
-                                for email in emails {
-                                    let abuse_contact_email = &email.email;
-                                    if !abuse_contact_email.is_empty() {
-                                        front_formatted_data.push_str(&format!(
-                                            "Registrar Abuse Contact Email: {}\n",
-                                            abuse_contact_email
-                                        ));
-                                    }
+                    if let Some(contact) = entity.contact() {
+                        // Emails
+                        if let Some(emails) = &contact.emails {
+                            for email in emails {
+                                let abuse_contact_email = &email.email;
+                                if !abuse_contact_email.is_empty() {
+                                    front_formatted_data.push_str(&format!(
+                                        "Registrar Abuse Contact Email: {}\n",
+                                        abuse_contact_email
+                                    ));
                                 }
                             }
-                            // Phones
-                            if let Some(phones) = &contact.phones {
-                                for phone in phones {
-                                    let abuse_contact_phone = &phone.phone;
-                                    if !abuse_contact_phone.is_empty() {
-                                        front_formatted_data.push_str(&format!(
-                                            "Registrar Abuse Contact Phone: {}\n",
-                                            abuse_contact_phone
-                                        ));
-                                    }
+                        }
+                        // Phones
+                        if let Some(phones) = &contact.phones {
+                            for phone in phones {
+                                let abuse_contact_phone = &phone.phone;
+                                if !abuse_contact_phone.is_empty() {
+                                    front_formatted_data.push_str(&format!(
+                                        "Registrar Abuse Contact Phone: {}\n",
+                                        abuse_contact_phone
+                                    ));
                                 }
                             }
                         }
@@ -29,6 +29,8 @@ pub mod prelude {
     #[doc(inline)]
     pub use crate::rdap::rdap_bootstrapped_request;
     #[doc(inline)]
+    pub use crate::rdap::rdap_paged_request;
+    #[doc(inline)]
     pub use crate::rdap::rdap_request;
     #[doc(inline)]
     pub use crate::rdap::rdap_url_request;
@@ -56,6 +58,9 @@ pub enum RdapClientError {
     #[error("Error parsing response")]
     ParsingError(Box<ParsingErrorInfo>),
 
+    #[error("Invalid paging link: {0}")]
+    InvalidPagingLink(String),
+
     #[error(transparent)]
     Json(#[from] serde_json::Error),
 
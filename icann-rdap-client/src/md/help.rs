@@ -2,12 +2,31 @@ use std::any::TypeId;
 
 use icann_rdap_common::response::Help;
 
-use super::{MdHeaderText, MdParams, MdUtil, ToMd, HR};
+use super::{table::MultiPartTable, MdHeaderText, MdParams, MdUtil, ToMd, HR};
 
 impl ToMd for Help {
     fn to_md(&self, params: MdParams) -> String {
         let mut md = String::new();
         md.push_str(&self.common.to_md(params.from_parent(TypeId::of::<Self>())));
+
+        // reverse searches
+        if !self.reverse_search_properties().is_empty() {
+            let mut table = MultiPartTable::new().header_ref(&"Reverse Searches");
+            for property in self.reverse_search_properties() {
+                let search = format!(
+                    "{} by {} {}",
+                    property.searchable_resource_type.as_deref().unwrap_or("?"),
+                    property.related_resource_type.as_deref().unwrap_or("?"),
+                    property.property.as_deref().unwrap_or("?"),
+                );
+                table = table.nv_ref(
+                    &search,
+                    &property.property_path.as_deref().unwrap_or_default(),
+                );
+            }
+            md.push_str(&table.to_md(params));
+        }
+
         md.push_str(HR);
         md.push('\n');
         md
@@ -1,16 +1,19 @@
 use std::any::TypeId;
 
 use icann_rdap_common::response::{
-    DomainSearchResults, EntitySearchResults, NameserverSearchResults,
+    AutnumSearchResults, DomainSearchResults, EntitySearchResults, NameserverSearchResults,
+    NetworkSearchResults, SortingMetadata, SubsettingMetadata,
 };
 
-use super::{MdHeaderText, MdParams, MdUtil, ToMd};
+use super::{table::MultiPartTable, MdHeaderText, MdParams, MdUtil, ToMd};
 
 impl ToMd for DomainSearchResults {
     fn to_md(&self, params: MdParams) -> String {
         let typeid = TypeId::of::<Self>();
         let mut md = String::new();
         md.push_str(&self.common.to_md(params.from_parent(typeid)));
+        md.push_str(&sorting_to_md(self.sorting_metadata.as_ref(), params));
+        md.push_str(&subsetting_to_md(self.subsetting_metadata.as_ref(), params));
         self.results.iter().for_each(|result| {
             md.push_str(&result.to_md(MdParams {
                 heading_level: params.heading_level + 1,
@@ -28,6 +31,8 @@ impl ToMd for NameserverSearchResults {
         let typeid = TypeId::of::<Self>();
         let mut md = String::new();
         md.push_str(&self.common.to_md(params.from_parent(typeid)));
+        md.push_str(&sorting_to_md(self.sorting_metadata.as_ref(), params));
+        md.push_str(&subsetting_to_md(self.subsetting_metadata.as_ref(), params));
         self.results.iter().for_each(|result| {
             md.push_str(&result.to_md(MdParams {
                 heading_level: params.heading_level + 1,
@@ -45,6 +50,8 @@ impl ToMd for EntitySearchResults {
         let typeid = TypeId::of::<Self>();
         let mut md = String::new();
         md.push_str(&self.common.to_md(params.from_parent(typeid)));
+        md.push_str(&sorting_to_md(self.sorting_metadata.as_ref(), params));
+        md.push_str(&subsetting_to_md(self.subsetting_metadata.as_ref(), params));
         self.results.iter().for_each(|result| {
             md.push_str(&result.to_md(MdParams {
                 heading_level: params.heading_level + 1,
@@ -57,6 +64,92 @@ impl ToMd for EntitySearchResults {
     }
 }
 
+impl ToMd for NetworkSearchResults {
+    fn to_md(&self, params: MdParams) -> String {
+        let typeid = TypeId::of::<Self>();
+        let mut md = String::new();
+        md.push_str(&self.common.to_md(params.from_parent(typeid)));
+        md.push_str(&sorting_to_md(self.sorting_metadata.as_ref(), params));
+        md.push_str(&subsetting_to_md(self.subsetting_metadata.as_ref(), params));
+        self.results.iter().for_each(|result| {
+            md.push_str(&result.to_md(MdParams {
+                heading_level: params.heading_level + 1,
+                parent_type: typeid,
+                ..params
+            }))
+        });
+        md.push('\n');
+        md
+    }
+}
+
+impl ToMd for AutnumSearchResults {
+    fn to_md(&self, params: MdParams) -> String {
+        let typeid = TypeId::of::<Self>();
+        let mut md = String::new();
+        md.push_str(&self.common.to_md(params.from_parent(typeid)));
+        md.push_str(&sorting_to_md(self.sorting_metadata.as_ref(), params));
+        md.push_str(&subsetting_to_md(self.subsetting_metadata.as_ref(), params));
+        self.results.iter().for_each(|result| {
+            md.push_str(&result.to_md(MdParams {
+                heading_level: params.heading_level + 1,
+                parent_type: typeid,
+                ..params
+            }))
+        });
+        md.push('\n');
+        md
+    }
+}
+
+/// Renders the RFC 8977 sorting metadata of search results.
+fn sorting_to_md(sorting_metadata: Option<&SortingMetadata>, params: MdParams) -> String {
+    let Some(sorting_metadata) = sorting_metadata else {
+        return String::new();
+    };
+    let available_sorts = sorting_metadata
+        .available_sorts()
+        .iter()
+        .filter_map(|sort| {
+            let property = sort.property.as_deref()?;
+            if sort.is_default() {
+                Some(format!("{property} (default)"))
+            } else {
+                Some(property.to_string())
+            }
+        })
+        .collect::<Vec<String>>();
+    let table = MultiPartTable::new()
+        .header_ref(&"Sorting")
+        .and_nv_ref_maybe(&"Current Sort", &sorting_metadata.current_sort)
+        .nv_ul(&"Available Sorts", available_sorts);
+    table.to_md(params)
+}
+
+/// Renders the RFC 8982 subsetting metadata of search results.
+fn subsetting_to_md(subsetting_metadata: Option<&SubsettingMetadata>, params: MdParams) -> String {
+    let Some(subsetting_metadata) = subsetting_metadata else {
+        return String::new();
+    };
+    let available_field_sets = subsetting_metadata
+        .available_field_sets()
+        .iter()
+        .filter_map(|field_set| {
+            let name = field_set.name.as_deref()?;
+            if field_set.is_default() {
+                Some(format!("{name} (default)"))
+            } else {
+                Some(name.to_string())
+            }
+        })
+        .collect::<Vec<String>>();
+    let table = MultiPartTable::new()
+        .header_ref(&"Subsetting")
+        .and_nv_ref_maybe(&"Current Field Set", &subsetting_metadata.current_field_set)
+        .nv_ul(&"Available Field Sets", available_field_sets);
+    table.to_md(params)
+}
+
 impl MdUtil for DomainSearchResults {
     fn get_header_text(&self) -> MdHeaderText {
         MdHeaderText::builder()
@@ -80,3 +173,19 @@ impl MdUtil for NameserverSearchResults {
             .build()
     }
 }
+
+impl MdUtil for NetworkSearchResults {
+    fn get_header_text(&self) -> MdHeaderText {
+        MdHeaderText::builder()
+            .header_text("IP Network Search Results")
+            .build()
+    }
+}
+
+impl MdUtil for AutnumSearchResults {
+    fn get_header_text(&self) -> MdHeaderText {
+        MdHeaderText::builder()
+            .header_text("Autnum Search Results")
+            .build()
+    }
+}
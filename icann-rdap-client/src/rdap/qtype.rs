@@ -7,7 +7,11 @@ use std::{
 
 use {
     cidr::{IpCidr, Ipv4Cidr, Ipv6Cidr},
-    icann_rdap_common::{check::StringCheck, dns_types::DomainName},
+    icann_rdap_common::{
+        check::StringCheck,
+        dns_types::DomainName,
+        search_types::{RelatedEntityProperty, RirSearchRelation},
+    },
     pct_str::{PctString, URIReserved},
     regex::Regex,
     strum_macros::Display,
@@ -66,6 +70,30 @@ pub enum QueryType {
     #[strum(serialize = "Nameserver IP Address Search")]
     NameserverIpSearch(IpAddr),
 
+    #[strum(serialize = "Domain Reverse Search")]
+    DomainReverseSearch(ReverseSearch),
+
+    #[strum(serialize = "Nameserver Reverse Search")]
+    NameserverReverseSearch(ReverseSearch),
+
+    #[strum(serialize = "Entity Reverse Search")]
+    EntityReverseSearch(ReverseSearch),
+
+    #[strum(serialize = "IP Network Handle Search")]
+    NetworkHandleSearch(String),
+
+    #[strum(serialize = "IP Network Name Search")]
+    NetworkNameSearch(String),
+
+    #[strum(serialize = "Autonomous System Number Handle Search")]
+    AutnumHandleSearch(String),
+
+    #[strum(serialize = "Autonomous System Number Name Search")]
+    AutnumNameSearch(String),
+
+    #[strum(serialize = "IP Network Relation Search")]
+    NetworkRelationSearch(NetworkRelationSearch),
+
     #[strum(serialize = "Server Help Lookup")]
     Help,
 
@@ -126,6 +154,14 @@ impl QueryType {
             Self::NameserverIpSearch(value) => {
                 search_query(&value.to_string(), "nameservers?ip", base_url)
             }
+            Self::DomainReverseSearch(value) => value.query_url("domains", base_url),
+            Self::NameserverReverseSearch(value) => value.query_url("nameservers", base_url),
+            Self::EntityReverseSearch(value) => value.query_url("entities", base_url),
+            Self::NetworkHandleSearch(value) => search_query(value, "ips?handle", base_url),
+            Self::NetworkNameSearch(value) => search_query(value, "ips?name", base_url),
+            Self::AutnumHandleSearch(value) => search_query(value, "autnums?handle", base_url),
+            Self::AutnumNameSearch(value) => search_query(value, "autnums?name", base_url),
+            Self::NetworkRelationSearch(value) => Ok(value.query_url(base_url)),
             Self::Help => Ok(format!("{base_url}/help")),
             Self::Url(url) => Ok(url.to_owned()),
         }
@@ -196,6 +232,106 @@ impl QueryType {
         let value = IpAddr::from_str(ip).map_err(|_e| RdapClientError::InvalidQueryValue)?;
         Ok(Self::NameserverIpSearch(value))
     }
+
+    /// Creates a search for the networks related to an IP address or CIDR.
+    pub fn network_relation_search(
+        relation: RirSearchRelation,
+        ip_or_cidr: &str,
+    ) -> Result<QueryType, RdapClientError> {
+        let cidr = match IpAddr::from_str(ip_or_cidr) {
+            Ok(ip) => IpCidr::new_host(ip),
+            Err(_) => parse_cidr(ip_or_cidr)?,
+        };
+        Ok(Self::NetworkRelationSearch(
+            NetworkRelationSearch::builder()
+                .relation(relation)
+                .cidr(cidr)
+                .build(),
+        ))
+    }
+}
+
+/// A reverse search as specified in RFC 9536, which finds objects by the
+/// properties of their related entities.
+///
+/// ```rust
+/// use icann_rdap_client::rdap::{QueryType, ReverseSearch};
+/// use icann_rdap_common::search_types::RelatedEntityProperty;
+///
+/// let query = QueryType::DomainReverseSearch(
+///     ReverseSearch::builder()
+///         .property(RelatedEntityProperty::FullName)
+///         .value("Bob Smurd")
+///         .role("registrant")
+///         .build(),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseSearch {
+    pub property: RelatedEntityProperty,
+    pub value: String,
+    pub role: Option<String>,
+}
+
+#[buildstructor::buildstructor]
+impl ReverseSearch {
+    /// Builds a reverse search.
+    #[builder(visibility = "pub")]
+    fn new(property: RelatedEntityProperty, value: String, role: Option<String>) -> Self {
+        Self {
+            property,
+            value,
+            role,
+        }
+    }
+
+    fn query_url(&self, searchable_type: &str, base_url: &str) -> Result<String, RdapClientError> {
+        let mut url = format!(
+            "{base_url}/{searchable_type}/reverse_search/entity?{}={}",
+            self.property,
+            PctString::encode(self.value.chars(), URIReserved)
+        );
+        if let Some(role) = &self.role {
+            url.push_str(&format!(
+                "&role={}",
+                PctString::encode(role.chars(), URIReserved)
+            ));
+        }
+        Ok(url)
+    }
+}
+
+/// A search for the IP networks related to a network, as specified in
+/// draft-ietf-regext-rdap-rir-search.
+///
+/// ```rust
+/// use icann_rdap_client::rdap::QueryType;
+/// use icann_rdap_common::search_types::RirSearchRelation;
+///
+/// let query = QueryType::network_relation_search(RirSearchRelation::Up, "192.0.2.0/24").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkRelationSearch {
+    pub relation: RirSearchRelation,
+    pub cidr: IpCidr,
+}
+
+#[buildstructor::buildstructor]
+impl NetworkRelationSearch {
+    /// Builds a network relation search.
+    #[builder(visibility = "pub")]
+    fn new(relation: RirSearchRelation, cidr: IpCidr) -> Self {
+        Self { relation, cidr }
+    }
+
+    fn query_url(&self, base_url: &str) -> String {
+        format!(
+            "{base_url}/ips/rirSearch1/{}/{}/{}",
+            self.relation,
+            PctString::encode(self.cidr.first_address().to_string().chars(), URIReserved),
+            self.cidr.network_length()
+        )
+    }
 }
 
 fn search_query(value: &str, path_query: &str, base_url: &str) -> Result<String, RdapClientError> {
@@ -642,4 +778,96 @@ mod tests {
         // THEN
         assert_eq!(actual, "https://example.com/nameservers?ip=1.1.1.1")
     }
+
+    #[test]
+    fn test_domain_reverse_search_query_url() {
+        // GIVEN
+        let q = QueryType::DomainReverseSearch(
+            ReverseSearch::builder()
+                .property(RelatedEntityProperty::FullName)
+                .value("Bob Smurd")
+                .role("registrant")
+                .build(),
+        );
+
+        // WHEN
+        let actual = q.query_url("https://example.com").expect("query url");
+
+        // THEN
+        assert_eq!(
+            actual,
+            "https://example.com/domains/reverse_search/entity?fn=Bob%20Smurd&role=registrant"
+        )
+    }
+
+    #[test]
+    fn test_ns_reverse_search_query_url() {
+        // GIVEN
+        let q = QueryType::NameserverReverseSearch(
+            ReverseSearch::builder()
+                .property(RelatedEntityProperty::Handle)
+                .value("FOO*")
+                .build(),
+        );
+
+        // WHEN
+        let actual = q.query_url("https://example.com").expect("query url");
+
+        // THEN
+        assert_eq!(
+            actual,
+            "https://example.com/nameservers/reverse_search/entity?handle=FOO%2A"
+        )
+    }
+
+    #[rstest]
+    #[case(QueryType::NetworkHandleSearch("NET-*".to_string()), "https://example.com/ips?handle=NET-%2A")]
+    #[case(QueryType::NetworkNameSearch("EXAMPLE".to_string()), "https://example.com/ips?name=EXAMPLE")]
+    #[case(QueryType::AutnumHandleSearch("AS64496".to_string()), "https://example.com/autnums?handle=AS64496")]
+    #[case(QueryType::AutnumNameSearch("EXAMPLE*".to_string()), "https://example.com/autnums?name=EXAMPLE%2A")]
+    fn test_rir_search_query_url(#[case] q: QueryType, #[case] expected: &str) {
+        // GIVEN q
+
+        // WHEN
+        let actual = q.query_url("https://example.com").expect("query url");
+
+        // THEN
+        assert_eq!(actual, expected)
+    }
+
+    #[rstest]
+    #[case(
+        RirSearchRelation::Up,
+        "192.0.2.0/24",
+        "https://example.com/ips/rirSearch1/rdap-up/192.0.2.0/24"
+    )]
+    #[case(
+        RirSearchRelation::Down,
+        "10/8",
+        "https://example.com/ips/rirSearch1/rdap-down/10.0.0.0/8"
+    )]
+    #[case(
+        RirSearchRelation::Top,
+        "192.0.2.1",
+        "https://example.com/ips/rirSearch1/rdap-top/192.0.2.1/32"
+    )]
+    #[case(
+        RirSearchRelation::Bottom,
+        "2001:db8::/32",
+        "https://example.com/ips/rirSearch1/rdap-bottom/2001%3Adb8%3A%3A/32"
+    )]
+    fn test_network_relation_search_query_url(
+        #[case] relation: RirSearchRelation,
+        #[case] ip_or_cidr: &str,
+        #[case] expected: &str,
+    ) {
+        // GIVEN
+        let q = QueryType::network_relation_search(relation, ip_or_cidr).expect("query type");
+
+        // WHEN
+        let actual = q.query_url("https://example.com").expect("query url");
+
+        // THEN
+        assert_eq!(actual, expected)
+    }
 }
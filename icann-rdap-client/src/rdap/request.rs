@@ -1,7 +1,9 @@
 //! Functions to make RDAP requests.
 
 use {
+    futures_util::{stream, Stream},
     icann_rdap_common::{httpdata::HttpData, iana::IanaRegistryType, response::RdapResponse},
+    reqwest::Url,
     serde::{Deserialize, Serialize},
     serde_json::Value,
 };
@@ -111,6 +113,83 @@ pub async fn rdap_request(
     rdap_url_request(&url, client).await
 }
 
+/// Makes an RDAP search request and follows the "next" links of the RFC 8977
+/// paging metadata, yielding each page of search results as a stream.
+///
+/// This function takes the following parameters:
+/// * base_url - a string reference of the base URL
+/// * query_type - a reference to the RDAP query.
+/// * client - a reference to a [reqwest::Client].
+///
+/// The stream ends with the first page that has no "next" link or after the
+/// first error. Relative "next" links are resolved against the URL of the page
+/// they are in.
+///
+/// ```no_run
+/// use icann_rdap_client::prelude::*;
+/// use futures_util::StreamExt;
+/// use std::pin::pin;
+/// use tokio::main;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), RdapClientError> {
+///
+///     // create a query
+///     let query = QueryType::DomainNameSearch("example*.com".to_string());
+///
+///     // create a client (from icann-rdap-common)
+///     let config = ClientConfig::default();
+///     let client = create_client(&config)?;
+///
+///     // issue the RDAP query and get each page of results
+///     let mut pages = pin!(rdap_paged_request(
+///         "https://rdap.example.com/rdap",
+///         &query,
+///         &client,
+///     ));
+///     while let Some(page) = pages.next().await {
+///         let page = page?;
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub fn rdap_paged_request<'a>(
+    base_url: &str,
+    query_type: &QueryType,
+    client: &'a Client,
+) -> impl Stream<Item = Result<ResponseData, RdapClientError>> + 'a {
+    let first_url = query_type.query_url(base_url);
+    stream::unfold(Some(first_url), move |next_url| async move {
+        let url = match next_url? {
+            Ok(url) => url,
+            Err(e) => return Some((Err(e), None)),
+        };
+        match rdap_url_request(&url, client).await {
+            Ok(response) => {
+                let next_url = next_page_url(&url, &response.rdap).transpose();
+                Some((Ok(response), next_url))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
+
+/// Gets the URL of the next page of search results from the paging metadata.
+fn next_page_url(url: &str, rdap: &RdapResponse) -> Result<Option<String>, RdapClientError> {
+    let Some(href) = rdap
+        .get_paging_metadata()
+        .and_then(|paging| paging.next_link())
+        .and_then(|link| link.href.as_deref())
+    else {
+        return Ok(None);
+    };
+    let next_url = Url::parse(url)
+        .and_then(|url| url.join(href))
+        .map_err(|e| RdapClientError::InvalidPagingLink(format!("{href}: {e}")))?;
+    Ok(Some(next_url.to_string()))
+}
+
 /// Makes an RDAP request using bootstrapping.
 ///
 /// This function takes the following parameters:
@@ -175,3 +254,159 @@ pub struct ResponseData {
     pub rdap_type: String,
     pub http_data: HttpData,
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        futures_util::StreamExt,
+        icann_rdap_common::{
+            prelude::ToResponse,
+            response::{DomainSearchResults, Link, PagingMetadata, RdapResponse},
+        },
+        serde_json::json,
+        std::pin::pin,
+        tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        },
+    };
+
+    use crate::{
+        http::{create_client, ClientConfig},
+        rdap::QueryType,
+    };
+
+    use super::{next_page_url, rdap_paged_request};
+
+    /// Serves the first page of a domain search, with a link to the second
+    /// page, and then the second page, with no link.
+    async fn serve_two_pages() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("binding listener");
+        let addr = listener.local_addr().expect("listener address");
+        tokio::spawn(async move {
+            for (ldh_name, next) in [("foo1.example", true), ("foo2.example", false)] {
+                let (mut stream, _) = listener.accept().await.expect("accepting connection");
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request).await.expect("reading request");
+                let mut paging = json!({"totalCount": 2, "pageSize": 1});
+                if next {
+                    paging["links"] = json!([{
+                        "value": "/rdap/domains?name=foo*",
+                        "rel": "next",
+                        "href": "/rdap/domains?name=foo*&cursor=1"
+                    }]);
+                }
+                let body = json!({
+                    "rdapConformance": ["rdap_level_0", "paging"],
+                    "domainSearchResults": [{"objectClassName": "domain", "ldhName": ldh_name}],
+                    "paging_metadata": paging
+                })
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/rdap+json\r\n\
+                     content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream
+                    .write_all(response.as_bytes())
+                    .await
+                    .expect("writing response");
+            }
+        });
+        format!("http://{addr}/rdap")
+    }
+
+    #[test]
+    fn GIVEN_relative_next_link_WHEN_next_page_url_THEN_url_is_resolved() {
+        // GIVEN
+        let rdap = DomainSearchResults::builder()
+            .results(vec![])
+            .paging_metadata(
+                PagingMetadata::builder()
+                    .link(
+                        Link::builder()
+                            .value("/rdap/domains?name=foo*")
+                            .rel("next")
+                            .href("/rdap/domains?name=foo*&cursor=2")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build()
+            .to_response();
+
+        // WHEN
+        let actual = next_page_url("http://example.com/rdap/domains?name=foo*", &rdap)
+            .expect("invalid next link");
+
+        // THEN
+        assert_eq!(
+            actual.as_deref(),
+            Some("http://example.com/rdap/domains?name=foo*&cursor=2")
+        );
+    }
+
+    #[test]
+    fn GIVEN_no_paging_metadata_WHEN_next_page_url_THEN_none() {
+        // GIVEN
+        let rdap = DomainSearchResults::builder()
+            .results(vec![])
+            .build()
+            .to_response();
+
+        // WHEN
+        let actual = next_page_url("http://example.com/rdap/domains?name=foo*", &rdap)
+            .expect("invalid next link");
+
+        // THEN
+        assert!(actual.is_none());
+    }
+
+    #[tokio::test]
+    async fn GIVEN_two_pages_of_results_WHEN_rdap_paged_request_THEN_each_page_is_streamed() {
+        // GIVEN
+        let base_url = serve_two_pages().await;
+        let client_config = ClientConfig::builder().https_only(false).build();
+        let client = create_client(&client_config).expect("creating client");
+        let query = QueryType::DomainNameSearch("foo*".to_string());
+
+        // WHEN
+        let mut pages = pin!(rdap_paged_request(&base_url, &query, &client));
+        let mut names = vec![];
+        while let Some(page) = pages.next().await {
+            let RdapResponse::DomainSearchResults(results) = page.expect("getting page").rdap
+            else {
+                panic!("response is not domain search results")
+            };
+            names.extend(results.results.into_iter().filter_map(|d| d.ldh_name));
+        }
+
+        // THEN
+        assert_eq!(names, ["foo1.example", "foo2.example"]);
+    }
+
+    #[tokio::test]
+    async fn GIVEN_unreachable_server_WHEN_rdap_paged_request_THEN_one_error_is_streamed() {
+        // GIVEN
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("binding listener");
+        let addr = listener.local_addr().expect("listener address");
+        drop(listener);
+        let client_config = ClientConfig::builder().https_only(false).build();
+        let client = create_client(&client_config).expect("creating client");
+        let query = QueryType::DomainNameSearch("foo*".to_string());
+
+        // WHEN
+        let pages = rdap_paged_request(&format!("http://{addr}/rdap"), &query, &client)
+            .collect::<Vec<_>>()
+            .await;
+
+        // THEN
+        assert_eq!(pages.len(), 1);
+        assert!(pages[0].is_err());
+    }
+}
@@ -100,6 +100,7 @@ pub enum CheckClass {
 #[serde(rename_all = "snake_case")]
 pub enum RdapStructure {
     Autnum,
+    AutnumSearchResults,
     Cidr0,
     Domain,
     DomainSearchResults,
@@ -111,6 +112,7 @@ pub enum RdapStructure {
     Handle,
     HttpData,
     IpNetwork,
+    IpNetworkSearchResults,
     Link,
     Links,
     Nameserver,
@@ -209,6 +211,8 @@ impl GetChecks for RdapResponse {
             Self::DomainSearchResults(r) => r.get_checks(params),
             Self::EntitySearchResults(r) => r.get_checks(params),
             Self::NameserverSearchResults(r) => r.get_checks(params),
+            Self::NetworkSearchResults(r) => r.get_checks(params),
+            Self::AutnumSearchResults(r) => r.get_checks(params),
             Self::ErrorResponse(e) => e.get_checks(params),
             Self::Help(h) => h.get_checks(params),
         }
@@ -0,0 +1,646 @@
+//! Convert a Contact to and from a JSContact card (RFC 9553).
+//!
+//! RDAP carries JSContact cards in the `jscard` member of an entity, as specified
+//! by draft-ietf-regext-rdap-jscontact.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use super::{Contact, Email, Lang, NameParts, Phone, PostalAddress};
+
+/// A JSContact card.
+///
+/// Only the properties with counterparts in [Contact] are modeled. Maps of
+/// properties are keyed by IDs that have no meaning beyond the card.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsCard {
+    #[serde(rename = "@type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<JsName>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nicknames: Option<BTreeMap<String, JsNickname>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organizations: Option<BTreeMap<String, JsOrganization>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub titles: Option<BTreeMap<String, JsTitle>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emails: Option<BTreeMap<String, JsEmailAddress>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phones: Option<BTreeMap<String, JsPhone>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addresses: Option<BTreeMap<String, JsAddress>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<BTreeMap<String, JsLink>>,
+
+    #[serde(rename = "preferredLanguages")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_languages: Option<BTreeMap<String, JsLanguagePref>>,
+}
+
+/// The name of a contact.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsName {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<JsComponent>>,
+}
+
+/// A part of a name or address, such as a given name or a locality.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsComponent {
+    pub kind: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsNickname {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsOrganization {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A title or organizational role, which are told apart by the kind.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsTitle {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsEmailAddress {
+    pub address: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<BTreeMap<String, bool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pref: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsPhone {
+    pub number: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<BTreeMap<String, bool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<BTreeMap<String, bool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pref: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsAddress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<JsComponent>>,
+
+    #[serde(rename = "countryCode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<BTreeMap<String, bool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pref: Option<u64>,
+}
+
+/// A link of a contact. Contact URIs have the kind "contact".
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsLink {
+    pub uri: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsLanguagePref {
+    pub language: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pref: Option<u64>,
+}
+
+/// Deserializes a JSContact card, which is dropped rather than failing the
+/// whole response if it is malformed.
+pub(crate) fn deserialize_lenient_jscard<'de, D>(
+    deserializer: D,
+) -> Result<Option<JsCard>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| serde_json::from_value(value).ok()))
+}
+
+/// Name components and the parts of [NameParts] they hold.
+const NAME_KINDS: [&str; 5] = ["title", "given", "given2", "surname", "credential"];
+
+impl Contact {
+    /// Output the Contact data as a JSContact card.
+    ///
+    /// ```rust
+    /// use icann_rdap_common::contact::Contact;
+    ///
+    /// let contact = Contact::builder()
+    ///   .kind("individual")
+    ///   .full_name("Bob Smurd")
+    ///   .build();
+    ///
+    /// let jscard = contact.to_jscard();
+    /// let json = serde_json::to_string(&jscard);
+    /// ```
+    pub fn to_jscard(&self) -> JsCard {
+        let name = (self.full_name.is_some() || self.name_parts.is_some()).then(|| JsName {
+            full: self.full_name.clone(),
+            components: self.name_parts.as_ref().map(name_components),
+        });
+        let mut titles = vec![];
+        for title in self.titles.iter().flatten() {
+            titles.push(JsTitle {
+                name: title.clone(),
+                kind: Some("title".to_string()),
+            });
+        }
+        for role in self.roles.iter().flatten() {
+            titles.push(JsTitle {
+                name: role.clone(),
+                kind: Some("role".to_string()),
+            });
+        }
+        let mut links = vec![];
+        for uri in self.contact_uris.iter().flatten() {
+            links.push(JsLink {
+                uri: uri.clone(),
+                kind: Some("contact".to_string()),
+            });
+        }
+        for url in self.urls.iter().flatten() {
+            links.push(JsLink {
+                uri: url.clone(),
+                kind: None,
+            });
+        }
+        JsCard {
+            card_type: Some("Card".to_string()),
+            version: Some("1.0".to_string()),
+            uid: None,
+            kind: self.kind.clone(),
+            name,
+            nicknames: keyed(
+                "nick",
+                self.nick_names
+                    .iter()
+                    .flatten()
+                    .map(|name| JsNickname { name: name.clone() }),
+            ),
+            organizations: keyed(
+                "org",
+                self.organization_names
+                    .iter()
+                    .flatten()
+                    .map(|name| JsOrganization {
+                        name: Some(name.clone()),
+                    }),
+            ),
+            titles: keyed("title", titles),
+            emails: keyed(
+                "email",
+                self.emails.iter().flatten().map(|email| JsEmailAddress {
+                    address: email.email.clone(),
+                    contexts: to_contexts(&email.contexts),
+                    pref: email.preference,
+                }),
+            ),
+            phones: keyed(
+                "phone",
+                self.phones.iter().flatten().map(|phone| JsPhone {
+                    number: phone.phone.clone(),
+                    features: to_flags(&phone.features),
+                    contexts: to_contexts(&phone.contexts),
+                    pref: phone.preference,
+                }),
+            ),
+            addresses: keyed("addr", self.postal_addresses.iter().flatten().map(address)),
+            links: keyed("link", links),
+            preferred_languages: keyed(
+                "lang",
+                self.langs.iter().flatten().map(|lang| JsLanguagePref {
+                    language: lang.tag.clone(),
+                    pref: lang.preference,
+                }),
+            ),
+        }
+    }
+
+    /// Creates a Contact from a JSContact card.
+    ///
+    /// ```rust
+    /// use icann_rdap_common::contact::{Contact, JsCard};
+    ///
+    /// let json = r#"
+    /// {
+    ///   "@type": "Card",
+    ///   "version": "1.0",
+    ///   "kind": "individual",
+    ///   "name": { "full": "Joe User" },
+    ///   "emails": { "email-1": { "address": "joe.user@example.com" } }
+    /// }"#;
+    ///
+    /// let jscard: JsCard = serde_json::from_str(json).unwrap();
+    /// let contact = Contact::from_jscard(&jscard);
+    /// ```
+    pub fn from_jscard(jscard: &JsCard) -> Contact {
+        let name = jscard.name.as_ref();
+        let titles = values(&jscard.titles);
+        let links = values(&jscard.links);
+        Contact::builder()
+            .langs(
+                values(&jscard.preferred_languages)
+                    .into_iter()
+                    .map(|lang| {
+                        Lang::builder()
+                            .tag(lang.language.clone())
+                            .and_preference(lang.pref)
+                            .build()
+                    })
+                    .collect(),
+            )
+            .and_kind(jscard.kind.clone())
+            .and_full_name(name.and_then(|name| name.full.clone()))
+            .and_name_parts(
+                name.and_then(|name| name.components.as_ref())
+                    .map(|components| name_parts(components)),
+            )
+            .nick_names(
+                values(&jscard.nicknames)
+                    .into_iter()
+                    .map(|nickname| nickname.name.clone())
+                    .collect(),
+            )
+            .titles(
+                titles
+                    .iter()
+                    .filter(|title| title.kind.as_deref() != Some("role"))
+                    .map(|title| title.name.clone())
+                    .collect(),
+            )
+            .roles(
+                titles
+                    .iter()
+                    .filter(|title| title.kind.as_deref() == Some("role"))
+                    .map(|title| title.name.clone())
+                    .collect(),
+            )
+            .organization_names(
+                values(&jscard.organizations)
+                    .into_iter()
+                    .filter_map(|organization| organization.name.clone())
+                    .collect(),
+            )
+            .postal_addresses(
+                values(&jscard.addresses)
+                    .into_iter()
+                    .map(postal_address)
+                    .collect(),
+            )
+            .emails(
+                values(&jscard.emails)
+                    .into_iter()
+                    .map(|email| {
+                        Email::builder()
+                            .email(email.address.clone())
+                            .contexts(from_contexts(&email.contexts))
+                            .and_preference(email.pref)
+                            .build()
+                    })
+                    .collect(),
+            )
+            .phones(
+                values(&jscard.phones)
+                    .into_iter()
+                    .map(|phone| {
+                        Phone::builder()
+                            .phone(phone.number.clone())
+                            .features(from_flags(&phone.features))
+                            .contexts(from_contexts(&phone.contexts))
+                            .and_preference(phone.pref)
+                            .build()
+                    })
+                    .collect(),
+            )
+            .contact_uris(
+                links
+                    .iter()
+                    .filter(|link| link.kind.as_deref() == Some("contact"))
+                    .map(|link| link.uri.clone())
+                    .collect(),
+            )
+            .urls(
+                links
+                    .iter()
+                    .filter(|link| link.kind.as_deref() != Some("contact"))
+                    .map(|link| link.uri.clone())
+                    .collect(),
+            )
+            .build()
+    }
+}
+
+/// Keys the values of a JSContact map with IDs made from the prefix and their
+/// position, which keeps them in order.
+fn keyed<T>(prefix: &str, values: impl IntoIterator<Item = T>) -> Option<BTreeMap<String, T>> {
+    let map: BTreeMap<String, T> = values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| (format!("{prefix}-{:03}", i + 1), value))
+        .collect();
+    (!map.is_empty()).then_some(map)
+}
+
+fn values<T>(map: &Option<BTreeMap<String, T>>) -> Vec<&T> {
+    map.iter().flat_map(|map| map.values()).collect()
+}
+
+/// Converts vCard types to JSContact contexts, which call "home" "private".
+fn to_contexts(contexts: &Option<Vec<String>>) -> Option<BTreeMap<String, bool>> {
+    let contexts = contexts
+        .as_ref()?
+        .iter()
+        .map(|context| match context.as_str() {
+            "home" => "private".to_string(),
+            _ => context.clone(),
+        });
+    let contexts: BTreeMap<String, bool> = contexts.map(|context| (context, true)).collect();
+    (!contexts.is_empty()).then_some(contexts)
+}
+
+fn from_contexts(contexts: &Option<BTreeMap<String, bool>>) -> Vec<String> {
+    from_flags(contexts)
+        .into_iter()
+        .map(|context| match context.as_str() {
+            "private" => "home".to_string(),
+            _ => context,
+        })
+        .collect()
+}
+
+fn to_flags(flags: &Option<Vec<String>>) -> Option<BTreeMap<String, bool>> {
+    let flags: BTreeMap<String, bool> = flags
+        .iter()
+        .flatten()
+        .map(|flag| (flag.clone(), true))
+        .collect();
+    (!flags.is_empty()).then_some(flags)
+}
+
+fn from_flags(flags: &Option<BTreeMap<String, bool>>) -> Vec<String> {
+    flags
+        .iter()
+        .flatten()
+        .filter(|(_, set)| **set)
+        .map(|(flag, _)| flag.clone())
+        .collect()
+}
+
+fn name_components(name_parts: &NameParts) -> Vec<JsComponent> {
+    let parts = [
+        &name_parts.prefixes,
+        &name_parts.given_names,
+        &name_parts.middle_names,
+        &name_parts.surnames,
+        &name_parts.suffixes,
+    ];
+    NAME_KINDS
+        .iter()
+        .zip(parts)
+        .flat_map(|(kind, values)| {
+            values.iter().flatten().map(|value| JsComponent {
+                kind: kind.to_string(),
+                value: value.clone(),
+            })
+        })
+        .collect()
+}
+
+fn name_parts(components: &[JsComponent]) -> NameParts {
+    let of_kind = |kind: &str| {
+        components
+            .iter()
+            .filter(|component| component.kind == kind)
+            .map(|component| component.value.clone())
+            .collect::<Vec<String>>()
+    };
+    NameParts::builder()
+        .prefixes(of_kind("title"))
+        .given_names(of_kind("given"))
+        .middle_names(of_kind("given2"))
+        .surnames(of_kind("surname"))
+        .suffixes(of_kind("credential"))
+        .build()
+}
+
+fn address(postal_address: &PostalAddress) -> JsAddress {
+    let mut components = vec![];
+    let mut push = |kind: &str, value: &Option<String>| {
+        if let Some(value) = value {
+            components.push(JsComponent {
+                kind: kind.to_string(),
+                value: value.clone(),
+            });
+        }
+    };
+    for street_part in postal_address.street_parts.iter().flatten() {
+        push("name", &Some(street_part.clone()));
+    }
+    push("locality", &postal_address.locality);
+    push(
+        "region",
+        postal_address
+            .region_name
+            .as_ref()
+            .map_or(&postal_address.region_code, |_| &postal_address.region_name),
+    );
+    push("postcode", &postal_address.postal_code);
+    push("country", &postal_address.country_name);
+    JsAddress {
+        full: postal_address.full_address.clone(),
+        components: (!components.is_empty()).then_some(components),
+        country_code: postal_address.country_code.clone(),
+        contexts: to_contexts(&postal_address.contexts),
+        pref: postal_address.preference,
+    }
+}
+
+fn postal_address(address: &JsAddress) -> PostalAddress {
+    let components = address.components.iter().flatten();
+    let first_of_kind = |kind: &str| {
+        components
+            .clone()
+            .find(|component| component.kind == kind)
+            .map(|component| component.value.clone())
+    };
+    PostalAddress::builder()
+        .and_full_address(address.full.clone())
+        .street_parts(
+            components
+                .clone()
+                .filter(|component| component.kind == "name")
+                .map(|component| component.value.clone())
+                .collect(),
+        )
+        .and_locality(first_of_kind("locality"))
+        .and_region_name(first_of_kind("region"))
+        .and_postal_code(first_of_kind("postcode"))
+        .and_country_name(first_of_kind("country"))
+        .and_country_code(address.country_code.clone())
+        .contexts(from_contexts(&address.contexts))
+        .and_preference(address.pref)
+        .build()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use crate::contact::{Contact, Email, Lang, NameParts, Phone, PostalAddress};
+
+    use super::JsCard;
+
+    fn contact() -> Contact {
+        Contact::builder()
+            .langs(vec![Lang::builder().tag("fr").preference(1).build()])
+            .kind("individual")
+            .full_name("Joe User")
+            .name_parts(
+                NameParts::builder()
+                    .prefixes(vec!["Dr.".to_string()])
+                    .given_names(vec!["Joe".to_string()])
+                    .surnames(vec!["User".to_string()])
+                    .build(),
+            )
+            .titles(vec!["Research Scientist".to_string()])
+            .roles(vec!["Project Lead".to_string()])
+            .organization_names(vec!["Example".to_string()])
+            .postal_addresses(vec![PostalAddress::builder()
+                .contexts(vec!["work".to_string()])
+                .street_parts(vec![
+                    "Suite 1234".to_string(),
+                    "4321 Rue Somewhere".to_string(),
+                ])
+                .locality("Quebec")
+                .region_name("QC")
+                .postal_code("G1V 2M2")
+                .country_name("Canada")
+                .country_code("CA")
+                .build()])
+            .emails(vec![Email::builder()
+                .email("joe.user@example.com")
+                .contexts(vec!["home".to_string()])
+                .build()])
+            .phones(vec![Phone::builder()
+                .phone("tel:+1-555-555-1234;ext=102")
+                .features(vec!["voice".to_string()])
+                .contexts(vec!["work".to_string()])
+                .preference(1)
+                .build()])
+            .contact_uris(vec!["https://example.com/contact-form".to_string()])
+            .urls(vec!["https://example.com/joe".to_string()])
+            .build()
+    }
+
+    #[test]
+    fn GIVEN_contact_WHEN_to_jscard_THEN_from_jscard_is_same() {
+        // GIVEN
+        let contact = contact();
+
+        // WHEN
+        let actual = Contact::from_jscard(&contact.to_jscard());
+
+        // THEN
+        assert_eq!(actual, contact);
+    }
+
+    #[test]
+    fn GIVEN_contact_WHEN_to_jscard_THEN_jscontact_properties() {
+        // GIVEN
+        let contact = contact();
+
+        // WHEN
+        let actual = serde_json::to_value(contact.to_jscard()).expect("serializing jscard");
+
+        // THEN
+        assert_eq!(actual["@type"], "Card");
+        assert_eq!(actual["name"]["full"], "Joe User");
+        assert_eq!(actual["name"]["components"][0]["kind"], "title");
+        assert_eq!(actual["titles"]["title-002"]["kind"], "role");
+        assert_eq!(
+            actual["emails"]["email-001"]["contexts"]["private"],
+            serde_json::Value::Bool(true)
+        );
+        assert_eq!(actual["addresses"]["addr-001"]["countryCode"], "CA");
+        assert_eq!(actual["links"]["link-001"]["kind"], "contact");
+    }
+
+    #[test]
+    fn GIVEN_jscard_text_WHEN_deserialize_THEN_contact() {
+        // GIVEN
+        let json = r#"
+          {
+            "@type": "Card",
+            "version": "1.0",
+            "uid": "urn:uuid:e7b0a4b5-1c2e-4f8e-9a3e-1c2e4f8e9a3e",
+            "kind": "org",
+            "name": { "full": "Example Registrar" },
+            "emails": { "e1": { "address": "abuse@example.com", "contexts": { "work": true } } },
+            "phones": { "p1": { "number": "+1.5555551234", "features": { "fax": true } } }
+          }
+        "#;
+
+        // WHEN
+        let jscard: JsCard = serde_json::from_str(json).expect("parsing jscard");
+        let actual = Contact::from_jscard(&jscard);
+
+        // THEN
+        assert_eq!(actual.kind.as_deref(), Some("org"));
+        assert_eq!(actual.full_name.as_deref(), Some("Example Registrar"));
+        let email = &actual.emails.expect("emails")[0];
+        assert_eq!(email.email, "abuse@example.com");
+        assert_eq!(email.contexts, Some(vec!["work".to_string()]));
+        let phone = &actual.phones.expect("phones")[0];
+        assert_eq!(phone.features, Some(vec!["fax".to_string()]));
+    }
+}
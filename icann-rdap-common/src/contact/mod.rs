@@ -2,7 +2,7 @@
 //!
 //! This module converts contact information to and from vCard/jCard, which is hard to
 //! work with directly. It is also intended as a way of bridging the between vCard/jCard
-//! and any new contact model.
+//! and any new contact model, and converts to and from JSContact ([JsCard]) as well.
 //!
 //! This struct can be built using the builder.
 //!
@@ -80,8 +80,15 @@
 //! ```
 
 mod from_vcard;
+mod jscontact;
 mod to_vcard;
 
+pub(crate) use jscontact::deserialize_lenient_jscard;
+pub use jscontact::{
+    JsAddress, JsCard, JsComponent, JsEmailAddress, JsLanguagePref, JsLink, JsName, JsNickname,
+    JsOrganization, JsPhone, JsTitle,
+};
+
 use std::fmt::Display;
 
 use buildstructor::Builder;
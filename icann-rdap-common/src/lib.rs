@@ -7,6 +7,7 @@ pub mod httpdata;
 pub mod iana;
 pub mod media_types;
 pub mod response;
+pub mod search_types;
 
 /// Basics RDAP structures.
 pub mod prelude {
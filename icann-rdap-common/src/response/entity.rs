@@ -1,7 +1,7 @@
 //! Entity object class.
 use {
     crate::{
-        contact::Contact,
+        contact::{deserialize_lenient_jscard, Contact, JsCard},
         prelude::{Common, Extension, ObjectCommon},
     },
     serde::{Deserialize, Serialize},
@@ -95,6 +95,11 @@ pub struct Entity {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vcard_array: Option<Vec<Value>>,
 
+    /// The contact as a JSContact card. A card that cannot be parsed is ignored.
+    #[serde(default, deserialize_with = "deserialize_lenient_jscard")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jscard: Option<JsCard>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roles: Option<VectorStringish>,
 
@@ -173,6 +178,7 @@ impl Entity {
                 .and_redacted(redacted)
                 .build(),
             vcard_array: contact.map(|c| c.to_vcard()),
+            jscard: None,
             roles: to_opt_vectorstringish(roles),
             public_ids: to_opt_vec(public_ids),
             as_event_actor: to_opt_vec(as_event_actors),
@@ -181,10 +187,13 @@ impl Entity {
         }
     }
 
-    /// Convenience method to get a [Contact] from the impentrable vCard.
+    /// Convenience method to get a [Contact] from the impentrable vCard,
+    /// or from the JSContact card if there is no vCard.
     pub fn contact(&self) -> Option<Contact> {
-        let vcard = self.vcard_array.as_ref()?;
-        Contact::from_vcard(vcard)
+        match &self.vcard_array {
+            Some(vcard) => Contact::from_vcard(vcard),
+            None => self.jscard.as_ref().map(Contact::from_jscard),
+        }
     }
 
     /// Convenience method to get the roles.
@@ -417,4 +426,48 @@ mod tests {
         assert!(actual.object_common.events.is_some());
         assert!(actual.as_event_actor.is_some());
     }
+
+    #[test]
+    fn GIVEN_entity_with_jscard_only_WHEN_contact_THEN_contact_from_jscard() {
+        // GIVEN
+        let json = r#"
+            {
+              "objectClassName": "entity",
+              "handle": "XXXX",
+              "jscard": {
+                "@type": "Card",
+                "version": "1.0",
+                "kind": "individual",
+                "name": { "full": "Joe User" }
+              }
+            }
+        "#;
+        let entity = serde_json::from_str::<Entity>(json).expect("parsing entity");
+
+        // WHEN
+        let actual = entity.contact();
+
+        // THEN
+        let actual = actual.expect("contact from jscard");
+        assert_eq!(actual.full_name.as_deref(), Some("Joe User"));
+    }
+
+    #[test]
+    fn GIVEN_entity_with_malformed_jscard_WHEN_deserialize_THEN_jscard_is_ignored() {
+        // GIVEN
+        let json = r#"
+            {
+              "objectClassName": "entity",
+              "handle": "XXXX",
+              "jscard": { "emails": "not a map" }
+            }
+        "#;
+
+        // WHEN
+        let actual = serde_json::from_str::<Entity>(json);
+
+        // THEN
+        let actual = actual.expect("parsing entity");
+        assert!(actual.jscard.is_none());
+    }
 }
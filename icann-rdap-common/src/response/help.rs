@@ -11,20 +11,37 @@ use super::{to_opt_vec, Common, CommonFields, ToResponse};
 pub struct Help {
     #[serde(flatten)]
     pub common: Common,
+
+    /// The reverse searches supported by the server, as specified in RFC 9536.
+    #[serde(rename = "reverse_search_properties")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverse_search_properties: Option<Vec<ReverseSearchProperty>>,
 }
 
 #[buildstructor::buildstructor]
 impl Help {
     /// Builds a basic help response.
     #[builder(visibility = "pub")]
-    fn new(notices: Vec<Notice>, extensions: Vec<Extension>) -> Self {
+    fn new(
+        notices: Vec<Notice>,
+        extensions: Vec<Extension>,
+        reverse_search_properties: Vec<ReverseSearchProperty>,
+    ) -> Self {
         Self {
             common: Common::level0()
                 .extensions(extensions)
                 .and_notices(to_opt_vec(notices))
                 .build(),
+            reverse_search_properties: to_opt_vec(reverse_search_properties),
         }
     }
+
+    /// Convenience method to get the reverse search properties.
+    pub fn reverse_search_properties(&self) -> &[ReverseSearchProperty] {
+        self.reverse_search_properties
+            .as_deref()
+            .unwrap_or_default()
+    }
 }
 
 impl CommonFields for Help {
@@ -38,3 +55,118 @@ impl ToResponse for Help {
         super::RdapResponse::Help(Box::new(self))
     }
 }
+
+/// Describes a reverse search supported by a server, as specified in RFC 9536.
+///
+/// ```rust
+/// use icann_rdap_common::prelude::*;
+///
+/// let property = ReverseSearchProperty::builder()
+///   .searchable_resource_type("domains")
+///   .related_resource_type("entity")
+///   .property("fn")
+///   .property_path("$.entities[*].vcardArray[1][?(@[0]=='fn')][3]")
+///   .build();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReverseSearchProperty {
+    #[serde(rename = "searchableResourceType")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub searchable_resource_type: Option<String>,
+
+    #[serde(rename = "relatedResourceType")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_resource_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<String>,
+
+    #[serde(rename = "propertyPath")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property_path: Option<String>,
+}
+
+#[buildstructor::buildstructor]
+impl ReverseSearchProperty {
+    /// Builds a reverse search property.
+    #[builder(visibility = "pub")]
+    fn new(
+        searchable_resource_type: String,
+        related_resource_type: String,
+        property: String,
+        property_path: String,
+    ) -> Self {
+        Self {
+            searchable_resource_type: Some(searchable_resource_type),
+            related_resource_type: Some(related_resource_type),
+            property: Some(property),
+            property_path: Some(property_path),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use crate::response::RdapResponse;
+
+    use super::{Help, ReverseSearchProperty};
+
+    #[test]
+    fn GIVEN_help_with_reverse_search_properties_WHEN_serialize_THEN_rfc9536_names_used() {
+        // GIVEN
+        let help = Help::builder()
+            .reverse_search_property(
+                ReverseSearchProperty::builder()
+                    .searchable_resource_type("domains")
+                    .related_resource_type("entity")
+                    .property("handle")
+                    .property_path("$.entities[*].handle")
+                    .build(),
+            )
+            .build();
+
+        // WHEN
+        let actual = serde_json::to_value(&help).expect("serializing help");
+
+        // THEN
+        let property = &actual["reverse_search_properties"][0];
+        assert_eq!(property["searchableResourceType"], "domains");
+        assert_eq!(property["relatedResourceType"], "entity");
+        assert_eq!(property["property"], "handle");
+        assert_eq!(property["propertyPath"], "$.entities[*].handle");
+    }
+
+    #[test]
+    fn GIVEN_help_json_with_reverse_search_properties_WHEN_deserialize_THEN_help_response() {
+        // GIVEN
+        let json = r#"
+          {
+            "rdapConformance": ["rdap_level_0", "reverse_search"],
+            "notices": [{"description": ["help"]}],
+            "reverse_search_properties": [
+              {
+                "searchableResourceType": "domains",
+                "relatedResourceType": "entity",
+                "property": "fn",
+                "propertyPath": "$.entities[*].vcardArray[1][?(@[0]=='fn')][3]"
+              }
+            ]
+          }
+        "#;
+        let value: serde_json::Value = serde_json::from_str(json).expect("parsing json");
+
+        // WHEN
+        let actual = RdapResponse::try_from(value).expect("not rdap");
+
+        // THEN
+        let RdapResponse::Help(help) = actual else {
+            panic!("not a help response")
+        };
+        assert_eq!(help.reverse_search_properties().len(), 1);
+        assert_eq!(
+            help.reverse_search_properties()[0].property.as_deref(),
+            Some("fn")
+        );
+    }
+}
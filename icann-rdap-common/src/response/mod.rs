@@ -130,6 +130,8 @@ pub enum RdapResponse {
     DomainSearchResults(Box<DomainSearchResults>),
     EntitySearchResults(Box<EntitySearchResults>),
     NameserverSearchResults(Box<NameserverSearchResults>),
+    NetworkSearchResults(Box<NetworkSearchResults>),
+    AutnumSearchResults(Box<AutnumSearchResults>),
 
     // Error
     ErrorResponse(Box<Rfc9083Error>),
@@ -202,6 +204,26 @@ impl TryFrom<Value> for RdapResponse {
                 ));
             }
         }
+        // else if it is an ip network search result
+        if let Some(result) = response.get("ipSearchResults") {
+            if result.is_array() {
+                return Ok(serde_json::from_value::<NetworkSearchResults>(value)?.to_response());
+            } else {
+                return Err(RdapResponseError::WrongJsonType(
+                    "'ipSearchResults' is not an array".to_string(),
+                ));
+            }
+        }
+        // else if it is an autnum search result
+        if let Some(result) = response.get("autnumSearchResults") {
+            if result.is_array() {
+                return Ok(serde_json::from_value::<AutnumSearchResults>(value)?.to_response());
+            } else {
+                return Err(RdapResponseError::WrongJsonType(
+                    "'autnumSearchResults' is not an array".to_string(),
+                ));
+            }
+        }
 
         // else if it has an errorCode
         if let Some(result) = response.get("errorCode") {
@@ -239,6 +261,8 @@ impl RdapResponse {
             Self::DomainSearchResults(_) => TypeId::of::<DomainSearchResults>(),
             Self::EntitySearchResults(_) => TypeId::of::<EntitySearchResults>(),
             Self::NameserverSearchResults(_) => TypeId::of::<NameserverSearchResults>(),
+            Self::NetworkSearchResults(_) => TypeId::of::<NetworkSearchResults>(),
+            Self::AutnumSearchResults(_) => TypeId::of::<AutnumSearchResults>(),
             Self::ErrorResponse(_) => TypeId::of::<crate::response::Rfc9083Error>(),
             Self::Help(_) => TypeId::of::<Help>(),
         }
@@ -254,6 +278,26 @@ impl RdapResponse {
             Self::DomainSearchResults(_)
             | Self::EntitySearchResults(_)
             | Self::NameserverSearchResults(_)
+            | Self::NetworkSearchResults(_)
+            | Self::AutnumSearchResults(_)
+            | Self::ErrorResponse(_)
+            | Self::Help(_) => None,
+        }
+    }
+
+    /// Gets the RFC 8977 paging metadata of search results.
+    pub fn get_paging_metadata(&self) -> Option<&PagingMetadata> {
+        match self {
+            Self::DomainSearchResults(s) => s.paging_metadata.as_ref(),
+            Self::EntitySearchResults(s) => s.paging_metadata.as_ref(),
+            Self::NameserverSearchResults(s) => s.paging_metadata.as_ref(),
+            Self::NetworkSearchResults(s) => s.paging_metadata.as_ref(),
+            Self::AutnumSearchResults(s) => s.paging_metadata.as_ref(),
+            Self::Entity(_)
+            | Self::Domain(_)
+            | Self::Nameserver(_)
+            | Self::Autnum(_)
+            | Self::Network(_)
             | Self::ErrorResponse(_)
             | Self::Help(_) => None,
         }
@@ -269,6 +313,8 @@ impl RdapResponse {
             Self::DomainSearchResults(s) => s.common.rdap_conformance.as_ref(),
             Self::EntitySearchResults(s) => s.common.rdap_conformance.as_ref(),
             Self::NameserverSearchResults(s) => s.common.rdap_conformance.as_ref(),
+            Self::NetworkSearchResults(s) => s.common.rdap_conformance.as_ref(),
+            Self::AutnumSearchResults(s) => s.common.rdap_conformance.as_ref(),
             Self::ErrorResponse(e) => e.common.rdap_conformance.as_ref(),
             Self::Help(h) => h.common.rdap_conformance.as_ref(),
         }
@@ -522,6 +568,32 @@ mod tests {
         assert!(matches!(actual, RdapResponse::EntitySearchResults(_)));
     }
 
+    #[test]
+    fn test_response_is_network_search_results() {
+        // GIVEN
+        let expected: Value =
+            serde_json::from_str(include_str!("test_files/ips_name_example.json")).unwrap();
+
+        // WHEN
+        let actual = RdapResponse::try_from(expected).unwrap();
+
+        // THEN
+        assert!(matches!(actual, RdapResponse::NetworkSearchResults(_)));
+    }
+
+    #[test]
+    fn test_response_is_autnum_search_results() {
+        // GIVEN
+        let expected: Value =
+            serde_json::from_str(include_str!("test_files/autnums_name_example.json")).unwrap();
+
+        // WHEN
+        let actual = RdapResponse::try_from(expected).unwrap();
+
+        // THEN
+        assert!(matches!(actual, RdapResponse::AutnumSearchResults(_)));
+    }
+
     #[test]
     fn test_response_is_help() {
         // GIVEN
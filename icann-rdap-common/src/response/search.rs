@@ -1,10 +1,13 @@
 //! RDAP Search Results.
 use {
-    crate::prelude::{Common, Extension},
+    crate::prelude::{Common, Extension, Link, Links},
     serde::{Deserialize, Serialize},
 };
 
-use super::{domain::Domain, entity::Entity, nameserver::Nameserver, CommonFields, ToResponse};
+use super::{
+    autnum::Autnum, domain::Domain, entity::Entity, nameserver::Nameserver, network::Network,
+    CommonFields, ToResponse,
+};
 
 /// Represents RDAP domain search results.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
@@ -14,16 +17,37 @@ pub struct DomainSearchResults {
 
     #[serde(rename = "domainSearchResults")]
     pub results: Vec<Domain>,
+
+    #[serde(rename = "paging_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    #[serde(rename = "sorting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting_metadata: Option<SortingMetadata>,
+
+    #[serde(rename = "subsetting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsetting_metadata: Option<SubsettingMetadata>,
 }
 
 #[buildstructor::buildstructor]
 impl DomainSearchResults {
     /// Builds a domain search result.
     #[builder(visibility = "pub")]
-    fn new(results: Vec<Domain>, extensions: Vec<Extension>) -> Self {
+    fn new(
+        results: Vec<Domain>,
+        extensions: Vec<Extension>,
+        paging_metadata: Option<PagingMetadata>,
+        sorting_metadata: Option<SortingMetadata>,
+        subsetting_metadata: Option<SubsettingMetadata>,
+    ) -> Self {
         Self {
             common: Common::level0().extensions(extensions).build(),
             results,
+            paging_metadata,
+            sorting_metadata,
+            subsetting_metadata,
         }
     }
 }
@@ -48,16 +72,37 @@ pub struct NameserverSearchResults {
 
     #[serde(rename = "nameserverSearchResults")]
     pub results: Vec<Nameserver>,
+
+    #[serde(rename = "paging_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    #[serde(rename = "sorting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting_metadata: Option<SortingMetadata>,
+
+    #[serde(rename = "subsetting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsetting_metadata: Option<SubsettingMetadata>,
 }
 
 #[buildstructor::buildstructor]
 impl NameserverSearchResults {
     /// Builds a nameserver search result.
     #[builder(visibility = "pub")]
-    fn new(results: Vec<Nameserver>, extensions: Vec<Extension>) -> Self {
+    fn new(
+        results: Vec<Nameserver>,
+        extensions: Vec<Extension>,
+        paging_metadata: Option<PagingMetadata>,
+        sorting_metadata: Option<SortingMetadata>,
+        subsetting_metadata: Option<SubsettingMetadata>,
+    ) -> Self {
         Self {
             common: Common::level0().extensions(extensions).build(),
             results,
+            paging_metadata,
+            sorting_metadata,
+            subsetting_metadata,
         }
     }
 }
@@ -82,16 +127,37 @@ pub struct EntitySearchResults {
 
     #[serde(rename = "entitySearchResults")]
     pub results: Vec<Entity>,
+
+    #[serde(rename = "paging_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    #[serde(rename = "sorting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting_metadata: Option<SortingMetadata>,
+
+    #[serde(rename = "subsetting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsetting_metadata: Option<SubsettingMetadata>,
 }
 
 #[buildstructor::buildstructor]
 impl EntitySearchResults {
     /// Builds an entity search result.
     #[builder(visibility = "pub")]
-    fn new(results: Vec<Entity>, extensions: Vec<Extension>) -> Self {
+    fn new(
+        results: Vec<Entity>,
+        extensions: Vec<Extension>,
+        paging_metadata: Option<PagingMetadata>,
+        sorting_metadata: Option<SortingMetadata>,
+        subsetting_metadata: Option<SubsettingMetadata>,
+    ) -> Self {
         Self {
             common: Common::level0().extensions(extensions).build(),
             results,
+            paging_metadata,
+            sorting_metadata,
+            subsetting_metadata,
         }
     }
 }
@@ -107,3 +173,499 @@ impl ToResponse for EntitySearchResults {
         super::RdapResponse::EntitySearchResults(Box::new(self))
     }
 }
+
+/// Represents RDAP IP network search results (draft-ietf-regext-rdap-rir-search).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
+pub struct NetworkSearchResults {
+    #[serde(flatten)]
+    pub common: Common,
+
+    #[serde(rename = "ipSearchResults")]
+    pub results: Vec<Network>,
+
+    #[serde(rename = "paging_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    #[serde(rename = "sorting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting_metadata: Option<SortingMetadata>,
+
+    #[serde(rename = "subsetting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsetting_metadata: Option<SubsettingMetadata>,
+}
+
+#[buildstructor::buildstructor]
+impl NetworkSearchResults {
+    /// Builds an IP network search result.
+    #[builder(visibility = "pub")]
+    fn new(
+        results: Vec<Network>,
+        extensions: Vec<Extension>,
+        paging_metadata: Option<PagingMetadata>,
+        sorting_metadata: Option<SortingMetadata>,
+        subsetting_metadata: Option<SubsettingMetadata>,
+    ) -> Self {
+        Self {
+            common: Common::level0().extensions(extensions).build(),
+            results,
+            paging_metadata,
+            sorting_metadata,
+            subsetting_metadata,
+        }
+    }
+}
+
+impl CommonFields for NetworkSearchResults {
+    fn common(&self) -> &Common {
+        &self.common
+    }
+}
+
+impl ToResponse for NetworkSearchResults {
+    fn to_response(self) -> super::RdapResponse {
+        super::RdapResponse::NetworkSearchResults(Box::new(self))
+    }
+}
+
+/// Represents RDAP autnum search results (draft-ietf-regext-rdap-rir-search).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
+pub struct AutnumSearchResults {
+    #[serde(flatten)]
+    pub common: Common,
+
+    #[serde(rename = "autnumSearchResults")]
+    pub results: Vec<Autnum>,
+
+    #[serde(rename = "paging_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paging_metadata: Option<PagingMetadata>,
+
+    #[serde(rename = "sorting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting_metadata: Option<SortingMetadata>,
+
+    #[serde(rename = "subsetting_metadata")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsetting_metadata: Option<SubsettingMetadata>,
+}
+
+#[buildstructor::buildstructor]
+impl AutnumSearchResults {
+    /// Builds an autnum search result.
+    #[builder(visibility = "pub")]
+    fn new(
+        results: Vec<Autnum>,
+        extensions: Vec<Extension>,
+        paging_metadata: Option<PagingMetadata>,
+        sorting_metadata: Option<SortingMetadata>,
+        subsetting_metadata: Option<SubsettingMetadata>,
+    ) -> Self {
+        Self {
+            common: Common::level0().extensions(extensions).build(),
+            results,
+            paging_metadata,
+            sorting_metadata,
+            subsetting_metadata,
+        }
+    }
+}
+
+impl CommonFields for AutnumSearchResults {
+    fn common(&self) -> &Common {
+        &self.common
+    }
+}
+
+impl ToResponse for AutnumSearchResults {
+    fn to_response(self) -> super::RdapResponse {
+        super::RdapResponse::AutnumSearchResults(Box::new(self))
+    }
+}
+
+/// Describes the page of search results in a response, as specified in RFC 8977.
+///
+/// ```rust
+/// use icann_rdap_common::prelude::*;
+///
+/// let paging_metadata = PagingMetadata::builder()
+///   .total_count(73)
+///   .page_size(50)
+///   .page_number(1)
+///   .link(
+///     Link::builder()
+///       .value("https://example.com/rdap/domains?name=example*.com")
+///       .rel("next")
+///       .href("https://example.com/rdap/domains?name=example*.com&cursor=a1b2c3")
+///       .media_type("application/rdap+json")
+///       .build(),
+///   )
+///   .build();
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
+pub struct PagingMetadata {
+    #[serde(rename = "totalCount")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_count: Option<u64>,
+
+    #[serde(rename = "pageSize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u64>,
+
+    #[serde(rename = "pageNumber")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_number: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Links>,
+}
+
+#[buildstructor::buildstructor]
+impl PagingMetadata {
+    /// Builds paging metadata.
+    #[builder(visibility = "pub")]
+    fn new(
+        total_count: Option<u64>,
+        page_size: Option<u64>,
+        page_number: Option<u64>,
+        links: Vec<Link>,
+    ) -> Self {
+        Self {
+            total_count,
+            page_size,
+            page_number,
+            links: (!links.is_empty()).then_some(links),
+        }
+    }
+
+    /// Gets the link to the next page of results, if there is one.
+    pub fn next_link(&self) -> Option<&Link> {
+        self.links
+            .as_ref()
+            .and_then(|links| links.iter().find(|link| link.is_relation("next")))
+    }
+}
+
+/// Describes the sort of search results and the sorts available, as specified in RFC 8977.
+///
+/// ```rust
+/// use icann_rdap_common::prelude::*;
+///
+/// let sorting_metadata = SortingMetadata::builder()
+///   .current_sort("name")
+///   .available_sort(
+///     AvailableSort::builder()
+///       .property("registrationDate")
+///       .json_path("$.domainSearchResults[*].events[?(@.eventAction==\"registration\")].eventDate")
+///       .default(false)
+///       .build(),
+///   )
+///   .build();
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
+pub struct SortingMetadata {
+    #[serde(rename = "currentSort")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_sort: Option<String>,
+
+    #[serde(rename = "availableSorts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_sorts: Option<Vec<AvailableSort>>,
+}
+
+#[buildstructor::buildstructor]
+impl SortingMetadata {
+    /// Builds sorting metadata.
+    #[builder(visibility = "pub")]
+    fn new(current_sort: Option<String>, available_sorts: Vec<AvailableSort>) -> Self {
+        Self {
+            current_sort,
+            available_sorts: (!available_sorts.is_empty()).then_some(available_sorts),
+        }
+    }
+
+    /// Convenience method to get the available sorts.
+    pub fn available_sorts(&self) -> &[AvailableSort] {
+        self.available_sorts.as_deref().unwrap_or_default()
+    }
+}
+
+/// A property search results may be sorted by, as specified in RFC 8977.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
+pub struct AvailableSort {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<String>,
+
+    #[serde(rename = "jsonPath")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Links>,
+}
+
+#[buildstructor::buildstructor]
+impl AvailableSort {
+    /// Builds an available sort.
+    #[builder(visibility = "pub")]
+    fn new(property: String, json_path: Option<String>, default: bool, links: Vec<Link>) -> Self {
+        Self {
+            property: Some(property),
+            json_path,
+            default: Some(default),
+            links: (!links.is_empty()).then_some(links),
+        }
+    }
+
+    /// True if the search results are sorted by this property when no sort is given.
+    pub fn is_default(&self) -> bool {
+        self.default.unwrap_or(false)
+    }
+}
+
+/// Describes the field set of search results and the field sets available, as specified in RFC 8982.
+///
+/// ```rust
+/// use icann_rdap_common::prelude::*;
+///
+/// let subsetting_metadata = SubsettingMetadata::builder()
+///   .current_field_set("brief")
+///   .available_field_set(
+///     FieldSet::builder()
+///       .name("full")
+///       .description("All the information the server can provide.")
+///       .default(true)
+///       .build(),
+///   )
+///   .build();
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
+pub struct SubsettingMetadata {
+    #[serde(rename = "currentFieldSet")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_field_set: Option<String>,
+
+    #[serde(rename = "availableFieldSets")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_field_sets: Option<Vec<FieldSet>>,
+}
+
+#[buildstructor::buildstructor]
+impl SubsettingMetadata {
+    /// Builds subsetting metadata.
+    #[builder(visibility = "pub")]
+    fn new(current_field_set: String, available_field_sets: Vec<FieldSet>) -> Self {
+        Self {
+            current_field_set: Some(current_field_set),
+            available_field_sets: (!available_field_sets.is_empty())
+                .then_some(available_field_sets),
+        }
+    }
+
+    /// Convenience method to get the available field sets.
+    pub fn available_field_sets(&self) -> &[FieldSet] {
+        self.available_field_sets.as_deref().unwrap_or_default()
+    }
+}
+
+/// A field set search results may be returned with, as specified in RFC 8982.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Eq)]
+pub struct FieldSet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Links>,
+}
+
+#[buildstructor::buildstructor]
+impl FieldSet {
+    /// Builds a field set.
+    #[builder(visibility = "pub")]
+    fn new(name: String, default: bool, description: Option<String>, links: Vec<Link>) -> Self {
+        Self {
+            name: Some(name),
+            default: Some(default),
+            description,
+            links: (!links.is_empty()).then_some(links),
+        }
+    }
+
+    /// True if search results have this field set when no field set is given.
+    pub fn is_default(&self) -> bool {
+        self.default.unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use crate::{
+        prelude::ToResponse,
+        response::{Link, RdapResponse},
+    };
+
+    use super::{
+        AvailableSort, DomainSearchResults, FieldSet, PagingMetadata, SortingMetadata,
+        SubsettingMetadata,
+    };
+
+    #[test]
+    fn GIVEN_search_results_with_paging_metadata_WHEN_round_trip_THEN_metadata_is_kept() {
+        // GIVEN
+        let results = DomainSearchResults::builder()
+            .results(vec![])
+            .paging_metadata(
+                PagingMetadata::builder()
+                    .total_count(73)
+                    .page_size(50)
+                    .page_number(1)
+                    .link(
+                        Link::builder()
+                            .value("/rdap/domains?name=foo*")
+                            .rel("next")
+                            .href("/rdap/domains?name=foo*&cursor=32")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+
+        // WHEN
+        let json = serde_json::to_value(&results).expect("serializing");
+        let actual = RdapResponse::try_from(json.clone()).expect("deserializing");
+
+        // THEN
+        assert_eq!(json["paging_metadata"]["totalCount"], 73);
+        assert_eq!(json["paging_metadata"]["pageSize"], 50);
+        assert_eq!(json["paging_metadata"]["pageNumber"], 1);
+        assert_eq!(actual, results.to_response());
+        let RdapResponse::DomainSearchResults(actual) = actual else {
+            panic!("not domain search results")
+        };
+        let next = actual
+            .paging_metadata
+            .as_ref()
+            .and_then(|paging| paging.next_link())
+            .expect("no next link");
+        assert_eq!(
+            next.href.as_deref(),
+            Some("/rdap/domains?name=foo*&cursor=32")
+        );
+    }
+
+    #[test]
+    fn GIVEN_rfc8977_sorting_metadata_WHEN_deserialize_THEN_metadata_is_typed() {
+        // GIVEN
+        let json = r#"
+          {
+            "rdapConformance": ["rdap_level_0", "sorting"],
+            "domainSearchResults": [],
+            "sorting_metadata": {
+              "currentSort": "name",
+              "availableSorts": [
+                {
+                  "property": "registrationDate",
+                  "jsonPath": "$.domainSearchResults[*].events[?(@.eventAction==\"registration\")].eventDate",
+                  "default": false,
+                  "links": [
+                    {
+                      "value": "https://example.com/rdap/domains?name=example*.com&sort=name",
+                      "rel": "alternate",
+                      "href": "https://example.com/rdap/domains?name=example*.com&sort=registrationDate",
+                      "title": "Result Ascending Sort Link",
+                      "type": "application/rdap+json"
+                    }
+                  ]
+                }
+              ]
+            }
+          }
+        "#;
+
+        // WHEN
+        let actual = serde_json::from_str::<DomainSearchResults>(json).expect("deserializing");
+
+        // THEN
+        let sorting = actual.sorting_metadata.expect("no sorting metadata");
+        assert_eq!(sorting.current_sort.as_deref(), Some("name"));
+        let available = sorting.available_sorts();
+        assert_eq!(available.len(), 1);
+        assert_eq!(available[0].property.as_deref(), Some("registrationDate"));
+        assert!(!available[0].is_default());
+        assert_eq!(available[0].links.as_ref().map(|l| l.len()), Some(1));
+    }
+
+    #[test]
+    fn GIVEN_sorting_metadata_WHEN_serialize_THEN_rfc8977_names_used() {
+        // GIVEN
+        let sorting = SortingMetadata::builder()
+            .current_sort("name:d")
+            .available_sort(
+                AvailableSort::builder()
+                    .property("name")
+                    .json_path("$.domainSearchResults[*].ldhName")
+                    .default(true)
+                    .build(),
+            )
+            .build();
+
+        // WHEN
+        let actual = serde_json::to_value(sorting).expect("serializing");
+
+        // THEN
+        assert_eq!(actual["currentSort"], "name:d");
+        assert_eq!(
+            actual["availableSorts"][0]["jsonPath"],
+            "$.domainSearchResults[*].ldhName"
+        );
+        assert_eq!(actual["availableSorts"][0]["default"], true);
+    }
+
+    #[test]
+    fn GIVEN_search_results_with_subsetting_metadata_WHEN_round_trip_THEN_rfc8982_names_used() {
+        // GIVEN
+        let results = DomainSearchResults::builder()
+            .results(vec![])
+            .subsetting_metadata(
+                SubsettingMetadata::builder()
+                    .current_field_set("brief")
+                    .available_field_set(
+                        FieldSet::builder()
+                            .name("full")
+                            .default(true)
+                            .description("All the information the server can provide.")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+
+        // WHEN
+        let json = serde_json::to_value(&results).expect("serializing");
+        let actual = RdapResponse::try_from(json.clone()).expect("deserializing");
+
+        // THEN
+        assert_eq!(json["subsetting_metadata"]["currentFieldSet"], "brief");
+        assert_eq!(
+            json["subsetting_metadata"]["availableFieldSets"][0]["name"],
+            "full"
+        );
+        assert_eq!(actual, results.to_response());
+        let RdapResponse::DomainSearchResults(actual) = actual else {
+            panic!("not domain search results")
+        };
+        let subsetting = actual.subsetting_metadata.expect("no subsetting metadata");
+        assert!(subsetting.available_field_sets()[0].is_default());
+    }
+}
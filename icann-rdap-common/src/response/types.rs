@@ -69,6 +69,10 @@ pub enum ExtensionId {
     ArinOriginAs0,
     #[strum(serialize = "artRecord")]
     ArtRecord,
+    #[strum(serialize = "autnums")]
+    Autnums,
+    #[strum(serialize = "autnumSearchResults")]
+    AutnumSearchResults,
     #[strum(serialize = "cidr0")]
     Cidr0,
     #[strum(serialize = "farv1")]
@@ -83,6 +87,12 @@ pub enum ExtensionId {
     IcannRdapTechnicalImplementationGuide0,
     #[strum(serialize = "icann_rdap_technical_implementation_guide_1")]
     IcannRdapTechnicalImplementationGuide1,
+    #[strum(serialize = "ips")]
+    Ips,
+    #[strum(serialize = "ipSearchResults")]
+    IpSearchResults,
+    #[strum(serialize = "jscontact_level_0")]
+    JsContactLevel0,
     #[strum(serialize = "nro_rdap_profile_0")]
     NroRdapProfile0,
     #[strum(serialize = "nro_rdap_profile_asn_flat_0")]
@@ -103,6 +113,8 @@ pub enum ExtensionId {
     RegType,
     #[strum(serialize = "reverse_search")]
     ReverseSearch,
+    #[strum(serialize = "rirSearch1")]
+    RirSearch1,
     #[strum(serialize = "sorting")]
     Sorting,
     #[strum(serialize = "subsetting")]
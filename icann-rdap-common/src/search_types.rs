@@ -0,0 +1,37 @@
+//! Types of RDAP searches shared by clients and servers.
+
+use strum_macros::{Display, EnumIter, EnumString};
+
+/// The properties of a related entity used in a reverse search, as specified in RFC 9536.
+#[derive(Display, EnumString, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelatedEntityProperty {
+    #[strum(serialize = "fn")]
+    FullName,
+
+    #[strum(serialize = "handle")]
+    Handle,
+
+    #[strum(serialize = "email")]
+    Email,
+}
+
+/// The relations between IP networks used in searches, as specified in
+/// draft-ietf-regext-rdap-rir-search.
+#[derive(Display, EnumString, EnumIter, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RirSearchRelation {
+    /// The most specific network covering a network.
+    #[strum(serialize = "rdap-up")]
+    Up,
+
+    /// The networks directly under a network.
+    #[strum(serialize = "rdap-down")]
+    Down,
+
+    /// The least specific network covering a network.
+    #[strum(serialize = "rdap-top")]
+    Top,
+
+    /// The most specific networks under a network.
+    #[strum(serialize = "rdap-bottom")]
+    Bottom,
+}
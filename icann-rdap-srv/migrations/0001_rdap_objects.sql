@@ -0,0 +1,42 @@
+-- RDAP object storage.
+--
+-- Each table holds the RDAP JSON of the object (or of the RFC 9083 error used
+-- as a redirect) along with the columns used to index it.
+
+create table domain (
+    ldh_name text primary key,
+    unicode_name text,
+    rdap jsonb not null
+);
+
+create index domain_unicode_name_idx on domain (unicode_name);
+create index domain_ldh_name_pattern_idx on domain (ldh_name text_pattern_ops);
+
+create table entity (
+    handle text primary key,
+    rdap jsonb not null
+);
+
+create table nameserver (
+    ldh_name text primary key,
+    rdap jsonb not null
+);
+
+create table autnum (
+    start_autnum bigint not null,
+    end_autnum bigint not null,
+    rdap jsonb not null,
+    primary key (start_autnum, end_autnum)
+);
+
+create table network (
+    cidr cidr primary key,
+    rdap jsonb not null
+);
+
+create index network_cidr_idx on network using gist (cidr inet_ops);
+
+create table srvhelp (
+    host text primary key,
+    rdap jsonb not null
+);
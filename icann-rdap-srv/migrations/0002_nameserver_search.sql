@@ -0,0 +1,9 @@
+-- Index of nameserver IP addresses for nameserver searches by IP address.
+
+create index nameserver_ldh_name_pattern_idx on nameserver (ldh_name text_pattern_ops);
+
+create table nameserver_ip (
+    ldh_name text not null references nameserver (ldh_name) on delete cascade,
+    ip inet not null,
+    primary key (ip, ldh_name)
+);
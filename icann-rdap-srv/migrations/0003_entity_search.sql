@@ -0,0 +1,13 @@
+-- Full names of entities for entity searches by 'fn'.
+
+alter table entity add column full_name text;
+
+update entity set full_name = (
+    select card ->> 3
+    from jsonb_array_elements(rdap -> 'vcardArray' -> 1) as card
+    where card ->> 0 = 'fn'
+    limit 1
+);
+
+create index entity_handle_pattern_idx on entity (handle text_pattern_ops);
+create index entity_full_name_pattern_idx on entity (lower(full_name) text_pattern_ops);
@@ -0,0 +1,17 @@
+-- Index of the nameservers of domains for domain searches by nsLdhName and nsIp.
+--
+-- Domains stored before this migration are indexed when they are next loaded.
+
+create table domain_nameserver (
+    ldh_name text not null references domain (ldh_name) on delete cascade,
+    ns_ldh_name text not null,
+    primary key (ns_ldh_name, ldh_name)
+);
+
+create index domain_nameserver_ns_ldh_name_pattern_idx on domain_nameserver (ns_ldh_name text_pattern_ops);
+
+create table domain_nameserver_ip (
+    ldh_name text not null references domain (ldh_name) on delete cascade,
+    ip inet not null,
+    primary key (ip, ldh_name)
+);
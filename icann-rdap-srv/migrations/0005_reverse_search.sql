@@ -0,0 +1,16 @@
+-- Index of the related entity properties of objects for RFC 9536 reverse searches.
+--
+-- Objects stored before this migration are indexed when they are next loaded.
+
+create table reverse_search (
+    searchable_type text not null,
+    object_key text not null,
+    property text not null,
+    value text not null,
+    role text not null default '',
+    primary key (searchable_type, property, value, role, object_key)
+);
+
+create index reverse_search_value_pattern_idx on reverse_search (searchable_type, property, value text_pattern_ops);
+create index reverse_search_lower_value_pattern_idx on reverse_search (searchable_type, property, lower(value) text_pattern_ops);
+create index reverse_search_object_key_idx on reverse_search (searchable_type, object_key);
@@ -0,0 +1,9 @@
+-- Index of the variant names of domains for lookups by variant.
+--
+-- Domains stored before this migration are indexed when they are next loaded.
+
+create table domain_variant (
+    ldh_name text not null references domain (ldh_name) on delete cascade,
+    variant_name text not null,
+    primary key (variant_name, ldh_name)
+);
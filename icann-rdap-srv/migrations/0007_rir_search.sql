@@ -0,0 +1,6 @@
+-- Handles and names of IP networks and autnums for RIR searches.
+
+create index network_handle_pattern_idx on network ((rdap ->> 'handle') text_pattern_ops);
+create index network_name_pattern_idx on network (lower(rdap ->> 'name') text_pattern_ops);
+create index autnum_handle_pattern_idx on autnum ((rdap ->> 'handle') text_pattern_ops);
+create index autnum_name_pattern_idx on autnum (lower(rdap ->> 'name') text_pattern_ops);
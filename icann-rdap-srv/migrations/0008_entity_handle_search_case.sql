@@ -0,0 +1,4 @@
+-- Entity searches by handle ignore case, as do those by full name.
+
+drop index entity_handle_pattern_idx;
+create index entity_handle_pattern_idx on entity (lower(handle) text_pattern_ops);
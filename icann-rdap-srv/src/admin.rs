@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use {
+    axum::{
+        extract::{Path, Request, State},
+        middleware::{self, Next},
+        response::{IntoResponse, Response},
+        routing::{delete, post, put},
+        Json, Router,
+    },
+    headers::{authorization::Bearer, Authorization, HeaderMapExt},
+    http::StatusCode,
+    icann_rdap_common::{
+        check::{traverse_checks, CheckClass, CheckParams, GetChecks},
+        prelude::ToResponse,
+        response::{RdapResponse, Rfc9083Error},
+    },
+    ipnet::IpNet,
+    serde_json::Value,
+    tracing::{debug, info},
+};
+
+use crate::{
+    config::AdminConfig,
+    error::RdapServerError,
+    server::DynServiceState,
+    storage::{
+        data::{
+            add_rdap, add_template, template_objects, AutnumId, DomainId, EntityId, NameserverId,
+            NetworkId, NetworkIdType, Template,
+        },
+        TxHandle,
+    },
+    util::is_secret,
+};
+
+/// State of the admin API.
+#[derive(Clone)]
+struct AdminState {
+    service_state: DynServiceState,
+    config: Arc<AdminConfig>,
+}
+
+/// Creates the router of the admin API.
+///
+/// * `PUT /object` adds or replaces an RDAP object.
+/// * `POST /template` adds or replaces the objects of a [Template].
+/// * `DELETE /{object class}/{id}` deletes an object, or an error put in its place.
+///   The object classes are the path segments of lookups: `domain`, `entity`,
+///   `nameserver`, `autnum` (given as `{start}-{end}` or a single number), and
+///   `ip` (given as a CIDR).
+///
+/// Each request is applied in its own transaction, so either all of its
+/// changes are made or none of them are. Requests must give one of the
+/// API keys as a bearer token.
+pub(crate) fn admin_router(service_state: DynServiceState, config: AdminConfig) -> Router {
+    let state = AdminState {
+        service_state,
+        config: Arc::new(config),
+    };
+    Router::new()
+        .route("/object", put(put_object))
+        .route("/template", post(post_template))
+        .route("/:object_class/*id", delete(delete_object))
+        .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .with_state(state)
+}
+
+/// Middleware answering with a 401 unless a request has one of the API keys.
+async fn authenticate(State(state): State<AdminState>, request: Request, next: Next) -> Response {
+    let authenticated = request
+        .headers()
+        .typed_get::<Authorization<Bearer>>()
+        .is_some_and(|Authorization(bearer)| is_secret(&state.config.api_keys, bearer.token()));
+    if authenticated {
+        next.run(request).await
+    } else {
+        admin_error(StatusCode::UNAUTHORIZED, vec![])
+    }
+}
+
+async fn put_object(State(state): State<AdminState>, body: String) -> Response {
+    let rdap = match serde_json::from_str::<Value>(&body)
+        .map_err(|e| e.to_string())
+        .and_then(|value| RdapResponse::try_from(value).map_err(|e| e.to_string()))
+    {
+        Ok(rdap) => rdap,
+        Err(e) => return admin_error(StatusCode::BAD_REQUEST, vec![e]),
+    };
+    let problems = check_errors(&rdap);
+    if !problems.is_empty() {
+        return admin_error(StatusCode::BAD_REQUEST, problems);
+    }
+    apply(&state, Change::Object(rdap)).await
+}
+
+async fn post_template(State(state): State<AdminState>, body: String) -> Response {
+    let template = match serde_json::from_str::<Template>(&body) {
+        Ok(template) => template,
+        Err(e) => return admin_error(StatusCode::BAD_REQUEST, vec![e.to_string()]),
+    };
+    let mut problems = match template_objects(&template) {
+        Ok(objects) => objects.iter().flat_map(check_errors).collect::<Vec<_>>(),
+        Err(e) => vec![e.to_string()],
+    };
+    // the objects of a template share most of their problems.
+    problems.sort();
+    problems.dedup();
+    if !problems.is_empty() {
+        return admin_error(StatusCode::BAD_REQUEST, problems);
+    }
+    apply(&state, Change::Template(template)).await
+}
+
+async fn delete_object(
+    State(state): State<AdminState>,
+    Path((object_class, id)): Path<(String, String)>,
+) -> Response {
+    debug!("delete of {object_class} {id} requested");
+    let object_id = match object_id(&object_class, &id) {
+        Ok(object_id) => object_id,
+        Err(e) => return admin_error(StatusCode::BAD_REQUEST, vec![e]),
+    };
+    apply(&state, Change::Delete(object_id)).await
+}
+
+/// A change to storage requested of the admin API.
+enum Change {
+    Object(RdapResponse),
+    Template(Template),
+    Delete(ObjectId),
+}
+
+/// The ID of an object to delete.
+enum ObjectId {
+    Domain(DomainId),
+    Entity(EntityId),
+    Nameserver(NameserverId),
+    Autnum(AutnumId),
+    Network(NetworkId),
+}
+
+/// Parses the object class and ID of the path of a delete request.
+fn object_id(object_class: &str, id: &str) -> Result<ObjectId, String> {
+    match object_class {
+        "domain" => Ok(ObjectId::Domain(DomainId::builder().ldh_name(id).build())),
+        "entity" => Ok(ObjectId::Entity(EntityId::builder().handle(id).build())),
+        "nameserver" => Ok(ObjectId::Nameserver(
+            NameserverId::builder().ldh_name(id).build(),
+        )),
+        "autnum" => {
+            let (start, end) = id.split_once('-').unwrap_or((id, id));
+            let parse = |num: &str| {
+                num.parse::<u32>()
+                    .map_err(|_| format!("{id} is not an autnum range"))
+            };
+            Ok(ObjectId::Autnum(
+                AutnumId::builder()
+                    .start_autnum(parse(start)?)
+                    .end_autnum(parse(end)?)
+                    .build(),
+            ))
+        }
+        "ip" => {
+            let cidr = id
+                .parse::<IpNet>()
+                .map_err(|_| format!("{id} is not a CIDR"))?;
+            Ok(ObjectId::Network(
+                NetworkId::builder()
+                    .network_id(NetworkIdType::Cidr(cidr))
+                    .build(),
+            ))
+        }
+        _ => Err(format!("{object_class} objects cannot be deleted")),
+    }
+}
+
+/// Applies a change to storage in a transaction, which is committed if the
+/// change is made and rolled back otherwise.
+async fn apply(state: &AdminState, change: Change) -> Response {
+    let result = async {
+        let storage = state.service_state.get_storage().await?;
+        let mut tx = storage.new_tx().await?;
+        match make_change(change, &mut tx).await {
+            Ok(found) => {
+                tx.commit().await?;
+                state
+                    .service_state
+                    .get_metrics()
+                    .count_objects(storage)
+                    .await;
+                Ok(found)
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                Err(e)
+            }
+        }
+    }
+    .await;
+    match result {
+        Ok(true) => {
+            info!("admin changes committed");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => admin_error(StatusCode::NOT_FOUND, vec![]),
+        Err(e @ (RdapServerError::SqlDb(_) | RdapServerError::IO(_))) => {
+            admin_error(StatusCode::INTERNAL_SERVER_ERROR, vec![e.to_string()])
+        }
+        Err(e) => admin_error(StatusCode::BAD_REQUEST, vec![e.to_string()]),
+    }
+}
+
+/// Makes a change in a transaction, returning false if there was no object to delete.
+async fn make_change(change: Change, tx: &mut Box<dyn TxHandle>) -> Result<bool, RdapServerError> {
+    match change {
+        Change::Object(rdap) => {
+            if !add_rdap(&rdap, tx).await? {
+                return Err(RdapServerError::InvalidArg(
+                    "only domains, entities, nameservers, autnums, and ip networks can be put"
+                        .to_string(),
+                ));
+            }
+            Ok(true)
+        }
+        Change::Template(template) => add_template(template, tx).await.map(|_| true),
+        Change::Delete(ObjectId::Domain(id)) => tx.delete_domain(&id).await,
+        Change::Delete(ObjectId::Entity(id)) => tx.delete_entity(&id).await,
+        Change::Delete(ObjectId::Nameserver(id)) => tx.delete_nameserver(&id).await,
+        Change::Delete(ObjectId::Autnum(id)) => tx.delete_autnum(&id).await,
+        Change::Delete(ObjectId::Network(id)) => tx.delete_network(&id).await,
+    }
+}
+
+/// Gets the specification errors of an RDAP object.
+fn check_errors(rdap: &RdapResponse) -> Vec<String> {
+    let checks = rdap.get_checks(CheckParams {
+        do_subchecks: true,
+        root: rdap,
+        parent_type: rdap.get_type(),
+        allow_unreg_ext: true,
+    });
+    let mut problems = vec![];
+    traverse_checks(
+        &checks,
+        &[CheckClass::StdError],
+        None,
+        &mut |struct_tree, check_item| problems.push(format!("{struct_tree} -> {check_item}")),
+    );
+    problems
+}
+
+fn admin_error(status: StatusCode, description: Vec<String>) -> Response {
+    let mut error = Rfc9083Error::builder().error_code(status.as_u16()).build();
+    error.description = (!description.is_empty()).then_some(description);
+    (status, Json(error.to_response())).into_response()
+}
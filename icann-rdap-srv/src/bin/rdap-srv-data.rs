@@ -14,7 +14,8 @@ use {
         VERSION,
     },
     icann_rdap_srv::{
-        config::{debug_config_vars, ServiceConfig, LOG},
+        config::{debug_config_vars, log_filter, ServiceConfig},
+        config_file::ConfigFile,
         error::RdapServerError,
         storage::{
             data::{
@@ -32,7 +33,7 @@ use {
     std::{fs, path::PathBuf, str::FromStr},
     tracing::{error, info},
     tracing_subscriber::{
-        fmt, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, EnvFilter,
+        fmt, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt,
     },
 };
 
@@ -44,8 +45,10 @@ struct Cli {
     check_args: CheckArgs,
 
     /// Specifies the directory where data will be written.
+    ///
+    /// If not given, the data directory of the configuration file is used.
     #[arg(long, env = "RDAP_SRV_DATA_DIR")]
-    data_dir: String,
+    data_dir: Option<String>,
 
     /// Output data as a redirect.
     ///
@@ -483,15 +486,22 @@ fn parse_cidr(arg: &str) -> Result<IpCidr, RdapServerError> {
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<(), RdapServerError> {
     dotenv::dotenv().ok();
+    let config_file = ConfigFile::from_env()?;
     let cli = Cli::parse();
     tracing_subscriber::registry()
         .with(fmt::layer())
-        .with(EnvFilter::from_env(LOG))
+        .with(log_filter(&config_file))
         .init();
 
     debug_config_vars();
 
-    let data_dir = cli.data_dir.clone();
+    let data_dir = cli
+        .data_dir
+        .clone()
+        .or(config_file.data_dir)
+        .ok_or_else(|| {
+            RdapServerError::InvalidArg("--data-dir or RDAP_SRV_DATA_DIR must be given".to_string())
+        })?;
     let config = ServiceConfig::non_server().data_dir(&data_dir).build()?;
     let storage = Mem::new(
         MemConfig::builder()
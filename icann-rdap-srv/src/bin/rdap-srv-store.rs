@@ -9,7 +9,8 @@ use {
         VERSION,
     },
     icann_rdap_srv::{
-        config::{data_dir, debug_config_vars, LOG},
+        config::{data_dir, debug_config_vars, log_filter},
+        config_file::ConfigFile,
         error::RdapServerError,
         storage::data::{
             trigger_reload, trigger_update, AutnumOrError, DomainOrError, EntityOrError,
@@ -21,7 +22,7 @@ use {
     serde_json::Value,
     tracing::{debug, error, warn},
     tracing_subscriber::{
-        fmt, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, EnvFilter,
+        fmt, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt,
     },
 };
 
@@ -53,17 +54,18 @@ struct Cli {
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<(), RdapServerError> {
     dotenv::dotenv().ok();
+    let config_file = ConfigFile::from_env()?;
     let cli = Cli::parse();
     tracing_subscriber::registry()
         .with(fmt::layer())
-        .with(EnvFilter::from_env(LOG))
+        .with(log_filter(&config_file))
         .init();
 
     debug_config_vars();
 
     let check_types = to_check_classes(&cli.check_args);
 
-    let data_dir = data_dir();
+    let data_dir = data_dir(&config_file);
 
     if let Some(directory) = cli.directory {
         if directory == data_dir {
@@ -189,6 +191,7 @@ fn verify_rdap_template(
                         DomainOrError::ErrorResponse(error) => {
                             errors_found |= check_rdap(error.clone().to_response(), check_types);
                         }
+                        DomainOrError::Delete => {}
                     };
                 }
             }
@@ -204,6 +207,7 @@ fn verify_rdap_template(
                         EntityOrError::ErrorResponse(error) => {
                             errors_found |= check_rdap(error.clone().to_response(), check_types);
                         }
+                        EntityOrError::Delete => {}
                     };
                 }
             }
@@ -222,6 +226,7 @@ fn verify_rdap_template(
                         NameserverOrError::ErrorResponse(error) => {
                             errors_found |= check_rdap(error.clone().to_response(), check_types);
                         }
+                        NameserverOrError::Delete => {}
                     };
                 }
             }
@@ -238,6 +243,7 @@ fn verify_rdap_template(
                         AutnumOrError::ErrorResponse(error) => {
                             errors_found |= check_rdap(error.clone().to_response(), check_types);
                         }
+                        AutnumOrError::Delete => {}
                     };
                 }
             }
@@ -279,6 +285,7 @@ fn verify_rdap_template(
                         NetworkOrError::ErrorResponse(error) => {
                             errors_found |= check_rdap(error.clone().to_response(), check_types);
                         }
+                        NetworkOrError::Delete => {}
                     };
                 }
             }
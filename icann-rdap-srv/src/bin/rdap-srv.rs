@@ -1,52 +1,73 @@
 use {
-    envmnt::{get_or, get_parse_or, get_u16},
+    clap::Parser,
+    icann_rdap_common::VERSION,
     icann_rdap_srv::{
-        config::{
-            data_dir, debug_config_vars, ListenConfig, ServiceConfig, StorageType, AUTO_RELOAD,
-            BOOTSTRAP, LISTEN_ADDR, LISTEN_PORT, LOG, UPDATE_ON_BOOTSTRAP,
-        },
+        config::{debug_config_vars, log_filter, ListenConfig, ServiceConfig, CONFIG},
+        config_file::ConfigFile,
         error::RdapServerError,
         server::Listener,
+        socket::inherit_sockets,
+        tls::check_cert,
     },
     tracing_subscriber::{
-        fmt, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, EnvFilter,
+        fmt, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt,
     },
 };
 
-#[tokio::main(flavor = "multi_thread")]
-async fn main() -> Result<(), RdapServerError> {
+#[derive(Parser, Debug)]
+#[command(author, version = VERSION, about, long_about)]
+/// This program serves RDAP.
+///
+/// It is configured with RDAP_SRV_* environment variables, which may also be
+/// given in a .env file or a TOML configuration file.
+struct Cli {
+    /// TOML configuration file.
+    ///
+    /// Environment variables override the values in the file.
+    #[arg(long, env = CONFIG)]
+    config: Option<String>,
+
+    /// Check the configuration and exit.
+    ///
+    /// If given, the configuration is validated, the TLS certificate and the
+    /// redaction policy are read, and the server is not started.
+    #[arg(long, required = false)]
+    check_config: bool,
+}
+
+fn main() -> Result<(), RdapServerError> {
     dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    let config_file = match &cli.config {
+        Some(config) => ConfigFile::from_file(config)?,
+        None => ConfigFile::default(),
+    };
     tracing_subscriber::registry()
         .with(fmt::layer())
-        .with(EnvFilter::from_env(LOG))
+        .with(log_filter(&config_file))
         .init();
 
     debug_config_vars();
 
-    let listen_addr = get_or(LISTEN_ADDR, "127.0.0.1");
-    let listen_port = get_u16(LISTEN_PORT, 3000);
-    let storage_type = StorageType::new_from_env()?;
-    let auto_reload: bool = get_parse_or(AUTO_RELOAD, true)?;
-    let bootstrap: bool = get_parse_or(BOOTSTRAP, false)?;
-    let update_on_bootstrap: bool = get_parse_or(UPDATE_ON_BOOTSTRAP, false)?;
+    let listen_config = ListenConfig::new_from_env(&config_file)?;
+    let service_config = ServiceConfig::new_from_env(&config_file)?;
+
+    if cli.check_config {
+        if let Some(tls) = &listen_config.tls {
+            check_cert(tls)?;
+        }
+        println!("configuration is valid");
+        return Ok(());
+    }
 
-    let listener = Listener::listen(
-        &ListenConfig::builder()
-            .ip_addr(listen_addr)
-            .port(listen_port)
-            .build(),
-    )
-    .await?;
-    listener
-        .start_server(
-            &ServiceConfig::builder()
-                .storage_type(storage_type)
-                .data_dir(data_dir())
-                .auto_reload(auto_reload)
-                .bootstrap(bootstrap)
-                .update_on_bootstrap(update_on_bootstrap)
-                .build(),
-        )
-        .await?;
-    Ok(())
+    // the runtime is started after the inherited sockets are taken, as that
+    // removes environment variables.
+    inherit_sockets();
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(async {
+            let listener = Listener::listen(&listen_config).await?;
+            listener.start_server(&service_config).await
+        })
 }
@@ -0,0 +1,115 @@
+use std::time::{Duration, SystemTime};
+
+use {
+    axum::{
+        extract::{Request, State},
+        middleware::Next,
+        response::Response,
+    },
+    headers::{CacheControl, Expires, HeaderMapExt},
+    http::{
+        header::{AUTHORIZATION, VARY},
+        HeaderValue, StatusCode,
+    },
+};
+
+use crate::{config::CacheConfig, rdap::first_rdap_segment, server::DynServiceState};
+
+/// Gets the cache lifetime of a response by the path of its request and its status.
+fn max_age(config: &CacheConfig, path: &str, status: StatusCode) -> Option<u32> {
+    if status.is_redirection() && status != StatusCode::NOT_MODIFIED {
+        return config.redirect;
+    }
+    if status.is_client_error() || status.is_server_error() {
+        return config.error;
+    }
+    match first_rdap_segment(path) {
+        "domain" => config.domain,
+        "nameserver" => config.nameserver,
+        "entity" => config.entity,
+        "autnum" => config.autnum,
+        "ip" => config.ip,
+        "domains" | "nameservers" | "entities" | "ips" | "autnums" => config.search,
+        "help" => config.help,
+        _ => None,
+    }
+}
+
+/// Middleware setting the `Cache-Control` and `Expires` headers of responses.
+///
+/// When responses are redacted for anonymous callers, they also vary by
+/// the `Authorization` header.
+pub(crate) async fn cache_headers(
+    State(state): State<DynServiceState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_owned();
+    let mut response = next.run(request).await;
+    let max_age = max_age(state.get_cache(), &path, response.status());
+    let headers = response.headers_mut();
+    if let Some(max_age) = max_age {
+        let cache_control = if max_age == 0 {
+            CacheControl::new().with_no_store()
+        } else {
+            CacheControl::new().with_max_age(Duration::from_secs(max_age.into()))
+        };
+        headers.typed_insert(cache_control);
+        headers.typed_insert(Expires::from(
+            SystemTime::now() + Duration::from_secs(max_age.into()),
+        ));
+    }
+    if state.get_redaction().policy.is_some() {
+        headers.append(VARY, HeaderValue::from_static(AUTHORIZATION.as_str()));
+    }
+    response
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {http::StatusCode, rstest::rstest};
+
+    use crate::config::CacheConfig;
+
+    use super::max_age;
+
+    #[rstest]
+    #[case("/rdap/domain/foo.example", StatusCode::OK, Some(1))]
+    #[case("/example/rdap/domain/foo.example", StatusCode::OK, Some(1))]
+    #[case("/rdap/ip/192.0.2.0/24", StatusCode::OK, Some(5))]
+    #[case("/rdap/domains", StatusCode::OK, Some(6))]
+    #[case("/rdap/entities/reverse_search/entity", StatusCode::OK, Some(6))]
+    #[case("/rdap/ips", StatusCode::OK, Some(6))]
+    #[case("/rdap/autnums", StatusCode::OK, Some(6))]
+    #[case("/rdap/help", StatusCode::OK, Some(7))]
+    #[case("/rdap/domain/foo.example", StatusCode::NOT_MODIFIED, Some(1))]
+    #[case("/rdap/domain/foo.example", StatusCode::TEMPORARY_REDIRECT, Some(8))]
+    #[case("/rdap/domain/foo.example", StatusCode::NOT_FOUND, Some(9))]
+    #[case("/rdap/domains", StatusCode::NOT_IMPLEMENTED, Some(9))]
+    #[case("/rdap/foo", StatusCode::OK, None)]
+    fn GIVEN_path_and_status_WHEN_max_age_THEN_max_age_of_class(
+        #[case] path: &str,
+        #[case] status: StatusCode,
+        #[case] expected: Option<u32>,
+    ) {
+        // GIVEN
+        let config = CacheConfig::builder()
+            .domain(1)
+            .nameserver(2)
+            .entity(3)
+            .autnum(4)
+            .ip(5)
+            .search(6)
+            .help(7)
+            .redirect(8)
+            .error(9)
+            .build();
+
+        // WHEN
+        let actual = max_age(&config, path, status);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+}
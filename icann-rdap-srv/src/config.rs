@@ -1,13 +1,22 @@
+use std::{collections::HashSet, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
 use {
+    axum_client_ip::SecureClientIpSource,
     buildstructor::Builder,
     envmnt::{get_or, get_parse_or},
-    strum_macros::Display,
+    serde::Deserialize,
+    strum_macros::{Display, EnumString},
     tracing::debug,
+    tracing_subscriber::{filter::LevelFilter, EnvFilter},
 };
 
 use crate::{
+    config_file::{ConfigFile, Tenants},
     error::RdapServerError,
-    storage::{mem::config::MemConfig, pg::config::PgConfig, CommonConfig},
+    rdap::redaction::RedactionPolicy,
+    storage::{
+        mem::config::MemConfig, pg::config::PgConfig, CommonConfig, DEFAULT_SEARCH_PAGE_SIZE,
+    },
 };
 
 pub const LOG: &str = "RDAP_SRV_LOG";
@@ -16,10 +25,48 @@ pub const LISTEN_PORT: &str = "RDAP_SRV_LISTEN_PORT";
 pub const STORAGE: &str = "RDAP_SRV_STORAGE";
 pub const DB_URL: &str = "RDAP_SRV_DB_URL";
 pub const DATA_DIR: &str = "RDAP_SRV_DATA_DIR";
+pub const MEM_SNAPSHOT_FILE: &str = "RDAP_SRV_MEM_SNAPSHOT_FILE";
 pub const AUTO_RELOAD: &str = "RDAP_SRV_AUTO_RELOAD";
 pub const BOOTSTRAP: &str = "RDAP_SRV_BOOTSTRAP";
 pub const UPDATE_ON_BOOTSTRAP: &str = "RDAP_SRV_UPDATE_ON_BOOTSTRAP";
 pub const DOMAIN_SEARCH_BY_NAME_ENABLE: &str = "RDAP_SRV_DOMAIN_SEARCH_BY_NAME";
+pub const DOMAIN_SEARCH_BY_NS_LDH_NAME_ENABLE: &str = "RDAP_SRV_DOMAIN_SEARCH_BY_NS_LDH_NAME";
+pub const DOMAIN_SEARCH_BY_NS_IP_ENABLE: &str = "RDAP_SRV_DOMAIN_SEARCH_BY_NS_IP";
+pub const NAMESERVER_SEARCH_BY_NAME_ENABLE: &str = "RDAP_SRV_NAMESERVER_SEARCH_BY_NAME";
+pub const NAMESERVER_SEARCH_BY_IP_ENABLE: &str = "RDAP_SRV_NAMESERVER_SEARCH_BY_IP";
+pub const ENTITY_SEARCH_BY_HANDLE_ENABLE: &str = "RDAP_SRV_ENTITY_SEARCH_BY_HANDLE";
+pub const ENTITY_SEARCH_BY_FULL_NAME_ENABLE: &str = "RDAP_SRV_ENTITY_SEARCH_BY_FULL_NAME";
+pub const REVERSE_SEARCH_ENABLE: &str = "RDAP_SRV_REVERSE_SEARCH";
+pub const RIR_SEARCH_ENABLE: &str = "RDAP_SRV_RIR_SEARCH";
+pub const SEARCH_PAGE_SIZE: &str = "RDAP_SRV_SEARCH_PAGE_SIZE";
+pub const CLIENT_IP_SOURCE: &str = "RDAP_SRV_CLIENT_IP_SOURCE";
+pub const LOOKUP_RATE_LIMIT: &str = "RDAP_SRV_LOOKUP_RATE_LIMIT";
+pub const LOOKUP_RATE_BURST: &str = "RDAP_SRV_LOOKUP_RATE_BURST";
+pub const SEARCH_RATE_LIMIT: &str = "RDAP_SRV_SEARCH_RATE_LIMIT";
+pub const SEARCH_RATE_BURST: &str = "RDAP_SRV_SEARCH_RATE_BURST";
+pub const REDACTION_POLICY: &str = "RDAP_SRV_REDACTION_POLICY";
+pub const API_KEYS: &str = "RDAP_SRV_API_KEYS";
+pub const BASIC_AUTH: &str = "RDAP_SRV_BASIC_AUTH";
+pub const TLS_CERT_FILE: &str = "RDAP_SRV_TLS_CERT_FILE";
+pub const TLS_KEY_FILE: &str = "RDAP_SRV_TLS_KEY_FILE";
+pub const TLS_LISTEN_PORT: &str = "RDAP_SRV_TLS_LISTEN_PORT";
+pub const HTTP_WITH_TLS: &str = "RDAP_SRV_HTTP_WITH_TLS";
+pub const CACHE_MAX_AGE_DOMAIN: &str = "RDAP_SRV_CACHE_MAX_AGE_DOMAIN";
+pub const CACHE_MAX_AGE_NAMESERVER: &str = "RDAP_SRV_CACHE_MAX_AGE_NAMESERVER";
+pub const CACHE_MAX_AGE_ENTITY: &str = "RDAP_SRV_CACHE_MAX_AGE_ENTITY";
+pub const CACHE_MAX_AGE_AUTNUM: &str = "RDAP_SRV_CACHE_MAX_AGE_AUTNUM";
+pub const CACHE_MAX_AGE_IP: &str = "RDAP_SRV_CACHE_MAX_AGE_IP";
+pub const CACHE_MAX_AGE_SEARCH: &str = "RDAP_SRV_CACHE_MAX_AGE_SEARCH";
+pub const CACHE_MAX_AGE_HELP: &str = "RDAP_SRV_CACHE_MAX_AGE_HELP";
+pub const CACHE_MAX_AGE_REDIRECT: &str = "RDAP_SRV_CACHE_MAX_AGE_REDIRECT";
+pub const CACHE_MAX_AGE_ERROR: &str = "RDAP_SRV_CACHE_MAX_AGE_ERROR";
+pub const CONTACT_FORMAT: &str = "RDAP_SRV_CONTACT_FORMAT";
+pub const METRICS_PORT: &str = "RDAP_SRV_METRICS_PORT";
+pub const ADMIN_PORT: &str = "RDAP_SRV_ADMIN_PORT";
+pub const ADMIN_API_KEYS: &str = "RDAP_SRV_ADMIN_API_KEYS";
+pub const TENANTS: &str = "RDAP_SRV_TENANTS";
+pub const CONFIG: &str = "RDAP_SRV_CONFIG";
+pub const SHUTDOWN_TIMEOUT: &str = "RDAP_SRV_SHUTDOWN_TIMEOUT";
 
 pub fn debug_config_vars() {
     let var_list = [
@@ -29,10 +76,45 @@ pub fn debug_config_vars() {
         STORAGE,
         DB_URL,
         DATA_DIR,
+        MEM_SNAPSHOT_FILE,
         AUTO_RELOAD,
         BOOTSTRAP,
         UPDATE_ON_BOOTSTRAP,
         DOMAIN_SEARCH_BY_NAME_ENABLE,
+        DOMAIN_SEARCH_BY_NS_LDH_NAME_ENABLE,
+        DOMAIN_SEARCH_BY_NS_IP_ENABLE,
+        NAMESERVER_SEARCH_BY_NAME_ENABLE,
+        NAMESERVER_SEARCH_BY_IP_ENABLE,
+        ENTITY_SEARCH_BY_HANDLE_ENABLE,
+        ENTITY_SEARCH_BY_FULL_NAME_ENABLE,
+        REVERSE_SEARCH_ENABLE,
+        RIR_SEARCH_ENABLE,
+        SEARCH_PAGE_SIZE,
+        CLIENT_IP_SOURCE,
+        LOOKUP_RATE_LIMIT,
+        LOOKUP_RATE_BURST,
+        SEARCH_RATE_LIMIT,
+        SEARCH_RATE_BURST,
+        REDACTION_POLICY,
+        CACHE_MAX_AGE_DOMAIN,
+        CACHE_MAX_AGE_NAMESERVER,
+        CACHE_MAX_AGE_ENTITY,
+        CACHE_MAX_AGE_AUTNUM,
+        CACHE_MAX_AGE_IP,
+        CACHE_MAX_AGE_SEARCH,
+        CACHE_MAX_AGE_HELP,
+        CACHE_MAX_AGE_REDIRECT,
+        CACHE_MAX_AGE_ERROR,
+        CONTACT_FORMAT,
+        TLS_CERT_FILE,
+        TLS_KEY_FILE,
+        TLS_LISTEN_PORT,
+        HTTP_WITH_TLS,
+        METRICS_PORT,
+        ADMIN_PORT,
+        TENANTS,
+        CONFIG,
+        SHUTDOWN_TIMEOUT,
     ];
     envmnt::vars()
         .iter()
@@ -40,8 +122,20 @@ pub fn debug_config_vars() {
         .for_each(|(k, v)| debug!("environment variable {k} = {v}"));
 }
 
-pub fn data_dir() -> String {
-    get_or(DATA_DIR, "/tmp/rdap-srv/data")
+/// Gets the data directory of a configuration file, overridden by the environment.
+pub fn data_dir(file: &ConfigFile) -> String {
+    get_or(
+        DATA_DIR,
+        file.data_dir.as_deref().unwrap_or("/tmp/rdap-srv/data"),
+    )
+}
+
+/// Gets the log filter of a configuration file, overridden by the environment.
+/// Logs are filtered to errors when neither gives a filter.
+pub fn log_filter(file: &ConfigFile) -> EnvFilter {
+    EnvFilter::builder()
+        .with_default_directive(LevelFilter::ERROR.into())
+        .parse_lossy(get_or(LOG, file.log.as_deref().unwrap_or_default()))
 }
 
 /// RDAP server listening configuration.
@@ -51,9 +145,131 @@ pub struct ListenConfig {
     /// If unspecified, the server will bind all interfaces.
     pub ip_addr: Option<String>,
 
-    /// If specified, determines the port number the server will bind to.
+    /// If specified, determines the port number the server will bind to for plain HTTP.
+    /// If unspecified, the server let's the OS determine the port.
+    pub port: Option<u16>,
+
+    /// If specified, the server terminates TLS.
+    pub tls: Option<TlsConfig>,
+
+    /// If specified, Prometheus metrics are served at `/metrics` on this port.
+    /// A port of 0 lets the OS determine the port.
+    pub metrics_port: Option<u16>,
+
+    /// If specified, the admin API is served.
+    pub admin: Option<AdminConfig>,
+
+    /// If specified, the time requests in flight are given to finish when the
+    /// server is shut down. If unspecified, they are given 30 seconds.
+    pub shutdown_timeout: Option<Duration>,
+}
+
+impl ListenConfig {
+    /// Gets the listening configuration of a configuration file, overridden by
+    /// the environment.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Self, RdapServerError> {
+        let listen = &file.listen;
+        let metrics_port = match get_or(METRICS_PORT, "").as_str() {
+            "" => listen.metrics_port,
+            port => Some(port.parse::<u16>()?),
+        };
+        let shutdown_timeout: u64 =
+            get_parse_or(SHUTDOWN_TIMEOUT, listen.shutdown_timeout.unwrap_or(30))?;
+        Ok(Self {
+            ip_addr: Some(get_or(
+                LISTEN_ADDR,
+                listen.ip_addr.as_deref().unwrap_or("127.0.0.1"),
+            )),
+            port: Some(get_parse_or(LISTEN_PORT, listen.port.unwrap_or(3000))?),
+            tls: TlsConfig::new_from_env(file)?,
+            metrics_port,
+            admin: AdminConfig::new_from_env(file)?,
+            shutdown_timeout: Some(Duration::from_secs(shutdown_timeout)),
+        })
+    }
+}
+
+/// The admin API, which changes the objects in storage.
+///
+/// The API is served over TLS when the server has TLS configured. Otherwise it
+/// is served over plain HTTP on the loopback address only.
+#[derive(Debug, Builder, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdminConfig {
+    /// The port number the admin API is served on. A port of 0 lets the OS
+    /// determine the port.
+    pub port: u16,
+
+    /// The API keys of callers allowed to use the admin API, given as bearer tokens.
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+}
+
+impl AdminConfig {
+    /// Gets the admin API configuration of a configuration file, overridden by
+    /// the environment, which is none if no port is given.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Option<Self>, RdapServerError> {
+        let admin = file.listen.admin.as_ref();
+        let port = match (get_or(ADMIN_PORT, "").as_str(), admin) {
+            ("", None) => return Ok(None),
+            ("", Some(admin)) => admin.port,
+            (port, _) => port.parse::<u16>()?,
+        };
+        let api_keys = get_list_or(ADMIN_API_KEYS, admin.map(|admin| &admin.api_keys));
+        if api_keys.is_empty() {
+            return Err(RdapServerError::Config(format!(
+                "{ADMIN_API_KEYS} must be given when {ADMIN_PORT} is given"
+            )));
+        }
+        Ok(Some(Self { port, api_keys }))
+    }
+}
+
+/// TLS termination of the server.
+///
+/// The certificate and key files are watched for changes, so renewed
+/// certificates are picked up without restarting the server.
+#[derive(Debug, Builder, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// The PEM file of the certificate chain, starting with the certificate of the server.
+    pub cert_file: String,
+
+    /// The PEM file of the private key of the certificate.
+    pub key_file: String,
+
+    /// If specified, determines the port number HTTPS is served on.
     /// If unspecified, the server let's the OS determine the port.
     pub port: Option<u16>,
+
+    /// If true, plain HTTP is also served on the port of the [ListenConfig].
+    #[serde(default)]
+    pub with_http: bool,
+}
+
+impl TlsConfig {
+    /// Gets the TLS configuration of a configuration file, overridden by the
+    /// environment, which is none if neither a certificate nor a key is given.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Option<Self>, RdapServerError> {
+        let tls = file.listen.tls.as_ref();
+        let cert_file = get_or(TLS_CERT_FILE, tls.map_or("", |tls| &tls.cert_file));
+        let key_file = get_or(TLS_KEY_FILE, tls.map_or("", |tls| &tls.key_file));
+        match (cert_file.is_empty(), key_file.is_empty()) {
+            (true, true) => Ok(None),
+            (false, false) => Ok(Some(Self {
+                cert_file,
+                key_file,
+                port: Some(get_parse_or(
+                    TLS_LISTEN_PORT,
+                    tls.and_then(|tls| tls.port).unwrap_or(3443),
+                )?),
+                with_http: get_parse_or(HTTP_WITH_TLS, tls.is_some_and(|tls| tls.with_http))?,
+            })),
+            _ => Err(RdapServerError::Config(format!(
+                "{TLS_CERT_FILE} and {TLS_KEY_FILE} must be given together"
+            ))),
+        }
+    }
 }
 
 /// Determines the storage type.
@@ -67,45 +283,584 @@ pub enum StorageType {
     Postgres(PgConfig),
 }
 
+/// The kinds of storage, which name the [StorageType] in configuration.
+#[derive(Debug, Display, EnumString, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    /// In-memory storage.
+    #[default]
+    Memory,
+
+    /// PostgreSQL storage.
+    Postgres,
+}
+
 impl StorageType {
-    pub fn new_from_env() -> Result<Self, RdapServerError> {
-        let domain_search_by_name = get_parse_or(DOMAIN_SEARCH_BY_NAME_ENABLE, false)?;
+    /// Gets the storage type of a configuration file, overridden by the environment.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Self, RdapServerError> {
+        let search = &file.search;
+        let enabled = |key: &str, value: Option<bool>| get_parse_or(key, value.unwrap_or(false));
+        let domain_search_by_name =
+            enabled(DOMAIN_SEARCH_BY_NAME_ENABLE, search.domain_search_by_name)?;
+        let domain_search_by_ns_ldh_name = enabled(
+            DOMAIN_SEARCH_BY_NS_LDH_NAME_ENABLE,
+            search.domain_search_by_ns_ldh_name,
+        )?;
+        let domain_search_by_ns_ip =
+            enabled(DOMAIN_SEARCH_BY_NS_IP_ENABLE, search.domain_search_by_ns_ip)?;
+        let nameserver_search_by_name = enabled(
+            NAMESERVER_SEARCH_BY_NAME_ENABLE,
+            search.nameserver_search_by_name,
+        )?;
+        let nameserver_search_by_ip = enabled(
+            NAMESERVER_SEARCH_BY_IP_ENABLE,
+            search.nameserver_search_by_ip,
+        )?;
+        let entity_search_by_handle = enabled(
+            ENTITY_SEARCH_BY_HANDLE_ENABLE,
+            search.entity_search_by_handle,
+        )?;
+        let entity_search_by_full_name = enabled(
+            ENTITY_SEARCH_BY_FULL_NAME_ENABLE,
+            search.entity_search_by_full_name,
+        )?;
+        let reverse_search = enabled(REVERSE_SEARCH_ENABLE, search.reverse_search)?;
+        let rir_search = enabled(RIR_SEARCH_ENABLE, search.rir_search)?;
+        let search_page_size = get_parse_or(
+            SEARCH_PAGE_SIZE,
+            search.page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE),
+        )?;
+        if search_page_size == 0 {
+            return Err(RdapServerError::Config(format!(
+                "{SEARCH_PAGE_SIZE} must be greater than zero"
+            )));
+        }
         let common_config = CommonConfig::builder()
             .domain_search_by_name_enable(domain_search_by_name)
+            .domain_search_by_ns_ldh_name_enable(domain_search_by_ns_ldh_name)
+            .domain_search_by_ns_ip_enable(domain_search_by_ns_ip)
+            .nameserver_search_by_name_enable(nameserver_search_by_name)
+            .nameserver_search_by_ip_enable(nameserver_search_by_ip)
+            .entity_search_by_handle_enable(entity_search_by_handle)
+            .entity_search_by_full_name_enable(entity_search_by_full_name)
+            .reverse_search_enable(reverse_search)
+            .rir_search_enable(rir_search)
+            .search_page_size(search_page_size)
             .build();
-        let storage = get_or(STORAGE, "memory");
-        if storage == "memory" {
-            Ok(Self::Memory(
-                MemConfig::builder().common_config(common_config).build(),
-            ))
-        } else if storage == "postgres" {
-            let db_url = get_or(DB_URL, "postgresql://127.0.0.1/rdap");
-            Ok(Self::Postgres(
-                PgConfig::builder()
-                    .db_url(db_url)
-                    .common_config(common_config)
-                    .build(),
+        let storage = &file.storage;
+        let kind = match get_or(STORAGE, "").as_str() {
+            "" => storage.storage_type.unwrap_or_default(),
+            kind => StorageKind::from_str(kind).map_err(|_| {
+                RdapServerError::Config(format!("storage type of '{kind}' is invalid"))
+            })?,
+        };
+        match kind {
+            StorageKind::Memory => {
+                let snapshot_file = get_or(
+                    MEM_SNAPSHOT_FILE,
+                    storage.snapshot_file.as_deref().unwrap_or_default(),
+                );
+                Ok(Self::Memory(
+                    MemConfig::builder()
+                        .common_config(common_config)
+                        .and_snapshot_file((!snapshot_file.is_empty()).then_some(snapshot_file))
+                        .build(),
+                ))
+            }
+            StorageKind::Postgres => {
+                let db_url = get_or(
+                    DB_URL,
+                    storage
+                        .db_url
+                        .as_deref()
+                        .unwrap_or("postgresql://127.0.0.1/rdap"),
+                );
+                Ok(Self::Postgres(
+                    PgConfig::builder()
+                        .db_url(db_url)
+                        .common_config(common_config)
+                        .build(),
+                ))
+            }
+        }
+    }
+}
+
+/// A token bucket limiting the requests of a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RateLimitTable")]
+pub struct RateLimit {
+    /// The number of requests per minute the bucket is refilled with.
+    pub(crate) per_minute: u32,
+
+    /// The most requests that may be made at once, which is the size of the bucket.
+    pub(crate) burst: u32,
+}
+
+/// The table of a rate limit in a configuration file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimitTable {
+    per_minute: u32,
+    burst: u32,
+}
+
+impl TryFrom<RateLimitTable> for RateLimit {
+    type Error = RdapServerError;
+
+    fn try_from(table: RateLimitTable) -> Result<Self, Self::Error> {
+        Self::new(table.per_minute, table.burst)
+    }
+}
+
+#[buildstructor::buildstructor]
+impl RateLimit {
+    /// Creates a limit, which must refill the bucket and let requests through.
+    #[builder(visibility = "pub")]
+    fn new(per_minute: u32, burst: u32) -> Result<Self, RdapServerError> {
+        if per_minute == 0 || burst == 0 {
+            return Err(RdapServerError::Config(
+                "rate limit per minute and burst must be greater than zero".to_string(),
+            ));
+        }
+        Ok(Self { per_minute, burst })
+    }
+
+    /// The number of requests per minute the bucket is refilled with.
+    pub fn per_minute(&self) -> u32 {
+        self.per_minute
+    }
+
+    /// The most requests that may be made at once, which is the size of the bucket.
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    fn new_from_env(
+        limit_var: &str,
+        burst_var: &str,
+        file: Option<Self>,
+    ) -> Result<Option<Self>, RdapServerError> {
+        let per_minute: u32 = get_parse_or(limit_var, file.map_or(0, |limit| limit.per_minute))?;
+        if per_minute == 0 {
+            return Ok(None);
+        }
+        let burst: u32 = get_parse_or(burst_var, file.map_or(per_minute, |limit| limit.burst))?;
+        if burst == 0 {
+            return Err(RdapServerError::Config(format!(
+                "{burst_var} must be greater than zero"
+            )));
+        }
+        Self::new(per_minute, burst).map(Some)
+    }
+}
+
+/// Rate limiting of clients by their IP address.
+///
+/// Lookups and searches have separate limits, and no limit means the requests
+/// are not limited.
+#[derive(Debug, Builder, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Where the IP address of the client is found.
+    pub client_ip_source: SecureClientIpSource,
+
+    /// The limit of lookups.
+    pub lookup: Option<RateLimit>,
+
+    /// The limit of searches.
+    pub search: Option<RateLimit>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            client_ip_source: SecureClientIpSource::ConnectInfo,
+            lookup: None,
+            search: None,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Gets the rate limits of a configuration file, overridden by the environment.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Self, RdapServerError> {
+        let rate_limit = &file.rate_limit;
+        let client_ip_source = match get_or(CLIENT_IP_SOURCE, "").as_str() {
+            "" => rate_limit.client_ip_source.clone(),
+            source => SecureClientIpSource::from_str(source)
+                .map_err(|e| RdapServerError::Config(e.to_string()))?,
+        };
+        Ok(Self {
+            client_ip_source,
+            lookup: RateLimit::new_from_env(
+                LOOKUP_RATE_LIMIT,
+                LOOKUP_RATE_BURST,
+                rate_limit.lookup,
+            )?,
+            search: RateLimit::new_from_env(
+                SEARCH_RATE_LIMIT,
+                SEARCH_RATE_BURST,
+                rate_limit.search,
+            )?,
+        })
+    }
+}
+
+/// Redaction of responses for anonymous callers.
+///
+/// Callers giving one of the API keys as a bearer token, or one of the basic
+/// credentials, get unredacted responses.
+#[derive(Debug, Builder, Clone, Default)]
+pub struct RedactionConfig {
+    /// The redaction policy. If none, responses are not redacted.
+    pub policy: Option<Arc<RedactionPolicy>>,
+
+    /// The API keys of authenticated callers.
+    pub api_keys: Vec<String>,
+
+    /// The basic auth credentials of authenticated callers, as `user:password`.
+    pub basic_credentials: Vec<String>,
+}
+
+impl RedactionConfig {
+    /// Gets the redaction of a configuration file, overridden by the environment.
+    /// The policy file is read, so this fails if it cannot be read or parsed.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Self, RdapServerError> {
+        let redaction = &file.redaction;
+        let policy_file = get_or(
+            REDACTION_POLICY,
+            redaction.policy_file.as_deref().unwrap_or_default(),
+        );
+        let policy = match policy_file.as_str() {
+            "" => None,
+            path => {
+                let json = std::fs::read_to_string(path).map_err(|e| {
+                    RdapServerError::Config(format!("cannot read redaction policy {path}: {e}"))
+                })?;
+                Some(Arc::new(RedactionPolicy::from_json(&json)?))
+            }
+        };
+        Ok(Self {
+            policy,
+            api_keys: get_list_or(API_KEYS, Some(&redaction.api_keys)),
+            basic_credentials: get_list_or(BASIC_AUTH, Some(&redaction.basic_auth)),
+        })
+    }
+}
+
+/// The lifetimes responses may be cached for, which are sent in the `Cache-Control`
+/// and `Expires` headers.
+///
+/// Each is given in seconds, where 0 means the response must not be stored.
+/// Responses without a configured lifetime are sent without these headers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Domain lookups.
+    pub domain: Option<u32>,
+
+    /// Nameserver lookups.
+    pub nameserver: Option<u32>,
+
+    /// Entity lookups.
+    pub entity: Option<u32>,
+
+    /// Autnum lookups.
+    pub autnum: Option<u32>,
+
+    /// IP network lookups.
+    pub ip: Option<u32>,
+
+    /// Searches, including reverse searches and the searches of networks and autnums.
+    pub search: Option<u32>,
+
+    /// Server help.
+    pub help: Option<u32>,
+
+    /// Redirects, such as those to bootstrapped servers.
+    pub redirect: Option<u32>,
+
+    /// Error responses, such as 404s.
+    pub error: Option<u32>,
+}
+
+#[buildstructor::buildstructor]
+impl CacheConfig {
+    #[builder(visibility = "pub")]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        domain: Option<u32>,
+        nameserver: Option<u32>,
+        entity: Option<u32>,
+        autnum: Option<u32>,
+        ip: Option<u32>,
+        search: Option<u32>,
+        help: Option<u32>,
+        redirect: Option<u32>,
+        error: Option<u32>,
+    ) -> Self {
+        Self {
+            domain,
+            nameserver,
+            entity,
+            autnum,
+            ip,
+            search,
+            help,
+            redirect,
+            error,
+        }
+    }
+
+    /// Gets the cache lifetimes of a configuration file, overridden by the environment.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Self, RdapServerError> {
+        let cache = &file.cache;
+        Ok(Self {
+            domain: get_max_age(CACHE_MAX_AGE_DOMAIN, cache.domain)?,
+            nameserver: get_max_age(CACHE_MAX_AGE_NAMESERVER, cache.nameserver)?,
+            entity: get_max_age(CACHE_MAX_AGE_ENTITY, cache.entity)?,
+            autnum: get_max_age(CACHE_MAX_AGE_AUTNUM, cache.autnum)?,
+            ip: get_max_age(CACHE_MAX_AGE_IP, cache.ip)?,
+            search: get_max_age(CACHE_MAX_AGE_SEARCH, cache.search)?,
+            help: get_max_age(CACHE_MAX_AGE_HELP, cache.help)?,
+            redirect: get_max_age(CACHE_MAX_AGE_REDIRECT, cache.redirect)?,
+            error: get_max_age(CACHE_MAX_AGE_ERROR, cache.error)?,
+        })
+    }
+}
+
+/// Gets a cache lifetime in seconds from an environment variable, which is
+/// the lifetime of the configuration file if the variable is not set.
+fn get_max_age(key: &str, file: Option<u32>) -> Result<Option<u32>, RdapServerError> {
+    match get_or(key, "").as_str() {
+        "" => Ok(file),
+        max_age => max_age
+            .parse()
+            .map(Some)
+            .map_err(|_| RdapServerError::Config(format!("{key} must be a number of seconds"))),
+    }
+}
+
+/// Gets a comma separated list from an environment variable, which is the list
+/// of the configuration file if the variable is not set.
+fn get_list_or(key: &str, file: Option<&Vec<String>>) -> Vec<String> {
+    match get_or(key, "").as_str() {
+        "" => file.cloned().unwrap_or_default(),
+        list => list
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// The formats contacts of entities are served in.
+#[derive(Debug, Display, EnumString, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ContactFormat {
+    /// Contacts are served as jCard in `vcardArray`.
+    #[default]
+    JCard,
+
+    /// Contacts are served as JSContact in `jscard`.
+    JsContact,
+
+    /// Contacts are served in both formats, unless the client asks for
+    /// JSContact only with the `jscard=1` query parameter.
+    Both,
+}
+
+impl ContactFormat {
+    /// Gets the contact format of a configuration file, overridden by the environment.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Self, RdapServerError> {
+        let format = match get_or(CONTACT_FORMAT, "").as_str() {
+            "" => return Ok(file.contact_format.unwrap_or_default()),
+            format => format.to_string(),
+        };
+        Self::from_str(&format).map_err(|_| {
+            RdapServerError::Config(format!(
+                "{CONTACT_FORMAT} of '{format}' is invalid, must be jcard, jscontact, or both"
             ))
-        } else {
-            Err(RdapServerError::Config(format!(
-                "storage type of '{storage}' is invalid"
-            )))
+        })
+    }
+}
+
+/// A tenant of the server, which is a registry served from its own storage.
+///
+/// Requests are served by a tenant when the `Host` header is one of its hosts,
+/// or when the path starts with its path prefix, as in `/{prefix}/rdap/domain/foo.example`.
+/// Other requests are served by the default storage of the server.
+#[derive(Debug, Builder, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TenantConfig {
+    /// The name of the tenant, which template files give to declare they belong to it.
+    pub name: String,
+
+    /// The host names served by the tenant.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+
+    /// If specified, the first segment of the paths served by the tenant.
+    #[serde(alias = "path_prefix")]
+    pub path_prefix: Option<String>,
+
+    /// The directory the data of the tenant is loaded from, including its help files.
+    /// If unspecified, this is the directory of the tenant's name in the data directory.
+    #[serde(alias = "data_dir")]
+    pub data_dir: Option<String>,
+
+    /// If true, the tenant bootstraps to other servers with the IANA registries.
+    #[serde(default)]
+    pub bootstrap: bool,
+
+    /// The URL of the PostgreSQL database of the tenant, which must be given
+    /// when PostgreSQL storage is used.
+    #[serde(alias = "db_url")]
+    pub db_url: Option<String>,
+}
+
+impl TenantConfig {
+    /// Gets the tenants of a configuration file, which are given in it or in a
+    /// JSON file it names. A JSON file named by the environment overrides them.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Vec<Self>, RdapServerError> {
+        let path = match (get_or(TENANTS, "").as_str(), &file.tenants) {
+            ("", None) => return Ok(vec![]),
+            ("", Some(Tenants::Inline(tenants))) => return Self::check(tenants.clone()),
+            ("", Some(Tenants::File(path))) => path.clone(),
+            (path, _) => path.to_string(),
+        };
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| RdapServerError::Config(format!("cannot read tenants {path}: {e}")))?;
+        Self::from_json(&json)
+    }
+
+    /// Parses and checks the tenants of a JSON array.
+    pub fn from_json(json: &str) -> Result<Vec<Self>, RdapServerError> {
+        let tenants = serde_json::from_str::<Vec<Self>>(json)
+            .map_err(|e| RdapServerError::Config(format!("tenants are invalid: {e}")))?;
+        Self::check(tenants)
+    }
+
+    /// Checks that tenants have unique names, hosts, and path prefixes.
+    fn check(tenants: Vec<Self>) -> Result<Vec<Self>, RdapServerError> {
+        let mut names = HashSet::new();
+        let mut hosts = HashSet::new();
+        let mut path_prefixes = HashSet::new();
+        for tenant in &tenants {
+            if tenant.name.is_empty() || !names.insert(tenant.name.as_str()) {
+                return Err(RdapServerError::Config(format!(
+                    "tenant name '{}' is empty or not unique",
+                    tenant.name
+                )));
+            }
+            if tenant.hosts.is_empty() && tenant.path_prefix.is_none() {
+                return Err(RdapServerError::Config(format!(
+                    "tenant '{}' must have hosts or a path prefix",
+                    tenant.name
+                )));
+            }
+            for host in &tenant.hosts {
+                if !hosts.insert(host.to_ascii_lowercase()) {
+                    return Err(RdapServerError::Config(format!(
+                        "host '{host}' is given to more than one tenant"
+                    )));
+                }
+            }
+            if let Some(path_prefix) = &tenant.path_prefix {
+                if path_prefix.is_empty()
+                    || path_prefix.contains('/')
+                    || path_prefix == "rdap"
+                    || !path_prefixes.insert(path_prefix.as_str())
+                {
+                    return Err(RdapServerError::Config(format!(
+                        "path prefix '{path_prefix}' of tenant '{}' is invalid or not unique",
+                        tenant.name
+                    )));
+                }
+            }
         }
+        Ok(tenants)
     }
 }
 
 /// RDAP service configuration.
-#[derive(Debug, Builder, Clone)]
+#[derive(Debug, Clone)]
 pub struct ServiceConfig {
     pub storage_type: StorageType,
     pub data_dir: String,
     pub auto_reload: bool,
     pub bootstrap: bool,
     pub update_on_bootstrap: bool,
+    pub rate_limit: RateLimitConfig,
+    pub redaction: RedactionConfig,
+    pub cache: CacheConfig,
+    pub contact_format: ContactFormat,
+    pub tenants: Vec<TenantConfig>,
+
+    /// The name of the tenant served with this configuration, which is none
+    /// for the default storage of the server.
+    pub tenant_name: Option<String>,
 }
 
 #[buildstructor::buildstructor]
 impl ServiceConfig {
+    #[builder(visibility = "pub")]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        storage_type: StorageType,
+        data_dir: String,
+        auto_reload: bool,
+        bootstrap: bool,
+        update_on_bootstrap: bool,
+        rate_limit: RateLimitConfig,
+        redaction: RedactionConfig,
+        cache: CacheConfig,
+        contact_format: ContactFormat,
+        tenants: Vec<TenantConfig>,
+    ) -> Self {
+        Self {
+            storage_type,
+            data_dir,
+            auto_reload,
+            bootstrap,
+            update_on_bootstrap,
+            rate_limit,
+            redaction,
+            cache,
+            contact_format,
+            tenants,
+            tenant_name: None,
+        }
+    }
+
+    /// Gets the service configuration of a configuration file, overridden by the
+    /// environment, checking the configuration of each of its tenants.
+    pub fn new_from_env(file: &ConfigFile) -> Result<Self, RdapServerError> {
+        let service_config = Self::new(
+            StorageType::new_from_env(file)?,
+            data_dir(file),
+            get_parse_or(AUTO_RELOAD, file.auto_reload.unwrap_or(true))?,
+            get_parse_or(BOOTSTRAP, file.bootstrap.unwrap_or(false))?,
+            get_parse_or(
+                UPDATE_ON_BOOTSTRAP,
+                file.update_on_bootstrap.unwrap_or(false),
+            )?,
+            RateLimitConfig::new_from_env(file)?,
+            RedactionConfig::new_from_env(file)?,
+            CacheConfig::new_from_env(file)?,
+            ContactFormat::new_from_env(file)?,
+            TenantConfig::new_from_env(file)?,
+        );
+        for tenant in &service_config.tenants {
+            service_config.for_tenant(tenant)?;
+        }
+        Ok(service_config)
+    }
+
     #[builder(entry = "non_server")]
     pub fn new_non_server(
         data_dir: String,
@@ -114,7 +869,7 @@ impl ServiceConfig {
         let storage_type = if let Some(storage_type) = storage_type {
             storage_type
         } else {
-            StorageType::new_from_env()?
+            StorageType::new_from_env(&ConfigFile::default())?
         };
         Ok(Self {
             storage_type,
@@ -122,6 +877,67 @@ impl ServiceConfig {
             auto_reload: false,
             bootstrap: false,
             update_on_bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            tenants: vec![],
+            tenant_name: None,
+        })
+    }
+
+    /// Gets the configuration serving a tenant, which has the data directory,
+    /// bootstrapping, and storage of the tenant.
+    ///
+    /// In-memory storage of a tenant is snapshotted to the snapshot file suffixed
+    /// with the name of the tenant.
+    pub fn for_tenant(&self, tenant: &TenantConfig) -> Result<Self, RdapServerError> {
+        let storage_type = match &self.storage_type {
+            StorageType::Memory(_) if tenant.db_url.is_some() => {
+                return Err(RdapServerError::Config(format!(
+                    "tenant '{}' has a database URL, but storage is in memory",
+                    tenant.name
+                )));
+            }
+            StorageType::Memory(config) => StorageType::Memory(
+                MemConfig::builder()
+                    .common_config(config.common_config)
+                    .and_snapshot_file(
+                        config
+                            .snapshot_file
+                            .as_ref()
+                            .map(|file| format!("{file}.{}", tenant.name)),
+                    )
+                    .build(),
+            ),
+            StorageType::Postgres(config) => {
+                let Some(db_url) = &tenant.db_url else {
+                    return Err(RdapServerError::Config(format!(
+                        "tenant '{}' must have a database URL with PostgreSQL storage",
+                        tenant.name
+                    )));
+                };
+                StorageType::Postgres(
+                    PgConfig::builder()
+                        .db_url(db_url)
+                        .common_config(config.common_config)
+                        .build(),
+                )
+            }
+        };
+        let data_dir = tenant.data_dir.clone().unwrap_or_else(|| {
+            PathBuf::from(&self.data_dir)
+                .join(&tenant.name)
+                .to_string_lossy()
+                .to_string()
+        });
+        Ok(Self {
+            storage_type,
+            data_dir,
+            bootstrap: tenant.bootstrap,
+            tenants: vec![],
+            tenant_name: Some(tenant.name.clone()),
+            ..self.clone()
         })
     }
 }
@@ -0,0 +1,311 @@
+use std::path::Path;
+
+use {envmnt::get_or, serde::Deserialize};
+
+use crate::{
+    config::{
+        AdminConfig, CacheConfig, ContactFormat, RateLimitConfig, StorageKind, TenantConfig,
+        TlsConfig, CONFIG,
+    },
+    error::RdapServerError,
+};
+
+/// The TOML configuration file of the server.
+///
+/// Every value of the file has an `RDAP_SRV_*` environment variable, which
+/// overrides the value when it is set. The `new_from_env` functions of the
+/// types in [crate::config] merge the two. Values given by neither have the
+/// defaults of the environment variables. An example documenting all of the
+/// values is in `resources/rdap-srv.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// The log filter, as with `RDAP_SRV_LOG`.
+    pub log: Option<String>,
+
+    /// The directory data is loaded from.
+    pub data_dir: Option<String>,
+
+    /// If true, data is updated or reloaded as the data directory changes and on SIGHUP.
+    pub auto_reload: Option<bool>,
+
+    /// If true, the server bootstraps to other servers with the IANA registries.
+    pub bootstrap: Option<bool>,
+
+    /// If true, bootstrapping updates storage instead of reloading it.
+    pub update_on_bootstrap: Option<bool>,
+
+    /// The formats contacts are served in.
+    pub contact_format: Option<ContactFormat>,
+
+    /// The tenants of the server.
+    pub tenants: Option<Tenants>,
+
+    #[serde(default)]
+    pub listen: ListenSection,
+
+    #[serde(default)]
+    pub storage: StorageSection,
+
+    #[serde(default)]
+    pub search: SearchSection,
+
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    #[serde(default)]
+    pub redaction: RedactionSection,
+
+    #[serde(default)]
+    pub cache: CacheConfig,
+}
+
+/// The tenants of a configuration file, which are either the JSON file of
+/// the tenants or the `[[tenants]]` tables of the tenants themselves.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Tenants {
+    File(String),
+    Inline(Vec<TenantConfig>),
+}
+
+/// The `[listen]` table, which configures the listeners of the server.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListenSection {
+    /// The IP address of the interface to bind to.
+    pub ip_addr: Option<String>,
+
+    /// The port plain HTTP is served on.
+    pub port: Option<u16>,
+
+    /// The port Prometheus metrics are served on.
+    pub metrics_port: Option<u16>,
+
+    /// The seconds requests in flight are given to finish on shutdown.
+    pub shutdown_timeout: Option<u64>,
+
+    /// The `[listen.tls]` table, which configures TLS termination.
+    pub tls: Option<TlsConfig>,
+
+    /// The `[listen.admin]` table, which configures the admin API.
+    pub admin: Option<AdminConfig>,
+}
+
+/// The `[storage]` table, which configures the storage type.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageSection {
+    /// The kind of storage.
+    #[serde(rename = "type")]
+    pub storage_type: Option<StorageKind>,
+
+    /// The URL of the PostgreSQL database.
+    pub db_url: Option<String>,
+
+    /// The file in-memory storage is snapshotted to.
+    pub snapshot_file: Option<String>,
+}
+
+/// The `[search]` table, which enables searches and sets their page size.
+///
+/// Searches are disabled unless they are enabled, which differs from the
+/// defaults of [crate::storage::CommonConfig].
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchSection {
+    pub domain_search_by_name: Option<bool>,
+    pub domain_search_by_ns_ldh_name: Option<bool>,
+    pub domain_search_by_ns_ip: Option<bool>,
+    pub nameserver_search_by_name: Option<bool>,
+    pub nameserver_search_by_ip: Option<bool>,
+    pub entity_search_by_handle: Option<bool>,
+    pub entity_search_by_full_name: Option<bool>,
+    pub reverse_search: Option<bool>,
+    pub rir_search: Option<bool>,
+    pub page_size: Option<usize>,
+}
+
+/// The `[redaction]` table, which redacts responses for anonymous callers.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionSection {
+    pub policy_file: Option<String>,
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    #[serde(default)]
+    pub basic_auth: Vec<String>,
+}
+
+impl ConfigFile {
+    /// Reads a configuration file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, RdapServerError> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path).map_err(|e| {
+            RdapServerError::Config(format!(
+                "cannot read configuration file {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_toml(&toml)
+    }
+
+    /// Parses the TOML of a configuration file.
+    pub fn from_toml(toml: &str) -> Result<Self, RdapServerError> {
+        toml::from_str(toml)
+            .map_err(|e| RdapServerError::Config(format!("configuration file is invalid: {e}")))
+    }
+
+    /// Reads the configuration file named by `RDAP_SRV_CONFIG`, which is an empty
+    /// configuration if there is none.
+    pub fn from_env() -> Result<Self, RdapServerError> {
+        match get_or(CONFIG, "").as_str() {
+            "" => Ok(Self::default()),
+            path => Self::from_file(path),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use crate::config::{ContactFormat, RateLimit, RateLimitConfig, StorageKind, TenantConfig};
+
+    use super::{ConfigFile, Tenants};
+
+    #[test]
+    fn GIVEN_example_config_file_WHEN_from_toml_THEN_values_parsed() {
+        // GIVEN
+        let toml = include_str!("../resources/rdap-srv.toml");
+
+        // WHEN
+        let actual = ConfigFile::from_toml(toml).expect("parsing example config file");
+
+        // THEN
+        assert_eq!(actual.listen.port, Some(3000));
+        assert_eq!(actual.storage.storage_type, Some(StorageKind::Memory));
+        assert_eq!(actual.contact_format, Some(ContactFormat::JCard));
+        assert_eq!(actual.search.domain_search_by_name, Some(false));
+        assert_eq!(actual.search.page_size, Some(100));
+    }
+
+    #[test]
+    fn GIVEN_rate_limit_tables_WHEN_rate_limit_config_THEN_limits_given() {
+        // GIVEN
+        let toml = r#"
+            [rate_limit]
+            client_ip_source = "RightmostXForwardedFor"
+
+            [rate_limit.lookup]
+            per_minute = 600
+            burst = 60
+        "#;
+        let file = ConfigFile::from_toml(toml).expect("parsing config file");
+
+        // WHEN
+        let actual = RateLimitConfig::new_from_env(&file).expect("rate limit config");
+
+        // THEN
+        assert_eq!(
+            actual.lookup,
+            Some(
+                RateLimit::builder()
+                    .per_minute(600)
+                    .burst(60)
+                    .build()
+                    .expect("rate limit")
+            )
+        );
+        assert!(actual.search.is_none());
+    }
+
+    #[test]
+    fn GIVEN_inline_tenants_WHEN_tenant_config_THEN_tenants_given() {
+        // GIVEN
+        let toml = r#"
+            [[tenants]]
+            name = "foo"
+            hosts = ["rdap.foo.example"]
+            path_prefix = "foo"
+
+            [[tenants]]
+            name = "bar"
+            hosts = ["rdap.bar.example"]
+        "#;
+        let file = ConfigFile::from_toml(toml).expect("parsing config file");
+
+        // WHEN
+        let actual = TenantConfig::new_from_env(&file).expect("tenant config");
+
+        // THEN
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].path_prefix.as_deref(), Some("foo"));
+        assert_eq!(actual[1].hosts, ["rdap.bar.example"]);
+    }
+
+    #[test]
+    fn GIVEN_inline_tenants_with_same_host_WHEN_tenant_config_THEN_error() {
+        // GIVEN
+        let toml = r#"
+            [[tenants]]
+            name = "foo"
+            hosts = ["rdap.example"]
+
+            [[tenants]]
+            name = "bar"
+            hosts = ["rdap.example"]
+        "#;
+        let file = ConfigFile::from_toml(toml).expect("parsing config file");
+
+        // WHEN
+        let actual = TenantConfig::new_from_env(&file);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn GIVEN_tenants_file_WHEN_from_toml_THEN_file_given() {
+        // GIVEN
+        let toml = r#"tenants = "/etc/rdap-srv/tenants.json""#;
+
+        // WHEN
+        let actual = ConfigFile::from_toml(toml).expect("parsing config file");
+
+        // THEN
+        assert!(matches!(
+            actual.tenants,
+            Some(Tenants::File(path)) if path == "/etc/rdap-srv/tenants.json"
+        ));
+    }
+
+    #[test]
+    fn GIVEN_unknown_value_WHEN_from_toml_THEN_error() {
+        // GIVEN
+        let toml = r#"
+            [search]
+            domain_search_by_nmae = true
+        "#;
+
+        // WHEN
+        let actual = ConfigFile::from_toml(toml);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn GIVEN_invalid_storage_type_WHEN_from_toml_THEN_error() {
+        // GIVEN
+        let toml = r#"
+            [storage]
+            type = "floppy"
+        "#;
+
+        // WHEN
+        let actual = ConfigFile::from_toml(toml);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+}
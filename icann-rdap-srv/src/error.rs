@@ -24,6 +24,8 @@ pub enum RdapServerError {
     #[error(transparent)]
     IO(#[from] std::io::Error),
     #[error(transparent)]
+    Tls(#[from] rustls::Error),
+    #[error(transparent)]
     EnvVar(#[from] std::env::VarError),
     #[error(transparent)]
     IntEnvVar(#[from] ParseIntError),
@@ -31,6 +33,8 @@ pub enum RdapServerError {
     Config(String),
     #[error(transparent)]
     SqlDb(#[from] sqlx::Error),
+    #[error(transparent)]
+    SqlMigrate(#[from] sqlx::migrate::MigrateError),
     #[error("index data for {0} is missing or empty")]
     EmptyIndexData(String),
     #[error("file at {0} is not JSON")]
@@ -54,6 +58,12 @@ pub enum RdapServerError {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
+    SnapshotEncode(#[from] rmp_serde::encode::Error),
+    #[error(transparent)]
+    SnapshotDecode(#[from] rmp_serde::decode::Error),
+    #[error("snapshot version {0} is not supported")]
+    SnapshotVersion(u32),
+    #[error(transparent)]
     Response(#[from] RdapResponseError),
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
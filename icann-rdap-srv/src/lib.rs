@@ -1,7 +1,16 @@
+pub mod admin;
 pub mod bootstrap;
+pub mod cache;
 pub mod config;
+pub mod config_file;
 pub mod error;
+pub mod metrics;
+pub mod rate_limit;
 pub mod rdap;
 pub mod server;
+pub mod shutdown;
+pub mod socket;
 pub mod storage;
+pub mod tenant;
+pub mod tls;
 pub mod util;
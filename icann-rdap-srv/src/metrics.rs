@@ -0,0 +1,236 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use {
+    axum::{
+        extract::{Request, State},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        routing::get,
+        Router,
+    },
+    http::{header::CONTENT_TYPE, StatusCode},
+    prometheus::{
+        Encoder, Gauge, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
+        TextEncoder,
+    },
+    tracing::warn,
+};
+
+use crate::{
+    rdap::first_rdap_segment,
+    server::DynServiceState,
+    storage::{ObjectCounts, StoreOps},
+};
+
+/// The routes requests are counted by. Requests for any other path are counted as `other`.
+const ROUTES: [&str; 11] = [
+    "domain",
+    "ip",
+    "autnum",
+    "nameserver",
+    "entity",
+    "domains",
+    "nameservers",
+    "entities",
+    "ips",
+    "autnums",
+    "help",
+];
+
+/// The Prometheus metrics of the server.
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    objects: IntGaugeVec,
+    last_reload: Gauge,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let requests = IntCounterVec::new(
+            Opts::new("rdap_requests_total", "The number of RDAP requests."),
+            &["route", "status"],
+        )
+        .expect("requests metric");
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "rdap_request_duration_seconds",
+                "The time taken to answer RDAP requests.",
+            ),
+            &["route", "status"],
+        )
+        .expect("request duration metric");
+        let objects = IntGaugeVec::new(
+            Opts::new("rdap_objects", "The number of objects in storage."),
+            &["object_type"],
+        )
+        .expect("objects metric");
+        let last_reload = Gauge::new(
+            "rdap_data_last_reload_timestamp_seconds",
+            "The time the data was last loaded into storage, in seconds since the Unix epoch.",
+        )
+        .expect("last reload metric");
+        let registry = Registry::new();
+        registry
+            .register(Box::new(requests.clone()))
+            .expect("registering requests metric");
+        registry
+            .register(Box::new(request_duration.clone()))
+            .expect("registering request duration metric");
+        registry
+            .register(Box::new(objects.clone()))
+            .expect("registering objects metric");
+        registry
+            .register(Box::new(last_reload.clone()))
+            .expect("registering last reload metric");
+        Self {
+            registry,
+            requests,
+            request_duration,
+            objects,
+            last_reload,
+        }
+    }
+
+    /// Records that the data has been loaded into storage, counting the objects
+    /// loaded.
+    pub async fn data_loaded(&self, store: &dyn StoreOps) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.last_reload.set(now.as_secs_f64());
+        self.count_objects(store).await;
+    }
+
+    /// Counts the objects in storage. This is done when the objects change rather
+    /// than on every scrape, as counting may be costly for large stores.
+    pub async fn count_objects(&self, store: &dyn StoreOps) {
+        match store.get_object_counts().await {
+            Ok(counts) => self.set_object_counts(&counts),
+            Err(e) => warn!("unable to count objects for metrics: {e}"),
+        }
+    }
+
+    fn observe_request(&self, path: &str, status: StatusCode, elapsed: f64) {
+        let labels = [route(path), status.as_str()];
+        self.requests.with_label_values(&labels).inc();
+        self.request_duration
+            .with_label_values(&labels)
+            .observe(elapsed);
+    }
+
+    fn set_object_counts(&self, counts: &ObjectCounts) {
+        for (object_type, count) in [
+            ("domain", counts.domains),
+            ("nameserver", counts.nameservers),
+            ("entity", counts.entities),
+            ("autnum", counts.autnums),
+            ("network", counts.networks),
+        ] {
+            self.objects
+                .with_label_values(&[object_type])
+                .set(count as i64);
+        }
+    }
+
+    /// Encodes the metrics in the Prometheus text format.
+    fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).to_string())
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gets the route of a request path for the labels of metrics.
+fn route(path: &str) -> &'static str {
+    let first_segment = first_rdap_segment(path);
+    ROUTES
+        .into_iter()
+        .find(|route| *route == first_segment)
+        .unwrap_or("other")
+}
+
+/// Middleware counting requests and observing how long they take.
+pub(crate) async fn track_requests(
+    State(state): State<DynServiceState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_owned();
+    let start = Instant::now();
+    let response = next.run(request).await;
+    state
+        .get_metrics()
+        .observe_request(&path, response.status(), start.elapsed().as_secs_f64());
+    response
+}
+
+/// Creates the router serving the metrics at `/metrics`.
+pub(crate) fn metrics_router(state: DynServiceState) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(state)
+}
+
+async fn metrics(State(state): State<DynServiceState>) -> Response {
+    match state.get_metrics().encode() {
+        Ok(text) => ([(CONTENT_TYPE, TextEncoder::new().format_type())], text).into_response(),
+        Err(e) => {
+            warn!("unable to encode metrics: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {http::StatusCode, rstest::rstest};
+
+    use super::{route, Metrics};
+
+    #[rstest]
+    #[case("/rdap/domain/foo.example", "domain")]
+    #[case("/rdap/ip/192.0.2.0/24", "ip")]
+    #[case("/rdap/domains/reverse_search/entity", "domains")]
+    #[case("/rdap/ips/rirSearch1/rdap-down/192.0.2.0/24", "ips")]
+    #[case("/rdap/autnums", "autnums")]
+    #[case("/rdap/help", "help")]
+    #[case("/rdap/foo", "other")]
+    #[case("/", "other")]
+    fn GIVEN_path_WHEN_route_THEN_route_label(#[case] path: &str, #[case] expected: &str) {
+        // GIVEN in parameters
+
+        // WHEN
+        let actual = route(path);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn GIVEN_observed_requests_WHEN_encode_THEN_counts_by_route_and_status() {
+        // GIVEN
+        let metrics = Metrics::new();
+        metrics.observe_request("/rdap/domain/foo.example", StatusCode::OK, 0.01);
+        metrics.observe_request("/rdap/domain/bar.example", StatusCode::NOT_FOUND, 0.01);
+        metrics.observe_request("/rdap/domain/baz.example", StatusCode::NOT_FOUND, 0.01);
+
+        // WHEN
+        let actual = metrics.encode().expect("encoding metrics");
+
+        // THEN
+        assert!(actual.contains(r#"rdap_requests_total{route="domain",status="200"} 1"#));
+        assert!(actual.contains(r#"rdap_requests_total{route="domain",status="404"} 2"#));
+        assert!(actual
+            .contains(r#"rdap_request_duration_seconds_count{route="domain",status="404"} 2"#));
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use {
+    axum::{
+        extract::{Request, State},
+        middleware::Next,
+        response::Response,
+    },
+    axum_client_ip::{SecureClientIp, SecureClientIpSource},
+    http::{header::RETRY_AFTER, HeaderValue},
+    tracing::{debug, warn},
+};
+
+use crate::{
+    config::{RateLimit, RateLimitConfig},
+    rdap::{
+        first_rdap_segment,
+        response::{ResponseUtil, TOO_MANY_REQUESTS},
+    },
+};
+
+/// The number of clients tracked before the buckets of idle clients are first
+/// dropped. Each time, the next drop is once the clients left have doubled.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// The path segments of searches. All other requests are lookups.
+const SEARCH_SEGMENTS: [&str; 5] = ["domains", "nameservers", "entities", "ips", "autnums"];
+
+/// Limits the rate of requests from each client with token buckets.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    client_ip_source: SecureClientIpSource,
+    lookup: Option<TokenBuckets>,
+    search: Option<TokenBuckets>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            client_ip_source: config.client_ip_source.clone(),
+            lookup: config.lookup.map(TokenBuckets::new),
+            search: config.search.map(TokenBuckets::new),
+        }
+    }
+
+    /// Gets the buckets limiting the request of the path, if there are any.
+    fn buckets(&self, path: &str) -> Option<&TokenBuckets> {
+        if SEARCH_SEGMENTS.contains(&first_rdap_segment(path)) {
+            self.search.as_ref()
+        } else {
+            self.lookup.as_ref()
+        }
+    }
+}
+
+/// The token buckets of a type of request, one for each client.
+#[derive(Debug)]
+struct TokenBuckets {
+    capacity: f64,
+    tokens_per_sec: f64,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    by_client: HashMap<IpAddr, Bucket>,
+    /// The number of clients at which the buckets of idle clients are dropped.
+    prune_at: usize,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBuckets {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            capacity: limit.burst as f64,
+            tokens_per_sec: limit.per_minute as f64 / 60.0,
+            buckets: Mutex::new(Buckets {
+                by_client: HashMap::new(),
+                prune_at: PRUNE_THRESHOLD,
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket of the client.
+    ///
+    /// If the bucket is empty, the error is how long until it has a token again.
+    fn take(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .expect("rate limit buckets lock poisoned");
+        if buckets.by_client.len() >= buckets.prune_at {
+            buckets
+                .by_client
+                .retain(|_, bucket| self.refill(bucket, now) < self.capacity);
+            buckets.prune_at = PRUNE_THRESHOLD.max(buckets.by_client.len() * 2);
+        }
+        let bucket = buckets.by_client.entry(client).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+        bucket.tokens = self.refill(bucket, now);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.tokens_per_sec;
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+
+    /// Gets the tokens of a bucket after it has been refilled for the time passed.
+    fn refill(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.tokens_per_sec).min(self.capacity)
+    }
+}
+
+/// Middleware answering with a 429 and a `Retry-After` header when a client
+/// has exceeded its rate limit.
+pub(crate) async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(buckets) = limiter.buckets(request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let client = match SecureClientIp::from(
+        &limiter.client_ip_source,
+        request.headers(),
+        request.extensions(),
+    ) {
+        Ok(SecureClientIp(client)) => client,
+        Err(_) => {
+            warn!("unable to find the client IP address to rate limit a request");
+            return next.run(request).await;
+        }
+    };
+    match buckets.take(client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            debug!("rate limit exceeded by {client}");
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            let mut response = TOO_MANY_REQUESTS.response();
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::{Duration, Instant},
+    };
+
+    use rstest::rstest;
+
+    use crate::config::{RateLimit, RateLimitConfig};
+
+    use super::{RateLimiter, TokenBuckets, PRUNE_THRESHOLD};
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+    #[test]
+    fn GIVEN_full_bucket_WHEN_take_more_than_burst_THEN_wait_for_refill() {
+        // GIVEN
+        let buckets = TokenBuckets::new(
+            RateLimit::builder()
+                .per_minute(60)
+                .burst(2)
+                .build()
+                .expect("rate limit"),
+        );
+        let now = Instant::now();
+
+        // WHEN
+        let first = buckets.take(CLIENT, now);
+        let second = buckets.take(CLIENT, now);
+        let third = buckets.take(CLIENT, now);
+
+        // THEN
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        assert_eq!(third, Err(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn GIVEN_empty_bucket_WHEN_time_passes_THEN_bucket_refilled() {
+        // GIVEN
+        let buckets = TokenBuckets::new(
+            RateLimit::builder()
+                .per_minute(60)
+                .burst(1)
+                .build()
+                .expect("rate limit"),
+        );
+        let now = Instant::now();
+        buckets.take(CLIENT, now).expect("first token");
+
+        // WHEN
+        let actual = buckets.take(CLIENT, now + Duration::from_secs(1));
+
+        // THEN
+        assert!(actual.is_ok());
+    }
+
+    #[test]
+    fn GIVEN_empty_bucket_of_client_WHEN_other_client_takes_THEN_not_limited() {
+        // GIVEN
+        let buckets = TokenBuckets::new(
+            RateLimit::builder()
+                .per_minute(1)
+                .burst(1)
+                .build()
+                .expect("rate limit"),
+        );
+        let now = Instant::now();
+        buckets.take(CLIENT, now).expect("first token");
+
+        // WHEN
+        let actual = buckets.take(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2)), now);
+
+        // THEN
+        assert!(actual.is_ok());
+    }
+
+    #[test]
+    fn GIVEN_active_clients_past_threshold_WHEN_take_THEN_pruned_again_once_doubled() {
+        // GIVEN
+        let buckets = TokenBuckets::new(
+            RateLimit::builder()
+                .per_minute(1)
+                .burst(1)
+                .build()
+                .expect("rate limit"),
+        );
+        let now = Instant::now();
+        let client = |i: usize| IpAddr::from((i as u128).to_be_bytes());
+        for i in 0..PRUNE_THRESHOLD {
+            buckets.take(client(i), now).expect("token");
+        }
+
+        // WHEN
+        buckets.take(client(PRUNE_THRESHOLD), now).expect("token");
+
+        // THEN
+        let buckets = buckets.buckets.lock().expect("buckets lock");
+        assert_eq!(buckets.by_client.len(), PRUNE_THRESHOLD + 1);
+        assert_eq!(buckets.prune_at, PRUNE_THRESHOLD * 2);
+    }
+
+    #[test]
+    fn GIVEN_zero_rate_WHEN_build_rate_limit_THEN_error() {
+        // GIVEN
+        let builder = RateLimit::builder().per_minute(0).burst(1);
+
+        // WHEN
+        let actual = builder.build();
+
+        // THEN
+        assert!(actual.is_err());
+    }
+
+    #[rstest]
+    #[case("/rdap/domains", true)]
+    #[case("/rdap/domains/reverse_search/entity", true)]
+    #[case("/rdap/entities", true)]
+    #[case("/rdap/ips", true)]
+    #[case("/rdap/ips/rirSearch1/rdap-up/192.0.2.0/24", true)]
+    #[case("/rdap/autnums", true)]
+    #[case("/rdap/domain/foo.example", false)]
+    #[case("/rdap/help", false)]
+    fn GIVEN_search_limit_only_WHEN_buckets_for_path_THEN_only_searches_limited(
+        #[case] path: &str,
+        #[case] expected: bool,
+    ) {
+        // GIVEN
+        let limiter = RateLimiter::new(
+            &RateLimitConfig::builder()
+                .client_ip_source(axum_client_ip::SecureClientIpSource::ConnectInfo)
+                .search(
+                    RateLimit::builder()
+                        .per_minute(1)
+                        .burst(1)
+                        .build()
+                        .expect("rate limit"),
+                )
+                .build(),
+        );
+
+        // WHEN
+        let actual = limiter.buckets(path);
+
+        // THEN
+        assert_eq!(actual.is_some(), expected);
+    }
+}
@@ -5,13 +5,15 @@ use axum::{
 
 use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
 
-use super::ToBootStrap;
+use super::{jscontact::Contacts, redaction::Redaction, ToBootStrap};
 
 /// Gets an autnum object by the number path.
 #[axum_macros::debug_handler]
 #[tracing::instrument(level = "debug")]
 pub(crate) async fn autnum_by_num(
     Path(as_num): Path<u32>,
+    redaction: Redaction,
+    contacts: Contacts,
     state: State<DynServiceState>,
 ) -> Result<Response, RdapServerError> {
     let storage = state.get_storage().await?;
@@ -19,6 +21,6 @@ pub(crate) async fn autnum_by_num(
     Ok(if state.get_bootstrap() {
         autnum.to_autnum_bootstrap(as_num).response()
     } else {
-        autnum.response()
+        contacts.apply(redaction.apply(autnum)).response()
     })
 }
@@ -0,0 +1,69 @@
+use axum::{
+    extract::{OriginalUri, Query, State},
+    response::Response,
+};
+
+use serde::Deserialize;
+
+use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
+
+use super::{
+    jscontact::Contacts,
+    redaction::Redaction,
+    response::NOT_IMPLEMENTED,
+    rir_search::add_rir_search_conformance,
+    search::{search_page, search_response},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AutnumsParams {
+    handle: Option<String>,
+
+    name: Option<String>,
+
+    sort: Option<String>,
+
+    cursor: Option<String>,
+
+    #[serde(rename = "fieldSet")]
+    field_set: Option<String>,
+}
+
+/// Searches for autnums by handle or name, as specified in
+/// draft-ietf-regext-rdap-rir-search.
+#[axum_macros::debug_handler]
+#[tracing::instrument(level = "debug")]
+pub(crate) async fn autnums(
+    Query(params): Query<AutnumsParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
+    state: State<DynServiceState>,
+) -> Result<Response, RdapServerError> {
+    let storage = state.get_storage().await?;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "autnum",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = if let Some(handle) = params.handle {
+        storage.search_autnums_by_handle(&handle, &page).await
+    } else if let Some(name) = params.name {
+        storage.search_autnums_by_name(&name, &page).await
+    } else {
+        return Ok(NOT_IMPLEMENTED.response());
+    };
+    search_response(
+        results.map(add_rir_search_conformance),
+        &redaction,
+        &contacts,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        params.field_set.as_deref(),
+        &uri,
+        page_size,
+    )
+}
@@ -0,0 +1,53 @@
+use {
+    axum::{
+        extract::Request,
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    headers::{ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch, LastModified},
+    http::{
+        header::{CACHE_CONTROL, ETAG, EXPIRES, LAST_MODIFIED, VARY},
+        StatusCode,
+    },
+};
+
+/// The headers of a response that are kept when it is answered with a 304.
+const NOT_MODIFIED_HEADERS: [http::HeaderName; 5] =
+    [ETAG, LAST_MODIFIED, CACHE_CONTROL, EXPIRES, VARY];
+
+/// Middleware answering conditional requests with a 304 (Not Modified) when the
+/// client already has the response, as judged by its entity tag or last modified time.
+///
+/// As in RFC 9110, `If-Modified-Since` is only considered when there is no
+/// `If-None-Match`.
+pub(crate) async fn conditional_get(request: Request, next: Next) -> Response {
+    let if_none_match = request.headers().typed_get::<IfNoneMatch>();
+    let if_modified_since = request.headers().typed_get::<IfModifiedSince>();
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    let not_modified = if let Some(if_none_match) = if_none_match {
+        response
+            .headers()
+            .typed_get::<ETag>()
+            .is_some_and(|etag| !if_none_match.precondition_passes(&etag))
+    } else if let Some(if_modified_since) = if_modified_since {
+        response
+            .headers()
+            .typed_get::<LastModified>()
+            .is_some_and(|last_modified| !if_modified_since.is_modified(last_modified.into()))
+    } else {
+        false
+    };
+    if !not_modified {
+        return response;
+    }
+    let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+    for name in NOT_MODIFIED_HEADERS {
+        for value in response.headers().get_all(&name) {
+            not_modified.headers_mut().append(&name, value.clone());
+        }
+    }
+    not_modified
+}
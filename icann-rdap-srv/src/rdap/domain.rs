@@ -1,43 +1,73 @@
 use {
     axum::{
-        extract::{Path, State},
+        extract::{OriginalUri, Path, State},
         response::Response,
     },
-    icann_rdap_common::response::RdapResponse,
+    http::Uri,
+    icann_rdap_common::{
+        media_types::RDAP_MEDIA_TYPE,
+        prelude::ToResponse,
+        response::{Domain, Link, Notice, NoticeOrRemark, RdapResponse, Rfc9083Error},
+    },
 };
 
 use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
 
-use super::ToBootStrap;
+use super::{
+    jscontact::Contacts, normalize_domain_name, redaction::Redaction, response::BAD_REQUEST,
+    DomainName, ToBootStrap,
+};
+
+/// Variant relations (RFC 9083) under which a variant name is registered along with
+/// its domain, so that looking up the name gives the domain.
+const REGISTERED_VARIANT_RELATIONS: [&str; 2] = ["registered", "conjoined"];
 
 /// Gets a domain object by the name path, which can be either A-label or U-label
-/// according to RFC 9082.
+/// according to RFC 9082. Names that are not valid IDNA names are bad requests.
+///
+/// A name that is the variant of a domain gives the domain when the variant is
+/// registered, or otherwise a 404 linking to the domain.
 #[axum_macros::debug_handler]
 #[tracing::instrument(level = "debug")]
 pub(crate) async fn domain_by_name(
     Path(domain_name): Path<String>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
     state: State<DynServiceState>,
 ) -> Result<Response, RdapServerError> {
-    // canonicalize the domain name by removing a trailing ".", trimming any whitespace,
-    // and lower casing any ASCII characters.
+    // canonicalize the domain name with IDNA so that mixed case names, differently
+    // normalized U-labels, and A-labels all find the same domain.
     // Addresses issues #13 and #16.
-    let domain_name = domain_name
-        .trim_end_matches('.')
-        .trim()
-        .to_ascii_lowercase();
+    let Some(domain_name) = normalize_domain_name(&domain_name) else {
+        return Ok(BAD_REQUEST.response());
+    };
 
     // TODO add option to verify it looks like a domain name and return BAD REQUEST if it does not.
     // not all servers may want to enforce that it has multiple labels, such as an IANA server.
     let storage = state.get_storage().await?;
-    let mut domain = storage.get_domain_by_ldh(&domain_name).await?;
+    let mut domain = storage.get_domain_by_ldh(&domain_name.ldh).await?;
 
     // if not found in domain names, check if it is an IDN
     if !matches!(domain, RdapResponse::Domain(_)) && !domain.is_redirect() {
-        domain = storage.get_domain_by_unicode(&domain_name).await?;
+        domain = storage.get_domain_by_unicode(&domain_name.unicode).await?;
+    }
+
+    // if still not found, check if it is a variant name of a domain
+    if !matches!(domain, RdapResponse::Domain(_)) && !domain.is_redirect() {
+        let mut found = storage.get_domain_by_variant(&domain_name.ldh).await?;
+        if !matches!(found, RdapResponse::Domain(_)) {
+            found = storage.get_domain_by_variant(&domain_name.unicode).await?;
+        }
+        if let RdapResponse::Domain(primary) = found {
+            let variant = variant_response(*primary, &domain_name, &uri);
+            return Ok(contacts.apply(redaction.apply(variant)).response());
+        }
     }
 
     if state.get_bootstrap() && !matches!(domain, RdapResponse::Domain(_)) && !domain.is_redirect()
     {
+        let domain_name = domain_name.ldh;
         let mut dn_slice = domain_name.as_str();
         while let Some(less_specific) = dn_slice.split_once('.') {
             let found = storage.get_domain_by_ldh(less_specific.1).await?;
@@ -49,5 +79,58 @@ pub(crate) async fn domain_by_name(
         }
     }
 
-    Ok(domain.response())
+    Ok(contacts.apply(redaction.apply(domain)).response())
+}
+
+/// Gets the response to a lookup of a variant name of a domain, as dictated by the
+/// relations of the variants the name belongs to.
+fn variant_response(primary: Domain, name: &DomainName, uri: &Uri) -> RdapResponse {
+    let is_name = |variant_name: Option<&str>| {
+        variant_name
+            .and_then(normalize_domain_name)
+            .is_some_and(|variant_name| variant_name == *name)
+    };
+    let registered = primary
+        .variants
+        .iter()
+        .flatten()
+        .filter(|variant| {
+            variant.variant_names().iter().any(|variant_name| {
+                is_name(variant_name.ldh_name()) || is_name(variant_name.unicode_name())
+            })
+        })
+        .flat_map(|variant| variant.relations())
+        .any(|relation| REGISTERED_VARIANT_RELATIONS.contains(&relation.as_str()));
+    if registered {
+        return primary.to_response();
+    }
+    let href = primary
+        .object_common
+        .get_self_link()
+        .and_then(|link| link.href.clone())
+        .unwrap_or_else(|| {
+            let base = uri.path().rsplit_once('/').map_or("", |(base, _)| base);
+            format!("{base}/{}", primary.ldh_name.as_deref().unwrap_or_default())
+        });
+    let link = Link::builder()
+        .href(&href)
+        .value(uri.path())
+        .media_type(RDAP_MEDIA_TYPE)
+        .rel("related")
+        .build();
+    let notice = Notice(
+        NoticeOrRemark::builder()
+            .title("Unregistered Variant")
+            .description_entry(format!(
+                "{} is a variant of a domain but is not registered.",
+                name.unicode
+            ))
+            .links(vec![link])
+            .build(),
+    );
+    Rfc9083Error::builder()
+        .error_code(404)
+        .notices(vec![notice])
+        .build()
+        .to_response()
 }
@@ -1,5 +1,7 @@
+use std::net::IpAddr;
+
 use axum::{
-    extract::{Query, State},
+    extract::{OriginalUri, Query, State},
     response::Response,
 };
 
@@ -7,30 +9,71 @@ use serde::Deserialize;
 
 use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
 
-use super::response::NOT_IMPLEMENTED;
+use super::{
+    jscontact::Contacts,
+    redaction::Redaction,
+    response::{BAD_REQUEST, NOT_IMPLEMENTED},
+    search::{search_page, search_response},
+};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct DomainsParams {
     name: Option<String>,
 
     #[serde(rename = "nsLdhName")]
-    _ns_ldh_name: Option<String>,
+    ns_ldh_name: Option<String>,
 
     #[serde(rename = "nsIp")]
-    _ns_ip: Option<String>,
+    ns_ip: Option<String>,
+
+    sort: Option<String>,
+
+    cursor: Option<String>,
+
+    #[serde(rename = "fieldSet")]
+    field_set: Option<String>,
 }
 
 #[axum_macros::debug_handler]
 #[tracing::instrument(level = "debug")]
 pub(crate) async fn domains(
     Query(params): Query<DomainsParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
     state: State<DynServiceState>,
 ) -> Result<Response, RdapServerError> {
-    Ok(if let Some(name) = params.name {
-        let storage = state.get_storage().await?;
-        let results = storage.search_domains_by_name(&name).await?;
-        results.response()
+    let storage = state.get_storage().await?;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "domain",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = if let Some(name) = params.name {
+        storage.search_domains_by_name(&name, &page).await
+    } else if let Some(ns_ldh_name) = params.ns_ldh_name {
+        storage
+            .search_domains_by_ns_ldh_name(&ns_ldh_name, &page)
+            .await
+    } else if let Some(ns_ip) = params.ns_ip {
+        let Ok(ns_ip) = ns_ip.parse::<IpAddr>() else {
+            return Ok(BAD_REQUEST.response());
+        };
+        storage.search_domains_by_ns_ip(ns_ip, &page).await
     } else {
-        NOT_IMPLEMENTED.response()
-    })
+        return Ok(NOT_IMPLEMENTED.response());
+    };
+    search_response(
+        results,
+        &redaction,
+        &contacts,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        params.field_set.as_deref(),
+        &uri,
+        page_size,
+    )
 }
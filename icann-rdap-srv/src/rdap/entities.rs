@@ -0,0 +1,75 @@
+use axum::{
+    extract::{OriginalUri, Query, State},
+    response::Response,
+};
+
+use serde::Deserialize;
+
+use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
+
+use super::{
+    jscontact::Contacts,
+    redaction::{RedactedField, Redaction},
+    response::{FORBIDDEN, NOT_IMPLEMENTED},
+    search::{search_page, search_response},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct EntitiesParams {
+    #[serde(rename = "fn")]
+    full_name: Option<String>,
+
+    handle: Option<String>,
+
+    sort: Option<String>,
+
+    cursor: Option<String>,
+
+    #[serde(rename = "fieldSet")]
+    field_set: Option<String>,
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(level = "debug")]
+pub(crate) async fn entities(
+    Query(params): Query<EntitiesParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
+    state: State<DynServiceState>,
+) -> Result<Response, RdapServerError> {
+    let storage = state.get_storage().await?;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "entity",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = if let Some(full_name) = params.full_name {
+        if redaction.redacts_search(RedactedField::Fn, None) {
+            return Ok(FORBIDDEN.response());
+        }
+        storage
+            .search_entities_by_full_name(&full_name, &page)
+            .await
+    } else if let Some(handle) = params.handle {
+        if redaction.redacts_search(RedactedField::Handle, None) {
+            return Ok(FORBIDDEN.response());
+        }
+        storage.search_entities_by_handle(&handle, &page).await
+    } else {
+        return Ok(NOT_IMPLEMENTED.response());
+    };
+    search_response(
+        results,
+        &redaction,
+        &contacts,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        params.field_set.as_deref(),
+        &uri,
+        page_size,
+    )
+}
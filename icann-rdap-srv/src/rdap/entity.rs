@@ -8,13 +8,15 @@ use {
 
 use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
 
-use super::ToBootStrap;
+use super::{jscontact::Contacts, redaction::Redaction, ToBootStrap};
 
 /// Gets an entity object by the handle path.
 #[axum_macros::debug_handler]
 #[tracing::instrument(level = "debug")]
 pub(crate) async fn entity_by_handle(
     Path(handle): Path<String>,
+    redaction: Redaction,
+    contacts: Contacts,
     state: State<DynServiceState>,
 ) -> Result<Response, RdapServerError> {
     let storage = state.get_storage().await?;
@@ -32,5 +34,5 @@ pub(crate) async fn entity_by_handle(
         }
     }
 
-    Ok(entity.response())
+    Ok(contacts.apply(redaction.apply(entity)).response())
 }
@@ -2,7 +2,7 @@ use std::{net::IpAddr, str::FromStr};
 
 use {
     axum::{
-        extract::{Path, State},
+        extract::{OriginalUri, Path, State},
         response::Response,
     },
     cidr::IpInet,
@@ -12,7 +12,10 @@ use {
 use crate::{
     error::RdapServerError,
     rdap::{
+        jscontact::Contacts,
+        redaction::Redaction,
         response::{ResponseUtil, BAD_REQUEST},
+        rir_search::add_relation_links,
         ToBootStrap,
     },
     server::DynServiceState,
@@ -23,6 +26,9 @@ use crate::{
 #[tracing::instrument(level = "debug")]
 pub(crate) async fn network_by_netid(
     Path(netid): Path<String>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
     state: State<DynServiceState>,
 ) -> Result<Response, RdapServerError> {
     if netid.contains('/') {
@@ -33,7 +39,12 @@ pub(crate) async fn network_by_netid(
             if state.get_bootstrap() {
                 Ok(network.to_ip_bootstrap(&netid).response())
             } else {
-                Ok(network.response())
+                let network = if storage.get_common_config().rir_search_enable {
+                    add_relation_links(network, &uri, "/ip/")
+                } else {
+                    network
+                };
+                Ok(contacts.apply(redaction.apply(network)).response())
             }
         } else {
             Ok(BAD_REQUEST.response())
@@ -49,7 +60,12 @@ pub(crate) async fn network_by_netid(
             if state.get_bootstrap() {
                 Ok(network.to_ip_bootstrap(&netid).response())
             } else {
-                Ok(network.response())
+                let network = if storage.get_common_config().rir_search_enable {
+                    add_relation_links(network, &uri, "/ip/")
+                } else {
+                    network
+                };
+                Ok(contacts.apply(redaction.apply(network)).response())
             }
         }
     }
@@ -0,0 +1,454 @@
+use std::convert::Infallible;
+
+use {
+    async_trait::async_trait,
+    axum::extract::{FromRequestParts, Query},
+    http::request::Parts,
+    icann_rdap_common::{
+        contact::Contact,
+        response::{
+            Common, Domain, Entity, ExtensionId, Nameserver, Network, ObjectCommon, RdapResponse,
+        },
+    },
+    serde::Deserialize,
+};
+
+use crate::{config::ContactFormat, server::DynServiceState};
+
+use super::add_conformance;
+
+/// The formats the contacts of entities are given in by the response to a request.
+///
+/// Servers configured for both formats give only JSContact to clients asking
+/// for it with the `jscard=1` query parameter of draft-ietf-regext-rdap-jscontact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Contacts {
+    jcard: bool,
+    jscontact: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsCardParam {
+    jscard: Option<String>,
+}
+
+impl Contacts {
+    fn new(format: ContactFormat, jscard_only: bool) -> Self {
+        match format {
+            ContactFormat::JCard => Self {
+                jcard: true,
+                jscontact: false,
+            },
+            ContactFormat::JsContact => Self {
+                jcard: false,
+                jscontact: true,
+            },
+            ContactFormat::Both => Self {
+                jcard: !jscard_only,
+                jscontact: true,
+            },
+        }
+    }
+
+    /// Gives the contacts of the entities of a response in the formats, converting
+    /// jCards to JSContact cards and back. This is applied after redaction so that
+    /// the JSContact cards hold only what remains of the jCards.
+    pub(crate) fn apply(&self, response: RdapResponse) -> RdapResponse {
+        match response {
+            RdapResponse::Entity(mut entity) => {
+                self.convert_entity(&mut entity);
+                self.add_conformance(&mut entity.common);
+                RdapResponse::Entity(entity)
+            }
+            RdapResponse::Domain(mut domain) => {
+                self.convert_domain(&mut domain);
+                self.add_conformance(&mut domain.common);
+                RdapResponse::Domain(domain)
+            }
+            RdapResponse::Nameserver(mut nameserver) => {
+                self.convert_nameserver(&mut nameserver);
+                self.add_conformance(&mut nameserver.common);
+                RdapResponse::Nameserver(nameserver)
+            }
+            RdapResponse::Autnum(mut autnum) => {
+                self.convert_object(&mut autnum.object_common);
+                self.add_conformance(&mut autnum.common);
+                RdapResponse::Autnum(autnum)
+            }
+            RdapResponse::Network(mut network) => {
+                self.convert_network(&mut network);
+                self.add_conformance(&mut network.common);
+                RdapResponse::Network(network)
+            }
+            RdapResponse::DomainSearchResults(mut search) => {
+                search
+                    .results
+                    .iter_mut()
+                    .for_each(|domain| self.convert_domain(domain));
+                self.add_conformance(&mut search.common);
+                RdapResponse::DomainSearchResults(search)
+            }
+            RdapResponse::NameserverSearchResults(mut search) => {
+                search
+                    .results
+                    .iter_mut()
+                    .for_each(|nameserver| self.convert_nameserver(nameserver));
+                self.add_conformance(&mut search.common);
+                RdapResponse::NameserverSearchResults(search)
+            }
+            RdapResponse::EntitySearchResults(mut search) => {
+                search
+                    .results
+                    .iter_mut()
+                    .for_each(|entity| self.convert_entity(entity));
+                self.add_conformance(&mut search.common);
+                RdapResponse::EntitySearchResults(search)
+            }
+            RdapResponse::NetworkSearchResults(mut search) => {
+                search
+                    .results
+                    .iter_mut()
+                    .for_each(|network| self.convert_network(network));
+                self.add_conformance(&mut search.common);
+                RdapResponse::NetworkSearchResults(search)
+            }
+            RdapResponse::AutnumSearchResults(mut search) => {
+                search
+                    .results
+                    .iter_mut()
+                    .for_each(|autnum| self.convert_object(&mut autnum.object_common));
+                self.add_conformance(&mut search.common);
+                RdapResponse::AutnumSearchResults(search)
+            }
+            _ => response,
+        }
+    }
+
+    fn add_conformance(&self, common: &mut Common) {
+        if self.jscontact {
+            add_conformance(common, ExtensionId::JsContactLevel0);
+        }
+    }
+
+    fn convert_entity(&self, entity: &mut Entity) {
+        if !self.jscontact {
+            vcard_from_jscard(entity);
+        } else if entity.jscard.is_none() {
+            entity.jscard = entity.contact().map(|contact| {
+                let mut jscard = contact.to_jscard();
+                jscard.uid = entity.object_common.handle.clone();
+                jscard
+            });
+        }
+        if !self.jcard && entity.jscard.is_some() {
+            entity.vcard_array = None;
+        }
+        self.convert_object(&mut entity.object_common);
+        for autnum in entity.autnums.iter_mut().flatten() {
+            self.convert_object(&mut autnum.object_common);
+        }
+        for network in entity.networks.iter_mut().flatten() {
+            self.convert_network(network);
+        }
+    }
+
+    fn convert_domain(&self, domain: &mut Domain) {
+        self.convert_object(&mut domain.object_common);
+        for nameserver in domain.nameservers.iter_mut().flatten() {
+            self.convert_nameserver(nameserver);
+        }
+        if let Some(network) = domain.network.as_mut() {
+            self.convert_network(network);
+        }
+    }
+
+    fn convert_nameserver(&self, nameserver: &mut Nameserver) {
+        self.convert_object(&mut nameserver.object_common);
+    }
+
+    fn convert_network(&self, network: &mut Network) {
+        self.convert_object(&mut network.object_common);
+    }
+
+    /// Converts the entities of an object. Without jCards, the paths of the
+    /// redactions of the object into them are given into the JSContact cards.
+    fn convert_object(&self, object_common: &mut ObjectCommon) {
+        for entity in object_common.entities.iter_mut().flatten() {
+            self.convert_entity(entity);
+        }
+        if !self.jcard {
+            for redacted in object_common.redacted.iter_mut().flatten() {
+                for path in [&mut redacted.pre_path, &mut redacted.post_path] {
+                    if let Some(jscard_path) = path.as_deref().and_then(jscard_path) {
+                        *path = Some(jscard_path);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Moves the JSContact card of an entity into its jCard, unless it has one. The
+/// card is regenerated from the jCard when JSContact is given, such as once the
+/// jCard has been redacted.
+pub(crate) fn vcard_from_jscard(entity: &mut Entity) {
+    if let Some(jscard) = entity.jscard.take() {
+        entity
+            .vcard_array
+            .get_or_insert_with(|| Contact::from_jscard(&jscard).to_vcard());
+    }
+}
+
+/// Gets the path into the JSContact card of an entity of a path into a property
+/// of its jCard, as given by redactions, or into the values of the property if
+/// followed by `[3]`.
+fn jscard_path(path: &str) -> Option<String> {
+    let (entity, rest) = path.split_once(".vcardArray[1][?(@[0]=='")?;
+    let (property, rest) = rest.split_once("')]")?;
+    let member = match (property, rest) {
+        ("fn", "" | "[3]") => "name.full",
+        ("org", "") => "organizations",
+        ("org", "[3]") => "organizations.*.name",
+        ("adr", "") => "addresses",
+        ("adr", "[3]") => "addresses.*",
+        ("tel", "") => "phones",
+        ("tel", "[3]") => "phones.*.number",
+        ("email", "") => "emails",
+        ("email", "[3]") => "emails.*.address",
+        _ => return None,
+    };
+    Some(format!("{entity}.jscard.{member}"))
+}
+
+#[async_trait]
+impl FromRequestParts<DynServiceState> for Contacts {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &DynServiceState,
+    ) -> Result<Self, Self::Rejection> {
+        let jscard_only = Query::<JsCardParam>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(param)| param.jscard)
+            .is_some_and(|jscard| jscard == "1" || jscard.eq_ignore_ascii_case("true"));
+        Ok(Self::new(state.get_contact_format(), jscard_only))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        icann_rdap_common::{
+            contact::{Contact, Email},
+            prelude::ToResponse,
+            response::{Domain, Entity, ExtensionId, Nameserver, RdapResponse},
+        },
+        rstest::rstest,
+    };
+
+    use crate::{config::ContactFormat, rdap::redaction::RedactionPolicy};
+
+    use super::Contacts;
+
+    fn domain_with_jscard_registrant() -> RdapResponse {
+        let contact = Contact::builder()
+            .full_name("Joe User")
+            .emails(vec![Email::builder().email("joe@example.com").build()])
+            .build();
+        let mut registrant = Entity::builder().handle("foo").role("registrant").build();
+        registrant.jscard = Some(contact.to_jscard());
+        Domain::builder()
+            .ldh_name("foo.example")
+            .entity(registrant)
+            .build()
+            .to_response()
+    }
+
+    fn domain_with_registrant() -> RdapResponse {
+        let registrant = Entity::builder()
+            .handle("foo")
+            .role("registrant")
+            .contact(Contact::builder().full_name("Joe User").build())
+            .build();
+        Domain::builder()
+            .ldh_name("foo.example")
+            .entity(registrant)
+            .build()
+            .to_response()
+    }
+
+    #[rstest]
+    #[case(ContactFormat::JCard, false, true, false)]
+    #[case(ContactFormat::JCard, true, true, false)]
+    #[case(ContactFormat::JsContact, false, false, true)]
+    #[case(ContactFormat::Both, false, true, true)]
+    #[case(ContactFormat::Both, true, false, true)]
+    fn GIVEN_contact_format_WHEN_apply_THEN_entities_have_formats(
+        #[case] format: ContactFormat,
+        #[case] jscard_only: bool,
+        #[case] expect_jcard: bool,
+        #[case] expect_jscontact: bool,
+    ) {
+        // GIVEN
+        let contacts = Contacts::new(format, jscard_only);
+
+        // WHEN
+        let actual = contacts.apply(domain_with_registrant());
+
+        // THEN
+        assert_eq!(
+            actual.has_extension_id(ExtensionId::JsContactLevel0),
+            expect_jscontact
+        );
+        let RdapResponse::Domain(domain) = actual else {
+            panic!("not a domain")
+        };
+        let registrant = &domain.object_common.entities.as_ref().expect("entities")[0];
+        assert_eq!(registrant.vcard_array.is_some(), expect_jcard);
+        assert_eq!(registrant.jscard.is_some(), expect_jscontact);
+        if let Some(jscard) = &registrant.jscard {
+            assert_eq!(jscard.uid.as_deref(), Some("foo"));
+            assert_eq!(
+                jscard.name.as_ref().and_then(|name| name.full.as_deref()),
+                Some("Joe User")
+            );
+        }
+    }
+
+    #[test]
+    fn GIVEN_registrant_with_jscard_WHEN_apply_jcard_THEN_jscard_given_as_jcard() {
+        // GIVEN
+        let contacts = Contacts::new(ContactFormat::JCard, false);
+
+        // WHEN
+        let actual = contacts.apply(domain_with_jscard_registrant());
+
+        // THEN
+        let RdapResponse::Domain(domain) = actual else {
+            panic!("not a domain")
+        };
+        let registrant = &domain.object_common.entities.as_ref().expect("entities")[0];
+        assert!(registrant.jscard.is_none());
+        assert!(registrant.vcard_array.is_some());
+        let contact = registrant.contact().expect("registrant contact");
+        assert_eq!(contact.full_name.as_deref(), Some("Joe User"));
+    }
+
+    #[test]
+    fn GIVEN_registrant_with_jscard_and_policy_WHEN_redact_and_apply_jscontact_THEN_jscard_redacted(
+    ) {
+        // GIVEN
+        let policy = RedactionPolicy::from_json(
+            r#"{"rules": [{"objectClassName": "entity", "role": "registrant", "field": "email",
+                "method": "removal", "name": "Registrant Email"}]}"#,
+        )
+        .expect("parsing policy");
+        let contacts = Contacts::new(ContactFormat::JsContact, false);
+
+        // WHEN
+        let actual = contacts.apply(policy.redact(domain_with_jscard_registrant()));
+
+        // THEN
+        let RdapResponse::Domain(domain) = actual else {
+            panic!("not a domain")
+        };
+        let registrant = &domain.object_common.entities.as_ref().expect("entities")[0];
+        assert!(registrant.vcard_array.is_none());
+        let jscard = registrant.jscard.as_ref().expect("jscard");
+        assert!(jscard.emails.is_none());
+        assert_eq!(
+            jscard.name.as_ref().and_then(|name| name.full.as_deref()),
+            Some("Joe User")
+        );
+        let redacted = domain.object_common.redacted.as_ref().expect("redacted");
+        assert_eq!(
+            redacted[0].pre_path.as_deref(),
+            Some("$.entities[0].jscard.emails")
+        );
+    }
+
+    #[test]
+    fn GIVEN_nested_entity_with_jscard_and_policy_WHEN_redact_and_apply_jscontact_THEN_jscard_redacted(
+    ) {
+        // GIVEN
+        let policy = RedactionPolicy::from_json(
+            r#"{"rules": [{"objectClassName": "entity", "role": "abuse", "field": "email",
+                "method": "removal", "name": "Abuse Email"}]}"#,
+        )
+        .expect("parsing policy");
+        let mut abuse = Entity::builder().handle("bar").role("abuse").build();
+        abuse.jscard = Some(
+            Contact::builder()
+                .full_name("Joe Abuse")
+                .emails(vec![Email::builder().email("abuse@example.com").build()])
+                .build()
+                .to_jscard(),
+        );
+        let registrar = Entity::builder()
+            .handle("foo")
+            .role("registrar")
+            .entity(abuse)
+            .build();
+        let nameserver = Nameserver::builder()
+            .ldh_name("ns1.foo.example")
+            .entity(registrar)
+            .build()
+            .expect("nameserver");
+        let domain = Domain::builder()
+            .ldh_name("foo.example")
+            .nameservers(vec![nameserver])
+            .build()
+            .to_response();
+        let contacts = Contacts::new(ContactFormat::JsContact, false);
+
+        // WHEN
+        let actual = contacts.apply(policy.redact(domain));
+
+        // THEN
+        let RdapResponse::Domain(domain) = actual else {
+            panic!("not a domain")
+        };
+        let nameservers = domain.nameservers.as_ref().expect("nameservers");
+        let registrar = &nameservers[0]
+            .object_common
+            .entities
+            .as_ref()
+            .expect("entities")[0];
+        let abuse = &registrar.object_common.entities.as_ref().expect("entities")[0];
+        let jscard = abuse.jscard.as_ref().expect("jscard");
+        assert!(jscard.emails.is_none());
+        assert_eq!(
+            jscard.name.as_ref().and_then(|name| name.full.as_deref()),
+            Some("Joe Abuse")
+        );
+        let redacted = domain.object_common.redacted.as_ref().expect("redacted");
+        assert_eq!(
+            redacted[0].pre_path.as_deref(),
+            Some("$.nameservers[0].entities[0].entities[0].jscard.emails")
+        );
+    }
+
+    #[rstest]
+    #[case("$.vcardArray[1][?(@[0]=='fn')][3]", Some("$.jscard.name.full"))]
+    #[case(
+        "$.entities[1].vcardArray[1][?(@[0]=='email')]",
+        Some("$.entities[1].jscard.emails")
+    )]
+    #[case(
+        "$.entities[0].vcardArray[1][?(@[0]=='tel')][3]",
+        Some("$.entities[0].jscard.phones.*.number")
+    )]
+    #[case("$.entities[0].handle", None)]
+    fn GIVEN_redacted_path_WHEN_jscard_path_THEN_path_into_jscard(
+        #[case] path: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        // WHEN
+        let actual = super::jscard_path(path);
+
+        // THEN
+        assert_eq!(actual.as_deref(), expected);
+    }
+}
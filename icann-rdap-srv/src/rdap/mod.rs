@@ -1,17 +1,46 @@
-use icann_rdap_common::{
-    prelude::ToResponse,
-    response::{RdapResponse, Rfc9083Error},
+use {
+    http::Uri,
+    icann_rdap_common::{
+        prelude::ToResponse,
+        response::{Common, ExtensionId, RdapResponse, Rfc9083Error},
+    },
+    idna::AsciiDenyList,
 };
 
 pub mod autnum;
+pub mod autnums;
+pub mod conditional;
 pub mod domain;
 pub mod domains;
+pub mod entities;
 pub mod entity;
 pub mod ip;
+pub mod ips;
+pub mod jscontact;
 pub mod nameserver;
+pub mod nameservers;
+pub mod paging;
+pub mod redaction;
 pub mod response;
+pub mod reverse_search;
+pub mod rir_search;
 pub mod router;
+pub mod search;
+pub mod sorting;
 pub mod srvhelp;
+pub mod subsetting;
+
+/// Gets the first segment of the path of a request after `rdap`, which names
+/// the lookup or search requested. Tenants served by path prefix have their
+/// prefix before `rdap`.
+pub(crate) fn first_rdap_segment(path: &str) -> &str {
+    let mut segments = path.trim_start_matches('/').split('/');
+    let first = segments.clone().next().unwrap_or_default();
+    segments
+        .position(|segment| segment == "rdap")
+        .and_then(|_| segments.next())
+        .unwrap_or(first)
+}
 
 trait ToBootStrap {
     fn to_ip_bootstrap(self, ip_id: &str) -> RdapResponse;
@@ -78,3 +107,127 @@ fn bootstrap_redirect(error: Rfc9083Error, path: &str, id: &str) -> RdapResponse
     let redirect = Rfc9083Error::redirect().url(href).build();
     redirect.to_response()
 }
+
+/// Adds the identifier of an extension to the RDAP conformance if not already there.
+pub(crate) fn add_conformance(common: &mut Common, extension_id: ExtensionId) {
+    let extension = extension_id.to_extension();
+    let conformance = common.rdap_conformance.get_or_insert_with(Vec::new);
+    if !conformance.contains(&extension) {
+        conformance.push(extension);
+    }
+}
+
+/// Creates the reference of the request with the given query parameters removed
+/// and the named parameter set to the value.
+///
+/// The reference is relative to the server so that it does not depend on the
+/// scheme and host the server is reached by.
+pub(crate) fn replace_query_param(uri: &Uri, remove: &[&str], name: &str, value: &str) -> String {
+    let mut params = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| {
+            !param.is_empty()
+                && param
+                    .split('=')
+                    .next()
+                    .is_none_or(|n| n != name && !remove.contains(&n))
+        })
+        .collect::<Vec<&str>>();
+    let param = format!("{name}={value}");
+    params.push(&param);
+    format!("{}?{}", uri.path(), params.join("&"))
+}
+
+/// The forms of a domain or nameserver name given in the path of a request.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct DomainName {
+    /// The name with A-labels, as used by `ldhName`.
+    pub(crate) ldh: String,
+
+    /// The name with U-labels, as used by `unicodeName`.
+    pub(crate) unicode: String,
+}
+
+/// Normalizes a domain or nameserver name with UTS 46 processing (case folding,
+/// NFC normalization, and conversion between A-labels and U-labels) so that every
+/// form of a name finds the same object. A trailing "." and surrounding whitespace
+/// are removed.
+///
+/// Returns none if the name is not a valid IDNA name.
+pub(crate) fn normalize_domain_name(name: &str) -> Option<DomainName> {
+    let name = name.trim().trim_end_matches('.');
+    let ldh = idna::domain_to_ascii_cow(name.as_bytes(), AsciiDenyList::URL).ok()?;
+    let (unicode, result) = idna::domain_to_unicode(&ldh);
+    result.ok()?;
+    Some(DomainName {
+        ldh: ldh.into_owned(),
+        unicode,
+    })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{first_rdap_segment, normalize_domain_name};
+
+    #[rstest]
+    #[case("/rdap/domain/foo.example", "domain")]
+    #[case("/example/rdap/domains", "domains")]
+    #[case("/myrdap/rdap/ips", "ips")]
+    #[case("/ardap/rdap/domain/rdap.example", "domain")]
+    #[case("/rdap/domain/rdap", "domain")]
+    #[case("/help", "help")]
+    #[case("/", "")]
+    fn GIVEN_path_WHEN_first_rdap_segment_THEN_segment_after_rdap(
+        #[case] path: &str,
+        #[case] expected: &str,
+    ) {
+        // GIVEN in parameters
+
+        // WHEN
+        let actual = first_rdap_segment(path);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("foo.example", "foo.example", "foo.example")]
+    #[case(" FOO.Example. ", "foo.example", "foo.example")]
+    #[case("café.example", "xn--caf-dma.example", "café.example")]
+    #[case("CAFÉ.example", "xn--caf-dma.example", "café.example")]
+    #[case("cafe\u{0301}.example", "xn--caf-dma.example", "café.example")]
+    #[case("XN--CAF-DMA.example", "xn--caf-dma.example", "café.example")]
+    fn GIVEN_name_WHEN_normalize_THEN_ldh_and_unicode_forms(
+        #[case] name: &str,
+        #[case] expected_ldh: &str,
+        #[case] expected_unicode: &str,
+    ) {
+        // GIVEN in parameters
+
+        // WHEN
+        let actual = normalize_domain_name(name).expect("normalizing name");
+
+        // THEN
+        assert_eq!(actual.ldh, expected_ldh);
+        assert_eq!(actual.unicode, expected_unicode);
+    }
+
+    #[rstest]
+    #[case("xn--a.example")]
+    #[case("foo/bar.example")]
+    #[case("xn--caf-dma\u{0301}.example")]
+    fn GIVEN_invalid_name_WHEN_normalize_THEN_none(#[case] name: &str) {
+        // GIVEN in parameters
+
+        // WHEN
+        let actual = normalize_domain_name(name);
+
+        // THEN
+        assert!(actual.is_none());
+    }
+}
@@ -8,15 +8,25 @@ use {
 
 use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
 
-use super::{response::BAD_REQUEST, ToBootStrap};
+use super::{
+    jscontact::Contacts, normalize_domain_name, redaction::Redaction, response::BAD_REQUEST,
+    ToBootStrap,
+};
 
-/// Gets a nameserver object by the name path.
+/// Gets a nameserver object by the name path, which can be either A-label or U-label.
+/// Names that are not valid IDNA names are bad requests.
 #[axum_macros::debug_handler]
 #[tracing::instrument(level = "debug")]
 pub(crate) async fn nameserver_by_name(
     Path(ns_name): Path<String>,
+    redaction: Redaction,
+    contacts: Contacts,
     state: State<DynServiceState>,
 ) -> Result<Response, RdapServerError> {
+    let Some(ns_name) = normalize_domain_name(&ns_name) else {
+        return Ok(BAD_REQUEST.response());
+    };
+    let ns_name = ns_name.ldh;
     let count = ns_name.chars().filter(|c| *c == '.').count();
     // if the nameserver name does not have at least 2 'dot' characters, return bad request.
     if count < 2 {
@@ -42,5 +52,5 @@ pub(crate) async fn nameserver_by_name(
         }
     }
 
-    Ok(nameserver.response())
+    Ok(contacts.apply(redaction.apply(nameserver)).response())
 }
@@ -0,0 +1,71 @@
+use std::net::IpAddr;
+
+use axum::{
+    extract::{OriginalUri, Query, State},
+    response::Response,
+};
+
+use serde::Deserialize;
+
+use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
+
+use super::{
+    jscontact::Contacts,
+    redaction::Redaction,
+    response::{BAD_REQUEST, NOT_IMPLEMENTED},
+    search::{search_page, search_response},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NameserversParams {
+    name: Option<String>,
+
+    ip: Option<String>,
+
+    sort: Option<String>,
+
+    cursor: Option<String>,
+
+    #[serde(rename = "fieldSet")]
+    field_set: Option<String>,
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(level = "debug")]
+pub(crate) async fn nameservers(
+    Query(params): Query<NameserversParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
+    state: State<DynServiceState>,
+) -> Result<Response, RdapServerError> {
+    let storage = state.get_storage().await?;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "nameserver",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = if let Some(name) = params.name {
+        storage.search_nameservers_by_name(&name, &page).await
+    } else if let Some(ip) = params.ip {
+        let Ok(ip) = ip.parse::<IpAddr>() else {
+            return Ok(BAD_REQUEST.response());
+        };
+        storage.search_nameservers_by_ip(ip, &page).await
+    } else {
+        return Ok(NOT_IMPLEMENTED.response());
+    };
+    search_response(
+        results,
+        &redaction,
+        &contacts,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        params.field_set.as_deref(),
+        &uri,
+        page_size,
+    )
+}
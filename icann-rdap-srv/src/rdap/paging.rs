@@ -0,0 +1,268 @@
+use {
+    http::Uri,
+    icann_rdap_common::{
+        media_types::RDAP_MEDIA_TYPE,
+        response::{ExtensionId, Link, PagingMetadata, RdapResponse},
+    },
+};
+
+use crate::error::RdapServerError;
+
+use super::{add_conformance, replace_query_param};
+
+/// The query parameter of the RFC 8977 paging cursor.
+pub(crate) const CURSOR_PARAM: &str = "cursor";
+
+/// Pages search results as specified in RFC 8977.
+///
+/// The results are expected to be in a stable order, such as given by
+/// [super::sorting::sort_search_results], so that the pages are the same
+/// across requests. Results already paged by storage, which have paging
+/// metadata with the count of all of them, are kept as they are. The cursor is
+/// the opaque position of the first result of a page, and an invalid cursor is
+/// an [RdapServerError::InvalidArg]. Responses other than search results are
+/// returned as they are.
+pub(crate) fn page_search_results(
+    results: RdapResponse,
+    cursor: Option<&str>,
+    uri: &Uri,
+    page_size: usize,
+) -> Result<RdapResponse, RdapServerError> {
+    let offset = match cursor {
+        Some(cursor) => cursor_offset(cursor)?,
+        None => 0,
+    };
+    let paged = match results {
+        RdapResponse::DomainSearchResults(mut search) => {
+            let (page, paging_metadata) = page(
+                search.results,
+                search.paging_metadata.take(),
+                offset,
+                uri,
+                page_size,
+            );
+            search.results = page;
+            search.paging_metadata = Some(paging_metadata);
+            add_conformance(&mut search.common, ExtensionId::Paging);
+            RdapResponse::DomainSearchResults(search)
+        }
+        RdapResponse::NameserverSearchResults(mut search) => {
+            let (page, paging_metadata) = page(
+                search.results,
+                search.paging_metadata.take(),
+                offset,
+                uri,
+                page_size,
+            );
+            search.results = page;
+            search.paging_metadata = Some(paging_metadata);
+            add_conformance(&mut search.common, ExtensionId::Paging);
+            RdapResponse::NameserverSearchResults(search)
+        }
+        RdapResponse::EntitySearchResults(mut search) => {
+            let (page, paging_metadata) = page(
+                search.results,
+                search.paging_metadata.take(),
+                offset,
+                uri,
+                page_size,
+            );
+            search.results = page;
+            search.paging_metadata = Some(paging_metadata);
+            add_conformance(&mut search.common, ExtensionId::Paging);
+            RdapResponse::EntitySearchResults(search)
+        }
+        RdapResponse::NetworkSearchResults(mut search) => {
+            let (page, paging_metadata) = page(
+                search.results,
+                search.paging_metadata.take(),
+                offset,
+                uri,
+                page_size,
+            );
+            search.results = page;
+            search.paging_metadata = Some(paging_metadata);
+            add_conformance(&mut search.common, ExtensionId::Paging);
+            RdapResponse::NetworkSearchResults(search)
+        }
+        RdapResponse::AutnumSearchResults(mut search) => {
+            let (page, paging_metadata) = page(
+                search.results,
+                search.paging_metadata.take(),
+                offset,
+                uri,
+                page_size,
+            );
+            search.results = page;
+            search.paging_metadata = Some(paging_metadata);
+            add_conformance(&mut search.common, ExtensionId::Paging);
+            RdapResponse::AutnumSearchResults(search)
+        }
+        _ => results,
+    };
+    Ok(paged)
+}
+
+/// Takes one page of results starting at the offset, unless storage has paged them.
+fn page<T>(
+    results: Vec<T>,
+    paged: Option<PagingMetadata>,
+    offset: usize,
+    uri: &Uri,
+    page_size: usize,
+) -> (Vec<T>, PagingMetadata) {
+    let (page, total_count) = match paged.and_then(|paged| paged.total_count) {
+        Some(total_count) => (results, total_count as usize),
+        None => {
+            let total_count = results.len();
+            let page = results
+                .into_iter()
+                .skip(offset)
+                .take(page_size)
+                .collect::<Vec<T>>();
+            (page, total_count)
+        }
+    };
+    let next_offset = offset.saturating_add(page_size);
+    let links = if next_offset < total_count {
+        vec![Link::builder()
+            .value(uri.to_string())
+            .rel("next")
+            .href(replace_query_param(
+                uri,
+                &[],
+                CURSOR_PARAM,
+                &encode_cursor(next_offset),
+            ))
+            .media_type(RDAP_MEDIA_TYPE)
+            .build()]
+    } else {
+        vec![]
+    };
+    let paging_metadata = PagingMetadata::builder()
+        .total_count(total_count as u64)
+        .page_size(page_size as u64)
+        .page_number((offset / page_size.max(1)) as u64 + 1)
+        .links(links)
+        .build();
+    (page, paging_metadata)
+}
+
+fn encode_cursor(offset: usize) -> String {
+    format!("{offset:x}")
+}
+
+/// Gets the offset of the first result of the page of a cursor.
+pub(crate) fn cursor_offset(cursor: &str) -> Result<usize, RdapServerError> {
+    usize::from_str_radix(cursor, 16)
+        .map_err(|_| RdapServerError::InvalidArg(format!("paging cursor '{cursor}' is invalid")))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        http::Uri,
+        icann_rdap_common::{
+            prelude::ToResponse,
+            response::{Domain, DomainSearchResults, ExtensionId, RdapResponse},
+        },
+        rstest::rstest,
+    };
+
+    use crate::rdap::replace_query_param;
+
+    use super::{page_search_results, CURSOR_PARAM};
+
+    fn domain_search_results(count: usize) -> RdapResponse {
+        DomainSearchResults::builder()
+            .results(
+                (0..count)
+                    .map(|i| {
+                        Domain::builder()
+                            .ldh_name(format!("foo{i:02}.example"))
+                            .build()
+                    })
+                    .collect::<Vec<Domain>>(),
+            )
+            .build()
+            .to_response()
+    }
+
+    #[test]
+    fn GIVEN_more_results_than_page_size_WHEN_page_THEN_first_page_and_next_link() {
+        // GIVEN
+        let results = domain_search_results(5);
+        let uri = Uri::from_static("/rdap/domains?name=foo*");
+
+        // WHEN
+        let actual = page_search_results(results, None, &uri, 2).expect("paging results");
+
+        // THEN
+        assert!(actual.has_extension_id(ExtensionId::Paging));
+        let RdapResponse::DomainSearchResults(search) = actual else {
+            panic!("not domain search results")
+        };
+        assert_eq!(search.results.len(), 2);
+        assert_eq!(search.results[0].ldh_name.as_deref(), Some("foo00.example"));
+        let paging = search.paging_metadata.expect("no paging metadata");
+        assert_eq!(paging.total_count, Some(5));
+        assert_eq!(paging.page_size, Some(2));
+        assert_eq!(paging.page_number, Some(1));
+        assert_eq!(
+            paging.next_link().and_then(|l| l.href.as_deref()),
+            Some("/rdap/domains?name=foo*&cursor=2")
+        );
+    }
+
+    #[test]
+    fn GIVEN_cursor_of_last_page_WHEN_page_THEN_last_page_and_no_next_link() {
+        // GIVEN
+        let results = domain_search_results(5);
+        let uri = Uri::from_static("/rdap/domains?name=foo*&cursor=4");
+
+        // WHEN
+        let actual = page_search_results(results, Some("4"), &uri, 2).expect("paging results");
+
+        // THEN
+        let RdapResponse::DomainSearchResults(search) = actual else {
+            panic!("not domain search results")
+        };
+        assert_eq!(search.results.len(), 1);
+        assert_eq!(search.results[0].ldh_name.as_deref(), Some("foo04.example"));
+        let paging = search.paging_metadata.expect("no paging metadata");
+        assert_eq!(paging.page_number, Some(3));
+        assert!(paging.next_link().is_none());
+    }
+
+    #[test]
+    fn GIVEN_invalid_cursor_WHEN_page_THEN_error() {
+        // GIVEN
+        let results = domain_search_results(5);
+        let uri = Uri::from_static("/rdap/domains?name=foo*&cursor=zz");
+
+        // WHEN
+        let actual = page_search_results(results, Some("zz"), &uri, 2);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+
+    #[rstest]
+    #[case("/rdap/domains?name=foo*", "/rdap/domains?name=foo*&cursor=a")]
+    #[case("/rdap/domains?cursor=2&name=foo*", "/rdap/domains?name=foo*&cursor=a")]
+    #[case("/rdap/domains", "/rdap/domains?cursor=a")]
+    fn GIVEN_uri_WHEN_replace_cursor_THEN_cursor_is_replaced(
+        #[case] uri: &'static str,
+        #[case] expected: &str,
+    ) {
+        // GIVEN
+        let uri = Uri::from_static(uri);
+
+        // WHEN
+        let actual = replace_query_param(&uri, &[], CURSOR_PARAM, "a");
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,801 @@
+use std::{convert::Infallible, sync::Arc};
+
+use {
+    async_trait::async_trait,
+    axum::extract::FromRequestParts,
+    headers::{
+        authorization::{Basic, Bearer},
+        Authorization, HeaderMapExt,
+    },
+    http::{request::Parts, HeaderMap},
+    icann_rdap_common::{
+        response::{
+            redacted::{Method, Name, Reason, Redacted},
+            Autnum, Domain, Entity, ExtensionId, Nameserver, Network, ObjectCommon, RdapResponse,
+        },
+        search_types::RelatedEntityProperty,
+    },
+    serde::Deserialize,
+    serde_json::Value,
+};
+
+use crate::{
+    config::RedactionConfig, error::RdapServerError, server::DynServiceState, util::is_secret,
+};
+
+use super::{add_conformance, jscontact::vcard_from_jscard};
+
+/// The fields of objects that a redaction policy may redact.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RedactedField {
+    /// The handle of any object class.
+    Handle,
+    /// The formatted name in the jCard of an entity.
+    Fn,
+    /// The organization name in the jCard of an entity.
+    Org,
+    /// The postal address in the jCard of an entity.
+    Adr,
+    /// The telephone numbers in the jCard of an entity.
+    Tel,
+    /// The email addresses in the jCard of an entity.
+    Email,
+}
+
+impl From<RelatedEntityProperty> for RedactedField {
+    fn from(property: RelatedEntityProperty) -> Self {
+        match property {
+            RelatedEntityProperty::Handle => Self::Handle,
+            RelatedEntityProperty::FullName => Self::Fn,
+            RelatedEntityProperty::Email => Self::Email,
+        }
+    }
+}
+
+impl RedactedField {
+    /// Gets the name of the jCard property of the field, if it is one.
+    fn vcard_property(&self) -> Option<&'static str> {
+        match self {
+            Self::Handle => None,
+            Self::Fn => Some("fn"),
+            Self::Org => Some("org"),
+            Self::Adr => Some("adr"),
+            Self::Tel => Some("tel"),
+            Self::Email => Some("email"),
+        }
+    }
+}
+
+/// A rule of a redaction policy.
+///
+/// ```json
+/// {
+///   "objectClassName": "entity",
+///   "role": "registrant",
+///   "field": "email",
+///   "method": "replacementValue",
+///   "replacement": "https://example.com/contact-registrant",
+///   "name": "Registrant Email",
+///   "reason": "Server policy"
+/// }
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionRule {
+    /// The class of the objects the rule applies to.
+    pub object_class_name: String,
+
+    /// If given, the rule only applies to entities with this role, and to
+    /// entities without roles, such as those looked up by their handle.
+    pub role: Option<String>,
+
+    /// The field that is redacted.
+    pub field: RedactedField,
+
+    /// How the field is redacted. Partial values are not supported.
+    pub method: Method,
+
+    /// The value replacing the field when the method is `replacementValue`.
+    pub replacement: Option<String>,
+
+    /// The name of the redaction given in the `redacted` member.
+    pub name: String,
+
+    /// The reason of the redaction given in the `redacted` member.
+    pub reason: Option<String>,
+}
+
+/// A policy of RFC 9537 redactions applied to responses for anonymous callers.
+///
+/// The policy applies to the object of a response, or to each object of search
+/// results, and to all the objects and entities within them. The JSContact
+/// cards of the entities are redacted as jCards.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RedactionPolicy {
+    pub rules: Vec<RedactionRule>,
+}
+
+impl RedactionPolicy {
+    /// Parses and validates a policy from JSON.
+    pub fn from_json(json: &str) -> Result<Self, RdapServerError> {
+        let policy = serde_json::from_str::<Self>(json)?;
+        for rule in &policy.rules {
+            if rule.field.vcard_property().is_some() && rule.object_class_name != "entity" {
+                return Err(RdapServerError::Config(format!(
+                    "redaction '{}' of a jCard field must be for the entity object class",
+                    rule.name
+                )));
+            }
+            match rule.method {
+                Method::PartialValue => {
+                    return Err(RdapServerError::Config(format!(
+                        "redaction '{}' by partial value is not supported",
+                        rule.name
+                    )))
+                }
+                Method::ReplacementValue
+                    if rule.replacement.is_none() || rule.field == RedactedField::Adr =>
+                {
+                    return Err(RdapServerError::Config(format!(
+                        "redaction '{}' by replacement value needs a replacement of a text field",
+                        rule.name
+                    )))
+                }
+                _ => {}
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Redacts the objects of a response.
+    pub(crate) fn redact(&self, response: RdapResponse) -> RdapResponse {
+        match response {
+            RdapResponse::Entity(mut entity) => {
+                let mut redactions = vec![];
+                self.redact_entity(&mut entity, "$", &mut redactions);
+                if add_redactions(&mut entity.object_common, redactions) {
+                    add_conformance(&mut entity.common, ExtensionId::Redacted);
+                }
+                RdapResponse::Entity(entity)
+            }
+            RdapResponse::Domain(mut domain) => {
+                let mut redactions = vec![];
+                self.redact_domain(&mut domain, "$", &mut redactions);
+                if add_redactions(&mut domain.object_common, redactions) {
+                    add_conformance(&mut domain.common, ExtensionId::Redacted);
+                }
+                RdapResponse::Domain(domain)
+            }
+            RdapResponse::Nameserver(mut nameserver) => {
+                let mut redactions = vec![];
+                self.redact_nameserver(&mut nameserver, "$", &mut redactions);
+                if add_redactions(&mut nameserver.object_common, redactions) {
+                    add_conformance(&mut nameserver.common, ExtensionId::Redacted);
+                }
+                RdapResponse::Nameserver(nameserver)
+            }
+            RdapResponse::Autnum(mut autnum) => {
+                let mut redactions = vec![];
+                self.redact_autnum(&mut autnum, "$", &mut redactions);
+                if add_redactions(&mut autnum.object_common, redactions) {
+                    add_conformance(&mut autnum.common, ExtensionId::Redacted);
+                }
+                RdapResponse::Autnum(autnum)
+            }
+            RdapResponse::Network(mut network) => {
+                let mut redactions = vec![];
+                self.redact_network(&mut network, "$", &mut redactions);
+                if add_redactions(&mut network.object_common, redactions) {
+                    add_conformance(&mut network.common, ExtensionId::Redacted);
+                }
+                RdapResponse::Network(network)
+            }
+            RdapResponse::DomainSearchResults(mut search) => {
+                let mut redacted = false;
+                for domain in search.results.iter_mut() {
+                    let mut redactions = vec![];
+                    self.redact_domain(domain, "$", &mut redactions);
+                    redacted |= add_redactions(&mut domain.object_common, redactions);
+                }
+                if redacted {
+                    add_conformance(&mut search.common, ExtensionId::Redacted);
+                }
+                RdapResponse::DomainSearchResults(search)
+            }
+            RdapResponse::NameserverSearchResults(mut search) => {
+                let mut redacted = false;
+                for nameserver in search.results.iter_mut() {
+                    let mut redactions = vec![];
+                    self.redact_nameserver(nameserver, "$", &mut redactions);
+                    redacted |= add_redactions(&mut nameserver.object_common, redactions);
+                }
+                if redacted {
+                    add_conformance(&mut search.common, ExtensionId::Redacted);
+                }
+                RdapResponse::NameserverSearchResults(search)
+            }
+            RdapResponse::NetworkSearchResults(mut search) => {
+                let mut redacted = false;
+                for network in search.results.iter_mut() {
+                    let mut redactions = vec![];
+                    self.redact_network(network, "$", &mut redactions);
+                    redacted |= add_redactions(&mut network.object_common, redactions);
+                }
+                if redacted {
+                    add_conformance(&mut search.common, ExtensionId::Redacted);
+                }
+                RdapResponse::NetworkSearchResults(search)
+            }
+            RdapResponse::AutnumSearchResults(mut search) => {
+                let mut redacted = false;
+                for autnum in search.results.iter_mut() {
+                    let mut redactions = vec![];
+                    self.redact_autnum(autnum, "$", &mut redactions);
+                    redacted |= add_redactions(&mut autnum.object_common, redactions);
+                }
+                if redacted {
+                    add_conformance(&mut search.common, ExtensionId::Redacted);
+                }
+                RdapResponse::AutnumSearchResults(search)
+            }
+            RdapResponse::EntitySearchResults(mut search) => {
+                let mut redacted = false;
+                for entity in search.results.iter_mut() {
+                    let mut redactions = vec![];
+                    self.redact_entity(entity, "$", &mut redactions);
+                    redacted |= add_redactions(&mut entity.object_common, redactions);
+                }
+                if redacted {
+                    add_conformance(&mut search.common, ExtensionId::Redacted);
+                }
+                RdapResponse::EntitySearchResults(search)
+            }
+            _ => response,
+        }
+    }
+
+    /// Redacts an entity found at the path and the objects within it. Its
+    /// JSContact card is folded into its jCard first, so that it is redacted too.
+    ///
+    /// The roles of an entity are only known when it is related to another
+    /// object, so the rules of every role apply to an entity without roles.
+    fn redact_entity(&self, entity: &mut Entity, path: &str, redactions: &mut Vec<Redacted>) {
+        vcard_from_jscard(entity);
+        let roles = entity.roles().clone();
+        self.redact_fields(
+            "entity",
+            &roles,
+            path,
+            &mut entity.object_common.handle,
+            entity.vcard_array.as_mut(),
+            redactions,
+        );
+        self.redact_entities(&mut entity.object_common, path, redactions);
+        for (i, autnum) in entity.autnums.iter_mut().flatten().enumerate() {
+            self.redact_autnum(autnum, &format!("{path}.autnums[{i}]"), redactions);
+        }
+        for (i, network) in entity.networks.iter_mut().flatten().enumerate() {
+            self.redact_network(network, &format!("{path}.networks[{i}]"), redactions);
+        }
+    }
+
+    fn redact_domain(&self, domain: &mut Domain, path: &str, redactions: &mut Vec<Redacted>) {
+        self.redact_common(&mut domain.object_common, path, redactions);
+        for (i, nameserver) in domain.nameservers.iter_mut().flatten().enumerate() {
+            self.redact_nameserver(nameserver, &format!("{path}.nameservers[{i}]"), redactions);
+        }
+        if let Some(network) = domain.network.as_mut() {
+            self.redact_network(network, &format!("{path}.network"), redactions);
+        }
+    }
+
+    fn redact_nameserver(
+        &self,
+        nameserver: &mut Nameserver,
+        path: &str,
+        redactions: &mut Vec<Redacted>,
+    ) {
+        self.redact_common(&mut nameserver.object_common, path, redactions);
+    }
+
+    fn redact_autnum(&self, autnum: &mut Autnum, path: &str, redactions: &mut Vec<Redacted>) {
+        self.redact_common(&mut autnum.object_common, path, redactions);
+    }
+
+    fn redact_network(&self, network: &mut Network, path: &str, redactions: &mut Vec<Redacted>) {
+        self.redact_common(&mut network.object_common, path, redactions);
+    }
+
+    /// Redacts the handle of an object other than an entity and its entities.
+    fn redact_common(
+        &self,
+        object_common: &mut ObjectCommon,
+        path: &str,
+        redactions: &mut Vec<Redacted>,
+    ) {
+        let object_class_name = object_common.object_class_name.clone();
+        self.redact_fields(
+            &object_class_name,
+            &[],
+            path,
+            &mut object_common.handle,
+            None,
+            redactions,
+        );
+        self.redact_entities(object_common, path, redactions);
+    }
+
+    fn redact_entities(
+        &self,
+        object_common: &mut ObjectCommon,
+        path: &str,
+        redactions: &mut Vec<Redacted>,
+    ) {
+        for (i, entity) in object_common.entities.iter_mut().flatten().enumerate() {
+            self.redact_entity(entity, &format!("{path}.entities[{i}]"), redactions);
+        }
+    }
+
+    /// Redacts the fields of one object found at the path with the rules that apply to it.
+    fn redact_fields(
+        &self,
+        object_class_name: &str,
+        roles: &[String],
+        path: &str,
+        handle: &mut Option<String>,
+        mut vcard_array: Option<&mut Vec<Value>>,
+        redactions: &mut Vec<Redacted>,
+    ) {
+        let rules = self.rules.iter().filter(|rule| {
+            rule.object_class_name == object_class_name
+                && rule.role.as_ref().is_none_or(|role| {
+                    roles.is_empty() || roles.iter().any(|r| r.eq_ignore_ascii_case(role))
+                })
+        });
+        for rule in rules {
+            let redacted = match rule.field.vcard_property() {
+                None => redact_handle(rule, handle, path),
+                Some(property) => vcard_array
+                    .as_deref_mut()
+                    .and_then(|vcard_array| redact_vcard(rule, vcard_array, property, path)),
+            };
+            redactions.extend(redacted);
+        }
+    }
+}
+
+/// Adds the redactions to the `redacted` member of an object.
+///
+/// Returns true if there were any.
+fn add_redactions(object_common: &mut ObjectCommon, mut redactions: Vec<Redacted>) -> bool {
+    if redactions.is_empty() {
+        return false;
+    }
+    object_common
+        .redacted
+        .get_or_insert_with(Vec::new)
+        .append(&mut redactions);
+    true
+}
+
+fn redact_handle(
+    rule: &RedactionRule,
+    handle: &mut Option<String>,
+    path: &str,
+) -> Option<Redacted> {
+    handle.as_ref()?;
+    let path = format!("{path}.handle");
+    match rule.method {
+        Method::Removal => *handle = None,
+        Method::EmptyValue => *handle = Some(String::new()),
+        _ => *handle = rule.replacement.clone(),
+    }
+    Some(redacted(rule, path))
+}
+
+fn redact_vcard(
+    rule: &RedactionRule,
+    vcard_array: &mut [Value],
+    property: &str,
+    path: &str,
+) -> Option<Redacted> {
+    let properties = vcard_array.get_mut(1)?.as_array_mut()?;
+    let is_property = |p: &Value| p.get(0).and_then(Value::as_str) == Some(property);
+    if !properties.iter().any(is_property) {
+        return None;
+    }
+    let property_path = format!("{path}.vcardArray[1][?(@[0]=='{property}')]");
+    match rule.method {
+        Method::Removal => {
+            properties.retain(|p| !is_property(p));
+            Some(redacted(rule, property_path))
+        }
+        Method::EmptyValue => {
+            properties
+                .iter_mut()
+                .filter(|p| is_property(p))
+                .filter_map(|p| p.get_mut(3))
+                .for_each(|value| *value = empty_value(value));
+            Some(redacted(rule, format!("{property_path}[3]")))
+        }
+        _ => {
+            let replacement = rule.replacement.clone().unwrap_or_default();
+            properties
+                .iter_mut()
+                .filter(|p| is_property(p))
+                .filter_map(|p| p.get_mut(3))
+                .for_each(|value| *value = Value::String(replacement.clone()));
+            Some(redacted(rule, format!("{property_path}[3]")))
+        }
+    }
+}
+
+/// Empties a jCard value, keeping the structure of structured values such as addresses.
+fn empty_value(value: &Value) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(values.iter().map(empty_value).collect()),
+        _ => Value::String(String::new()),
+    }
+}
+
+/// Creates the `redacted` entry of a rule. Removals give the path the field
+/// had before redaction, and the others give the path of the field after.
+fn redacted(rule: &RedactionRule, path: String) -> Redacted {
+    let (pre_path, post_path) = match rule.method {
+        Method::Removal => (Some(path), None),
+        _ => (None, Some(path)),
+    };
+    Redacted {
+        name: Name {
+            description: Some(rule.name.clone()),
+            type_field: None,
+        },
+        reason: rule.reason.as_ref().map(|reason| Reason {
+            description: Some(reason.clone()),
+            type_field: None,
+        }),
+        pre_path,
+        post_path,
+        path_lang: Some("jsonpath".to_string()),
+        replacement_path: None,
+        method: Some(rule.method.clone()),
+    }
+}
+
+/// The redaction to apply to the response of a request.
+///
+/// Anonymous callers get responses redacted according to the policy of the
+/// server, while callers with a configured API key, given as a bearer token,
+/// or with configured basic auth credentials get unredacted responses.
+pub(crate) struct Redaction(Option<Arc<RedactionPolicy>>);
+
+impl std::fmt::Debug for Redaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Redaction").field(&self.0.is_some()).finish()
+    }
+}
+
+impl Redaction {
+    /// Applies the redaction to a response.
+    pub(crate) fn apply(&self, response: RdapResponse) -> RdapResponse {
+        match &self.0 {
+            Some(policy) => policy.redact(response),
+            None => response,
+        }
+    }
+
+    /// True if searching entities, or objects by their related entities, by a
+    /// field of entities with the role could reveal values the redaction hides.
+    /// Without a role, rules of any role apply.
+    pub(crate) fn redacts_search(&self, field: RedactedField, role: Option<&str>) -> bool {
+        self.0.as_ref().is_some_and(|policy| {
+            policy.rules.iter().any(|rule| {
+                rule.object_class_name == "entity"
+                    && rule.field == field
+                    && rule
+                        .role
+                        .as_ref()
+                        .zip(role)
+                        .is_none_or(|(rule_role, role)| rule_role.eq_ignore_ascii_case(role))
+            })
+        })
+    }
+
+    /// True if the redaction may change the values of the default sort of
+    /// search results of an object class, which are the handles of entities.
+    pub(crate) fn redacts_default_sort(&self, object_class_name: &str) -> bool {
+        self.0.as_ref().is_some_and(|policy| {
+            object_class_name == "entity"
+                && policy.rules.iter().any(|rule| {
+                    rule.object_class_name == "entity" && rule.field == RedactedField::Handle
+                })
+        })
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<DynServiceState> for Redaction {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &DynServiceState,
+    ) -> Result<Self, Self::Rejection> {
+        let config = state.get_redaction();
+        let policy = config
+            .policy
+            .clone()
+            .filter(|_| !is_authenticated(config, &parts.headers));
+        Ok(Self(policy))
+    }
+}
+
+fn is_authenticated(config: &RedactionConfig, headers: &HeaderMap) -> bool {
+    if let Some(Authorization(bearer)) = headers.typed_get::<Authorization<Bearer>>() {
+        return is_secret(&config.api_keys, bearer.token());
+    }
+    if let Some(Authorization(basic)) = headers.typed_get::<Authorization<Basic>>() {
+        let credentials = format!("{}:{}", basic.username(), basic.password());
+        return is_secret(&config.basic_credentials, &credentials);
+    }
+    false
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        http::HeaderMap,
+        icann_rdap_common::{
+            contact::{Contact, Email},
+            prelude::ToResponse,
+            response::{redacted::Method, Domain, Entity, ExtensionId, Nameserver, RdapResponse},
+        },
+        rstest::rstest,
+        std::sync::Arc,
+    };
+
+    use crate::config::RedactionConfig;
+
+    use super::{is_authenticated, RedactionPolicy};
+
+    const POLICY: &str = r#"
+      {
+        "rules": [
+          {
+            "objectClassName": "entity",
+            "role": "registrant",
+            "field": "email",
+            "method": "removal",
+            "name": "Registrant Email"
+          },
+          {
+            "objectClassName": "entity",
+            "role": "registrant",
+            "field": "fn",
+            "method": "replacementValue",
+            "replacement": "REDACTED",
+            "name": "Registrant Name",
+            "reason": "Server policy"
+          },
+          {
+            "objectClassName": "domain",
+            "field": "handle",
+            "method": "emptyValue",
+            "name": "Registry Domain ID"
+          }
+        ]
+      }
+    "#;
+
+    fn contact() -> Contact {
+        Contact::builder()
+            .full_name("Bob Smurd")
+            .emails(vec![Email::builder().email("bob@example.com").build()])
+            .build()
+    }
+
+    fn domain() -> RdapResponse {
+        Domain::builder()
+            .ldh_name("foo.example")
+            .handle("FOO-1")
+            .entity(
+                Entity::builder()
+                    .handle("TECH-1")
+                    .contact(contact())
+                    .role("technical")
+                    .build(),
+            )
+            .entity(
+                Entity::builder()
+                    .handle("REG-1")
+                    .contact(contact())
+                    .role("registrant")
+                    .build(),
+            )
+            .build()
+            .to_response()
+    }
+
+    #[test]
+    fn GIVEN_policy_WHEN_redact_domain_THEN_fields_redacted_with_paths() {
+        // GIVEN
+        let policy = RedactionPolicy::from_json(POLICY).expect("parsing policy");
+
+        // WHEN
+        let actual = policy.redact(domain());
+
+        // THEN
+        assert!(actual.has_extension_id(ExtensionId::Redacted));
+        let RdapResponse::Domain(domain) = actual else {
+            panic!("not a domain")
+        };
+        assert_eq!(domain.object_common.handle.as_deref(), Some(""));
+        let entities = domain.object_common.entities.as_ref().expect("no entities");
+        let tech = entities[0].contact().expect("no tech contact");
+        assert_eq!(tech.full_name.as_deref(), Some("Bob Smurd"));
+        assert!(tech.emails.is_some());
+        let registrant = entities[1].contact().expect("no registrant contact");
+        assert_eq!(registrant.full_name.as_deref(), Some("REDACTED"));
+        assert!(registrant.emails.is_none());
+
+        let redacted = domain.object_common.redacted.expect("no redacted");
+        assert_eq!(redacted.len(), 3);
+        let email = redacted
+            .iter()
+            .find(|r| r.name.description() == Some(&"Registrant Email".to_string()))
+            .expect("no email redaction");
+        assert_eq!(email.method, Some(Method::Removal));
+        assert_eq!(
+            email.pre_path.as_deref(),
+            Some("$.entities[1].vcardArray[1][?(@[0]=='email')]")
+        );
+        let name = redacted
+            .iter()
+            .find(|r| r.name.description() == Some(&"Registrant Name".to_string()))
+            .expect("no name redaction");
+        assert_eq!(
+            name.post_path.as_deref(),
+            Some("$.entities[1].vcardArray[1][?(@[0]=='fn')][3]")
+        );
+        assert_eq!(
+            name.reason.as_ref().map(|r| r.to_string()),
+            Some("Server policy".to_string())
+        );
+    }
+
+    #[test]
+    fn GIVEN_policy_WHEN_redact_registrant_entity_THEN_top_level_paths() {
+        // GIVEN
+        let policy = RedactionPolicy::from_json(POLICY).expect("parsing policy");
+        let entity = Entity::builder()
+            .handle("REG-1")
+            .contact(contact())
+            .role("registrant")
+            .build()
+            .to_response();
+
+        // WHEN
+        let actual = policy.redact(entity);
+
+        // THEN
+        let RdapResponse::Entity(entity) = actual else {
+            panic!("not an entity")
+        };
+        let redacted = entity.object_common.redacted.expect("no redacted");
+        assert!(redacted
+            .iter()
+            .any(|r| r.pre_path.as_deref() == Some("$.vcardArray[1][?(@[0]=='email')]")));
+    }
+
+    #[test]
+    fn GIVEN_policy_WHEN_redact_domain_with_nameserver_entity_THEN_nested_entity_redacted() {
+        // GIVEN
+        let policy = RedactionPolicy::from_json(POLICY).expect("parsing policy");
+        let domain = Domain::builder()
+            .ldh_name("foo.example")
+            .nameservers(vec![Nameserver::builder()
+                .ldh_name("ns1.foo.example")
+                .entity(
+                    Entity::builder()
+                        .handle("REG-1")
+                        .contact(contact())
+                        .role("registrant")
+                        .build(),
+                )
+                .build()
+                .expect("nameserver")])
+            .build()
+            .to_response();
+
+        // WHEN
+        let actual = policy.redact(domain);
+
+        // THEN
+        let RdapResponse::Domain(domain) = actual else {
+            panic!("not a domain")
+        };
+        let nameservers = domain.nameservers.as_ref().expect("no nameservers");
+        let entities = nameservers[0]
+            .object_common
+            .entities
+            .as_ref()
+            .expect("no entities");
+        let registrant = entities[0].contact().expect("no registrant contact");
+        assert!(registrant.emails.is_none());
+        let redacted = domain.object_common.redacted.expect("no redacted");
+        assert!(redacted.iter().any(|r| r.pre_path.as_deref()
+            == Some("$.nameservers[0].entities[0].vcardArray[1][?(@[0]=='email')]")));
+    }
+
+    #[test]
+    fn GIVEN_policy_WHEN_redact_entity_without_roles_THEN_rules_of_all_roles_apply() {
+        // GIVEN
+        let policy = RedactionPolicy::from_json(POLICY).expect("parsing policy");
+        let entity = Entity::builder()
+            .handle("REG-1")
+            .contact(contact())
+            .build()
+            .to_response();
+
+        // WHEN
+        let actual = policy.redact(entity);
+
+        // THEN
+        let RdapResponse::Entity(entity) = actual else {
+            panic!("not an entity")
+        };
+        let contact = entity.contact().expect("no contact");
+        assert_eq!(contact.full_name.as_deref(), Some("REDACTED"));
+        assert!(contact.emails.is_none());
+    }
+
+    #[rstest]
+    #[case(
+        r#"{"rules":[{"objectClassName":"domain","field":"email","method":"removal","name":"x"}]}"#
+    )]
+    #[case(r#"{"rules":[{"objectClassName":"entity","field":"fn","method":"partialValue","name":"x"}]}"#)]
+    #[case(r#"{"rules":[{"objectClassName":"entity","field":"fn","method":"replacementValue","name":"x"}]}"#)]
+    #[case(
+        r#"{"rules":[{"objectClassName":"entity","field":"phone","method":"removal","name":"x"}]}"#
+    )]
+    fn GIVEN_invalid_policy_WHEN_from_json_THEN_error(#[case] json: &str) {
+        // GIVEN
+        // json
+
+        // WHEN
+        let actual = RedactionPolicy::from_json(json);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+
+    #[rstest]
+    #[case("Bearer secret-key", true)]
+    #[case("Bearer wrong-key", false)]
+    #[case("Basic Ym9iOnBhc3N3b3Jk", true)] // bob:password
+    #[case("Basic Ym9iOndyb25n", false)] // bob:wrong
+    fn GIVEN_authorization_WHEN_is_authenticated_THEN_result_is_correct(
+        #[case] authorization: &str,
+        #[case] expected: bool,
+    ) {
+        // GIVEN
+        let config = RedactionConfig::builder()
+            .policy(Arc::new(RedactionPolicy::default()))
+            .api_key("secret-key")
+            .basic_credential("bob:password")
+            .build();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            authorization.parse().expect("header value"),
+        );
+
+        // WHEN
+        let actual = is_authenticated(&config, &headers);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+}
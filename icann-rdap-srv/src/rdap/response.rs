@@ -1,16 +1,19 @@
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::SystemTime};
 
 use {
     axum::{
         response::{IntoResponse, Response},
         Json,
     },
+    chrono::DateTime,
+    headers::{ETag, HeaderMapExt, LastModified},
     http::StatusCode,
     icann_rdap_common::{
         media_types::RDAP_MEDIA_TYPE,
-        prelude::ToResponse,
+        prelude::{ObjectCommonFields, ToResponse},
         response::{RdapResponse, Rfc9083Error},
     },
+    sha2::{Digest, Sha256},
     tracing::warn,
 };
 
@@ -32,12 +35,25 @@ pub static BAD_REQUEST: LazyLock<RdapResponse> = LazyLock::new(|| {
         .build()
         .to_response()
 });
+pub static FORBIDDEN: LazyLock<RdapResponse> = LazyLock::new(|| {
+    Rfc9083Error::builder()
+        .error_code(403)
+        .build()
+        .to_response()
+});
+pub static TOO_MANY_REQUESTS: LazyLock<RdapResponse> = LazyLock::new(|| {
+    Rfc9083Error::builder()
+        .error_code(429)
+        .build()
+        .to_response()
+});
 
 pub(crate) const RDAP_HEADERS: [(&str, &str); 1] = [("content-type", RDAP_MEDIA_TYPE)];
 
 pub(crate) trait ResponseUtil {
     fn status_code(&self) -> StatusCode;
     fn first_notice_link_href(&self) -> Option<&str>;
+    fn last_changed(&self) -> Option<SystemTime>;
     fn response(&self) -> Response;
 }
 
@@ -63,6 +79,29 @@ impl ResponseUtil for RdapResponse {
         }
     }
 
+    /// Gets the date of the "last changed" event of an object.
+    fn last_changed(&self) -> Option<SystemTime> {
+        let events = match self {
+            RdapResponse::Entity(entity) => entity.events(),
+            RdapResponse::Domain(domain) => domain.events(),
+            RdapResponse::Nameserver(nameserver) => nameserver.events(),
+            RdapResponse::Autnum(autnum) => autnum.events(),
+            RdapResponse::Network(network) => network.events(),
+            _ => return None,
+        };
+        let event_date = events
+            .iter()
+            .find(|event| event.event_action.as_deref() == Some("last changed"))?
+            .event_date
+            .as_ref()?;
+        DateTime::parse_from_rfc3339(event_date)
+            .ok()
+            .map(SystemTime::from)
+    }
+
+    /// Creates the HTTP response. Successful responses carry a strong entity tag
+    /// of their content and, for objects with a "last changed" event, its date as
+    /// the last modified time.
     fn response(&self) -> Response {
         let status_code = self.status_code();
         match status_code {
@@ -82,6 +121,21 @@ impl ResponseUtil for RdapResponse {
                     (status_code, RDAP_HEADERS, Json(self)).into_response()
                 }
             }
+            StatusCode::OK => {
+                let Ok(body) = serde_json::to_vec(self) else {
+                    return (status_code, RDAP_HEADERS, Json(self)).into_response();
+                };
+                let etag = format!("\"{:x}\"", Sha256::digest(&body)).parse::<ETag>();
+                let mut response = (status_code, RDAP_HEADERS, body).into_response();
+                let headers = response.headers_mut();
+                if let Ok(etag) = etag {
+                    headers.typed_insert(etag);
+                }
+                if let Some(last_changed) = self.last_changed() {
+                    headers.typed_insert(LastModified::from(last_changed));
+                }
+                response
+            }
             _ => (status_code, RDAP_HEADERS, Json(self)).into_response(),
         }
     }
@@ -0,0 +1,163 @@
+use axum::{
+    extract::{OriginalUri, Query, State},
+    response::Response,
+};
+
+use {icann_rdap_common::search_types::RelatedEntityProperty, serde::Deserialize};
+
+use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
+
+use super::{
+    jscontact::Contacts,
+    redaction::Redaction,
+    response::{FORBIDDEN, NOT_IMPLEMENTED},
+    search::{search_page, search_response},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ReverseSearchParams {
+    #[serde(rename = "fn")]
+    full_name: Option<String>,
+
+    handle: Option<String>,
+
+    email: Option<String>,
+
+    role: Option<String>,
+
+    sort: Option<String>,
+
+    cursor: Option<String>,
+
+    #[serde(rename = "fieldSet")]
+    field_set: Option<String>,
+}
+
+impl ReverseSearchParams {
+    /// Gets the one related entity property being searched.
+    fn property(&self) -> Option<(RelatedEntityProperty, &str)> {
+        match (&self.full_name, &self.handle, &self.email) {
+            (Some(full_name), None, None) => Some((RelatedEntityProperty::FullName, full_name)),
+            (None, Some(handle), None) => Some((RelatedEntityProperty::Handle, handle)),
+            (None, None, Some(email)) => Some((RelatedEntityProperty::Email, email)),
+            _ => None,
+        }
+    }
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(level = "debug")]
+pub(crate) async fn domains_reverse_search(
+    Query(params): Query<ReverseSearchParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
+    state: State<DynServiceState>,
+) -> Result<Response, RdapServerError> {
+    let Some((property, value)) = params.property() else {
+        return Ok(NOT_IMPLEMENTED.response());
+    };
+    if redaction.redacts_search(property.into(), params.role.as_deref()) {
+        return Ok(FORBIDDEN.response());
+    }
+    let storage = state.get_storage().await?;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "domain",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = storage
+        .reverse_search_domains(property, value, params.role.as_deref(), &page)
+        .await;
+    search_response(
+        results,
+        &redaction,
+        &contacts,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        params.field_set.as_deref(),
+        &uri,
+        page_size,
+    )
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(level = "debug")]
+pub(crate) async fn nameservers_reverse_search(
+    Query(params): Query<ReverseSearchParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
+    state: State<DynServiceState>,
+) -> Result<Response, RdapServerError> {
+    let Some((property, value)) = params.property() else {
+        return Ok(NOT_IMPLEMENTED.response());
+    };
+    if redaction.redacts_search(property.into(), params.role.as_deref()) {
+        return Ok(FORBIDDEN.response());
+    }
+    let storage = state.get_storage().await?;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "nameserver",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = storage
+        .reverse_search_nameservers(property, value, params.role.as_deref(), &page)
+        .await;
+    search_response(
+        results,
+        &redaction,
+        &contacts,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        params.field_set.as_deref(),
+        &uri,
+        page_size,
+    )
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(level = "debug")]
+pub(crate) async fn entities_reverse_search(
+    Query(params): Query<ReverseSearchParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
+    state: State<DynServiceState>,
+) -> Result<Response, RdapServerError> {
+    let Some((property, value)) = params.property() else {
+        return Ok(NOT_IMPLEMENTED.response());
+    };
+    if redaction.redacts_search(property.into(), params.role.as_deref()) {
+        return Ok(FORBIDDEN.response());
+    }
+    let storage = state.get_storage().await?;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "entity",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = storage
+        .reverse_search_entities(property, value, params.role.as_deref(), &page)
+        .await;
+    search_response(
+        results,
+        &redaction,
+        &contacts,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        params.field_set.as_deref(),
+        &uri,
+        page_size,
+    )
+}
@@ -0,0 +1,178 @@
+use std::{net::IpAddr, str::FromStr};
+
+use {
+    axum::{
+        extract::{OriginalUri, Path, Query, State},
+        response::Response,
+    },
+    http::Uri,
+    icann_rdap_common::{
+        media_types::RDAP_MEDIA_TYPE,
+        response::{ExtensionId, Link, Network, RdapResponse},
+        search_types::RirSearchRelation,
+    },
+    ipnet::{IpNet, Ipv4Subnets, Ipv6Subnets},
+    serde::Deserialize,
+    strum::IntoEnumIterator,
+    tracing::debug,
+};
+
+use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
+
+use super::{
+    add_conformance,
+    jscontact::Contacts,
+    redaction::Redaction,
+    response::BAD_REQUEST,
+    search::{search_page, search_response},
+};
+
+/// The path segment of the searches of draft-ietf-regext-rdap-rir-search.
+const RIR_SEARCH_PATH: &str = "rirSearch1";
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RelationParams {
+    sort: Option<String>,
+
+    cursor: Option<String>,
+
+    #[serde(rename = "fieldSet")]
+    field_set: Option<String>,
+}
+
+/// Gets the networks related to the IP address or CIDR of the path.
+///
+/// The `rdap-up` and `rdap-top` relations are answered with a network and
+/// `rdap-down` and `rdap-bottom` with network search results.
+#[axum_macros::debug_handler]
+#[tracing::instrument(level = "debug")]
+pub(crate) async fn ips_by_relation(
+    Path((relation, netid)): Path<(String, String)>,
+    Query(params): Query<RelationParams>,
+    OriginalUri(uri): OriginalUri,
+    redaction: Redaction,
+    contacts: Contacts,
+    state: State<DynServiceState>,
+) -> Result<Response, RdapServerError> {
+    debug!("getting networks by relation {relation} to {netid}");
+    let Ok(relation) = RirSearchRelation::from_str(&relation) else {
+        return Ok(BAD_REQUEST.response());
+    };
+    let net = if netid.contains('/') {
+        netid.parse::<IpNet>().map(|net| net.trunc()).ok()
+    } else {
+        netid.parse::<IpAddr>().map(IpNet::from).ok()
+    };
+    let Some(net) = net else {
+        return Ok(BAD_REQUEST.response());
+    };
+    let storage = state.get_storage().await?;
+    let rir_search_enable = storage.get_common_config().rir_search_enable;
+    let page_size = storage.get_common_config().search_page_size;
+    let page = search_page(
+        "ip network",
+        &redaction,
+        params.sort.as_deref(),
+        params.cursor.as_deref(),
+        page_size,
+    );
+    let results = storage
+        .search_networks_by_relation(relation, &net.to_string(), &page)
+        .await
+        .map(add_rir_search_conformance);
+    match relation {
+        RirSearchRelation::Up | RirSearchRelation::Top => {
+            let mut network = results?;
+            if rir_search_enable {
+                network = add_relation_links(network, &uri, &format!("/ips/{RIR_SEARCH_PATH}/"));
+            }
+            Ok(contacts.apply(redaction.apply(network)).response())
+        }
+        RirSearchRelation::Down | RirSearchRelation::Bottom => search_response(
+            results,
+            &redaction,
+            &contacts,
+            params.sort.as_deref(),
+            params.cursor.as_deref(),
+            params.field_set.as_deref(),
+            &uri,
+            page_size,
+        ),
+    }
+}
+
+/// Adds the identifiers of the RIR search extension to the conformance of
+/// the results of RIR searches.
+pub(crate) fn add_rir_search_conformance(response: RdapResponse) -> RdapResponse {
+    match response {
+        RdapResponse::Network(mut network) => {
+            add_conformance(&mut network.common, ExtensionId::RirSearch1);
+            add_conformance(&mut network.common, ExtensionId::Ips);
+            RdapResponse::Network(network)
+        }
+        RdapResponse::NetworkSearchResults(mut search) => {
+            add_conformance(&mut search.common, ExtensionId::RirSearch1);
+            add_conformance(&mut search.common, ExtensionId::Ips);
+            add_conformance(&mut search.common, ExtensionId::IpSearchResults);
+            RdapResponse::NetworkSearchResults(search)
+        }
+        RdapResponse::AutnumSearchResults(mut search) => {
+            add_conformance(&mut search.common, ExtensionId::RirSearch1);
+            add_conformance(&mut search.common, ExtensionId::Autnums);
+            add_conformance(&mut search.common, ExtensionId::AutnumSearchResults);
+            RdapResponse::AutnumSearchResults(search)
+        }
+        _ => response,
+    }
+}
+
+/// Adds links to the searches for the networks related to a network, with the
+/// relations as their `rel`.
+///
+/// The links are relative to the server, found by the part of the path of the
+/// request before `path_segment`. Networks that are not a single CIDR are
+/// left without links, as the searches are by CIDR.
+pub(crate) fn add_relation_links(
+    response: RdapResponse,
+    uri: &Uri,
+    path_segment: &str,
+) -> RdapResponse {
+    let RdapResponse::Network(mut network) = response else {
+        return response;
+    };
+    let Some(cidr) = network_cidr(&network) else {
+        return RdapResponse::Network(network);
+    };
+    let base = uri
+        .path()
+        .split_once(path_segment)
+        .map_or("", |(base, _)| base);
+    let links = network.object_common.links.get_or_insert_with(Vec::new);
+    for relation in RirSearchRelation::iter() {
+        links.push(
+            Link::builder()
+                .value(uri.path())
+                .rel(relation.to_string())
+                .href(format!("{base}/ips/{RIR_SEARCH_PATH}/{relation}/{cidr}"))
+                .media_type(RDAP_MEDIA_TYPE)
+                .build(),
+        );
+    }
+    add_rir_search_conformance(RdapResponse::Network(network))
+}
+
+/// Gets the CIDR of a network whose range is a single CIDR.
+fn network_cidr(network: &Network) -> Option<IpNet> {
+    let start = network.start_address()?.parse::<IpAddr>().ok()?;
+    let end = network.end_address()?.parse::<IpAddr>().ok()?;
+    let mut subnets: Vec<IpNet> = match (start, end) {
+        (IpAddr::V4(start), IpAddr::V4(end)) => {
+            Ipv4Subnets::new(start, end, 0).map(IpNet::V4).collect()
+        }
+        (IpAddr::V6(start), IpAddr::V6(end)) => {
+            Ipv6Subnets::new(start, end, 0).map(IpNet::V6).collect()
+        }
+        _ => return None,
+    };
+    (subnets.len() == 1).then(|| subnets.remove(0))
+}
@@ -1,13 +1,19 @@
-use axum::{response::IntoResponse, routing::get, Router};
+use axum::{middleware, routing::get, Router};
 
 use super::{
     autnum::autnum_by_num,
+    autnums::autnums,
+    conditional::conditional_get,
     domain::domain_by_name,
     domains::domains,
+    entities::entities,
     entity::entity_by_handle,
     ip::network_by_netid,
+    ips::ips,
     nameserver::nameserver_by_name,
-    response::{ResponseUtil, NOT_IMPLEMENTED},
+    nameservers::nameservers,
+    reverse_search::{domains_reverse_search, entities_reverse_search, nameservers_reverse_search},
+    rir_search::ips_by_relation,
     srvhelp::srvhelp,
 };
 
@@ -19,11 +25,23 @@ pub(crate) fn rdap_router() -> Router<crate::server::DynServiceState> {
         .route("/nameserver/:name", get(nameserver_by_name))
         .route("/entity/:handle", get(entity_by_handle))
         .route("/domains", get(domains))
-        .route("/nameservers", get(not_implemented))
-        .route("/entities", get(not_implemented))
+        .route("/nameservers", get(nameservers))
+        .route("/entities", get(entities))
+        .route("/ips", get(ips))
+        .route("/autnums", get(autnums))
+        .route("/ips/rirSearch1/:relation/*netid", get(ips_by_relation))
+        .route(
+            "/domains/reverse_search/entity",
+            get(domains_reverse_search),
+        )
+        .route(
+            "/nameservers/reverse_search/entity",
+            get(nameservers_reverse_search),
+        )
+        .route(
+            "/entities/reverse_search/entity",
+            get(entities_reverse_search),
+        )
         .route("/help", get(srvhelp))
-}
-
-async fn not_implemented() -> impl IntoResponse {
-    NOT_IMPLEMENTED.response()
+        .layer(middleware::from_fn(conditional_get))
 }
@@ -0,0 +1,64 @@
+use {axum::response::Response, http::Uri, icann_rdap_common::response::RdapResponse};
+
+use crate::{error::RdapServerError, rdap::response::ResponseUtil, storage::SearchPage};
+
+use super::{
+    jscontact::Contacts,
+    paging::{cursor_offset, page_search_results},
+    redaction::Redaction,
+    response::BAD_REQUEST,
+    sorting::sort_search_results,
+    subsetting::subset_search_results,
+};
+
+/// Gets the page of the results of a search to get from storage.
+///
+/// Storage gives results in the order of their default sort, so it only pages
+/// them when no other sort is asked for and redaction keeps the values of the
+/// default sort. Otherwise, or with an invalid cursor, all the results are
+/// gotten so that [search_response] sorts and pages them.
+pub(crate) fn search_page(
+    object_class_name: &str,
+    redaction: &Redaction,
+    sort: Option<&str>,
+    cursor: Option<&str>,
+    page_size: usize,
+) -> SearchPage {
+    if sort.is_some() || redaction.redacts_default_sort(object_class_name) {
+        return SearchPage::all();
+    }
+    match cursor.map_or(Ok(0), cursor_offset) {
+        Ok(offset) => SearchPage::new(offset, page_size),
+        Err(_) => SearchPage::all(),
+    }
+}
+
+/// Creates the response of a search.
+///
+/// The results are redacted, sorted, paged, trimmed to the field set, and then
+/// given the contact formats. Redaction is first so that the order of results
+/// does not reveal redacted values. Invalid search strings, sorts, cursors and
+/// field sets are all bad requests.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn search_response(
+    results: Result<RdapResponse, RdapServerError>,
+    redaction: &Redaction,
+    contacts: &Contacts,
+    sort: Option<&str>,
+    cursor: Option<&str>,
+    field_set: Option<&str>,
+    uri: &Uri,
+    page_size: usize,
+) -> Result<Response, RdapServerError> {
+    let results = results
+        .map(|results| redaction.apply(results))
+        .and_then(|results| sort_search_results(results, sort, uri))
+        .and_then(|results| page_search_results(results, cursor, uri, page_size))
+        .and_then(|results| subset_search_results(results, field_set, uri))
+        .map(|results| contacts.apply(results));
+    match results {
+        Ok(results) => Ok(results.response()),
+        Err(RdapServerError::InvalidArg(_)) => Ok(BAD_REQUEST.response()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,493 @@
+use std::{cmp::Ordering, net::IpAddr};
+
+use {
+    chrono::{DateTime, SecondsFormat, Utc},
+    http::Uri,
+    icann_rdap_common::{
+        media_types::RDAP_MEDIA_TYPE,
+        response::{
+            Autnum, AvailableSort, Domain, Entity, Events, ExtensionId, Link, Nameserver, Network,
+            ObjectCommonFields, RdapResponse, SortingMetadata,
+        },
+    },
+};
+
+use crate::error::RdapServerError;
+
+use super::{add_conformance, paging::CURSOR_PARAM, replace_query_param};
+
+/// The query parameter of the RFC 8977 sort.
+const SORT_PARAM: &str = "sort";
+
+/// A property that search results may be sorted by.
+struct SortProperty<T> {
+    name: &'static str,
+    json_path: &'static str,
+    value: fn(&T) -> Option<String>,
+}
+
+/// Search results having a default sort, by which storage may also give them.
+pub(crate) trait DefaultSort {
+    /// Gets the value of the default sort property.
+    fn default_sort_value(&self) -> Option<String>;
+}
+
+impl DefaultSort for Domain {
+    fn default_sort_value(&self) -> Option<String> {
+        self.ldh_name.as_ref().map(|n| n.to_lowercase())
+    }
+}
+
+impl DefaultSort for Nameserver {
+    fn default_sort_value(&self) -> Option<String> {
+        self.ldh_name.as_ref().map(|n| n.to_lowercase())
+    }
+}
+
+impl DefaultSort for Entity {
+    fn default_sort_value(&self) -> Option<String> {
+        self.object_common.handle.clone()
+    }
+}
+
+impl DefaultSort for Network {
+    fn default_sort_value(&self) -> Option<String> {
+        self.start_address()
+            .and_then(|addr| addr.parse::<IpAddr>().ok())
+            .map(address_key)
+    }
+}
+
+impl DefaultSort for Autnum {
+    fn default_sort_value(&self) -> Option<String> {
+        self.start_autnum().map(|num| format!("{num:010}"))
+    }
+}
+
+/// The sorts of domain search results. The first is the default.
+fn domain_sorts() -> Vec<SortProperty<Domain>> {
+    vec![
+        SortProperty {
+            name: "name",
+            json_path: "$.domainSearchResults[*].ldhName",
+            value: Domain::default_sort_value,
+        },
+        SortProperty {
+            name: "registrationDate",
+            json_path: r#"$.domainSearchResults[*].events[?(@.eventAction=="registration")].eventDate"#,
+            value: |d| event_date(d.events(), "registration"),
+        },
+        SortProperty {
+            name: "expirationDate",
+            json_path: r#"$.domainSearchResults[*].events[?(@.eventAction=="expiration")].eventDate"#,
+            value: |d| event_date(d.events(), "expiration"),
+        },
+        SortProperty {
+            name: "lastChangedDate",
+            json_path: r#"$.domainSearchResults[*].events[?(@.eventAction=="last changed")].eventDate"#,
+            value: |d| event_date(d.events(), "last changed"),
+        },
+    ]
+}
+
+/// The sorts of nameserver search results. The first is the default.
+fn nameserver_sorts() -> Vec<SortProperty<Nameserver>> {
+    vec![
+        SortProperty {
+            name: "name",
+            json_path: "$.nameserverSearchResults[*].ldhName",
+            value: Nameserver::default_sort_value,
+        },
+        SortProperty {
+            name: "registrationDate",
+            json_path: r#"$.nameserverSearchResults[*].events[?(@.eventAction=="registration")].eventDate"#,
+            value: |n| event_date(n.events(), "registration"),
+        },
+        SortProperty {
+            name: "lastChangedDate",
+            json_path: r#"$.nameserverSearchResults[*].events[?(@.eventAction=="last changed")].eventDate"#,
+            value: |n| event_date(n.events(), "last changed"),
+        },
+    ]
+}
+
+/// The sorts of entity search results. The first is the default.
+fn entity_sorts() -> Vec<SortProperty<Entity>> {
+    vec![
+        SortProperty {
+            name: "handle",
+            json_path: "$.entitySearchResults[*].handle",
+            value: Entity::default_sort_value,
+        },
+        SortProperty {
+            name: "fn",
+            json_path: r#"$.entitySearchResults[*].vcardArray[1][?(@[0]=="fn")][3]"#,
+            value: |e| {
+                e.contact()
+                    .and_then(|c| c.full_name)
+                    .map(|n| n.to_lowercase())
+            },
+        },
+        SortProperty {
+            name: "org",
+            json_path: r#"$.entitySearchResults[*].vcardArray[1][?(@[0]=="org")][3]"#,
+            value: |e| {
+                e.contact()
+                    .and_then(|c| c.organization_names)
+                    .and_then(|o| o.first().map(|o| o.to_lowercase()))
+            },
+        },
+        SortProperty {
+            name: "email",
+            json_path: r#"$.entitySearchResults[*].vcardArray[1][?(@[0]=="email")][3]"#,
+            value: |e| {
+                e.contact()
+                    .and_then(|c| c.emails)
+                    .and_then(|emails| emails.first().map(|e| e.email.to_lowercase()))
+            },
+        },
+        SortProperty {
+            name: "registrationDate",
+            json_path: r#"$.entitySearchResults[*].events[?(@.eventAction=="registration")].eventDate"#,
+            value: |e| event_date(e.events(), "registration"),
+        },
+        SortProperty {
+            name: "lastChangedDate",
+            json_path: r#"$.entitySearchResults[*].events[?(@.eventAction=="last changed")].eventDate"#,
+            value: |e| event_date(e.events(), "last changed"),
+        },
+    ]
+}
+
+/// The sorts of IP network search results. The first is the default.
+fn network_sorts() -> Vec<SortProperty<Network>> {
+    vec![
+        SortProperty {
+            name: "startAddress",
+            json_path: "$.ipSearchResults[*].startAddress",
+            value: Network::default_sort_value,
+        },
+        SortProperty {
+            name: "handle",
+            json_path: "$.ipSearchResults[*].handle",
+            value: |n| n.object_common.handle.clone(),
+        },
+        SortProperty {
+            name: "name",
+            json_path: "$.ipSearchResults[*].name",
+            value: |n| n.name.as_ref().map(|n| n.to_lowercase()),
+        },
+        SortProperty {
+            name: "registrationDate",
+            json_path: r#"$.ipSearchResults[*].events[?(@.eventAction=="registration")].eventDate"#,
+            value: |n| event_date(n.events(), "registration"),
+        },
+        SortProperty {
+            name: "lastChangedDate",
+            json_path: r#"$.ipSearchResults[*].events[?(@.eventAction=="last changed")].eventDate"#,
+            value: |n| event_date(n.events(), "last changed"),
+        },
+    ]
+}
+
+/// The sorts of autnum search results. The first is the default.
+fn autnum_sorts() -> Vec<SortProperty<Autnum>> {
+    vec![
+        SortProperty {
+            name: "startAutnum",
+            json_path: "$.autnumSearchResults[*].startAutnum",
+            value: Autnum::default_sort_value,
+        },
+        SortProperty {
+            name: "handle",
+            json_path: "$.autnumSearchResults[*].handle",
+            value: |a| a.object_common.handle.clone(),
+        },
+        SortProperty {
+            name: "name",
+            json_path: "$.autnumSearchResults[*].name",
+            value: |a| a.name.as_ref().map(|n| n.to_lowercase()),
+        },
+        SortProperty {
+            name: "registrationDate",
+            json_path: r#"$.autnumSearchResults[*].events[?(@.eventAction=="registration")].eventDate"#,
+            value: |a| event_date(a.events(), "registration"),
+        },
+        SortProperty {
+            name: "lastChangedDate",
+            json_path: r#"$.autnumSearchResults[*].events[?(@.eventAction=="last changed")].eventDate"#,
+            value: |a| event_date(a.events(), "last changed"),
+        },
+    ]
+}
+
+/// Sorts search results as specified in RFC 8977.
+///
+/// The sort is a comma separated list of properties, each optionally followed by
+/// ":a" for ascending or ":d" for descending order. Without a sort, results are
+/// ordered by their default sort property so that pages of results are stable.
+/// Results without a value for a property are placed last. An unknown property
+/// is an [RdapServerError::InvalidArg]. Responses other than search results are
+/// returned as they are.
+pub(crate) fn sort_search_results(
+    results: RdapResponse,
+    sort: Option<&str>,
+    uri: &Uri,
+) -> Result<RdapResponse, RdapServerError> {
+    let sorted = match results {
+        RdapResponse::DomainSearchResults(mut search) => {
+            let (sorted, sorting_metadata) = sort_by(search.results, &domain_sorts(), sort, uri)?;
+            search.results = sorted;
+            search.sorting_metadata = Some(sorting_metadata);
+            add_conformance(&mut search.common, ExtensionId::Sorting);
+            RdapResponse::DomainSearchResults(search)
+        }
+        RdapResponse::NameserverSearchResults(mut search) => {
+            let (sorted, sorting_metadata) =
+                sort_by(search.results, &nameserver_sorts(), sort, uri)?;
+            search.results = sorted;
+            search.sorting_metadata = Some(sorting_metadata);
+            add_conformance(&mut search.common, ExtensionId::Sorting);
+            RdapResponse::NameserverSearchResults(search)
+        }
+        RdapResponse::EntitySearchResults(mut search) => {
+            let (sorted, sorting_metadata) = sort_by(search.results, &entity_sorts(), sort, uri)?;
+            search.results = sorted;
+            search.sorting_metadata = Some(sorting_metadata);
+            add_conformance(&mut search.common, ExtensionId::Sorting);
+            RdapResponse::EntitySearchResults(search)
+        }
+        RdapResponse::NetworkSearchResults(mut search) => {
+            let (sorted, sorting_metadata) = sort_by(search.results, &network_sorts(), sort, uri)?;
+            search.results = sorted;
+            search.sorting_metadata = Some(sorting_metadata);
+            add_conformance(&mut search.common, ExtensionId::Sorting);
+            RdapResponse::NetworkSearchResults(search)
+        }
+        RdapResponse::AutnumSearchResults(mut search) => {
+            let (sorted, sorting_metadata) = sort_by(search.results, &autnum_sorts(), sort, uri)?;
+            search.results = sorted;
+            search.sorting_metadata = Some(sorting_metadata);
+            add_conformance(&mut search.common, ExtensionId::Sorting);
+            RdapResponse::AutnumSearchResults(search)
+        }
+        _ => results,
+    };
+    Ok(sorted)
+}
+
+fn sort_by<T>(
+    results: Vec<T>,
+    sorts: &[SortProperty<T>],
+    sort: Option<&str>,
+    uri: &Uri,
+) -> Result<(Vec<T>, SortingMetadata), RdapServerError> {
+    let mut keys = parse_sort(sort, sorts)?;
+    // ties are always broken by the default sort.
+    keys.push((&sorts[0], false));
+
+    let mut decorated = results
+        .into_iter()
+        .map(|result| {
+            let values = keys
+                .iter()
+                .map(|(property, _)| (property.value)(&result))
+                .collect::<Vec<Option<String>>>();
+            (values, result)
+        })
+        .collect::<Vec<(Vec<Option<String>>, T)>>();
+    decorated.sort_by(|(a, _), (b, _)| {
+        keys.iter()
+            .zip(a.iter().zip(b.iter()))
+            .map(|((_, descending), (a, b))| compare(a, b, *descending))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    let sorted = decorated
+        .into_iter()
+        .map(|(_, result)| result)
+        .collect::<Vec<T>>();
+
+    let available_sorts = sorts
+        .iter()
+        .enumerate()
+        .map(|(i, property)| {
+            AvailableSort::builder()
+                .property(property.name)
+                .json_path(property.json_path)
+                .default(i == 0)
+                .link(
+                    Link::builder()
+                        .value(uri.to_string())
+                        .rel("alternate")
+                        .href(replace_query_param(
+                            uri,
+                            &[SORT_PARAM, CURSOR_PARAM],
+                            SORT_PARAM,
+                            property.name,
+                        ))
+                        .title("Result Ascending Sort Link")
+                        .media_type(RDAP_MEDIA_TYPE)
+                        .build(),
+                )
+                .build()
+        })
+        .collect::<Vec<AvailableSort>>();
+    let sorting_metadata = SortingMetadata::builder()
+        .and_current_sort(sort.map(|sort| sort.to_string()))
+        .available_sorts(available_sorts)
+        .build();
+    Ok((sorted, sorting_metadata))
+}
+
+/// Parses a sort into its properties and whether each is descending.
+fn parse_sort<'a, T>(
+    sort: Option<&str>,
+    sorts: &'a [SortProperty<T>],
+) -> Result<Vec<(&'a SortProperty<T>, bool)>, RdapServerError> {
+    let Some(sort) = sort else {
+        return Ok(vec![]);
+    };
+    sort.split(',')
+        .map(|key| {
+            let (name, descending) = match key.split_once(':') {
+                None | Some((_, "a")) => (key.split(':').next().unwrap_or_default(), false),
+                Some((name, "d")) => (name, true),
+                Some(_) => {
+                    return Err(RdapServerError::InvalidArg(format!(
+                        "sort order of '{key}' must be 'a' or 'd'"
+                    )))
+                }
+            };
+            let property = sorts
+                .iter()
+                .find(|property| property.name == name)
+                .ok_or_else(|| {
+                    RdapServerError::InvalidArg(format!("sort by '{name}' is not supported"))
+                })?;
+            Ok((property, descending))
+        })
+        .collect()
+}
+
+/// Compares sort values, placing missing values last in either order.
+fn compare(a: &Option<String>, b: &Option<String>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.cmp(a),
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Gives an IP address as text that sorts in the order of the addresses, with
+/// IPv4 addresses before IPv6 addresses.
+fn address_key(addr: IpAddr) -> String {
+    match addr {
+        IpAddr::V4(v4) => format!("4{:08x}", u32::from(v4)),
+        IpAddr::V6(v6) => format!("6{:032x}", u128::from(v6)),
+    }
+}
+
+/// Gets the date of an event as a string that sorts in time order.
+fn event_date(events: &Events, event_action: &str) -> Option<String> {
+    let event_date = events
+        .iter()
+        .find(|event| event.event_action.as_deref() == Some(event_action))?
+        .event_date
+        .as_ref()?;
+    let date = DateTime::parse_from_rfc3339(event_date).ok()?;
+    Some(
+        date.with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Nanos, true),
+    )
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        http::Uri,
+        icann_rdap_common::{
+            prelude::ToResponse,
+            response::{Domain, DomainSearchResults, Event, ExtensionId, RdapResponse},
+        },
+        rstest::rstest,
+    };
+
+    use super::sort_search_results;
+
+    fn domain(ldh_name: &str, registration: Option<&str>) -> Domain {
+        let events = registration
+            .map(|date| {
+                vec![Event::builder()
+                    .event_action("registration")
+                    .event_date(date)
+                    .build()]
+            })
+            .unwrap_or_default();
+        Domain::builder().ldh_name(ldh_name).events(events).build()
+    }
+
+    fn domain_search_results() -> RdapResponse {
+        DomainSearchResults::builder()
+            .results(vec![
+                domain("c.example", Some("2020-01-01T00:00:00Z")),
+                domain("a.example", None),
+                domain("b.example", Some("2021-01-01T00:00:00+05:00")),
+                domain("d.example", Some("2021-01-01T00:00:00Z")),
+            ])
+            .build()
+            .to_response()
+    }
+
+    #[rstest]
+    #[case(None, &["a.example", "b.example", "c.example", "d.example"])]
+    #[case(Some("name:d"), &["d.example", "c.example", "b.example", "a.example"])]
+    #[case(Some("registrationDate"), &["c.example", "b.example", "d.example", "a.example"])]
+    #[case(Some("registrationDate:d,name"), &["d.example", "b.example", "c.example", "a.example"])]
+    fn GIVEN_sort_WHEN_sort_search_results_THEN_results_are_sorted(
+        #[case] sort: Option<&str>,
+        #[case] expected: &[&str],
+    ) {
+        // GIVEN
+        let uri = Uri::from_static("/rdap/domains?name=*.example");
+
+        // WHEN
+        let actual =
+            sort_search_results(domain_search_results(), sort, &uri).expect("sorting results");
+
+        // THEN
+        assert!(actual.has_extension_id(ExtensionId::Sorting));
+        let RdapResponse::DomainSearchResults(search) = actual else {
+            panic!("not domain search results")
+        };
+        let names = search
+            .results
+            .iter()
+            .map(|d| d.ldh_name.as_deref().expect("ldhName is none"))
+            .collect::<Vec<&str>>();
+        assert_eq!(names, expected);
+        let sorting = search.sorting_metadata.expect("no sorting metadata");
+        assert_eq!(sorting.current_sort.as_deref(), sort);
+        assert!(sorting
+            .available_sorts()
+            .iter()
+            .any(|s| s.property.as_deref() == Some("name") && s.is_default()));
+    }
+
+    #[rstest]
+    #[case("registrar")]
+    #[case("name:x")]
+    fn GIVEN_invalid_sort_WHEN_sort_search_results_THEN_error(#[case] sort: &str) {
+        // GIVEN
+        let uri = Uri::from_static("/rdap/domains?name=*.example");
+
+        // WHEN
+        let actual = sort_search_results(domain_search_results(), Some(sort), &uri);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+}
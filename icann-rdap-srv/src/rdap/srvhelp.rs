@@ -2,10 +2,20 @@ use {
     axum::{extract::State, response::Response},
     axum_extra::typed_header::TypedHeader,
     headers::Host,
-    icann_rdap_common::response::RdapResponse,
+    icann_rdap_common::{
+        response::{ExtensionId, Help, RdapResponse, ReverseSearchProperty},
+        search_types::RelatedEntityProperty,
+    },
 };
 
-use crate::{error::RdapServerError, rdap::response::ResponseUtil, server::DynServiceState};
+use crate::{
+    error::RdapServerError,
+    rdap::{add_conformance, response::ResponseUtil},
+    server::DynServiceState,
+};
+
+/// The resource types that may be reverse searched.
+const REVERSE_SEARCHABLE_TYPES: [&str; 3] = ["domains", "nameservers", "entities"];
 
 /// Get server help.
 #[axum_macros::debug_handler]
@@ -23,5 +33,76 @@ pub(crate) async fn srvhelp(
         srv_help = storage.get_srv_help(None).await?;
     }
 
+    if storage.get_common_config().reverse_search_enable {
+        if let RdapResponse::Help(help) = &mut srv_help {
+            add_reverse_search(help);
+        }
+    }
+
+    if storage.get_common_config().rir_search_enable {
+        if let RdapResponse::Help(help) = &mut srv_help {
+            add_rir_search(help);
+        }
+    }
+
     Ok(srv_help.response())
 }
+
+/// Advertises the supported reverse searches in a help response as specified
+/// in RFC 9536, unless the help response already describes them.
+fn add_reverse_search(help: &mut Help) {
+    if help.reverse_search_properties.is_some() {
+        return;
+    }
+    let mut properties = vec![];
+    for searchable_type in REVERSE_SEARCHABLE_TYPES {
+        for property in [
+            RelatedEntityProperty::FullName,
+            RelatedEntityProperty::Handle,
+            RelatedEntityProperty::Email,
+        ] {
+            properties.push(
+                ReverseSearchProperty::builder()
+                    .searchable_resource_type(searchable_type)
+                    .related_resource_type("entity")
+                    .property(property.to_string())
+                    .property_path(property_path(property))
+                    .build(),
+            );
+        }
+        properties.push(
+            ReverseSearchProperty::builder()
+                .searchable_resource_type(searchable_type)
+                .related_resource_type("entity")
+                .property("role")
+                .property_path("$.entities[*].roles")
+                .build(),
+        );
+    }
+    help.reverse_search_properties = Some(properties);
+
+    add_conformance(&mut help.common, ExtensionId::ReverseSearch);
+}
+
+/// Advertises the searches of draft-ietf-regext-rdap-rir-search in the
+/// conformance of a help response.
+fn add_rir_search(help: &mut Help) {
+    for extension in [
+        ExtensionId::RirSearch1,
+        ExtensionId::Ips,
+        ExtensionId::IpSearchResults,
+        ExtensionId::Autnums,
+        ExtensionId::AutnumSearchResults,
+    ] {
+        add_conformance(&mut help.common, extension);
+    }
+}
+
+/// The JSONPath of a related entity property, as given in RFC 9536.
+fn property_path(property: RelatedEntityProperty) -> &'static str {
+    match property {
+        RelatedEntityProperty::FullName => "$.entities[*].vcardArray[1][?(@[0]=='fn')][3]",
+        RelatedEntityProperty::Handle => "$.entities[*].handle",
+        RelatedEntityProperty::Email => "$.entities[*].vcardArray[1][?(@[0]=='email')][3]",
+    }
+}
@@ -0,0 +1,458 @@
+use std::str::FromStr;
+
+use {
+    http::Uri,
+    icann_rdap_common::{
+        contact::{JsCard, JsName},
+        media_types::RDAP_MEDIA_TYPE,
+        response::{
+            Autnum, Domain, Entity, ExtensionId, FieldSet, Link, Nameserver, Network, ObjectCommon,
+            RdapResponse, SubsettingMetadata,
+        },
+    },
+    serde_json::Value,
+    strum::IntoEnumIterator,
+    strum_macros::{Display, EnumIter, EnumString},
+};
+
+use crate::error::RdapServerError;
+
+use super::{add_conformance, replace_query_param};
+
+/// The query parameter of the RFC 8982 field set.
+const FIELD_SET_PARAM: &str = "fieldSet";
+
+/// The field sets of RFC 8982 that search results may be returned with.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Display, EnumString, EnumIter)]
+enum FieldSetName {
+    #[strum(serialize = "id")]
+    Id,
+    #[strum(serialize = "brief")]
+    Brief,
+    #[strum(serialize = "full")]
+    Full,
+}
+
+impl FieldSetName {
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Id => "Only the key fields of each object.",
+            Self::Brief => "The key fields, handle, status, and self link of each object.",
+            Self::Full => "All the information the server can provide.",
+        }
+    }
+}
+
+/// Trims search results to a field set as specified in RFC 8982.
+///
+/// Without a field set, results are returned in full. An unknown field set is
+/// an [RdapServerError::InvalidArg]. Responses other than search results are
+/// returned as they are.
+pub(crate) fn subset_search_results(
+    results: RdapResponse,
+    field_set: Option<&str>,
+    uri: &Uri,
+) -> Result<RdapResponse, RdapServerError> {
+    let field_set = match field_set {
+        Some(field_set) => FieldSetName::from_str(field_set).map_err(|_| {
+            RdapServerError::InvalidArg(format!("field set '{field_set}' is not supported"))
+        })?,
+        None => FieldSetName::Full,
+    };
+    let subset = match results {
+        RdapResponse::DomainSearchResults(mut search) => {
+            search.results = search
+                .results
+                .into_iter()
+                .map(|domain| subset_domain(domain, field_set))
+                .collect();
+            search.subsetting_metadata = Some(subsetting_metadata(field_set, uri));
+            add_conformance(&mut search.common, ExtensionId::Subsetting);
+            RdapResponse::DomainSearchResults(search)
+        }
+        RdapResponse::NameserverSearchResults(mut search) => {
+            search.results = search
+                .results
+                .into_iter()
+                .map(|nameserver| subset_nameserver(nameserver, field_set))
+                .collect();
+            search.subsetting_metadata = Some(subsetting_metadata(field_set, uri));
+            add_conformance(&mut search.common, ExtensionId::Subsetting);
+            RdapResponse::NameserverSearchResults(search)
+        }
+        RdapResponse::EntitySearchResults(mut search) => {
+            search.results = search
+                .results
+                .into_iter()
+                .map(|entity| subset_entity(entity, field_set))
+                .collect();
+            search.subsetting_metadata = Some(subsetting_metadata(field_set, uri));
+            add_conformance(&mut search.common, ExtensionId::Subsetting);
+            RdapResponse::EntitySearchResults(search)
+        }
+        RdapResponse::NetworkSearchResults(mut search) => {
+            search.results = search
+                .results
+                .into_iter()
+                .map(|network| subset_network(network, field_set))
+                .collect();
+            search.subsetting_metadata = Some(subsetting_metadata(field_set, uri));
+            add_conformance(&mut search.common, ExtensionId::Subsetting);
+            RdapResponse::NetworkSearchResults(search)
+        }
+        RdapResponse::AutnumSearchResults(mut search) => {
+            search.results = search
+                .results
+                .into_iter()
+                .map(|autnum| subset_autnum(autnum, field_set))
+                .collect();
+            search.subsetting_metadata = Some(subsetting_metadata(field_set, uri));
+            add_conformance(&mut search.common, ExtensionId::Subsetting);
+            RdapResponse::AutnumSearchResults(search)
+        }
+        _ => results,
+    };
+    Ok(subset)
+}
+
+fn subsetting_metadata(field_set: FieldSetName, uri: &Uri) -> SubsettingMetadata {
+    let available_field_sets = FieldSetName::iter()
+        .map(|name| {
+            FieldSet::builder()
+                .name(name.to_string())
+                .default(name == FieldSetName::Full)
+                .description(name.description())
+                .link(
+                    Link::builder()
+                        .value(uri.to_string())
+                        .rel("alternate")
+                        .href(replace_query_param(
+                            uri,
+                            &[],
+                            FIELD_SET_PARAM,
+                            &name.to_string(),
+                        ))
+                        .title("Result Subset Link")
+                        .media_type(RDAP_MEDIA_TYPE)
+                        .build(),
+                )
+                .build()
+        })
+        .collect::<Vec<FieldSet>>();
+    SubsettingMetadata::builder()
+        .current_field_set(field_set.to_string())
+        .available_field_sets(available_field_sets)
+        .build()
+}
+
+fn subset_domain(mut domain: Domain, field_set: FieldSetName) -> Domain {
+    if field_set == FieldSetName::Full {
+        return domain;
+    }
+    subset_object_common(&mut domain.object_common, field_set, false);
+    domain.variants = None;
+    domain.secure_dns = None;
+    domain.nameservers = None;
+    domain.public_ids = None;
+    domain.network = None;
+    domain
+}
+
+fn subset_nameserver(mut nameserver: Nameserver, field_set: FieldSetName) -> Nameserver {
+    if field_set == FieldSetName::Full {
+        return nameserver;
+    }
+    subset_object_common(&mut nameserver.object_common, field_set, false);
+    nameserver.ip_addresses = None;
+    nameserver
+}
+
+fn subset_entity(mut entity: Entity, field_set: FieldSetName) -> Entity {
+    if field_set == FieldSetName::Full {
+        return entity;
+    }
+    subset_object_common(&mut entity.object_common, field_set, true);
+    entity.vcard_array = match field_set {
+        FieldSetName::Brief => entity.vcard_array.map(brief_vcard),
+        _ => None,
+    };
+    entity.jscard = match field_set {
+        FieldSetName::Brief => entity.jscard.map(brief_jscard),
+        _ => None,
+    };
+    if field_set == FieldSetName::Id {
+        entity.roles = None;
+    }
+    entity.public_ids = None;
+    entity.as_event_actor = None;
+    entity.autnums = None;
+    entity.networks = None;
+    entity
+}
+
+fn subset_network(mut network: Network, field_set: FieldSetName) -> Network {
+    if field_set == FieldSetName::Full {
+        return network;
+    }
+    subset_object_common(&mut network.object_common, field_set, true);
+    if field_set == FieldSetName::Id {
+        network.name = None;
+        network.network_type = None;
+        network.parent_handle = None;
+        network.country = None;
+    }
+    network
+}
+
+fn subset_autnum(mut autnum: Autnum, field_set: FieldSetName) -> Autnum {
+    if field_set == FieldSetName::Full {
+        return autnum;
+    }
+    subset_object_common(&mut autnum.object_common, field_set, true);
+    if field_set == FieldSetName::Id {
+        autnum.name = None;
+        autnum.autnum_type = None;
+        autnum.country = None;
+    }
+    autnum
+}
+
+/// Trims the members common to all objects.
+///
+/// The handle is the key of entities, IP networks, and autnums, and so it is
+/// kept in every field set for them.
+fn subset_object_common(
+    object_common: &mut ObjectCommon,
+    field_set: FieldSetName,
+    handle_is_key: bool,
+) {
+    if field_set == FieldSetName::Id {
+        if !handle_is_key {
+            object_common.handle = None;
+        }
+        object_common.status = None;
+        object_common.links = None;
+    } else {
+        object_common.links = object_common.get_self_link().map(|link| vec![link.clone()]);
+    }
+    object_common.remarks = None;
+    object_common.events = None;
+    object_common.port_43 = None;
+    object_common.entities = None;
+    object_common.redacted = None;
+}
+
+/// Keeps only the version and formatted name of a jCard.
+fn brief_vcard(vcard_array: Vec<Value>) -> Vec<Value> {
+    vcard_array
+        .into_iter()
+        .map(|value| match value {
+            Value::Array(properties) => Value::Array(
+                properties
+                    .into_iter()
+                    .filter(|property| {
+                        matches!(
+                            property.get(0).and_then(Value::as_str),
+                            Some("version") | Some("fn")
+                        )
+                    })
+                    .collect(),
+            ),
+            value => value,
+        })
+        .collect()
+}
+
+/// Keeps only the type, version, identifier, and full name of a JSContact card.
+fn brief_jscard(jscard: JsCard) -> JsCard {
+    JsCard {
+        card_type: jscard.card_type,
+        version: jscard.version,
+        uid: jscard.uid,
+        name: jscard.name.and_then(|name| name.full).map(|full| JsName {
+            full: Some(full),
+            components: None,
+        }),
+        ..JsCard::default()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        http::Uri,
+        icann_rdap_common::{
+            contact::{Contact, Email},
+            prelude::ToResponse,
+            response::{
+                Domain, DomainSearchResults, Entity, EntitySearchResults, Event, ExtensionId, Link,
+                Nameserver, ObjectCommonFields, RdapResponse,
+            },
+        },
+        rstest::rstest,
+    };
+
+    use super::subset_search_results;
+
+    fn domain_search_results() -> RdapResponse {
+        DomainSearchResults::builder()
+            .result(
+                Domain::builder()
+                    .ldh_name("foo.example")
+                    .handle("FOO-1")
+                    .status("active")
+                    .event(
+                        Event::builder()
+                            .event_action("registration")
+                            .event_date("2020-01-01T00:00:00Z")
+                            .build(),
+                    )
+                    .link(
+                        Link::builder()
+                            .value("https://example.com/rdap/domain/foo.example")
+                            .rel("self")
+                            .href("https://example.com/rdap/domain/foo.example")
+                            .build(),
+                    )
+                    .link(
+                        Link::builder()
+                            .value("https://example.com/rdap/domain/foo.example")
+                            .rel("related")
+                            .href("https://example.com/rdap/domain/bar.example")
+                            .build(),
+                    )
+                    .nameservers(vec![Nameserver::builder()
+                        .ldh_name("ns.foo.example")
+                        .build()
+                        .expect("building nameserver")])
+                    .build(),
+            )
+            .build()
+            .to_response()
+    }
+
+    #[rstest]
+    #[case(None, "full", true, 2, 1)]
+    #[case(Some("full"), "full", true, 2, 1)]
+    #[case(Some("brief"), "brief", true, 1, 0)]
+    #[case(Some("id"), "id", false, 0, 0)]
+    fn GIVEN_field_set_WHEN_subset_domains_THEN_fields_are_trimmed(
+        #[case] field_set: Option<&str>,
+        #[case] expected_field_set: &str,
+        #[case] expected_handle: bool,
+        #[case] expected_links: usize,
+        #[case] expected_nameservers: usize,
+    ) {
+        // GIVEN
+        let uri = Uri::from_static("/rdap/domains?name=foo*");
+
+        // WHEN
+        let actual =
+            subset_search_results(domain_search_results(), field_set, &uri).expect("subsetting");
+
+        // THEN
+        assert!(actual.has_extension_id(ExtensionId::Subsetting));
+        let RdapResponse::DomainSearchResults(search) = actual else {
+            panic!("not domain search results")
+        };
+        let domain = &search.results[0];
+        assert_eq!(domain.ldh_name.as_deref(), Some("foo.example"));
+        assert_eq!(domain.object_common.handle.is_some(), expected_handle);
+        assert_eq!(domain.links().len(), expected_links);
+        assert_eq!(domain.nameservers().len(), expected_nameservers);
+        let subsetting = search.subsetting_metadata.expect("no subsetting metadata");
+        assert_eq!(
+            subsetting.current_field_set.as_deref(),
+            Some(expected_field_set)
+        );
+        assert_eq!(subsetting.available_field_sets().len(), 3);
+    }
+
+    #[test]
+    fn GIVEN_brief_field_set_WHEN_subset_entities_THEN_only_fn_in_vcard() {
+        // GIVEN
+        let results = EntitySearchResults::builder()
+            .result(
+                Entity::builder()
+                    .handle("BOB-1")
+                    .contact(
+                        Contact::builder()
+                            .full_name("Bob Smurd")
+                            .emails(vec![Email::builder().email("bob@example.com").build()])
+                            .build(),
+                    )
+                    .role("registrant")
+                    .build(),
+            )
+            .build()
+            .to_response();
+        let uri = Uri::from_static("/rdap/entities?handle=BOB*");
+
+        // WHEN
+        let actual = subset_search_results(results, Some("brief"), &uri).expect("subsetting");
+
+        // THEN
+        let RdapResponse::EntitySearchResults(search) = actual else {
+            panic!("not entity search results")
+        };
+        let entity = &search.results[0];
+        assert_eq!(entity.object_common.handle.as_deref(), Some("BOB-1"));
+        let contact = entity.contact().expect("no contact");
+        assert_eq!(contact.full_name.as_deref(), Some("Bob Smurd"));
+        assert!(contact.emails.is_none());
+        assert_eq!(entity.roles(), &vec!["registrant".to_string()]);
+    }
+
+    #[rstest]
+    #[case("brief", true)]
+    #[case("id", false)]
+    fn GIVEN_entity_with_jscard_WHEN_subset_entities_THEN_jscard_trimmed(
+        #[case] field_set: &str,
+        #[case] expected_name: bool,
+    ) {
+        // GIVEN
+        let mut entity = Entity::builder().handle("BOB-1").role("registrant").build();
+        entity.jscard = Some(
+            Contact::builder()
+                .full_name("Bob Smurd")
+                .emails(vec![Email::builder().email("bob@example.com").build()])
+                .build()
+                .to_jscard(),
+        );
+        let results = EntitySearchResults::builder()
+            .result(entity)
+            .build()
+            .to_response();
+        let uri = Uri::from_static("/rdap/entities?handle=BOB*");
+
+        // WHEN
+        let actual = subset_search_results(results, Some(field_set), &uri).expect("subsetting");
+
+        // THEN
+        let RdapResponse::EntitySearchResults(search) = actual else {
+            panic!("not entity search results")
+        };
+        let jscard = search.results[0].jscard.as_ref();
+        assert_eq!(jscard.is_some(), expected_name);
+        if let Some(jscard) = jscard {
+            assert_eq!(
+                jscard.name.as_ref().and_then(|name| name.full.as_deref()),
+                Some("Bob Smurd")
+            );
+            assert!(jscard.emails.is_none());
+        }
+    }
+
+    #[test]
+    fn GIVEN_unknown_field_set_WHEN_subset_THEN_error() {
+        // GIVEN
+        let uri = Uri::from_static("/rdap/domains?name=foo*&fieldSet=tiny");
+
+        // WHEN
+        let actual = subset_search_results(domain_search_results(), Some("tiny"), &uri);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+}
@@ -1,11 +1,11 @@
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{collections::HashMap, future::Future, net::SocketAddr, sync::Arc, time::Duration};
 
 use {
     async_trait::async_trait,
-    axum::{error_handling::HandleErrorLayer, Router},
+    axum::{error_handling::HandleErrorLayer, middleware, Router},
     http::{Method, StatusCode},
     icann_rdap_common::VERSION,
-    tokio::net::TcpListener,
+    tokio::{net::TcpListener, task::JoinSet},
     tower::{BoxError, ServiceBuilder},
     tower_http::{
         cors::{Any, CorsLayer},
@@ -14,22 +14,51 @@ use {
 };
 
 use crate::{
+    admin::admin_router,
     bootstrap::init_bootstrap,
-    config::{ListenConfig, ServiceConfig, StorageType},
+    cache::cache_headers,
+    config::{
+        AdminConfig, CacheConfig, ContactFormat, ListenConfig, RateLimitConfig, RedactionConfig,
+        ServiceConfig, StorageType, TenantConfig,
+    },
     error::RdapServerError,
+    metrics::{metrics_router, track_requests, Metrics},
+    rate_limit::{rate_limit, RateLimiter},
     rdap::router::rdap_router,
+    shutdown::{Shutdown, DEFAULT_SHUTDOWN_TIMEOUT},
+    socket::take_inherited,
     storage::{
-        data::{load_data, reload_data},
+        data::{load_data, load_data_since, reload_data, save_snapshot},
         mem::{config::MemConfig, ops::Mem},
         pg::{config::PgConfig, ops::Pg},
         StoreOps,
     },
+    tenant::{tenant_by_host, TenantHosts},
+    tls::{serve_tls, tls_acceptor, ReloadingCertResolver},
 };
 
+/// The IP address the admin API is bound to when TLS is not configured.
+const ADMIN_PLAIN_HTTP_IP_ADDR: &str = "127.0.0.1";
+
 /// Holds information on the server listening.
 pub struct Listener {
     pub local_addr: SocketAddr,
     tcp_listener: TcpListener,
+
+    /// If TLS is configured, the certificate of the server.
+    tls: Option<Arc<ReloadingCertResolver>>,
+
+    /// If TLS is configured with plain HTTP alongside, the plain HTTP listener.
+    http: Option<(SocketAddr, TcpListener)>,
+
+    /// If metrics are configured, the listener of the metrics endpoint.
+    metrics: Option<(SocketAddr, TcpListener)>,
+
+    /// If the admin API is configured, its listener and configuration.
+    admin: Option<(SocketAddr, TcpListener, AdminConfig)>,
+
+    /// Shuts down the server gracefully.
+    shutdown: Shutdown,
 }
 
 /// Starts the RDAP service.
@@ -40,94 +69,361 @@ impl Listener {
         #[cfg(debug_assertions)]
         tracing::warn!("Server is running in development mode");
 
-        let binding = format!(
-            "{}:{}",
-            config.ip_addr.as_ref().unwrap_or(&"[::]".to_string()),
-            config.port.as_ref().unwrap_or(&0)
-        );
-
-        tracing::debug!("tcp binding to {}", binding);
+        let ip_addr = config.ip_addr.clone().unwrap_or("[::]".to_string());
+        let shutdown = Shutdown::new(config.shutdown_timeout.unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT));
+        let metrics = match config.metrics_port {
+            Some(port) => {
+                let metrics_listener = bind(&ip_addr, Some(port)).await?;
+                Some((metrics_listener.local_addr()?, metrics_listener))
+            }
+            None => None,
+        };
+        let admin = match &config.admin {
+            Some(admin_config) => {
+                // without TLS, the API keys would cross the network in the clear.
+                let admin_ip_addr = if config.tls.is_some() {
+                    ip_addr.as_str()
+                } else {
+                    ADMIN_PLAIN_HTTP_IP_ADDR
+                };
+                let admin_listener = bind(admin_ip_addr, Some(admin_config.port)).await?;
+                Some((
+                    admin_listener.local_addr()?,
+                    admin_listener,
+                    admin_config.clone(),
+                ))
+            }
+            None => None,
+        };
+        let Some(tls_config) = &config.tls else {
+            let listener = bind(&ip_addr, config.port).await?;
+            return Ok(Self {
+                local_addr: listener.local_addr()?,
+                tcp_listener: listener,
+                tls: None,
+                http: None,
+                metrics,
+                admin,
+                shutdown,
+            });
+        };
 
-        let listener = TcpListener::bind(binding).await?;
-        let local_addr = listener.local_addr()?;
+        let resolver = Arc::new(ReloadingCertResolver::new(tls_config)?);
+        let listener = bind(&ip_addr, tls_config.port).await?;
+        let http = if tls_config.with_http {
+            let http_listener = bind(&ip_addr, config.port).await?;
+            Some((http_listener.local_addr()?, http_listener))
+        } else {
+            None
+        };
         Ok(Self {
-            local_addr,
+            local_addr: listener.local_addr()?,
             tcp_listener: listener,
+            tls: Some(resolver),
+            http,
+            metrics,
+            admin,
+            shutdown,
         })
     }
 
+    /// Gets the base URL of RDAP, which is HTTPS if TLS is configured.
     pub fn rdap_base(&self) -> String {
-        if self.local_addr.is_ipv4() {
-            format!(
-                "http://{}:{}/rdap",
-                self.local_addr.ip(),
-                self.local_addr.port()
-            )
-        } else {
-            format!(
-                "http://[{}]:{}/rdap",
-                self.local_addr.ip(),
-                self.local_addr.port()
-            )
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        rdap_base(scheme, self.local_addr)
+    }
+
+    /// Gets the base URL of RDAP over plain HTTP, if it is served.
+    pub fn http_rdap_base(&self) -> Option<String> {
+        match (&self.tls, &self.http) {
+            (None, _) => Some(rdap_base("http", self.local_addr)),
+            (Some(_), Some((local_addr, _))) => Some(rdap_base("http", *local_addr)),
+            (Some(_), None) => None,
         }
     }
 
+    /// Gets the URL of the metrics endpoint, if metrics are served.
+    pub fn metrics_url(&self) -> Option<String> {
+        self.metrics.as_ref().map(|(local_addr, _)| {
+            let base = rdap_base("http", *local_addr);
+            format!("{}/metrics", base.trim_end_matches("/rdap"))
+        })
+    }
+
+    /// Gets the base URL of the admin API, if it is served.
+    pub fn admin_url(&self) -> Option<String> {
+        self.admin.as_ref().map(|(local_addr, _, _)| {
+            let scheme = if self.tls.is_some() { "https" } else { "http" };
+            let base = rdap_base(scheme, *local_addr);
+            base.trim_end_matches("/rdap").to_string()
+        })
+    }
+
+    /// Gets the handle shutting down the server gracefully once it is started.
+    pub fn shutdown_handle(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
     /// Starts the server using a [ServiceConfig]. This is the entry point for a CLI.
     /// This function will initiate any needed non-HTTP services and then call
     /// call [Listener::start_with_state], which initiates the HTTP service.
+    ///
+    /// The server is shut down gracefully on SIGTERM or Ctrl-C.
     pub async fn start_server(self, service_config: &ServiceConfig) -> Result<(), RdapServerError> {
+        tokio::spawn(self.shutdown.clone().on_signal());
         init_bootstrap(service_config).await?;
         if let StorageType::Memory(config) = &service_config.storage_type {
             let app_state = AppState::new_mem(config.clone(), service_config).await?;
-            self.start_with_state(app_state).await?;
+            let mut tenants = vec![];
+            for tenant in &service_config.tenants {
+                let tenant_service_config = service_config.for_tenant(tenant)?;
+                init_bootstrap(&tenant_service_config).await?;
+                let StorageType::Memory(config) = &tenant_service_config.storage_type else {
+                    return Err(tenant_storage_error(tenant));
+                };
+                let state = AppState::new_mem(config.clone(), &tenant_service_config).await?;
+                tenants.push(Tenant::new(tenant.clone(), state));
+            }
+            self.start_with_tenants(app_state, tenants).await?;
         } else if let StorageType::Postgres(config) = &service_config.storage_type {
             let app_state = AppState::new_pg(config.clone(), service_config).await?;
-            self.start_with_state(app_state).await?;
+            let mut tenants = vec![];
+            for tenant in &service_config.tenants {
+                let tenant_service_config = service_config.for_tenant(tenant)?;
+                init_bootstrap(&tenant_service_config).await?;
+                let StorageType::Postgres(config) = &tenant_service_config.storage_type else {
+                    return Err(tenant_storage_error(tenant));
+                };
+                let state = AppState::new_pg(config.clone(), &tenant_service_config).await?;
+                tenants.push(Tenant::new(tenant.clone(), state));
+            }
+            self.start_with_tenants(app_state, tenants).await?;
         };
         Ok(())
     }
 
     /// Starts the HTTP server with a specific [AppState]. This is the entry point for a library or testing
-    /// framework.
+    /// framework. It runs until shut down with the [Listener::shutdown_handle].
     pub async fn start_with_state<T>(self, app_state: AppState<T>) -> Result<(), RdapServerError>
     where
         T: StoreOps + Clone + Send + Sync + 'static,
         AppState<T>: ServiceState,
     {
-        let app = app_router::<T>(app_state);
-
-        tracing::debug!("listening on {}", self.local_addr);
-        // axum::Server::from_tcp(self.tcp_listener)?
-        //     .serve(app.into_make_service_with_connect_info::<SocketAddr>())
-        //     .await?;
-        axum::serve(
-            self.tcp_listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
+        self.start_with_tenants(app_state, vec![]).await
+    }
+
+    /// Starts the HTTP server with a specific [AppState] for the default storage and
+    /// the [Tenant]s served from their own storage.
+    ///
+    /// The metrics and admin API are those of the default storage.
+    pub async fn start_with_tenants<T>(
+        self,
+        app_state: AppState<T>,
+        tenants: Vec<Tenant<T>>,
+    ) -> Result<(), RdapServerError>
+    where
+        T: StoreOps + Clone + Send + Sync + 'static,
+        AppState<T>: ServiceState,
+    {
+        let rate_limiter = Arc::new(RateLimiter::new(&app_state.rate_limit));
+        let state = Arc::new(app_state) as DynServiceState;
+        let tenants = tenants
+            .into_iter()
+            .map(|tenant| {
+                tracing::info!("serving tenant {}", tenant.config.name);
+                (tenant.config, Arc::new(tenant.state) as DynServiceState)
+            })
+            .collect();
+        let app = app_router(state.clone(), tenants, rate_limiter);
+        let shutdown = self.shutdown;
+        let acceptor = match &self.tls {
+            Some(resolver) => Some(tls_acceptor(resolver.clone())?),
+            None => None,
+        };
+
+        let mut side_servers = JoinSet::new();
+        if let Some((metrics_local_addr, metrics_listener)) = self.metrics {
+            tracing::debug!("serving metrics on {}", metrics_local_addr);
+            let metrics_app = metrics_router(state.clone());
+            let shutdown = shutdown.clone();
+            side_servers.spawn(async move {
+                if let Err(e) = serve_http(metrics_listener, metrics_app, shutdown).await {
+                    tracing::error!("metrics server failed: {e}");
+                }
+            });
+        }
+        if let Some((admin_local_addr, admin_listener, admin_config)) = self.admin {
+            tracing::debug!("serving admin API on {}", admin_local_addr);
+            let admin_app = admin_router(state, admin_config);
+            let shutdown = shutdown.clone();
+            let acceptor = acceptor.clone();
+            side_servers.spawn(async move {
+                let served = match acceptor {
+                    Some(acceptor) => {
+                        serve_tls(admin_listener, acceptor, admin_app, shutdown).await
+                    }
+                    None => serve_http(admin_listener, admin_app, shutdown).await,
+                };
+                if let Err(e) = served {
+                    tracing::error!("admin API server failed: {e}");
+                }
+            });
+        }
+
+        let (Some(resolver), Some(acceptor)) = (self.tls, acceptor) else {
+            tracing::debug!("listening on {}", self.local_addr);
+            let server = serve_http(self.tcp_listener, app, shutdown.clone());
+            return shutdown
+                .drain(with_side_servers(server, side_servers))
+                .await;
+        };
+
+        tokio::spawn(resolver.reload_periodically());
+        tracing::debug!("listening for TLS on {}", self.local_addr);
+        if let Some((http_local_addr, http_listener)) = self.http {
+            tracing::debug!("listening on {}", http_local_addr);
+            let servers = async {
+                tokio::try_join!(
+                    serve_tls(self.tcp_listener, acceptor, app.clone(), shutdown.clone()),
+                    serve_http(http_listener, app, shutdown.clone())
+                )?;
+                Ok(())
+            };
+            shutdown
+                .drain(with_side_servers(servers, side_servers))
+                .await
+        } else {
+            let server = serve_tls(self.tcp_listener, acceptor, app, shutdown.clone());
+            shutdown
+                .drain(with_side_servers(server, side_servers))
+                .await
+        }
+    }
+}
+
+/// Runs the main server, then waits for the metrics and admin servers to finish
+/// draining. If the main server fails, the others are stopped.
+async fn with_side_servers<F>(
+    server: F,
+    mut side_servers: JoinSet<()>,
+) -> Result<(), RdapServerError>
+where
+    F: Future<Output = Result<(), RdapServerError>>,
+{
+    let served = server.await;
+    if served.is_err() {
+        side_servers.abort_all();
+    }
+    while side_servers.join_next().await.is_some() {}
+    served
+}
+
+/// The error of a tenant whose storage is not of the type of the default storage,
+/// as all the storage of a server is of one type.
+fn tenant_storage_error(tenant: &TenantConfig) -> RdapServerError {
+    RdapServerError::Config(format!(
+        "tenant '{}' must use the storage type of the server",
+        tenant.name
+    ))
+}
+
+async fn bind(ip_addr: &str, port: Option<u16>) -> Result<TcpListener, RdapServerError> {
+    let binding = format!("{}:{}", ip_addr, port.unwrap_or(0));
+    if let Some(listener) = binding.parse().ok().and_then(|addr| take_inherited(&addr)) {
+        tracing::debug!("using inherited socket for {}", binding);
+        listener.set_nonblocking(true)?;
+        return Ok(TcpListener::from_std(listener)?);
+    }
+    tracing::debug!("tcp binding to {}", binding);
+    Ok(TcpListener::bind(binding).await?)
+}
+
+fn rdap_base(scheme: &str, local_addr: SocketAddr) -> String {
+    if local_addr.is_ipv4() {
+        format!("{scheme}://{}:{}/rdap", local_addr.ip(), local_addr.port())
+    } else {
+        format!(
+            "{scheme}://[{}]:{}/rdap",
+            local_addr.ip(),
+            local_addr.port()
         )
-        .await?;
-        Ok(())
     }
 }
 
+/// Serves plain HTTP until shut down, when the connections are drained.
+async fn serve_http(
+    tcp_listener: TcpListener,
+    app: Router,
+    shutdown: Shutdown,
+) -> Result<(), RdapServerError> {
+    axum::serve(
+        tcp_listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move { shutdown.signaled().await })
+    .await?;
+    Ok(())
+}
+
 async fn init_data(
     store: Box<dyn StoreOps>,
     config: &ServiceConfig,
+    metrics: Arc<Metrics>,
 ) -> Result<(), RdapServerError> {
-    load_data(config, &*store, false).await?;
+    let restored = match store.restore_snapshot().await {
+        Ok(restored) => restored,
+        Err(e) => {
+            tracing::warn!("unable to restore snapshot of storage, loading all data: {e}");
+            None
+        }
+    };
+    let files = match restored.as_ref() {
+        Some(loaded) => {
+            tracing::info!("Storage restored from snapshot.");
+            load_data_since(config, &*store, loaded).await?
+        }
+        None => load_data(config, &*store, false).await?,
+    };
+    metrics.data_loaded(&*store).await;
+    if restored.as_ref() != Some(&files) {
+        save_snapshot(&*store, &files).await;
+    }
     if config.auto_reload {
-        tokio::spawn(reload_data(store, config.clone()));
+        tokio::spawn(reload_data(store, config.clone(), metrics, files));
     }
     Ok(())
 }
 
-fn app_router<T>(state: AppState<T>) -> Router
-where
-    T: StoreOps + Clone + Send + Sync + 'static,
-    AppState<T>: ServiceState,
-{
-    let state = Arc::new(state) as DynServiceState;
+fn app_router(
+    state: DynServiceState,
+    tenants: Vec<(TenantConfig, DynServiceState)>,
+    rate_limiter: Arc<RateLimiter>,
+) -> Router {
+    let mut routes = Router::new().nest("/rdap", rdap_router());
+    let mut hosts = HashMap::new();
+    for (config, tenant_state) in tenants {
+        if let Some(path_prefix) = &config.path_prefix {
+            routes = routes.nest(
+                &format!("/{path_prefix}/rdap"),
+                rdap_router().with_state(tenant_state.clone()),
+            );
+        }
+        let host_routes = Router::new()
+            .nest("/rdap", rdap_router())
+            .with_state(tenant_state);
+        for host in &config.hosts {
+            hosts.insert(host.to_ascii_lowercase(), host_routes.clone());
+        }
+    }
+    // the routes are the fallback so that tenants are chosen by host before routing.
     Router::new()
-        .nest("/rdap", rdap_router())
+        .fallback_service(routes.with_state(state.clone()))
+        .layer(middleware::from_fn_with_state(
+            TenantHosts::new(hosts),
+            tenant_by_host,
+        ))
+        .layer(middleware::from_fn_with_state(state.clone(), cache_headers))
+        .layer(middleware::from_fn_with_state(rate_limiter, rate_limit))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(|error: BoxError| async move {
@@ -145,11 +441,15 @@ where
                 .layer(
                     CorsLayer::new()
                         .allow_origin(Any)
-                        .allow_methods(vec![Method::GET])
+                        .allow_methods(vec![Method::GET, Method::HEAD])
                         .allow_headers(Any),
                 )
                 .into_inner(),
         )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            track_requests,
+        ))
         .with_state(state)
 }
 
@@ -163,6 +463,18 @@ pub trait ServiceState: std::fmt::Debug {
     /// If returns true, this indicates the server has been configured to do
     /// bootstrapping.
     fn get_bootstrap(&self) -> bool;
+
+    /// Gets the redaction of responses for anonymous callers.
+    fn get_redaction(&self) -> &RedactionConfig;
+
+    /// Gets the cache lifetimes of responses.
+    fn get_cache(&self) -> &CacheConfig;
+
+    /// Gets the formats contacts of entities are served in.
+    fn get_contact_format(&self) -> ContactFormat;
+
+    /// Gets the metrics of the server.
+    fn get_metrics(&self) -> &Metrics;
 }
 
 /// State that is passed to the HTTP service router and used by functions
@@ -171,6 +483,23 @@ pub trait ServiceState: std::fmt::Debug {
 pub struct AppState<T: StoreOps + Clone + Send + Sync + 'static> {
     pub storage: T,
     pub bootstrap: bool,
+    pub rate_limit: RateLimitConfig,
+    pub redaction: RedactionConfig,
+    pub cache: CacheConfig,
+    pub contact_format: ContactFormat,
+    pub metrics: Arc<Metrics>,
+}
+
+/// A tenant of the server, with the state serving it from its own storage.
+pub struct Tenant<T: StoreOps + Clone + Send + Sync + 'static> {
+    pub config: TenantConfig,
+    pub state: AppState<T>,
+}
+
+impl<T: StoreOps + Clone + Send + Sync + 'static> Tenant<T> {
+    pub fn new(config: TenantConfig, state: AppState<T>) -> Self {
+        Self { config, state }
+    }
 }
 
 impl AppState<Mem> {
@@ -180,10 +509,16 @@ impl AppState<Mem> {
     ) -> Result<Self, RdapServerError> {
         let storage = Mem::new(config);
         storage.init().await?;
-        init_data(Box::new(storage.clone()), service_config).await?;
+        let metrics = Arc::new(Metrics::new());
+        init_data(Box::new(storage.clone()), service_config, metrics.clone()).await?;
         Ok(Self {
             storage,
             bootstrap: service_config.bootstrap,
+            rate_limit: service_config.rate_limit.clone(),
+            redaction: service_config.redaction.clone(),
+            cache: service_config.cache,
+            contact_format: service_config.contact_format,
+            metrics,
         })
     }
 }
@@ -201,10 +536,16 @@ impl AppState<Pg> {
     ) -> Result<Self, RdapServerError> {
         let storage = Pg::new(config).await?;
         storage.init().await?;
-        init_data(Box::new(storage.clone()), service_config).await?;
+        let metrics = Arc::new(Metrics::new());
+        init_data(Box::new(storage.clone()), service_config, metrics.clone()).await?;
         Ok(Self {
             storage,
             bootstrap: service_config.bootstrap,
+            rate_limit: service_config.rate_limit.clone(),
+            redaction: service_config.redaction.clone(),
+            cache: service_config.cache,
+            contact_format: service_config.contact_format,
+            metrics,
         })
     }
 }
@@ -224,6 +565,22 @@ impl ServiceState for AppState<Pg> {
     fn get_bootstrap(&self) -> bool {
         self.bootstrap
     }
+
+    fn get_redaction(&self) -> &RedactionConfig {
+        &self.redaction
+    }
+
+    fn get_cache(&self) -> &CacheConfig {
+        &self.cache
+    }
+
+    fn get_contact_format(&self) -> ContactFormat {
+        self.contact_format
+    }
+
+    fn get_metrics(&self) -> &Metrics {
+        &self.metrics
+    }
 }
 
 #[async_trait]
@@ -235,4 +592,20 @@ impl ServiceState for AppState<Mem> {
     fn get_bootstrap(&self) -> bool {
         self.bootstrap
     }
+
+    fn get_redaction(&self) -> &RedactionConfig {
+        &self.redaction
+    }
+
+    fn get_cache(&self) -> &CacheConfig {
+        &self.cache
+    }
+
+    fn get_contact_format(&self) -> ContactFormat {
+        self.contact_format
+    }
+
+    fn get_metrics(&self) -> &Metrics {
+        &self.metrics
+    }
 }
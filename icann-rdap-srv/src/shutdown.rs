@@ -0,0 +1,137 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use {
+    tokio::{sync::watch, time::sleep},
+    tracing::{info, warn},
+};
+
+use crate::error::RdapServerError;
+
+/// The time given to requests in flight to finish when the server is shut down,
+/// if none is configured.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shuts down the server gracefully.
+///
+/// Once shut down, the listeners of the server stop accepting connections and
+/// the requests in flight are given up to the shutdown timeout to finish.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+    timeout: Duration,
+}
+
+impl Shutdown {
+    pub(crate) fn new(timeout: Duration) -> Self {
+        let (tx, _) = watch::channel(false);
+        Self {
+            tx: Arc::new(tx),
+            timeout,
+        }
+    }
+
+    /// Starts shutting down the server.
+    pub fn shutdown(&self) {
+        self.tx.send_replace(true);
+    }
+
+    /// Waits for the server to be shut down.
+    pub(crate) async fn signaled(&self) {
+        let mut rx = self.tx.subscribe();
+        let _ = rx.wait_for(|shutdown| *shutdown).await;
+    }
+
+    /// Runs a server until it has drained its connections after being shut
+    /// down, or until the shutdown timeout passes.
+    pub(crate) async fn drain<F>(&self, server: F) -> Result<(), RdapServerError>
+    where
+        F: Future<Output = Result<(), RdapServerError>>,
+    {
+        tokio::pin!(server);
+        tokio::select! {
+            result = &mut server => result,
+            _ = async {
+                self.signaled().await;
+                sleep(self.timeout).await;
+            } => {
+                warn!(
+                    "Requests in flight did not finish within {:?} of shutdown.",
+                    self.timeout
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Shuts down the server on SIGTERM or Ctrl-C.
+    pub(crate) async fn on_signal(self) {
+        let ctrl_c = async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                warn!("unable to listen for Ctrl-C: {e}");
+                std::future::pending::<()>().await;
+            }
+        };
+
+        #[cfg(unix)]
+        let terminate = async {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::terminate()) {
+                Ok(mut terminate) => {
+                    terminate.recv().await;
+                }
+                Err(e) => {
+                    warn!("unable to listen for SIGTERM: {e}");
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+
+        tokio::select! {
+            _ = ctrl_c => {},
+            _ = terminate => {},
+        }
+        info!(
+            "Shutting down, giving requests in flight {:?} to finish.",
+            self.timeout
+        );
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Shutdown;
+
+    #[tokio::test]
+    async fn GIVEN_server_not_draining_WHEN_shutdown_THEN_drain_ends_after_timeout() {
+        // GIVEN
+        let shutdown = Shutdown::new(Duration::from_millis(10));
+        let server = std::future::pending();
+
+        // WHEN
+        shutdown.shutdown();
+
+        // THEN
+        let actual = tokio::time::timeout(Duration::from_secs(5), shutdown.drain(server)).await;
+        assert!(matches!(actual, Ok(Ok(()))));
+    }
+
+    #[tokio::test]
+    async fn GIVEN_shutdown_WHEN_signaled_THEN_later_waiters_return() {
+        // GIVEN
+        let shutdown = Shutdown::new(Duration::from_secs(1));
+
+        // WHEN
+        shutdown.shutdown();
+
+        // THEN
+        let actual = tokio::time::timeout(Duration::from_secs(5), shutdown.signaled()).await;
+        assert!(actual.is_ok());
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    net::{SocketAddr, TcpListener},
+    sync::Mutex,
+};
+
+/// The listening sockets passed to the process, which have yet to be used.
+static INHERITED: Mutex<Vec<TcpListener>> = Mutex::new(Vec::new());
+
+/// Takes the listening sockets passed to the process, to be used by the
+/// listeners bound to their addresses.
+///
+/// This removes the `LISTEN_*` environment variables, so it must be called
+/// before other threads are started, such as those of the async runtime.
+pub fn inherit_sockets() {
+    let mut inherited = INHERITED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    inherited.extend(inherited_from_env());
+}
+
+/// Takes the socket passed to the process that is listening on an address, if
+/// there is one.
+///
+/// Sockets are passed with the `LISTEN_FDS` and `LISTEN_PID` environment
+/// variables of systemd socket activation, which are also used by other
+/// service managers and by processes handing their sockets to a new process.
+/// As the sockets stay open across restarts, connections wait to be accepted
+/// by the new process instead of being refused. They are only available once
+/// [inherit_sockets] has been called.
+pub(crate) fn take_inherited(addr: &SocketAddr) -> Option<TcpListener> {
+    let mut inherited = INHERITED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    take_matching(&mut inherited, addr)
+}
+
+/// Takes the listener bound to an address. A listener on any port matches an
+/// unspecified IP address, but port 0 matches nothing as it asks for a new port.
+fn take_matching(listeners: &mut Vec<TcpListener>, addr: &SocketAddr) -> Option<TcpListener> {
+    if addr.port() == 0 {
+        return None;
+    }
+    let index = listeners.iter().position(|listener| {
+        listener.local_addr().is_ok_and(|local| {
+            local.port() == addr.port() && (addr.ip().is_unspecified() || local.ip() == addr.ip())
+        })
+    })?;
+    Some(listeners.remove(index))
+}
+
+#[cfg(unix)]
+fn inherited_from_env() -> Vec<TcpListener> {
+    use std::os::fd::{FromRawFd, RawFd};
+
+    /// The first file descriptor passed by socket activation.
+    const LISTEN_FDS_START: RawFd = 3;
+
+    let for_this_process = envmnt::get_or("LISTEN_PID", "")
+        .parse::<u32>()
+        .is_ok_and(|pid| pid == std::process::id());
+    let count = envmnt::get_or("LISTEN_FDS", "")
+        .parse::<RawFd>()
+        .unwrap_or(0);
+    if !for_this_process || count <= 0 {
+        return vec![];
+    }
+    // the sockets are not passed on to child processes.
+    for var in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        envmnt::remove(var);
+    }
+    (LISTEN_FDS_START..LISTEN_FDS_START + count)
+        .filter_map(|fd| {
+            // SAFETY: the passed file descriptors are owned by this process and
+            // are taken only once, when the environment variables are removed.
+            let listener = unsafe { TcpListener::from_raw_fd(fd) };
+            match listener.local_addr() {
+                Ok(local_addr) => {
+                    tracing::info!("inherited socket listening on {local_addr}");
+                    Some(listener)
+                }
+                Err(e) => {
+                    tracing::warn!("inherited file descriptor {fd} is not a TCP socket: {e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn inherited_from_env() -> Vec<TcpListener> {
+    vec![]
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use std::net::{SocketAddr, TcpListener};
+
+    use rstest::rstest;
+
+    use super::take_matching;
+
+    #[rstest]
+    #[case("127.0.0.1", true)]
+    #[case("0.0.0.0", true)]
+    #[case("127.0.0.2", false)]
+    fn GIVEN_inherited_listener_WHEN_take_matching_THEN_taken_for_its_address(
+        #[case] ip_addr: &str,
+        #[case] expected: bool,
+    ) {
+        // GIVEN
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binding listener");
+        let port = listener.local_addr().expect("local address").port();
+        let mut listeners = vec![listener];
+        let addr: SocketAddr = format!("{ip_addr}:{port}").parse().expect("socket address");
+
+        // WHEN
+        let actual = take_matching(&mut listeners, &addr);
+
+        // THEN
+        assert_eq!(actual.is_some(), expected);
+        assert_eq!(listeners.is_empty(), expected);
+    }
+
+    #[test]
+    fn GIVEN_inherited_listener_WHEN_take_matching_port_0_THEN_none() {
+        // GIVEN
+        let mut listeners = vec![TcpListener::bind("127.0.0.1:0").expect("binding listener")];
+        let addr: SocketAddr = "127.0.0.1:0".parse().expect("socket address");
+
+        // WHEN
+        let actual = take_matching(&mut listeners, &addr);
+
+        // THEN
+        assert!(actual.is_none());
+    }
+}
@@ -1,29 +1,43 @@
 use std::{
+    collections::BTreeMap,
+    io::ErrorKind,
     net::IpAddr,
-    path::PathBuf,
-    time::{Duration, SystemTime},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
 };
 
 use {
     buildstructor::Builder,
     icann_rdap_common::{
-        prelude::Numberish,
+        prelude::{Numberish, ToResponse},
         response::{
             Autnum, Cidr0Cidr, Domain, Entity, GetSelfLink, Nameserver, Network, RdapResponse,
             SelfLink, V4Cidr, V6Cidr,
         },
     },
     ipnet::{IpNet, Ipv4Subnets, Ipv6Subnets},
+    notify::{
+        event::{AccessKind, AccessMode},
+        Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    },
     serde::{Deserialize, Serialize},
     serde_json::Value,
     strum_macros::Display,
-    tokio::time::sleep,
-    tracing::{debug, info, warn},
+    tokio::{
+        sync::mpsc,
+        time::{self, sleep, timeout},
+    },
+    tracing::{debug, error, info, warn},
 };
 
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
+
 use crate::{
     config::ServiceConfig,
     error::RdapServerError,
+    metrics::Metrics,
     storage::{StoreOps, TxHandle},
 };
 
@@ -61,6 +75,8 @@ pub enum DomainOrError {
     DomainObject(Box<Domain>),
     #[serde(rename = "error")]
     ErrorResponse(icann_rdap_common::response::Rfc9083Error),
+    #[serde(rename = "delete")]
+    Delete,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -69,6 +85,8 @@ pub enum EntityOrError {
     EntityObject(Box<Entity>),
     #[serde(rename = "error")]
     ErrorResponse(icann_rdap_common::response::Rfc9083Error),
+    #[serde(rename = "delete")]
+    Delete,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -77,6 +95,8 @@ pub enum NameserverOrError {
     NameserverObject(Box<Nameserver>),
     #[serde(rename = "error")]
     ErrorResponse(icann_rdap_common::response::Rfc9083Error),
+    #[serde(rename = "delete")]
+    Delete,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -85,6 +105,8 @@ pub enum AutnumOrError {
     AutnumObject(Box<Autnum>),
     #[serde(rename = "error")]
     ErrorResponse(icann_rdap_common::response::Rfc9083Error),
+    #[serde(rename = "delete")]
+    Delete,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -93,6 +115,8 @@ pub enum NetworkOrError {
     NetworkObject(Box<Network>),
     #[serde(rename = "error")]
     ErrorResponse(icann_rdap_common::response::Rfc9083Error),
+    #[serde(rename = "delete")]
+    Delete,
 }
 
 #[derive(Clone, Serialize, Deserialize, Builder, Debug, PartialEq, Eq)]
@@ -171,14 +195,101 @@ pub enum NetworkIdType {
 /// ```
 /// In this example, 2 domains will be created for "foo.example" and "bar.exaple" using
 /// the template.
+///
+/// A template may declare the tenant it belongs to with a `"tenant"` member, such as
+/// `"tenant": "example"`. Such templates are only loaded into the storage of that tenant,
+/// which lets the tenants of a server share a data directory.
+///
+/// Giving `"delete"` in place of the object deletes the objects with the IDs, along with
+/// any errors put in their place. This allows updates to retire objects:
+///
+/// ```json
+/// {
+///   "domain": "delete",
+///   "ids":
+///     [
+///       {"ldhName":"bar.example"}
+///     ]
+/// }
+/// ```
+///
+/// Returns the data files in the directory, as they were when loaded.
 pub async fn load_data(
     config: &ServiceConfig,
     store: &dyn StoreOps,
     truncate: bool,
-) -> Result<(), RdapServerError> {
+) -> Result<DataFiles, RdapServerError> {
+    load_files(config, store, truncate, None).await
+}
+
+/// Loads the files in the data directory that are new or modified since they were
+/// loaded, such as when storage has been restored from a snapshot of them. All the
+/// data is reloaded if any of them have been removed, as storage would otherwise keep
+/// their objects. Returns the data files now loaded.
+pub async fn load_data_since(
+    config: &ServiceConfig,
+    store: &dyn StoreOps,
+    loaded: &DataFiles,
+) -> Result<DataFiles, RdapServerError> {
+    let files = DataFiles::in_dir(Path::new(&config.data_dir)).await?;
+    if loaded
+        .modified
+        .keys()
+        .any(|name| !files.modified.contains_key(name))
+    {
+        info!("Data files have been removed, reloading all data.");
+        return load_files(config, store, true, None).await;
+    }
+    load_files(config, store, false, Some(loaded)).await
+}
+
+/// The data files loaded into storage, by name, with the times they were modified
+/// when they were loaded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataFiles {
+    modified: BTreeMap<String, SystemTime>,
+}
+
+impl DataFiles {
+    /// Gets the data files in a directory, which has none if it does not exist.
+    async fn in_dir(data_dir: &Path) -> Result<Self, RdapServerError> {
+        let mut modified = BTreeMap::new();
+        let mut entries = match tokio::fs::read_dir(data_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            // directories, such as those of tenants, are not loaded.
+            if !entry.file_type().await?.is_file() || !is_data_file(&entry.path()) {
+                continue;
+            }
+            modified.insert(
+                entry.file_name().to_string_lossy().to_string(),
+                entry.metadata().await?.modified()?,
+            );
+        }
+        Ok(Self { modified })
+    }
+}
+
+/// True for the files holding data, which are RDAP JSON, template and server help files.
+fn is_data_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "json" || ext == "template" || ext == "help")
+}
+
+async fn load_files(
+    config: &ServiceConfig,
+    store: &dyn StoreOps,
+    truncate: bool,
+    loaded: Option<&DataFiles>,
+) -> Result<DataFiles, RdapServerError> {
+    let timer = Instant::now();
     let mut json_count: usize = 0;
     let mut template_count: usize = 0;
     let mut srvhelp_count: usize = 0;
+    let mut object_count: usize = 0;
     let mut tx = if truncate {
         store.new_truncate_tx().await?
     } else {
@@ -190,42 +301,55 @@ pub async fn load_data(
             "Directory {} does not exist or is not a directory. Server has no content to serve.",
             path.to_string_lossy()
         );
-        return Ok(());
+        return Ok(DataFiles::default());
     }
 
-    let mut entries = tokio::fs::read_dir(path).await?;
-    while let Some(entry) = entries.next_entry().await? {
-        let entry_path = entry.path();
+    let files = DataFiles::in_dir(&path).await?;
+    for (file_name, modified) in &files.modified {
+        if loaded.is_some_and(|loaded| loaded.modified.get(file_name) == Some(modified)) {
+            continue;
+        }
+        let entry_path = path.join(file_name);
         let contents = tokio::fs::read_to_string(&entry_path).await?;
         if entry_path
             .extension()
             .map_or(false, |ext| ext == "template")
         {
-            load_rdap_template(&contents, &entry_path.to_string_lossy(), &mut tx).await?;
-            template_count += 1;
-        } else if entry_path.extension().map_or(false, |ext| ext == "json") {
-            load_rdap(&contents, &entry_path.to_string_lossy(), &mut tx).await?;
-            json_count += 1;
-        } else if entry_path.extension().map_or(false, |ext| ext == "help") {
-            load_srvhelp(
+            if let Some(count) = load_rdap_template(
                 &contents,
                 &entry_path.to_string_lossy(),
-                &entry.file_name().to_string_lossy(),
+                config.tenant_name.as_deref(),
                 &mut tx,
             )
-            .await?;
+            .await?
+            {
+                template_count += 1;
+                object_count += count;
+            }
+        } else if entry_path.extension().map_or(false, |ext| ext == "json") {
+            load_rdap(&contents, &entry_path.to_string_lossy(), &mut tx).await?;
+            json_count += 1;
+            object_count += 1;
+        } else if entry_path.extension().map_or(false, |ext| ext == "help") {
+            load_srvhelp(&contents, &entry_path.to_string_lossy(), file_name, &mut tx).await?;
             srvhelp_count += 1;
+            object_count += 1;
         }
     }
 
     info!("{json_count} RDAP JSON files loaded.");
     info!("{template_count} RDAP template files loaded.");
     info!("{srvhelp_count} RDAP server help files loaded.");
-    if json_count == 0 && template_count == 0 && srvhelp_count == 0 {
+    let loaded_count = json_count + template_count + srvhelp_count;
+    if loaded_count == 0 && loaded.is_none() {
         warn!("No data loaded. Server has no content to serve.");
     }
     tx.commit().await?;
-    Ok(())
+    info!(
+        "Data loaded in {:?}. {object_count} objects were added, replaced, or deleted.",
+        timer.elapsed()
+    );
+    Ok(files)
 }
 
 /// Loads the RDAP JSON files and puts them in storage.
@@ -243,15 +367,28 @@ async fn load_rdap(
     let Ok(rdap) = rdap else {
         return Err(RdapServerError::NonRdapJsonFile(path_name.to_owned()));
     };
+    if !add_rdap(&rdap, tx).await? {
+        return Err(RdapServerError::NonRdapJsonFile(path_name.to_owned()));
+    }
+    Ok(())
+}
+
+/// Puts an RDAP object into storage.
+///
+/// Returns false if the response is not an object that can be stored.
+pub(crate) async fn add_rdap(
+    rdap: &RdapResponse,
+    tx: &mut Box<dyn TxHandle>,
+) -> Result<bool, RdapServerError> {
     match rdap {
-        RdapResponse::Entity(entity) => tx.add_entity(&entity).await,
-        RdapResponse::Domain(domain) => tx.add_domain(&domain).await,
-        RdapResponse::Nameserver(nameserver) => tx.add_nameserver(&nameserver).await,
-        RdapResponse::Autnum(autnum) => tx.add_autnum(&autnum).await,
-        RdapResponse::Network(network) => tx.add_network(&network).await,
-        _ => return Err(RdapServerError::NonRdapJsonFile(path_name.to_owned())),
+        RdapResponse::Entity(entity) => tx.add_entity(entity).await,
+        RdapResponse::Domain(domain) => tx.add_domain(domain).await,
+        RdapResponse::Nameserver(nameserver) => tx.add_nameserver(nameserver).await,
+        RdapResponse::Autnum(autnum) => tx.add_autnum(autnum).await,
+        RdapResponse::Network(network) => tx.add_network(network).await,
+        _ => return Ok(false),
     }?;
-    Ok(())
+    Ok(true)
 }
 
 /// Loads the RDAP HELP files and puts them in storage.
@@ -281,123 +418,389 @@ async fn load_srvhelp(
     Ok(())
 }
 
+/// The tenant a template file may declare it belongs to with a `tenant` member.
+#[derive(Deserialize)]
+struct TemplateTenant {
+    tenant: Option<String>,
+}
+
 /// Loads the template files, creates RDAP objects from the templates, and puts them
 /// into storage.
+///
+/// Returns the number of objects the template put into or deleted from storage.
+/// Templates declaring a tenant other than the one being loaded are skipped, in which
+/// case none is returned.
 async fn load_rdap_template(
     contents: &str,
     path_name: &str,
+    tenant_name: Option<&str>,
     tx: &mut Box<dyn TxHandle>,
-) -> Result<(), RdapServerError> {
+) -> Result<Option<usize>, RdapServerError> {
     debug!("processing {path_name} template");
     let json = serde_json::from_str::<Template>(contents);
-    if let Ok(value) = json {
-        match value {
-            Template::Domain { domain, ids } => {
-                for id in ids {
-                    debug!("adding domain from template for {id:?}");
-                    match &domain {
-                        DomainOrError::DomainObject(domain) => {
-                            let domain = make_domain_from_template(domain, id);
-                            tx.add_domain(&domain).await?;
-                        }
-                        DomainOrError::ErrorResponse(error) => {
-                            tx.add_domain_err(&id, error).await?;
+    let Ok(template) = json else {
+        return Err(RdapServerError::NonJsonFile(path_name.to_owned()));
+    };
+    let declared = serde_json::from_str::<TemplateTenant>(contents)
+        .ok()
+        .and_then(|declared| declared.tenant);
+    if declared.is_some() && declared.as_deref() != tenant_name {
+        debug!("skipping {path_name} template of tenant {declared:?}");
+        return Ok(None);
+    }
+    let count = match &template {
+        Template::Domain { ids, .. } => ids.len(),
+        Template::Entity { ids, .. } => ids.len(),
+        Template::Nameserver { ids, .. } => ids.len(),
+        Template::Autnum { ids, .. } => ids.len(),
+        Template::Network { ids, .. } => ids.len(),
+    };
+    add_template(template, tx).await?;
+    Ok(Some(count))
+}
+
+/// Creates RDAP objects from a template and puts them into storage, or deletes
+/// them from storage for a delete template.
+pub(crate) async fn add_template(
+    template: Template,
+    tx: &mut Box<dyn TxHandle>,
+) -> Result<(), RdapServerError> {
+    match template {
+        Template::Domain { domain, ids } => {
+            for id in ids {
+                debug!("applying domain template for {id:?}");
+                match &domain {
+                    DomainOrError::DomainObject(domain) => {
+                        let domain = make_domain_from_template(domain, id);
+                        tx.add_domain(&domain).await?;
+                    }
+                    DomainOrError::ErrorResponse(error) => {
+                        tx.add_domain_err(&id, error).await?;
+                    }
+                    DomainOrError::Delete => {
+                        if !tx.delete_domain(&id).await? {
+                            debug!("no domain to delete for {id:?}");
                         }
-                    };
-                }
+                    }
+                };
             }
-            Template::Entity { entity, ids } => {
-                for id in ids {
-                    debug!("adding entity from template for {id:?}");
-                    match &entity {
-                        EntityOrError::EntityObject(entity) => {
-                            let entity = make_entity_from_template(entity, id);
-                            tx.add_entity(&entity).await?;
-                        }
-                        EntityOrError::ErrorResponse(error) => {
-                            tx.add_entity_err(&id, error).await?;
+        }
+        Template::Entity { entity, ids } => {
+            for id in ids {
+                debug!("applying entity template for {id:?}");
+                match &entity {
+                    EntityOrError::EntityObject(entity) => {
+                        let entity = make_entity_from_template(entity, id);
+                        tx.add_entity(&entity).await?;
+                    }
+                    EntityOrError::ErrorResponse(error) => {
+                        tx.add_entity_err(&id, error).await?;
+                    }
+                    EntityOrError::Delete => {
+                        if !tx.delete_entity(&id).await? {
+                            debug!("no entity to delete for {id:?}");
                         }
-                    };
-                }
+                    }
+                };
             }
-            Template::Nameserver { nameserver, ids } => {
-                for id in ids {
-                    debug!("adding nameserver from template for {id:?}");
-                    match &nameserver {
-                        NameserverOrError::NameserverObject(nameserver) => {
-                            let nameserver = make_nameserver_from_template(nameserver, id);
-                            tx.add_nameserver(&nameserver).await?;
-                        }
-                        NameserverOrError::ErrorResponse(error) => {
-                            tx.add_nameserver_err(&id, error).await?;
+        }
+        Template::Nameserver { nameserver, ids } => {
+            for id in ids {
+                debug!("applying nameserver template for {id:?}");
+                match &nameserver {
+                    NameserverOrError::NameserverObject(nameserver) => {
+                        let nameserver = make_nameserver_from_template(nameserver, id);
+                        tx.add_nameserver(&nameserver).await?;
+                    }
+                    NameserverOrError::ErrorResponse(error) => {
+                        tx.add_nameserver_err(&id, error).await?;
+                    }
+                    NameserverOrError::Delete => {
+                        if !tx.delete_nameserver(&id).await? {
+                            debug!("no nameserver to delete for {id:?}");
                         }
-                    };
-                }
+                    }
+                };
             }
-            Template::Autnum { autnum, ids } => {
-                for id in ids {
-                    debug!("adding autnum from template for {id:?}");
-                    match &autnum {
-                        AutnumOrError::AutnumObject(autnum) => {
-                            let autnum = make_autnum_from_template(autnum, id);
-                            tx.add_autnum(&autnum).await?;
-                        }
-                        AutnumOrError::ErrorResponse(error) => {
-                            tx.add_autnum_err(&id, error).await?;
+        }
+        Template::Autnum { autnum, ids } => {
+            for id in ids {
+                debug!("applying autnum template for {id:?}");
+                match &autnum {
+                    AutnumOrError::AutnumObject(autnum) => {
+                        let autnum = make_autnum_from_template(autnum, id);
+                        tx.add_autnum(&autnum).await?;
+                    }
+                    AutnumOrError::ErrorResponse(error) => {
+                        tx.add_autnum_err(&id, error).await?;
+                    }
+                    AutnumOrError::Delete => {
+                        if !tx.delete_autnum(&id).await? {
+                            debug!("no autnum to delete for {id:?}");
                         }
-                    };
-                }
+                    }
+                };
             }
-            Template::Network { network, ids } => {
-                for id in ids {
-                    debug!("adding network from template for {id:?}");
-                    match &network {
-                        NetworkOrError::NetworkObject(network) => {
-                            let network = make_network_from_template(network, id)?;
-                            tx.add_network(&network).await?;
-                        }
-                        NetworkOrError::ErrorResponse(error) => {
-                            tx.add_network_err(&id, error).await?;
+        }
+        Template::Network { network, ids } => {
+            for id in ids {
+                debug!("applying network template for {id:?}");
+                match &network {
+                    NetworkOrError::NetworkObject(network) => {
+                        let network = make_network_from_template(network, id)?;
+                        tx.add_network(&network).await?;
+                    }
+                    NetworkOrError::ErrorResponse(error) => {
+                        tx.add_network_err(&id, error).await?;
+                    }
+                    NetworkOrError::Delete => {
+                        if !tx.delete_network(&id).await? {
+                            debug!("no network to delete for {id:?}");
                         }
-                    };
-                }
+                    }
+                };
             }
-        };
-    } else {
-        return Err(RdapServerError::NonJsonFile(path_name.to_owned()));
-    }
+        }
+    };
     Ok(())
 }
 
+/// Creates the RDAP objects a template puts into storage. The errors it puts in
+/// place of objects and the objects it deletes are not included.
+pub(crate) fn template_objects(template: &Template) -> Result<Vec<RdapResponse>, RdapServerError> {
+    let objects = match template {
+        Template::Domain {
+            domain: DomainOrError::DomainObject(domain),
+            ids,
+        } => ids
+            .iter()
+            .map(|id| make_domain_from_template(domain, id.clone()).to_response())
+            .collect(),
+        Template::Entity {
+            entity: EntityOrError::EntityObject(entity),
+            ids,
+        } => ids
+            .iter()
+            .map(|id| make_entity_from_template(entity, id.clone()).to_response())
+            .collect(),
+        Template::Nameserver {
+            nameserver: NameserverOrError::NameserverObject(nameserver),
+            ids,
+        } => ids
+            .iter()
+            .map(|id| make_nameserver_from_template(nameserver, id.clone()).to_response())
+            .collect(),
+        Template::Autnum {
+            autnum: AutnumOrError::AutnumObject(autnum),
+            ids,
+        } => ids
+            .iter()
+            .map(|id| make_autnum_from_template(autnum, id.clone()).to_response())
+            .collect(),
+        Template::Network {
+            network: NetworkOrError::NetworkObject(network),
+            ids,
+        } => ids
+            .iter()
+            .map(|id| Ok(make_network_from_template(network, id.clone())?.to_response()))
+            .collect::<Result<_, RdapServerError>>()?,
+        _ => vec![],
+    };
+    Ok(objects)
+}
+
+/// How long the data directory must be quiet after a change before data is
+/// loaded, so that a burst of writes, such as a directory of files being
+/// copied in, loads data once.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The longest data loading waits for the data directory to be quiet, so that
+/// a steady stream of writes does not keep data from being loaded.
+const RELOAD_DEBOUNCE_MAX: Duration = Duration::from_secs(5);
+
+/// How often the `update` and `reload` files are checked when the data
+/// directory cannot be watched.
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// A change of data requiring it to be loaded. A reload supersedes an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum DataChange {
+    /// Data files were written, or the `update` file was touched.
+    Update,
+
+    /// The `reload` file was touched, or SIGHUP was received.
+    Reload,
+}
+
+impl DataChange {
+    /// Gets the change a write to a file in the data directory makes.
+    fn of_path(path: &Path) -> Option<Self> {
+        match path.file_name()?.to_str()? {
+            RELOAD => Some(Self::Reload),
+            UPDATE => Some(Self::Update),
+            _ => is_data_file(path).then_some(Self::Update),
+        }
+    }
+}
+
+/// Loads data as it changes, until the server stops.
+///
+/// Changes are found with filesystem notifications, such as inotify on Linux,
+/// falling back to polling for the `update` and `reload` files when the data
+/// directory cannot be watched. A reload is also done on SIGHUP.
+///
+/// Updates load only the files new or modified since the data files already
+/// loaded, while reloads load all the files.
 pub(crate) async fn reload_data(
     store: Box<dyn StoreOps>,
     config: ServiceConfig,
+    metrics: Arc<Metrics>,
+    mut loaded: DataFiles,
 ) -> Result<(), RdapServerError> {
-    let update_path = PathBuf::from(&config.data_dir);
-    let update_path = update_path.join(UPDATE);
-    let reload_path = PathBuf::from(&config.data_dir);
-    let reload_path = reload_path.join(RELOAD);
-    let mut last_time = SystemTime::now();
-    loop {
-        sleep(Duration::from_millis(1000)).await;
-        let update_meta = tokio::fs::metadata(&update_path).await;
-        if update_meta.is_ok() {
-            let modified = update_meta.unwrap().modified()?;
-            if modified > last_time {
-                last_time = modified;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let data_dir = PathBuf::from(&config.data_dir);
+    let _watcher = match watch_data_dir(&data_dir, tx.clone()) {
+        Ok(watcher) => {
+            info!("Watching {} for data changes.", data_dir.to_string_lossy());
+            Some(watcher)
+        }
+        Err(e) => {
+            warn!(
+                "Unable to watch {}, polling for updates and reloads instead: {e}",
+                data_dir.to_string_lossy()
+            );
+            tokio::spawn(poll_data_dir(data_dir, tx.clone()));
+            None
+        }
+    };
+    reload_on_hangup(tx)?;
+    while let Some(change) = rx.recv().await {
+        let change = debounce(change, &mut rx, RELOAD_DEBOUNCE, RELOAD_DEBOUNCE_MAX).await;
+        let files = match change {
+            DataChange::Update => {
                 info!("Data being updated.");
-                load_data(&config, &*store, false).await?;
+                load_data_since(&config, &*store, &loaded).await
+            }
+            DataChange::Reload => {
+                info!("Data being reloaded.");
+                load_data(&config, &*store, true).await
             }
         };
-        let reload_meta = tokio::fs::metadata(&reload_path).await;
-        if reload_meta.is_ok() {
-            let modified = reload_meta.unwrap().modified()?;
+        match files {
+            Ok(files) => {
+                metrics.data_loaded(&*store).await;
+                save_snapshot(&*store, &files).await;
+                loaded = files;
+            }
+            Err(e) => error!("unable to load data: {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Waits for changes to stop arriving for the quiet time, but no longer than
+/// the max wait in all, giving the change covering all of them.
+async fn debounce(
+    mut change: DataChange,
+    rx: &mut mpsc::UnboundedReceiver<DataChange>,
+    quiet: Duration,
+    max_wait: Duration,
+) -> DataChange {
+    let deadline = time::Instant::now() + max_wait;
+    loop {
+        let wait = quiet.min(deadline.saturating_duration_since(time::Instant::now()));
+        if wait.is_zero() {
+            break;
+        }
+        match timeout(wait, rx.recv()).await {
+            Ok(Some(next)) => change = change.max(next),
+            _ => break,
+        }
+    }
+    change
+}
+
+/// Watches the data directory, sending the changes of the files written to it.
+fn watch_data_dir(
+    data_dir: &Path,
+    tx: mpsc::UnboundedSender<DataChange>,
+) -> notify::Result<RecommendedWatcher> {
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) if is_write(&event.kind) => {
+                for change in event
+                    .paths
+                    .iter()
+                    .filter_map(|path| DataChange::of_path(path))
+                {
+                    let _ = tx.send(change);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("error watching data directory: {e}"),
+        })?;
+    watcher.watch(data_dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// True for the events of files being created or written. Files being read,
+/// such as when data is loaded, and removed are not changes.
+fn is_write(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Any
+            | EventKind::Create(_)
+            | EventKind::Modify(_)
+            | EventKind::Access(AccessKind::Close(AccessMode::Write))
+    )
+}
+
+/// Polls for the `update` and `reload` files being touched.
+async fn poll_data_dir(data_dir: PathBuf, tx: mpsc::UnboundedSender<DataChange>) {
+    let mut last_time = SystemTime::now();
+    loop {
+        sleep(RELOAD_POLL_INTERVAL).await;
+        for (file, change) in [(UPDATE, DataChange::Update), (RELOAD, DataChange::Reload)] {
+            let Ok(modified) = tokio::fs::metadata(data_dir.join(file))
+                .await
+                .and_then(|meta| meta.modified())
+            else {
+                continue;
+            };
             if modified > last_time {
                 last_time = modified;
-                info!("Data being reloaded.");
-                load_data(&config, &*store, true).await?;
+                if tx.send(change).is_err() {
+                    return;
+                }
             }
-        };
+        }
+    }
+}
+
+/// Sends a reload for each SIGHUP received.
+#[cfg(unix)]
+fn reload_on_hangup(tx: mpsc::UnboundedSender<DataChange>) -> Result<(), RdapServerError> {
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received.");
+            if tx.send(DataChange::Reload).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn reload_on_hangup(_tx: mpsc::UnboundedSender<DataChange>) -> Result<(), RdapServerError> {
+    Ok(())
+}
+
+/// Saves a snapshot of storage, which failing to do is not fatal to the server.
+pub(crate) async fn save_snapshot(store: &dyn StoreOps, files: &DataFiles) {
+    if let Err(e) = store.save_snapshot(files).await {
+        warn!("unable to save snapshot of storage: {e}");
     }
 }
 
@@ -559,7 +962,18 @@ fn make_network_from_template(
 #[allow(non_snake_case)]
 mod tests {
 
-    use icann_rdap_common::response::{Domain, Link};
+    use {
+        icann_rdap_common::response::{Domain, Link},
+        test_dir::{DirBuilder, TestDir},
+    };
+
+    use crate::{
+        config::StorageType,
+        storage::{
+            mem::{config::MemConfig, ops::Mem},
+            CommonConfig,
+        },
+    };
 
     use super::*;
 
@@ -601,6 +1015,22 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn GIVEN_delete_template_text_WHEN_deserialize_THEN_success() {
+        // GIVEN
+        let json_text = r#"{"entity":"delete","ids":[{"handle":"FOO-1"}]}"#;
+
+        // WHEN
+        let actual: Template = serde_json::from_str(json_text).expect("deserializing template");
+
+        // THEN
+        let expected = Template::Entity {
+            entity: EntityOrError::Delete,
+            ids: vec![EntityId::builder().handle("FOO-1").build()],
+        };
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn GIVEN_template_network_with_cidr_WHEN_serialize_THEN_success() {
         // GIVEN
@@ -1009,4 +1439,181 @@ mod tests {
             "http://reg.example/ip/11.0.0.0/24"
         );
     }
+
+    #[rstest::rstest]
+    #[case("/data/reload", Some(DataChange::Reload))]
+    #[case("/data/update", Some(DataChange::Update))]
+    #[case("/data/foo.json", Some(DataChange::Update))]
+    #[case("/data/foo.template", Some(DataChange::Update))]
+    #[case("/data/__default.help", Some(DataChange::Update))]
+    #[case("/data/snapshot", None)]
+    #[case("/data/foo.json.swp", None)]
+    fn GIVEN_written_path_WHEN_data_change_THEN_change_found(
+        #[case] path: &str,
+        #[case] expected: Option<DataChange>,
+    ) {
+        // GIVEN
+        let path = PathBuf::from(path);
+
+        // WHEN
+        let actual = DataChange::of_path(&path);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn GIVEN_burst_of_changes_WHEN_debounce_THEN_one_reload() {
+        // GIVEN
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        for change in [DataChange::Reload, DataChange::Update] {
+            tx.send(change).expect("sending change");
+        }
+
+        // WHEN
+        let actual = debounce(
+            DataChange::Update,
+            &mut rx,
+            RELOAD_DEBOUNCE,
+            RELOAD_DEBOUNCE_MAX,
+        )
+        .await;
+
+        // THEN
+        assert_eq!(actual, DataChange::Reload);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn GIVEN_steady_stream_of_changes_WHEN_debounce_THEN_max_wait_not_exceeded() {
+        // GIVEN
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while tx.send(DataChange::Update).is_ok() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        });
+
+        // WHEN
+        let start = Instant::now();
+        let actual = debounce(
+            DataChange::Update,
+            &mut rx,
+            Duration::from_millis(100),
+            Duration::from_millis(300),
+        )
+        .await;
+
+        // THEN
+        assert_eq!(actual, DataChange::Update);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn GIVEN_watched_data_dir_WHEN_file_written_THEN_data_loaded() {
+        // GIVEN
+        let temp = TestDir::temp();
+        let mem_config = MemConfig::builder()
+            .common_config(CommonConfig::default())
+            .build();
+        let mem = Mem::new(mem_config.clone());
+        let config = ServiceConfig::non_server()
+            .data_dir(temp.root().to_string_lossy())
+            .storage_type(StorageType::Memory(mem_config))
+            .build()
+            .expect("building service config");
+        tokio::spawn(reload_data(
+            Box::new(mem.clone()),
+            config,
+            Arc::new(Metrics::new()),
+            DataFiles::default(),
+        ));
+        sleep(Duration::from_millis(100)).await;
+
+        // WHEN
+        let domain = Domain::builder().ldh_name("foo.example").build();
+        std::fs::write(
+            temp.path("foo_example.json"),
+            serde_json::to_string(&domain).expect("serializing domain"),
+        )
+        .expect("writing file");
+
+        // THEN
+        let mut loaded = false;
+        for _ in 0..50 {
+            sleep(Duration::from_millis(100)).await;
+            let actual = mem
+                .get_domain_by_ldh("foo.example")
+                .await
+                .expect("getting domain by ldh");
+            if matches!(actual, RdapResponse::Domain(_)) {
+                loaded = true;
+                break;
+            }
+        }
+        assert!(loaded);
+    }
+
+    #[tokio::test]
+    async fn GIVEN_loaded_data_WHEN_file_written_THEN_only_new_file_loaded() {
+        // GIVEN
+        let temp = TestDir::temp();
+        let mem_config = MemConfig::builder()
+            .common_config(CommonConfig::default())
+            .build();
+        let mem = Mem::new(mem_config.clone());
+        let config = ServiceConfig::non_server()
+            .data_dir(temp.root().to_string_lossy())
+            .storage_type(StorageType::Memory(mem_config))
+            .build()
+            .expect("building service config");
+        let foo = Domain::builder().ldh_name("foo.example").build();
+        std::fs::write(
+            temp.path("foo_example.json"),
+            serde_json::to_string(&foo).expect("serializing domain"),
+        )
+        .expect("writing file");
+        let loaded = load_data(&config, &mem, false).await.expect("loading data");
+        // a file read again would add the domain back.
+        let mut tx = mem.new_tx().await.expect("new transaction");
+        tx.delete_domain(&DomainId::builder().ldh_name("foo.example").build())
+            .await
+            .expect("delete domain in tx");
+        tx.commit().await.expect("tx commit");
+        tokio::spawn(reload_data(
+            Box::new(mem.clone()),
+            config,
+            Arc::new(Metrics::new()),
+            loaded,
+        ));
+        sleep(Duration::from_millis(100)).await;
+
+        // WHEN
+        let bar = Domain::builder().ldh_name("bar.example").build();
+        std::fs::write(
+            temp.path("bar_example.json"),
+            serde_json::to_string(&bar).expect("serializing domain"),
+        )
+        .expect("writing file");
+
+        // THEN
+        let mut loaded = false;
+        for _ in 0..50 {
+            sleep(Duration::from_millis(100)).await;
+            let actual = mem
+                .get_domain_by_ldh("bar.example")
+                .await
+                .expect("getting domain by ldh");
+            if matches!(actual, RdapResponse::Domain(_)) {
+                loaded = true;
+                break;
+            }
+        }
+        assert!(loaded);
+        let foo = mem
+            .get_domain_by_ldh("foo.example")
+            .await
+            .expect("getting domain by ldh");
+        assert!(matches!(foo, RdapResponse::ErrorResponse(_)));
+    }
 }
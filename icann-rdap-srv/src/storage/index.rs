@@ -0,0 +1,186 @@
+use std::{net::IpAddr, str::FromStr};
+
+use {
+    icann_rdap_common::{
+        response::{Domain, Entity, Nameserver},
+        search_types::RelatedEntityProperty,
+    },
+    ipnet::{IpNet, IpSubnets, Ipv4Subnets, Ipv6Subnets},
+};
+
+use crate::{error::RdapServerError, rdap::normalize_domain_name};
+
+use super::data::{NetworkId, NetworkIdType};
+
+/// Gets the key of a domain or nameserver from its LDH name, which is the name
+/// normalized as lookups are, so that the name in any case, with U-labels, or with
+/// a trailing dot finds the object. Names that are not valid IDNA names are keyed
+/// as given.
+pub(crate) fn ldh_name_key(ldh_name: &str) -> String {
+    normalize_domain_name(ldh_name).map_or_else(|| ldh_name.to_owned(), |name| name.ldh)
+}
+
+/// Gets the key of a domain from its Unicode name, normalized as LDH names are.
+pub(crate) fn unicode_name_key(unicode_name: &str) -> String {
+    normalize_domain_name(unicode_name).map_or_else(|| unicode_name.to_owned(), |name| name.unicode)
+}
+
+/// Gets the keys of the variants of a domain, which are the LDH and Unicode names
+/// of the variants normalized as the names of domains are.
+pub(crate) fn domain_variant_names(domain: &Domain) -> Vec<String> {
+    domain
+        .variants
+        .iter()
+        .flatten()
+        .flat_map(|variant| variant.variant_names())
+        .flat_map(|variant_name| {
+            [
+                variant_name.ldh_name().map(ldh_name_key),
+                variant_name.unicode_name().map(unicode_name_key),
+            ]
+        })
+        .flatten()
+        .collect()
+}
+
+/// Gets the parsable IP addresses of a nameserver.
+pub(crate) fn nameserver_ips(nameserver: &Nameserver) -> Vec<IpAddr> {
+    nameserver
+        .ip_addresses
+        .as_ref()
+        .map(|ip_addresses| {
+            ip_addresses
+                .v4s()
+                .iter()
+                .chain(ip_addresses.v6s())
+                .filter_map(|addr| addr.parse::<IpAddr>().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Gets the CIDRs covering a network given by its ID.
+pub(crate) fn network_subnets(network_id: &NetworkId) -> Result<IpSubnets, RdapServerError> {
+    let subnets = match &network_id.network_id {
+        NetworkIdType::Cidr(cidr) => match cidr {
+            IpNet::V4(v4) => IpSubnets::from(Ipv4Subnets::new(v4.network(), v4.broadcast(), 0)),
+            IpNet::V6(v6) => IpSubnets::from(Ipv6Subnets::new(v6.network(), v6.broadcast(), 0)),
+        },
+        NetworkIdType::Range {
+            start_address,
+            end_address,
+        } => match (
+            IpAddr::from_str(start_address)?,
+            IpAddr::from_str(end_address)?,
+        ) {
+            (IpAddr::V4(start_addr), IpAddr::V4(end_addr)) => {
+                IpSubnets::from(Ipv4Subnets::new(start_addr, end_addr, 0))
+            }
+            (IpAddr::V6(start_addr), IpAddr::V6(end_addr)) => {
+                IpSubnets::from(Ipv6Subnets::new(start_addr, end_addr, 0))
+            }
+            _ => {
+                return Err(RdapServerError::EmptyIndexData(
+                    "mismatch ip version".to_string(),
+                ))
+            }
+        },
+    };
+    Ok(subnets)
+}
+
+/// A value of a related entity that an object may be found by in a reverse
+/// search, as specified in RFC 9536.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct RelatedValue {
+    pub(crate) property: RelatedEntityProperty,
+    pub(crate) value: String,
+    pub(crate) roles: Vec<String>,
+}
+
+/// Gets the values of the related entities of an object that are reverse searchable.
+///
+/// Only the entities directly related to the object are considered, and not
+/// the entities of those entities.
+pub(crate) fn related_values(entities: &[Entity]) -> Vec<RelatedValue> {
+    let mut values = vec![];
+    for entity in entities {
+        let roles = entity
+            .roles()
+            .iter()
+            .map(|role| role.to_lowercase())
+            .collect::<Vec<String>>();
+        if let Some(handle) = entity.object_common.handle.as_ref() {
+            values.push(RelatedValue {
+                property: RelatedEntityProperty::Handle,
+                value: handle.to_owned(),
+                roles: roles.clone(),
+            });
+        }
+        if let Some(contact) = entity.contact() {
+            if let Some(full_name) = contact.full_name {
+                values.push(RelatedValue {
+                    property: RelatedEntityProperty::FullName,
+                    value: full_name,
+                    roles: roles.clone(),
+                });
+            }
+            for email in contact.emails.unwrap_or_default() {
+                values.push(RelatedValue {
+                    property: RelatedEntityProperty::Email,
+                    value: email.email,
+                    roles: roles.clone(),
+                });
+            }
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use icann_rdap_common::{
+        contact::{Contact, Email},
+        response::Entity,
+        search_types::RelatedEntityProperty,
+    };
+
+    use super::related_values;
+
+    fn entities() -> Vec<Entity> {
+        vec![
+            Entity::builder()
+                .handle("REG-1")
+                .contact(
+                    Contact::builder()
+                        .full_name("Bob Smurd")
+                        .emails(vec![Email::builder().email("bob@example.com").build()])
+                        .build(),
+                )
+                .role("registrant")
+                .build(),
+            Entity::builder().handle("TECH-1").role("technical").build(),
+        ]
+    }
+
+    #[test]
+    fn GIVEN_entities_WHEN_related_values_THEN_all_values_found() {
+        // GIVEN
+        let entities = entities();
+
+        // WHEN
+        let actual = related_values(&entities);
+
+        // THEN
+        assert_eq!(actual.len(), 4);
+        assert!(actual
+            .iter()
+            .any(|v| v.property == RelatedEntityProperty::Email && v.value == "bob@example.com"));
+        assert!(actual
+            .iter()
+            .any(|v| v.property == RelatedEntityProperty::Handle
+                && v.value == "TECH-1"
+                && v.roles == vec!["technical".to_string()]));
+    }
+}
@@ -5,4 +5,7 @@ use crate::storage::CommonConfig;
 #[derive(Debug, Builder, Clone)]
 pub struct MemConfig {
     pub common_config: CommonConfig,
+    /// If specified, storage is snapshotted to this file after data is loaded
+    /// and restored from it at startup.
+    pub snapshot_file: Option<String>,
 }
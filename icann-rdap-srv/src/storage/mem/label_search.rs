@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 
-use {ab_radix_trie::Trie, buildstructor::Builder};
+use {
+    ab_radix_trie::Trie,
+    buildstructor::Builder,
+    serde::{Deserialize, Serialize},
+};
 
-use crate::error::RdapServerError;
+use crate::{error::RdapServerError, storage::search::split_label_search};
 
 /// A structure for searching DNS labels as specified in RFC 9082.
-/// For RDAP, type T is likely RdapResponse or Arc<RdapResponse>.
-#[derive(Builder)]
+/// For RDAP, type T is likely the name the object is stored by.
+#[derive(Builder, Serialize, Deserialize)]
 pub struct SearchLabels<T: Clone> {
     label_suffixes: HashMap<String, Trie<T>>,
 }
@@ -39,41 +43,10 @@ impl<T: Clone> SearchLabels<T> {
 
     /// Search values based on a label search
     pub(crate) fn search(&self, search: &str) -> Result<Vec<T>, RdapServerError> {
-        // search string is invalid if it doesn't have only one asterisk ('*')
-        if search.chars().filter(|c| *c == '*').count() != 1 {
-            return Err(RdapServerError::InvalidArg(
-                "Search string must contain one and only one asterisk ('*')".to_string(),
-            ));
-        }
-        // asterisk must not be followed by a character other than dot ('.')
-        let star = search
-            .find('*')
-            .expect("internal error. previous check should have caught this");
-        if star != search.chars().count() - 1
-            && search
-                .chars()
-                .nth(star + 1)
-                .expect("should have been short circuited")
-                != '.'
-        {
-            return Err(RdapServerError::InvalidArg(
-                "Search string asterisk ('*') must terminate domain label".to_string(),
-            ));
-        }
-
-        let parts = search
-            .split_once('*')
-            .expect("internal error. previous check should insure there is an asterisk");
-
-        // this is a limitation of the trie in that it requires a prefix
-        if parts.0.is_empty() {
-            return Err(RdapServerError::InvalidArg(
-                "Search string must have a prefix".to_string(),
-            ));
-        }
+        let (prefix, suffix) = split_label_search(search)?;
 
-        if let Some(trie) = self.label_suffixes.get(parts.1.trim_start_matches('.')) {
-            if let Some(entries) = trie.get_suffixes_values(parts.0) {
+        if let Some(trie) = self.label_suffixes.get(suffix) {
+            if let Some(entries) = trie.get_suffixes_values(prefix) {
                 if !entries.is_empty() {
                     let values = entries
                         .iter()
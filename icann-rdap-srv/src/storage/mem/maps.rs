@@ -0,0 +1,684 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::IpAddr,
+    sync::Arc,
+};
+
+use {
+    btree_range_map::RangeMap,
+    icann_rdap_common::{
+        prelude::ObjectCommonFields,
+        response::{Domain, Entity, Nameserver, RdapResponse},
+    },
+    ipnet::{IpNet, Ipv4Net, Ipv6Net},
+    prefix_trie::PrefixMap,
+};
+
+use crate::storage::{
+    data::{AutnumId, DomainId},
+    index::{domain_variant_names, nameserver_ips, unicode_name_key},
+    search::fold_case,
+    CommonConfig,
+};
+
+use super::{label_search::SearchLabels, reverse_search::ReverseIndex, snapshot::StoredMaps};
+
+/// Nameservers keyed by their LDH name.
+pub(crate) type NameserversByLdh = HashMap<String, Arc<RdapResponse>>;
+
+/// Domains keyed by their LDH name.
+pub(crate) type DomainsByLdh = HashMap<String, Arc<RdapResponse>>;
+
+/// Entity handles paired with a case folded value of their entity, ordered for
+/// prefix searches.
+pub(crate) type EntitiesByFolded = BTreeSet<(String, String)>;
+
+/// The maps of in-memory storage, which hold its objects and the indexes derived
+/// from them.
+pub(crate) struct MemMaps {
+    common_config: CommonConfig,
+    pub(crate) autnums: RangeMap<u32, Arc<RdapResponse>>,
+    pub(crate) ip4: PrefixMap<Ipv4Net, Arc<RdapResponse>>,
+    pub(crate) ip6: PrefixMap<Ipv6Net, Arc<RdapResponse>>,
+    pub(crate) domains: HashMap<String, Arc<RdapResponse>>,
+    pub(crate) domains_by_name: SearchLabels<String>,
+    pub(crate) domains_by_ns_name: SearchLabels<String>,
+    pub(crate) domains_by_ns_ldh: HashMap<String, DomainsByLdh>,
+    pub(crate) domains_by_ns_ip: HashMap<IpAddr, DomainsByLdh>,
+    pub(crate) idns: HashMap<String, Arc<RdapResponse>>,
+    pub(crate) domain_variants: HashMap<String, Arc<RdapResponse>>,
+    pub(crate) domains_by_related: ReverseIndex,
+    pub(crate) nameservers: HashMap<String, Arc<RdapResponse>>,
+    pub(crate) nameservers_by_name: SearchLabels<String>,
+    pub(crate) nameservers_by_ip: HashMap<IpAddr, NameserversByLdh>,
+    pub(crate) nameservers_by_related: ReverseIndex,
+    pub(crate) entities: HashMap<String, Arc<RdapResponse>>,
+    pub(crate) entities_by_handle: EntitiesByFolded,
+    pub(crate) entities_by_full_name: EntitiesByFolded,
+    pub(crate) entities_by_related: ReverseIndex,
+    pub(crate) srvhelps: HashMap<String, Arc<RdapResponse>>,
+    /// The names left in the label searches by deletions since they were built.
+    /// Searches skip them as they are no longer stored.
+    stale_search_labels: usize,
+}
+
+/// Label searches built from the maps, to replace those holding stale names.
+pub(crate) struct FreshSearchLabels {
+    domains_by_name: SearchLabels<String>,
+    nameservers_by_name: SearchLabels<String>,
+    domains_by_ns_name: SearchLabels<String>,
+}
+
+/// A change to the objects of storage, made as the maps are changed in place.
+pub(crate) enum Change {
+    AddEntity(String, Arc<RdapResponse>),
+    AddDomain(String, Arc<RdapResponse>),
+    AddNameserver(String, Arc<RdapResponse>),
+    AddAutnum(u32, u32, Arc<RdapResponse>),
+    AddNetwork(Vec<IpNet>, Arc<RdapResponse>),
+    AddSrvHelp(String, Arc<RdapResponse>),
+    DeleteEntity(String),
+    DeleteDomain(DomainId),
+    DeleteNameserver(String),
+    DeleteAutnum(AutnumId),
+    DeleteNetwork(Vec<IpNet>),
+}
+
+impl Change {
+    /// Returns true if a deletion finds its object in the maps once the earlier
+    /// changes are made to them, which is always the case for additions.
+    pub(crate) fn finds(&self, earlier: &[Change], maps: &MemMaps) -> bool {
+        let latest = |matches: &dyn Fn(&Change) -> Option<bool>| -> Option<bool> {
+            earlier.iter().rev().find_map(matches)
+        };
+        match self {
+            Self::DeleteEntity(handle) => latest(&|change| match change {
+                Self::AddEntity(added, _) if added == handle => Some(true),
+                Self::DeleteEntity(deleted) if deleted == handle => Some(false),
+                _ => None,
+            })
+            .unwrap_or_else(|| maps.entities.contains_key(handle)),
+            Self::DeleteDomain(domain_id) => latest(&|change| match change {
+                Self::AddDomain(added, _) if *added == domain_id.ldh_name => Some(true),
+                Self::DeleteDomain(deleted) if deleted.ldh_name == domain_id.ldh_name => {
+                    Some(false)
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| maps.domains.contains_key(&domain_id.ldh_name)),
+            Self::DeleteNameserver(ldh_name) => latest(&|change| match change {
+                Self::AddNameserver(added, _) if added == ldh_name => Some(true),
+                Self::DeleteNameserver(deleted) if deleted == ldh_name => Some(false),
+                _ => None,
+            })
+            .unwrap_or_else(|| maps.nameservers.contains_key(ldh_name)),
+            Self::DeleteAutnum(autnum_id) => {
+                let ends = [autnum_id.start_autnum, autnum_id.end_autnum];
+                latest(&|change| match change {
+                    Self::AddAutnum(start, end, _) if ends == [*start, *end] => Some(true),
+                    // an autnum added over either end of the range replaces it there.
+                    Self::AddAutnum(start, end, _)
+                        if ends.iter().any(|num| (*start..=*end).contains(num)) =>
+                    {
+                        Some(false)
+                    }
+                    Self::DeleteAutnum(deleted) if deleted == autnum_id => Some(false),
+                    _ => None,
+                })
+                .unwrap_or_else(|| maps.autnum_with_range(autnum_id).is_some())
+            }
+            Self::DeleteNetwork(nets) => nets.iter().any(|net| {
+                latest(&|change| match change {
+                    Self::AddNetwork(added, _) if added.contains(net) => Some(true),
+                    Self::DeleteNetwork(deleted) if deleted.contains(net) => Some(false),
+                    _ => None,
+                })
+                .unwrap_or_else(|| maps.has_network(net))
+            }),
+            _ => true,
+        }
+    }
+}
+
+impl MemMaps {
+    pub(crate) fn new(common_config: CommonConfig) -> Self {
+        Self {
+            common_config,
+            autnums: RangeMap::new(),
+            ip4: PrefixMap::new(),
+            ip6: PrefixMap::new(),
+            domains: HashMap::new(),
+            domains_by_name: SearchLabels::builder().build(),
+            domains_by_ns_name: SearchLabels::builder().build(),
+            domains_by_ns_ldh: HashMap::new(),
+            domains_by_ns_ip: HashMap::new(),
+            idns: HashMap::new(),
+            domain_variants: HashMap::new(),
+            domains_by_related: ReverseIndex::default(),
+            nameservers: HashMap::new(),
+            nameservers_by_name: SearchLabels::builder().build(),
+            nameservers_by_ip: HashMap::new(),
+            nameservers_by_related: ReverseIndex::default(),
+            entities: HashMap::new(),
+            entities_by_handle: EntitiesByFolded::new(),
+            entities_by_full_name: EntitiesByFolded::new(),
+            entities_by_related: ReverseIndex::default(),
+            srvhelps: HashMap::new(),
+            stale_search_labels: 0,
+        }
+    }
+
+    /// Creates the maps of a snapshot, building the indexes derived from them
+    /// other than the label searches it holds.
+    pub(crate) fn restore(common_config: CommonConfig, stored: StoredMaps) -> Self {
+        let mut maps = Self::new(common_config);
+        for (start_autnum, end_autnum, autnum) in stored.autnums {
+            maps.autnums.insert(start_autnum..=end_autnum, autnum);
+        }
+        for (net, network) in stored.ip4 {
+            maps.ip4.insert(net, network);
+        }
+        for (net, network) in stored.ip6 {
+            maps.ip6.insert(net, network);
+        }
+        for (ldh_name, domain_response) in stored.domains {
+            if let RdapResponse::Domain(domain) = domain_response.as_ref() {
+                maps.add_domain_nameservers(domain, &ldh_name, &domain_response);
+                maps.add_domain_variants(domain, &domain_response);
+            }
+            maps.add_related(&ldh_name, &domain_response);
+            maps.domains.insert(ldh_name, domain_response);
+        }
+        maps.idns.extend(stored.idns);
+        for (ldh_name, nameserver_response) in stored.nameservers {
+            if let RdapResponse::Nameserver(nameserver) = nameserver_response.as_ref() {
+                maps.add_nameserver_ips(nameserver, &ldh_name, &nameserver_response);
+            }
+            maps.add_related(&ldh_name, &nameserver_response);
+            maps.nameservers.insert(ldh_name, nameserver_response);
+        }
+        for (handle, entity_response) in stored.entities {
+            if let RdapResponse::Entity(entity) = entity_response.as_ref() {
+                maps.add_entity_names(entity, &handle);
+            }
+            maps.add_related(&handle, &entity_response);
+            maps.entities.insert(handle, entity_response);
+        }
+        maps.srvhelps.extend(stored.srvhelps);
+        // label searches not in the snapshot, as they were not enabled then, are built.
+        maps.domains_by_name = match stored.domains_by_name {
+            Some(labels) if common_config.domain_search_by_name_enable => labels,
+            _ => name_search_labels(&maps.domains, common_config.domain_search_by_name_enable),
+        };
+        maps.domains_by_ns_name = match stored.domains_by_ns_name {
+            Some(labels) if common_config.domain_search_by_ns_ldh_name_enable => labels,
+            _ => ns_name_search_labels(
+                &maps.domains_by_ns_ldh,
+                common_config.domain_search_by_ns_ldh_name_enable,
+            ),
+        };
+        maps.nameservers_by_name = match stored.nameservers_by_name {
+            Some(labels) if common_config.nameserver_search_by_name_enable => labels,
+            _ => name_search_labels(
+                &maps.nameservers,
+                common_config.nameserver_search_by_name_enable,
+            ),
+        };
+        maps
+    }
+
+    /// Makes a change, returning false if it is a deletion that found nothing
+    /// to delete.
+    pub(crate) fn apply(&mut self, change: Change) -> bool {
+        match change {
+            Change::AddEntity(handle, entity_response) => self.add_entity(handle, entity_response),
+            Change::AddDomain(ldh_name, domain_response) => {
+                self.add_domain(ldh_name, domain_response)
+            }
+            Change::AddNameserver(ldh_name, nameserver_response) => {
+                self.add_nameserver(ldh_name, nameserver_response)
+            }
+            Change::AddAutnum(start_autnum, end_autnum, autnum_response) => {
+                self.autnums
+                    .insert(start_autnum..=end_autnum, autnum_response);
+            }
+            Change::AddNetwork(nets, network_response) => {
+                for net in nets {
+                    match net {
+                        IpNet::V4(net) => self.ip4.insert(net, network_response.clone()),
+                        IpNet::V6(net) => self.ip6.insert(net, network_response.clone()),
+                    };
+                }
+            }
+            Change::AddSrvHelp(host, help_response) => {
+                self.srvhelps.insert(host, help_response);
+            }
+            Change::DeleteEntity(handle) => return self.delete_entity(&handle),
+            Change::DeleteDomain(domain_id) => return self.delete_domain(&domain_id),
+            Change::DeleteNameserver(ldh_name) => return self.delete_nameserver(&ldh_name),
+            Change::DeleteAutnum(autnum_id) => return self.delete_autnum(&autnum_id),
+            Change::DeleteNetwork(nets) => return self.delete_network(&nets),
+        }
+        true
+    }
+
+    /// True once the label searches hold more stale names than stored ones, so
+    /// that rebuilding them takes time in proportion to the deletions made.
+    pub(crate) fn has_stale_search_labels(&self) -> bool {
+        self.stale_search_labels
+            > self.domains.len() + self.nameservers.len() + self.domains_by_ns_ldh.len()
+    }
+
+    /// Builds the label searches from the stored objects.
+    pub(crate) fn fresh_search_labels(&self) -> FreshSearchLabels {
+        FreshSearchLabels {
+            domains_by_name: name_search_labels(
+                &self.domains,
+                self.common_config.domain_search_by_name_enable,
+            ),
+            nameservers_by_name: name_search_labels(
+                &self.nameservers,
+                self.common_config.nameserver_search_by_name_enable,
+            ),
+            domains_by_ns_name: ns_name_search_labels(
+                &self.domains_by_ns_ldh,
+                self.common_config.domain_search_by_ns_ldh_name_enable,
+            ),
+        }
+    }
+
+    /// Replaces the label searches with those built since the last change.
+    pub(crate) fn replace_search_labels(&mut self, fresh: FreshSearchLabels) {
+        self.domains_by_name = fresh.domains_by_name;
+        self.nameservers_by_name = fresh.nameservers_by_name;
+        self.domains_by_ns_name = fresh.domains_by_ns_name;
+        self.stale_search_labels = 0;
+    }
+
+    fn has_network(&self, net: &IpNet) -> bool {
+        match net {
+            IpNet::V4(net) => self.ip4.get(net).is_some(),
+            IpNet::V6(net) => self.ip6.get(net).is_some(),
+        }
+    }
+
+    fn add_entity(&mut self, handle: String, entity_response: Arc<RdapResponse>) {
+        let replaced = self
+            .entities
+            .insert(handle.clone(), entity_response.clone());
+        if let Some(replaced) = replaced.as_ref() {
+            self.remove_entity_names(replaced, &handle);
+            self.remove_related(&handle, replaced);
+        }
+        if let RdapResponse::Entity(entity) = entity_response.as_ref() {
+            self.add_entity_names(entity, &handle);
+        }
+        self.add_related(&handle, &entity_response);
+    }
+
+    fn delete_entity(&mut self, handle: &str) -> bool {
+        let Some(deleted) = self.entities.remove(handle) else {
+            return false;
+        };
+        self.remove_entity_names(&deleted, handle);
+        self.remove_related(handle, &deleted);
+        true
+    }
+
+    /// Adds an entity to the indexes of entity searches by handle and full name.
+    fn add_entity_names(&mut self, entity: &Entity, handle: &str) {
+        if self.common_config.entity_search_by_handle_enable {
+            self.entities_by_handle
+                .insert((fold_case(handle), handle.to_owned()));
+        }
+        if self.common_config.entity_search_by_full_name_enable {
+            if let Some(full_name) = entity.contact().and_then(|contact| contact.full_name) {
+                self.entities_by_full_name
+                    .insert((fold_case(&full_name), handle.to_owned()));
+            }
+        }
+    }
+
+    /// Removes an entity that is being replaced or deleted from the indexes of
+    /// entity searches.
+    fn remove_entity_names(&mut self, removed: &RdapResponse, handle: &str) {
+        let RdapResponse::Entity(removed) = removed else {
+            return;
+        };
+        self.entities_by_handle
+            .remove(&(fold_case(handle), handle.to_owned()));
+        if let Some(full_name) = removed.contact().and_then(|contact| contact.full_name) {
+            self.entities_by_full_name
+                .remove(&(fold_case(&full_name), handle.to_owned()));
+        }
+    }
+
+    /// Gets the entities of the handles found in an index of entity searches.
+    pub(crate) fn indexed_entities<'a>(
+        &'a self,
+        handles: impl Iterator<Item = &'a String> + 'a,
+    ) -> impl Iterator<Item = &'a Entity> + 'a {
+        handles.filter_map(|handle| match self.entities.get(handle)?.as_ref() {
+            RdapResponse::Entity(entity) => Some(entity.as_ref()),
+            _ => None,
+        })
+    }
+
+    fn add_domain(&mut self, ldh_name: String, domain_response: Arc<RdapResponse>) {
+        let replaced = self
+            .domains
+            .insert(ldh_name.clone(), domain_response.clone());
+        if let Some(replaced) = replaced.as_ref() {
+            self.remove_domain_indexes(replaced, &ldh_name, None);
+        }
+        let RdapResponse::Domain(domain) = domain_response.as_ref() else {
+            return;
+        };
+        for ns_name in self.add_domain_nameservers(domain, &ldh_name, &domain_response) {
+            self.domains_by_ns_name.insert(&ns_name, ns_name.clone());
+        }
+        self.add_domain_variants(domain, &domain_response);
+        self.add_related(&ldh_name, &domain_response);
+
+        // add the domain by unicodeName
+        if let Some(unicode_name) = domain.unicode_name.as_deref() {
+            self.idns
+                .insert(unicode_name_key(unicode_name), domain_response.clone());
+        };
+
+        if self.common_config.domain_search_by_name_enable {
+            self.domains_by_name.insert(&ldh_name, ldh_name.clone());
+        }
+    }
+
+    fn delete_domain(&mut self, domain_id: &DomainId) -> bool {
+        let Some(deleted) = self.domains.remove(&domain_id.ldh_name) else {
+            return false;
+        };
+        self.remove_domain_indexes(
+            &deleted,
+            &domain_id.ldh_name,
+            domain_id.unicode_name.as_ref(),
+        );
+        self.stale_search_labels += 1;
+        true
+    }
+
+    /// Removes a domain that is being replaced or deleted from the indexes
+    /// other than the label search. The unicode name of the domain is used when
+    /// it is an error, which does not have one.
+    fn remove_domain_indexes(
+        &mut self,
+        removed: &Arc<RdapResponse>,
+        ldh_name: &str,
+        unicode_name: Option<&String>,
+    ) {
+        self.remove_domain_nameservers(removed, ldh_name);
+        self.remove_domain_variants(removed);
+        self.remove_related(ldh_name, removed);
+        let unicode_name = match removed.as_ref() {
+            RdapResponse::Domain(domain) => domain.unicode_name.as_deref().map(unicode_name_key),
+            _ => unicode_name.cloned(),
+        };
+        if let Some(unicode_name) = unicode_name.as_ref() {
+            if self
+                .idns
+                .get(unicode_name)
+                .is_some_and(|idn| Arc::ptr_eq(idn, removed))
+            {
+                self.idns.remove(unicode_name);
+            }
+        }
+    }
+
+    fn add_nameserver(&mut self, ldh_name: String, nameserver_response: Arc<RdapResponse>) {
+        let replaced = self
+            .nameservers
+            .insert(ldh_name.clone(), nameserver_response.clone());
+        if let Some(replaced) = replaced.as_ref() {
+            self.remove_nameserver_ips(replaced, &ldh_name);
+            self.remove_related(&ldh_name, replaced);
+        }
+        let RdapResponse::Nameserver(nameserver) = nameserver_response.as_ref() else {
+            return;
+        };
+        if self.common_config.nameserver_search_by_name_enable {
+            self.nameservers_by_name.insert(&ldh_name, ldh_name.clone());
+        }
+        self.add_nameserver_ips(nameserver, &ldh_name, &nameserver_response);
+        self.add_related(&ldh_name, &nameserver_response);
+    }
+
+    fn delete_nameserver(&mut self, ldh_name: &str) -> bool {
+        let Some(deleted) = self.nameservers.remove(ldh_name) else {
+            return false;
+        };
+        self.remove_nameserver_ips(&deleted, ldh_name);
+        self.remove_related(ldh_name, &deleted);
+        self.stale_search_labels += 1;
+        true
+    }
+
+    /// Gets the autnum added with exactly the range of an ID, which holds both
+    /// ends of the range and neither number beyond them.
+    fn autnum_with_range(&self, autnum_id: &AutnumId) -> Option<&Arc<RdapResponse>> {
+        let (start, end) = (autnum_id.start_autnum, autnum_id.end_autnum);
+        let autnum = self.autnums.get(start)?;
+        let holds = |num: u32| {
+            self.autnums
+                .get(num)
+                .is_some_and(|other| Arc::ptr_eq(other, autnum))
+        };
+        let exact = holds(end)
+            && !start.checked_sub(1).is_some_and(holds)
+            && !end.checked_add(1).is_some_and(holds);
+        exact.then_some(autnum)
+    }
+
+    fn delete_autnum(&mut self, autnum_id: &AutnumId) -> bool {
+        let Some(deleted) = self.autnum_with_range(autnum_id).cloned() else {
+            return false;
+        };
+        // only the parts of the range still held by the autnum are removed.
+        self.autnums
+            .update((autnum_id.start_autnum)..=(autnum_id.end_autnum), |value| {
+                value.filter(|value| !Arc::ptr_eq(value, &deleted)).cloned()
+            });
+        true
+    }
+
+    fn delete_network(&mut self, nets: &[IpNet]) -> bool {
+        let mut deleted = false;
+        for net in nets {
+            deleted |= match net {
+                IpNet::V4(net) => self.ip4.remove(net).is_some(),
+                IpNet::V6(net) => self.ip6.remove(net).is_some(),
+            };
+        }
+        deleted
+    }
+
+    /// Adds a domain to the indexes of the nameservers it contains, returning
+    /// the names of those new to the index, which are yet to be label searched.
+    fn add_domain_nameservers(
+        &mut self,
+        domain: &Domain,
+        ldh_name: &str,
+        domain_response: &Arc<RdapResponse>,
+    ) -> Vec<String> {
+        let common_config = self.common_config;
+        let mut new_ns_names = vec![];
+        for nameserver in domain.nameservers.iter().flatten() {
+            if common_config.domain_search_by_ns_ldh_name_enable {
+                if let Some(ns_name) = nameserver.ldh_name.as_ref() {
+                    let domains = self
+                        .domains_by_ns_ldh
+                        .entry(ns_name.to_owned())
+                        .or_insert_with(|| {
+                            new_ns_names.push(ns_name.to_owned());
+                            DomainsByLdh::new()
+                        });
+                    domains.insert(ldh_name.to_owned(), domain_response.clone());
+                }
+            }
+            if common_config.domain_search_by_ns_ip_enable {
+                for ip in nameserver_ips(nameserver) {
+                    self.domains_by_ns_ip
+                        .entry(ip)
+                        .or_default()
+                        .insert(ldh_name.to_owned(), domain_response.clone());
+                }
+            }
+        }
+        new_ns_names
+    }
+
+    /// Removes a domain from the indexes of its nameservers.
+    fn remove_domain_nameservers(&mut self, replaced: &RdapResponse, ldh_name: &str) {
+        let RdapResponse::Domain(replaced) = replaced else {
+            return;
+        };
+        for nameserver in replaced.nameservers.iter().flatten() {
+            if let Some(ns_name) = nameserver.ldh_name.as_ref() {
+                if let Some(domains) = self.domains_by_ns_ldh.get_mut(ns_name) {
+                    domains.remove(ldh_name);
+                    if domains.is_empty() {
+                        self.domains_by_ns_ldh.remove(ns_name);
+                        // the label search is left holding the nameserver name.
+                        self.stale_search_labels += 1;
+                    }
+                }
+            }
+            for ip in nameserver_ips(nameserver) {
+                if let Some(domains) = self.domains_by_ns_ip.get_mut(&ip) {
+                    domains.remove(ldh_name);
+                    if domains.is_empty() {
+                        self.domains_by_ns_ip.remove(&ip);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds a domain to the index of its variant names.
+    fn add_domain_variants(&mut self, domain: &Domain, domain_response: &Arc<RdapResponse>) {
+        for variant_name in domain_variant_names(domain) {
+            self.domain_variants
+                .insert(variant_name, domain_response.clone());
+        }
+    }
+
+    /// Removes a domain that is being replaced or deleted from the index of its
+    /// variant names, leaving names since taken by other domains.
+    fn remove_domain_variants(&mut self, replaced: &Arc<RdapResponse>) {
+        let RdapResponse::Domain(domain) = replaced.as_ref() else {
+            return;
+        };
+        for variant_name in domain_variant_names(domain) {
+            if self
+                .domain_variants
+                .get(&variant_name)
+                .is_some_and(|indexed| Arc::ptr_eq(indexed, replaced))
+            {
+                self.domain_variants.remove(&variant_name);
+            }
+        }
+    }
+
+    /// Adds a domain, nameserver, or entity to the reverse search index of its
+    /// object class.
+    fn add_related(&mut self, key: &str, response: &RdapResponse) {
+        if self.common_config.reverse_search_enable {
+            if let Some((index, entities)) = self.reverse_index(response) {
+                index.insert(key, entities);
+            }
+        }
+    }
+
+    /// Removes a domain, nameserver, or entity that is being replaced or deleted
+    /// from the reverse search index of its object class.
+    fn remove_related(&mut self, key: &str, removed: &RdapResponse) {
+        if let Some((index, entities)) = self.reverse_index(removed) {
+            index.remove(key, entities);
+        }
+    }
+
+    /// Gets the reverse search index of an object and the entities it is indexed by.
+    fn reverse_index<'a>(
+        &mut self,
+        response: &'a RdapResponse,
+    ) -> Option<(&mut ReverseIndex, &'a [Entity])> {
+        match response {
+            RdapResponse::Domain(domain) => Some((&mut self.domains_by_related, domain.entities())),
+            RdapResponse::Nameserver(nameserver) => {
+                Some((&mut self.nameservers_by_related, nameserver.entities()))
+            }
+            RdapResponse::Entity(entity) => {
+                Some((&mut self.entities_by_related, entity.entities()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds a nameserver to the IP address index.
+    fn add_nameserver_ips(
+        &mut self,
+        nameserver: &Nameserver,
+        ldh_name: &str,
+        nameserver_response: &Arc<RdapResponse>,
+    ) {
+        if self.common_config.nameserver_search_by_ip_enable {
+            for ip in nameserver_ips(nameserver) {
+                self.nameservers_by_ip
+                    .entry(ip)
+                    .or_default()
+                    .insert(ldh_name.to_owned(), nameserver_response.clone());
+            }
+        }
+    }
+
+    /// Removes a nameserver that is being replaced or deleted from the IP address index.
+    fn remove_nameserver_ips(&mut self, replaced: &RdapResponse, ldh_name: &str) {
+        let RdapResponse::Nameserver(replaced) = replaced else {
+            return;
+        };
+        for ip in nameserver_ips(replaced) {
+            if let Some(nameservers) = self.nameservers_by_ip.get_mut(&ip) {
+                nameservers.remove(ldh_name);
+                if nameservers.is_empty() {
+                    self.nameservers_by_ip.remove(&ip);
+                }
+            }
+        }
+    }
+}
+
+/// Builds the label search of the names of the nameservers of domains, which
+/// is left empty unless the search is enabled.
+fn ns_name_search_labels(
+    domains_by_ns_ldh: &HashMap<String, DomainsByLdh>,
+    enabled: bool,
+) -> SearchLabels<String> {
+    let mut search_labels = SearchLabels::builder().build();
+    if enabled {
+        for ns_name in domains_by_ns_ldh.keys() {
+            search_labels.insert(ns_name, ns_name.to_owned());
+        }
+    }
+    search_labels
+}
+
+/// Builds the label search of the names of domains or nameservers, which is
+/// left empty unless the search is enabled.
+fn name_search_labels(
+    objects: &HashMap<String, Arc<RdapResponse>>,
+    enabled: bool,
+) -> SearchLabels<String> {
+    let mut search_labels = SearchLabels::builder().build();
+    if enabled {
+        for name in objects.keys() {
+            search_labels.insert(name, name.to_owned());
+        }
+    }
+    search_labels
+}
@@ -1,6 +1,9 @@
 #![allow(dead_code)] // TODO remove
 
 pub mod config;
-mod label_search;
+pub(crate) mod label_search;
+pub(crate) mod maps;
 pub mod ops;
+pub(crate) mod reverse_search;
+pub(crate) mod snapshot;
 pub mod tx;
@@ -1,56 +1,87 @@
-use std::{collections::HashMap, net::IpAddr, str::FromStr, sync::Arc};
+use std::{collections::HashSet, net::IpAddr, path::Path, str::FromStr, sync::Arc};
 
 use {
     async_trait::async_trait,
-    btree_range_map::RangeMap,
     icann_rdap_common::{
         prelude::ToResponse,
-        response::{Domain, DomainSearchResults, RdapResponse},
+        response::{
+            Autnum, AutnumSearchResults, Domain, DomainSearchResults, Entity, EntitySearchResults,
+            Nameserver, NameserverSearchResults, Network, NetworkSearchResults, RdapResponse,
+        },
+        search_types::{RelatedEntityProperty, RirSearchRelation},
     },
     ipnet::{IpNet, Ipv4Net, Ipv6Net},
-    prefix_trie::PrefixMap,
-    tokio::sync::RwLock,
+    tokio::sync::{Mutex, RwLock},
 };
 
 use crate::{
     error::RdapServerError,
     rdap::response::{NOT_FOUND, NOT_IMPLEMENTED},
-    storage::{CommonConfig, StoreOps, TxHandle},
+    storage::{
+        data::DataFiles,
+        rir_search::{distinct_networks, network_relation},
+        search::{page_results, PartialSearch},
+        CommonConfig, ObjectCounts, SearchPage, StoreOps, TxHandle,
+    },
 };
 
-use super::{config::MemConfig, label_search::SearchLabels, tx::MemTx};
+use super::{
+    config::MemConfig,
+    maps::{DomainsByLdh, MemMaps},
+    reverse_search::ReverseSearchMatch,
+    snapshot,
+    tx::MemTx,
+};
 
 #[derive(Clone)]
 pub struct Mem {
-    pub(crate) autnums: Arc<RwLock<RangeMap<u32, Arc<RdapResponse>>>>,
-    pub(crate) ip4: Arc<RwLock<PrefixMap<Ipv4Net, Arc<RdapResponse>>>>,
-    pub(crate) ip6: Arc<RwLock<PrefixMap<Ipv6Net, Arc<RdapResponse>>>>,
-    pub(crate) domains: Arc<RwLock<HashMap<String, Arc<RdapResponse>>>>,
-    pub(crate) domains_by_name: Arc<RwLock<SearchLabels<Arc<RdapResponse>>>>,
-    pub(crate) idns: Arc<RwLock<HashMap<String, Arc<RdapResponse>>>>,
-    pub(crate) nameservers: Arc<RwLock<HashMap<String, Arc<RdapResponse>>>>,
-    pub(crate) entities: Arc<RwLock<HashMap<String, Arc<RdapResponse>>>>,
-    pub(crate) srvhelps: Arc<RwLock<HashMap<String, Arc<RdapResponse>>>>,
+    pub(crate) maps: Arc<RwLock<MemMaps>>,
+    /// Held by transactions, so they are made one at a time.
+    pub(crate) writer: Arc<Mutex<()>>,
     pub(crate) config: MemConfig,
 }
 
 impl Mem {
     pub fn new(config: MemConfig) -> Self {
         Self {
-            autnums: <_>::default(),
-            ip4: <_>::default(),
-            ip6: <_>::default(),
-            domains: <_>::default(),
-            domains_by_name: Arc::new(RwLock::new(SearchLabels::builder().build())),
-            idns: <_>::default(),
-            nameservers: <_>::default(),
-            entities: <_>::default(),
-            srvhelps: <_>::default(),
+            maps: Arc::new(RwLock::new(MemMaps::new(config.common_config))),
+            writer: <_>::default(),
             config,
         }
     }
 }
 
+/// Gets one of each network.
+fn networks(maps: &MemMaps) -> Vec<&Network> {
+    distinct_networks(
+        maps.ip4
+            .iter()
+            .map(|(_, network)| network)
+            .chain(maps.ip6.iter().map(|(_, network)| network))
+            .filter_map(to_network),
+    )
+}
+
+/// Gets one of each autnum.
+fn autnums(maps: &MemMaps) -> Vec<&Autnum> {
+    let mut seen = HashSet::new();
+    maps.autnums
+        .iter()
+        .filter(|(_, autnum)| seen.insert(Arc::as_ptr(autnum)))
+        .filter_map(|(_, autnum)| match autnum.as_ref() {
+            RdapResponse::Autnum(a) => Some(a.as_ref()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn to_network(network: &Arc<RdapResponse>) -> Option<&Network> {
+    match network.as_ref() {
+        RdapResponse::Network(n) => Some(n.as_ref()),
+        _ => None,
+    }
+}
+
 impl Default for Mem {
     fn default() -> Self {
         Self::new(
@@ -72,11 +103,12 @@ impl StoreOps for Mem {
     }
 
     async fn new_truncate_tx(&self) -> Result<Box<dyn TxHandle>, RdapServerError> {
-        Ok(Box::new(MemTx::new_truncate(self)))
+        Ok(Box::new(MemTx::new_truncate(self).await))
     }
 
     async fn get_domain_by_ldh(&self, ldh: &str) -> Result<RdapResponse, RdapServerError> {
-        let domains = self.domains.read().await;
+        let maps = self.maps.read().await;
+        let domains = &maps.domains;
         let result = domains.get(ldh);
         match result {
             Some(domain) => Ok(RdapResponse::clone(domain)),
@@ -85,7 +117,8 @@ impl StoreOps for Mem {
     }
 
     async fn get_domain_by_unicode(&self, unicode: &str) -> Result<RdapResponse, RdapServerError> {
-        let idns = self.idns.read().await;
+        let maps = self.maps.read().await;
+        let idns = &maps.idns;
         let result = idns.get(unicode);
         match result {
             Some(domain) => Ok(RdapResponse::clone(domain)),
@@ -93,8 +126,19 @@ impl StoreOps for Mem {
         }
     }
 
+    async fn get_domain_by_variant(&self, variant: &str) -> Result<RdapResponse, RdapServerError> {
+        let maps = self.maps.read().await;
+        let domain_variants = &maps.domain_variants;
+        let result = domain_variants.get(variant);
+        match result {
+            Some(domain) => Ok(RdapResponse::clone(domain)),
+            None => Ok(NOT_FOUND.clone()),
+        }
+    }
+
     async fn get_entity_by_handle(&self, handle: &str) -> Result<RdapResponse, RdapServerError> {
-        let entities = self.entities.read().await;
+        let maps = self.maps.read().await;
+        let entities = &maps.entities;
         let result = entities.get(handle);
         match result {
             Some(entity) => Ok(RdapResponse::clone(entity)),
@@ -103,7 +147,8 @@ impl StoreOps for Mem {
     }
 
     async fn get_nameserver_by_ldh(&self, ldh: &str) -> Result<RdapResponse, RdapServerError> {
-        let nameservers = self.nameservers.read().await;
+        let maps = self.maps.read().await;
+        let nameservers = &maps.nameservers;
         let result = nameservers.get(ldh);
         match result {
             Some(nameserver) => Ok(RdapResponse::clone(nameserver)),
@@ -112,7 +157,8 @@ impl StoreOps for Mem {
     }
 
     async fn get_autnum_by_num(&self, num: u32) -> Result<RdapResponse, RdapServerError> {
-        let autnums = self.autnums.read().await;
+        let maps = self.maps.read().await;
+        let autnums = &maps.autnums;
         let result = autnums.get(num);
         match result {
             Some(autnum) => Ok(RdapResponse::clone(autnum)),
@@ -125,7 +171,8 @@ impl StoreOps for Mem {
         match addr {
             IpAddr::V4(v4) => {
                 let slash32 = Ipv4Net::new(v4, 32)?;
-                let ip4s = self.ip4.read().await;
+                let maps = self.maps.read().await;
+                let ip4s = &maps.ip4;
                 let result = ip4s.get_lpm(&slash32);
                 match result {
                     Some(network) => Ok(RdapResponse::clone(network.1)),
@@ -134,7 +181,8 @@ impl StoreOps for Mem {
             }
             IpAddr::V6(v6) => {
                 let slash128 = Ipv6Net::new(v6, 128)?;
-                let ip6s = self.ip6.read().await;
+                let maps = self.maps.read().await;
+                let ip6s = &maps.ip6;
                 let result = ip6s.get_lpm(&slash128);
                 match result {
                     Some(network) => Ok(RdapResponse::clone(network.1)),
@@ -148,7 +196,8 @@ impl StoreOps for Mem {
         let net = IpNet::from_str(cidr)?;
         match net {
             IpNet::V4(ipv4net) => {
-                let ip4s = self.ip4.read().await;
+                let maps = self.maps.read().await;
+                let ip4s = &maps.ip4;
                 let result = ip4s.get_lpm(&ipv4net);
                 match result {
                     Some(network) => Ok(RdapResponse::clone(network.1)),
@@ -156,7 +205,8 @@ impl StoreOps for Mem {
                 }
             }
             IpNet::V6(ipv6net) => {
-                let ip6s = self.ip6.read().await;
+                let maps = self.maps.read().await;
+                let ip6s = &maps.ip6;
                 let result = ip6s.get_lpm(&ipv6net);
                 match result {
                     Some(network) => Ok(RdapResponse::clone(network.1)),
@@ -168,7 +218,8 @@ impl StoreOps for Mem {
 
     async fn get_srv_help(&self, host: Option<&str>) -> Result<RdapResponse, RdapServerError> {
         let host = host.unwrap_or("..default");
-        let srvhelps = self.srvhelps.read().await;
+        let maps = self.maps.read().await;
+        let srvhelps = &maps.srvhelps;
         let result = srvhelps.get(host);
         match result {
             Some(srvhelp) => Ok(RdapResponse::clone(srvhelp)),
@@ -176,26 +227,480 @@ impl StoreOps for Mem {
         }
     }
 
-    async fn search_domains_by_name(&self, name: &str) -> Result<RdapResponse, RdapServerError> {
+    async fn search_domains_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
         if !self.config.common_config.domain_search_by_name_enable {
             return Ok(NOT_IMPLEMENTED.clone());
         }
         //else
-        let domains_by_name = self.domains_by_name.read().await;
-        let results = domains_by_name
-            .search(name)
-            .unwrap_or_default()
+        let maps = self.maps.read().await;
+        let found = maps.domains_by_name.search(name)?;
+        let found = found.iter().filter_map(|name| maps.domains.get(name));
+        let (results, paging_metadata) = page_results(domains(found), page);
+        let response = DomainSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_domains_by_ns_ldh_name(
+        &self,
+        ns_ldh_name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self
+            .config
+            .common_config
+            .domain_search_by_ns_ldh_name_enable
+        {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let maps = self.maps.read().await;
+        let domains_by_ns_name = &maps.domains_by_ns_name;
+        let ns_names = if ns_ldh_name.contains('*') {
+            domains_by_ns_name.search(ns_ldh_name)?
+        } else {
+            vec![ns_ldh_name.to_string()]
+        };
+        let domains_by_ns_ldh = &maps.domains_by_ns_ldh;
+        // a domain may be found by more than one of its nameservers.
+        let mut found = DomainsByLdh::new();
+        for ns_name in ns_names {
+            if let Some(domains) = domains_by_ns_ldh.get(&ns_name) {
+                found.extend(domains.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        let (results, paging_metadata) = page_results(domains(found.values()), page);
+        let response = DomainSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_domains_by_ns_ip(
+        &self,
+        ip: IpAddr,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.domain_search_by_ns_ip_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let maps = self.maps.read().await;
+        let found = maps
+            .domains_by_ns_ip
+            .get(&ip)
             .into_iter()
-            .map(Arc::<RdapResponse>::unwrap_or_clone)
-            .filter_map(|d| match d {
-                RdapResponse::Domain(d) => Some(*d),
-                _ => None,
-            })
-            .collect::<Vec<Domain>>();
+            .flat_map(|d| d.values());
+        let (results, paging_metadata) = page_results(domains(found), page);
         let response = DomainSearchResults::builder()
             .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_nameservers_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.nameserver_search_by_name_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let maps = self.maps.read().await;
+        let found = maps.nameservers_by_name.search(name)?;
+        let found = found.iter().filter_map(|name| maps.nameservers.get(name));
+        let (results, paging_metadata) = page_results(nameservers(found), page);
+        let response = NameserverSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_nameservers_by_ip(
+        &self,
+        ip: IpAddr,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.nameserver_search_by_ip_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let maps = self.maps.read().await;
+        let found = maps
+            .nameservers_by_ip
+            .get(&ip)
+            .into_iter()
+            .flat_map(|n| n.values());
+        let (results, paging_metadata) = page_results(nameservers(found), page);
+        let response = NameserverSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn reverse_search_domains(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.reverse_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = ReverseSearchMatch::parse(property, value, role)?;
+        let maps = self.maps.read().await;
+        let found = search
+            .find_keys(&maps.domains_by_related)
+            .into_iter()
+            .filter_map(|key| maps.domains.get(key));
+        let (results, paging_metadata) = page_results(domains(found), page);
+        let response = DomainSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn reverse_search_nameservers(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.reverse_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = ReverseSearchMatch::parse(property, value, role)?;
+        let maps = self.maps.read().await;
+        let found = search
+            .find_keys(&maps.nameservers_by_related)
+            .into_iter()
+            .filter_map(|key| maps.nameservers.get(key));
+        let (results, paging_metadata) = page_results(nameservers(found), page);
+        let response = NameserverSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn reverse_search_entities(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.reverse_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = ReverseSearchMatch::parse(property, value, role)?;
+        let maps = self.maps.read().await;
+        let found = search
+            .find_keys(&maps.entities_by_related)
+            .into_iter()
+            .filter_map(|key| maps.entities.get(key));
+        let (results, paging_metadata) = page_results(entities(found), page);
+        let response = EntitySearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_networks_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = PartialSearch::parse(handle)?;
+        let maps = self.maps.read().await;
+        let networks = networks(&maps);
+        let found = networks.iter().copied().filter(|n| {
+            n.object_common
+                .handle
+                .as_ref()
+                .is_some_and(|handle| search.is_match(handle))
+        });
+        let (results, paging_metadata) = page_results(found, page);
+        let response = NetworkSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_networks_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = PartialSearch::parse(name)?;
+        let maps = self.maps.read().await;
+        let networks = networks(&maps);
+        let found = networks.iter().copied().filter(|n| {
+            n.name
+                .as_ref()
+                .is_some_and(|name| search.is_match_ignore_case(name))
+        });
+        let (results, paging_metadata) = page_results(found, page);
+        let response = NetworkSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
             .build()
             .to_response();
         Ok(response)
     }
+
+    async fn search_autnums_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = PartialSearch::parse(handle)?;
+        let maps = self.maps.read().await;
+        let autnums = autnums(&maps);
+        let found = autnums.iter().copied().filter(|a| {
+            a.object_common
+                .handle
+                .as_ref()
+                .is_some_and(|handle| search.is_match(handle))
+        });
+        let (results, paging_metadata) = page_results(found, page);
+        let response = AutnumSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_autnums_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = PartialSearch::parse(name)?;
+        let maps = self.maps.read().await;
+        let autnums = autnums(&maps);
+        let found = autnums.iter().copied().filter(|a| {
+            a.name
+                .as_ref()
+                .is_some_and(|name| search.is_match_ignore_case(name))
+        });
+        let (results, paging_metadata) = page_results(found, page);
+        let response = AutnumSearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_networks_by_relation(
+        &self,
+        relation: RirSearchRelation,
+        cidr: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let net = IpNet::from_str(cidr)?.trunc();
+        let covering = matches!(relation, RirSearchRelation::Up | RirSearchRelation::Top);
+        // networks covering the query hold a prefix covering it, and networks
+        // under the query hold the prefixes under it.
+        let maps = self.maps.read().await;
+        let candidates = match net {
+            IpNet::V4(ipv4net) => {
+                let ip4s = &maps.ip4;
+                if covering {
+                    std::iter::successors(Some(ipv4net), Ipv4Net::supernet)
+                        .filter_map(|net| ip4s.get(&net))
+                        .filter_map(to_network)
+                        .collect::<Vec<&Network>>()
+                } else {
+                    ip4s.children(&ipv4net)
+                        .filter_map(|(_, network)| to_network(network))
+                        .collect::<Vec<&Network>>()
+                }
+            }
+            IpNet::V6(ipv6net) => {
+                let ip6s = &maps.ip6;
+                if covering {
+                    std::iter::successors(Some(ipv6net), Ipv6Net::supernet)
+                        .filter_map(|net| ip6s.get(&net))
+                        .filter_map(to_network)
+                        .collect::<Vec<&Network>>()
+                } else {
+                    ip6s.children(&ipv6net)
+                        .filter_map(|(_, network)| to_network(network))
+                        .collect::<Vec<&Network>>()
+                }
+            }
+        };
+        Ok(network_relation(relation, &net, candidates, page))
+    }
+
+    fn get_common_config(&self) -> &CommonConfig {
+        &self.config.common_config
+    }
+
+    async fn search_entities_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.entity_search_by_handle_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = PartialSearch::parse(handle)?;
+        let maps = self.maps.read().await;
+        let found = maps.indexed_entities(search.search_folded(&maps.entities_by_handle));
+        let (results, paging_metadata) = page_results(found, page);
+        let response = EntitySearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn search_entities_by_full_name(
+        &self,
+        full_name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.entity_search_by_full_name_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let search = PartialSearch::parse(full_name)?;
+        let maps = self.maps.read().await;
+        let found = maps.indexed_entities(search.search_folded(&maps.entities_by_full_name));
+        let (results, paging_metadata) = page_results(found, page);
+        let response = EntitySearchResults::builder()
+            .results(results)
+            .and_paging_metadata(paging_metadata)
+            .build()
+            .to_response();
+        Ok(response)
+    }
+
+    async fn get_object_counts(&self) -> Result<ObjectCounts, RdapServerError> {
+        let maps = self.maps.read().await;
+        // autnums and networks are held once per range or prefix they cover
+        let autnums = maps
+            .autnums
+            .iter()
+            .map(|(_, autnum)| Arc::as_ptr(autnum))
+            .collect::<HashSet<_>>()
+            .len();
+        let ip4_networks = maps
+            .ip4
+            .iter()
+            .map(|(_, network)| Arc::as_ptr(network))
+            .collect::<HashSet<_>>()
+            .len();
+        let ip6_networks = maps
+            .ip6
+            .iter()
+            .map(|(_, network)| Arc::as_ptr(network))
+            .collect::<HashSet<_>>()
+            .len();
+        Ok(ObjectCounts {
+            domains: maps.domains.len() as u64,
+            nameservers: maps.nameservers.len() as u64,
+            entities: maps.entities.len() as u64,
+            autnums: autnums as u64,
+            networks: (ip4_networks + ip6_networks) as u64,
+        })
+    }
+
+    async fn save_snapshot(&self, files: &DataFiles) -> Result<(), RdapServerError> {
+        let Some(snapshot_file) = self.config.snapshot_file.as_ref() else {
+            return Ok(());
+        };
+        snapshot::save(self, Path::new(snapshot_file), files).await
+    }
+
+    async fn restore_snapshot(&self) -> Result<Option<DataFiles>, RdapServerError> {
+        let Some(snapshot_file) = self.config.snapshot_file.as_ref() else {
+            return Ok(None);
+        };
+        snapshot::restore(self, Path::new(snapshot_file)).await
+    }
+}
+
+/// Gets the domains, ignoring any errors, from stored responses.
+fn domains<'a>(
+    responses: impl IntoIterator<Item = &'a Arc<RdapResponse>>,
+) -> impl Iterator<Item = &'a Domain> {
+    responses.into_iter().filter_map(|d| match d.as_ref() {
+        RdapResponse::Domain(d) => Some(d.as_ref()),
+        _ => None,
+    })
+}
+
+/// Gets the nameservers, ignoring any errors, from stored responses.
+fn nameservers<'a>(
+    responses: impl IntoIterator<Item = &'a Arc<RdapResponse>>,
+) -> impl Iterator<Item = &'a Nameserver> {
+    responses.into_iter().filter_map(|n| match n.as_ref() {
+        RdapResponse::Nameserver(n) => Some(n.as_ref()),
+        _ => None,
+    })
+}
+
+/// Gets the entities, ignoring any errors, from stored responses.
+fn entities<'a>(
+    responses: impl IntoIterator<Item = &'a Arc<RdapResponse>>,
+) -> impl Iterator<Item = &'a Entity> {
+    responses.into_iter().filter_map(|e| match e.as_ref() {
+        RdapResponse::Entity(e) => Some(e.as_ref()),
+        _ => None,
+    })
 }
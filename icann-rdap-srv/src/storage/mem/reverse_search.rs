@@ -0,0 +1,191 @@
+use std::collections::{BTreeSet, HashMap};
+
+use icann_rdap_common::{response::Entity, search_types::RelatedEntityProperty};
+
+use crate::{
+    error::RdapServerError,
+    storage::{
+        index::related_values,
+        search::{fold_case, PartialSearch},
+    },
+};
+
+/// An index of objects by the properties of their related entities, which is
+/// the in-memory counterpart of the `reverse_search` table of PostgreSQL.
+///
+/// Each property is indexed as `(value, object key, role)`, where the role is
+/// empty for entities without roles. Handles are indexed as given while full
+/// names and email addresses are indexed case folded.
+#[derive(Default)]
+pub(crate) struct ReverseIndex {
+    values: HashMap<RelatedEntityProperty, BTreeSet<(String, String, String)>>,
+}
+
+impl ReverseIndex {
+    /// Indexes an object by the values of its related entities.
+    pub(crate) fn insert(&mut self, key: &str, entities: &[Entity]) {
+        for (property, entry) in index_entries(key, entities) {
+            self.values.entry(property).or_default().insert(entry);
+        }
+    }
+
+    /// Removes an object indexed by the values of its related entities.
+    pub(crate) fn remove(&mut self, key: &str, entities: &[Entity]) {
+        for (property, entry) in index_entries(key, entities) {
+            if let Some(values) = self.values.get_mut(&property) {
+                values.remove(&entry);
+            }
+        }
+    }
+}
+
+/// Gets the entries an object is indexed with, by property.
+fn index_entries(
+    key: &str,
+    entities: &[Entity],
+) -> Vec<(RelatedEntityProperty, (String, String, String))> {
+    let mut entries = vec![];
+    for related in related_values(entities) {
+        let value = index_value(related.property, &related.value);
+        let roles = if related.roles.is_empty() {
+            vec![String::new()]
+        } else {
+            related.roles
+        };
+        for role in roles {
+            entries.push((related.property, (value.clone(), key.to_owned(), role)));
+        }
+    }
+    entries
+}
+
+/// Gets the value a property is indexed and searched by.
+fn index_value(property: RelatedEntityProperty, value: &str) -> String {
+    match property {
+        RelatedEntityProperty::Handle => value.to_owned(),
+        _ => fold_case(value),
+    }
+}
+
+/// A reverse search for a property of related entities.
+///
+/// Handles are matched exactly as given while full names and email addresses
+/// are matched without regard to case. Both may be partial string searches.
+pub(crate) struct ReverseSearchMatch<'a> {
+    property: RelatedEntityProperty,
+    search: PartialSearch<'a>,
+    role: Option<String>,
+}
+
+impl<'a> ReverseSearchMatch<'a> {
+    /// Validates and parses a reverse search.
+    pub(crate) fn parse(
+        property: RelatedEntityProperty,
+        value: &'a str,
+        role: Option<&str>,
+    ) -> Result<Self, RdapServerError> {
+        Ok(Self {
+            property,
+            search: PartialSearch::parse(value)?,
+            role: role.map(|role| role.to_lowercase()),
+        })
+    }
+
+    /// Finds the keys of the objects in an index matching this search.
+    pub(crate) fn find_keys<'i>(&self, index: &'i ReverseIndex) -> BTreeSet<&'i String> {
+        let Some(values) = index.values.get(&self.property) else {
+            return BTreeSet::new();
+        };
+        let (text, exact) = match self.search {
+            PartialSearch::Exact(s) => (index_value(self.property, s), true),
+            PartialSearch::Prefix(s) => (index_value(self.property, s), false),
+        };
+        values
+            .range((text.clone(), String::new(), String::new())..)
+            .take_while(|(value, _, _)| {
+                if exact {
+                    *value == text
+                } else {
+                    value.starts_with(&text)
+                }
+            })
+            .filter(|(_, _, role)| self.role.as_ref().is_none_or(|r| r == role))
+            .map(|(_, key, _)| key)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        icann_rdap_common::{
+            contact::{Contact, Email},
+            response::Entity,
+            search_types::RelatedEntityProperty,
+        },
+        rstest::rstest,
+    };
+
+    use super::{ReverseIndex, ReverseSearchMatch};
+
+    fn entities() -> Vec<Entity> {
+        vec![
+            Entity::builder()
+                .handle("REG-1")
+                .contact(
+                    Contact::builder()
+                        .full_name("Bob Smurd")
+                        .emails(vec![Email::builder().email("bob@example.com").build()])
+                        .build(),
+                )
+                .role("registrant")
+                .build(),
+            Entity::builder().handle("TECH-1").role("technical").build(),
+        ]
+    }
+
+    #[rstest]
+    #[case(RelatedEntityProperty::Handle, "REG-1", None, true)]
+    #[case(RelatedEntityProperty::Handle, "reg-1", None, false)]
+    #[case(RelatedEntityProperty::Handle, "TECH*", Some("technical"), true)]
+    #[case(RelatedEntityProperty::Handle, "TECH*", Some("registrant"), false)]
+    #[case(RelatedEntityProperty::FullName, "bob sm*", None, true)]
+    #[case(RelatedEntityProperty::FullName, "Bob Smurd", Some("Registrant"), true)]
+    #[case(RelatedEntityProperty::FullName, "Alice*", None, false)]
+    #[case(RelatedEntityProperty::Email, "BOB@example.com", None, true)]
+    fn GIVEN_indexed_object_WHEN_find_keys_THEN_found_if_matched(
+        #[case] property: RelatedEntityProperty,
+        #[case] value: &str,
+        #[case] role: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        // GIVEN
+        let mut index = ReverseIndex::default();
+        index.insert("foo.example", &entities());
+        let search = ReverseSearchMatch::parse(property, value, role).expect("invalid search");
+
+        // WHEN
+        let actual = search.find_keys(&index);
+
+        // THEN
+        assert_eq!(!actual.is_empty(), expected);
+    }
+
+    #[test]
+    fn GIVEN_removed_object_WHEN_find_keys_THEN_not_found() {
+        // GIVEN
+        let mut index = ReverseIndex::default();
+        index.insert("foo.example", &entities());
+        index.insert("bar.example", &entities());
+
+        // WHEN
+        index.remove("foo.example", &entities());
+
+        // THEN
+        let search = ReverseSearchMatch::parse(RelatedEntityProperty::Handle, "REG-1", None)
+            .expect("invalid search");
+        let actual = search.find_keys(&index);
+        assert_eq!(actual.into_iter().collect::<Vec<_>>(), ["bar.example"]);
+    }
+}
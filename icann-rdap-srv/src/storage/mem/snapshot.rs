@@ -0,0 +1,253 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs::File,
+    io::{BufReader, ErrorKind, Write},
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use {
+    btree_range_map::AnyRange,
+    icann_rdap_common::response::RdapResponse,
+    ipnet::{Ipv4Net, Ipv6Net},
+    serde::{Deserialize, Serialize},
+};
+
+use crate::{
+    error::RdapServerError,
+    storage::{data::DataFiles, CommonConfig, TxHandle},
+};
+
+use super::{label_search::SearchLabels, maps::MemMaps, ops::Mem, tx::MemTx};
+
+/// The version of the snapshot format. Snapshots of other versions are not restored.
+const SNAPSHOT_VERSION: u32 = 2;
+
+/// The maps of in-memory storage as restored from a snapshot, keyed as they are in storage.
+///
+/// The label searches, which are slow to build, are restored with the objects. They
+/// are none if the search was not enabled when the snapshot was saved. The other
+/// indexes of storage are rebuilt from the objects.
+#[derive(Default)]
+pub(crate) struct StoredMaps {
+    pub(crate) autnums: Vec<(u32, u32, Arc<RdapResponse>)>,
+    pub(crate) ip4: Vec<(Ipv4Net, Arc<RdapResponse>)>,
+    pub(crate) ip6: Vec<(Ipv6Net, Arc<RdapResponse>)>,
+    pub(crate) domains: Vec<(String, Arc<RdapResponse>)>,
+    pub(crate) idns: Vec<(String, Arc<RdapResponse>)>,
+    pub(crate) nameservers: Vec<(String, Arc<RdapResponse>)>,
+    pub(crate) entities: Vec<(String, Arc<RdapResponse>)>,
+    pub(crate) srvhelps: Vec<(String, Arc<RdapResponse>)>,
+    pub(crate) domains_by_name: Option<SearchLabels<String>>,
+    pub(crate) domains_by_ns_name: Option<SearchLabels<String>>,
+    pub(crate) nameservers_by_name: Option<SearchLabels<String>>,
+}
+
+/// A snapshot as written to its file. Each object is written once, however many
+/// keys it is stored under, and the keys refer to it by its index.
+#[derive(Serialize, Deserialize)]
+struct Snapshot<O, L> {
+    version: u32,
+    files: DataFiles,
+    objects: Vec<O>,
+    autnums: Vec<(u32, u32, usize)>,
+    ip4: Vec<(Ipv4Net, usize)>,
+    ip6: Vec<(Ipv6Net, usize)>,
+    domains: Vec<(String, usize)>,
+    idns: Vec<(String, usize)>,
+    nameservers: Vec<(String, usize)>,
+    entities: Vec<(String, usize)>,
+    srvhelps: Vec<(String, usize)>,
+    domains_by_name: Option<L>,
+    domains_by_ns_name: Option<L>,
+    nameservers_by_name: Option<L>,
+}
+
+/// Saves a snapshot of memory to a file.
+///
+/// Transactions wait while the snapshot is encoded, but searches and lookups do not.
+/// The snapshot is written to a temporary file that then replaces the file, so
+/// a partially written snapshot is never restored.
+pub(crate) async fn save(mem: &Mem, path: &Path, files: &DataFiles) -> Result<(), RdapServerError> {
+    // taking the writer lock first keeps transactions from queuing for the maps while
+    // they are read, which would hold up the searches and lookups behind them.
+    let writer = mem.writer.clone().lock_owned().await;
+    let maps = mem.maps.clone().read_owned().await;
+    let common_config = mem.config.common_config;
+    let files = files.clone();
+    let path = path.to_owned();
+    tokio::task::spawn_blocking(move || {
+        let mut encoded = vec![];
+        encode_snapshot(&mut encoded, &maps, common_config, files)?;
+        drop(maps);
+        drop(writer);
+        let temp_path = temp_path(&path);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&encoded)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| RdapServerError::IO(std::io::Error::other(e)))?
+}
+
+/// Restores memory from a snapshot file, returning the data files it holds the
+/// data of. Returns none if there is no snapshot file.
+pub(crate) async fn restore(mem: &Mem, path: &Path) -> Result<Option<DataFiles>, RdapServerError> {
+    let path = path.to_owned();
+    let snapshot = tokio::task::spawn_blocking(move || match File::open(&path) {
+        Ok(file) => read_snapshot(BufReader::new(file)).map(Some),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    })
+    .await
+    .map_err(|e| RdapServerError::IO(std::io::Error::other(e)))??;
+    let Some((files, maps)) = snapshot else {
+        return Ok(None);
+    };
+    let tx = MemTx::new_restore(mem, maps).await;
+    Box::new(tx).commit().await?;
+    Ok(Some(files))
+}
+
+/// Gets the first and last numbers of a range of autnums.
+fn inclusive(range: &AnyRange<u32>) -> Option<(u32, u32)> {
+    let start = match range.start {
+        Bound::Included(start) => start,
+        Bound::Excluded(start) => start.checked_add(1)?,
+        Bound::Unbounded => u32::MIN,
+    };
+    let end = match range.end {
+        Bound::Included(end) => end,
+        Bound::Excluded(end) => end.checked_sub(1)?,
+        Bound::Unbounded => u32::MAX,
+    };
+    Some((start, end))
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = OsString::from(path.as_os_str());
+    temp_path.push(".tmp");
+    PathBuf::from(temp_path)
+}
+
+/// Indexes the objects of a snapshot being written.
+#[derive(Default)]
+struct Objects<'a> {
+    indexes: HashMap<*const RdapResponse, usize>,
+    objects: Vec<&'a RdapResponse>,
+}
+
+impl<'a> Objects<'a> {
+    fn index(&mut self, object: &'a Arc<RdapResponse>) -> usize {
+        *self.indexes.entry(Arc::as_ptr(object)).or_insert_with(|| {
+            self.objects.push(object);
+            self.objects.len() - 1
+        })
+    }
+
+    fn keys<K: Clone + 'a>(
+        &mut self,
+        entries: impl Iterator<Item = (&'a K, &'a Arc<RdapResponse>)>,
+    ) -> Vec<(K, usize)> {
+        entries
+            .map(|(key, object)| (key.clone(), self.index(object)))
+            .collect()
+    }
+}
+
+fn encode_snapshot(
+    writer: &mut impl Write,
+    maps: &MemMaps,
+    common_config: CommonConfig,
+    files: DataFiles,
+) -> Result<(), RdapServerError> {
+    let mut objects = Objects::default();
+    let autnums = maps
+        .autnums
+        .iter()
+        .filter_map(|(range, autnum)| {
+            let (start, end) = inclusive(range)?;
+            Some((start, end, objects.index(autnum)))
+        })
+        .collect();
+    let ip4 = objects.keys(maps.ip4.iter());
+    let ip6 = objects.keys(maps.ip6.iter());
+    let domains = objects.keys(maps.domains.iter());
+    let idns = objects.keys(maps.idns.iter());
+    let nameservers = objects.keys(maps.nameservers.iter());
+    let entities = objects.keys(maps.entities.iter());
+    let srvhelps = objects.keys(maps.srvhelps.iter());
+    let snapshot = Snapshot {
+        version: SNAPSHOT_VERSION,
+        files,
+        objects: objects.objects,
+        autnums,
+        ip4,
+        ip6,
+        domains,
+        idns,
+        nameservers,
+        entities,
+        srvhelps,
+        domains_by_name: common_config
+            .domain_search_by_name_enable
+            .then_some(&maps.domains_by_name),
+        domains_by_ns_name: common_config
+            .domain_search_by_ns_ldh_name_enable
+            .then_some(&maps.domains_by_ns_name),
+        nameservers_by_name: common_config
+            .nameserver_search_by_name_enable
+            .then_some(&maps.nameservers_by_name),
+    };
+    rmp_serde::encode::write_named(writer, &snapshot)?;
+    Ok(())
+}
+
+fn read_snapshot(reader: impl std::io::Read) -> Result<(DataFiles, StoredMaps), RdapServerError> {
+    let snapshot: Snapshot<RdapResponse, SearchLabels<String>> = rmp_serde::from_read(reader)?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(RdapServerError::SnapshotVersion(snapshot.version));
+    }
+    let objects: Vec<Arc<RdapResponse>> = snapshot.objects.into_iter().map(Arc::new).collect();
+    let maps = StoredMaps {
+        autnums: snapshot
+            .autnums
+            .into_iter()
+            .map(|(start, end, index)| Ok((start, end, object(&objects, index)?)))
+            .collect::<Result<_, RdapServerError>>()?,
+        ip4: keyed(snapshot.ip4, &objects)?,
+        ip6: keyed(snapshot.ip6, &objects)?,
+        domains: keyed(snapshot.domains, &objects)?,
+        idns: keyed(snapshot.idns, &objects)?,
+        nameservers: keyed(snapshot.nameservers, &objects)?,
+        entities: keyed(snapshot.entities, &objects)?,
+        srvhelps: keyed(snapshot.srvhelps, &objects)?,
+        domains_by_name: snapshot.domains_by_name,
+        domains_by_ns_name: snapshot.domains_by_ns_name,
+        nameservers_by_name: snapshot.nameservers_by_name,
+    };
+    Ok((snapshot.files, maps))
+}
+
+fn object(
+    objects: &[Arc<RdapResponse>],
+    index: usize,
+) -> Result<Arc<RdapResponse>, RdapServerError> {
+    objects
+        .get(index)
+        .cloned()
+        .ok_or_else(|| RdapServerError::EmptyIndexData(format!("snapshot object {index}")))
+}
+
+fn keyed<K>(
+    keys: Vec<(K, usize)>,
+    objects: &[Arc<RdapResponse>],
+) -> Result<Vec<(K, Arc<RdapResponse>)>, RdapServerError> {
+    keys.into_iter()
+        .map(|(key, index)| Ok((key, object(objects, index)?)))
+        .collect()
+}
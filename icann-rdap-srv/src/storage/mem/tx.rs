@@ -1,81 +1,99 @@
-use std::{collections::HashMap, net::IpAddr, str::FromStr, sync::Arc};
+use std::sync::Arc;
 
 use {
     async_trait::async_trait,
-    btree_range_map::RangeMap,
     icann_rdap_common::{
         prelude::ToResponse,
-        response::{Autnum, Domain, Entity, Help, Nameserver, Network, RdapResponse, Rfc9083Error},
+        response::{Autnum, Domain, Entity, Help, Nameserver, Network, Rfc9083Error},
     },
-    ipnet::{IpSubnets, Ipv4Net, Ipv4Subnets, Ipv6Net, Ipv6Subnets},
-    prefix_trie::PrefixMap,
+    ipnet::{IpNet, IpSubnets, Ipv4Subnets, Ipv6Subnets},
+    tokio::sync::OwnedMutexGuard,
 };
 
 use crate::{
     error::RdapServerError,
     storage::{
         data::{AutnumId, DomainId, EntityId, NameserverId, NetworkId},
+        index::{ldh_name_key, network_subnets, unicode_name_key},
         TxHandle,
     },
 };
 
-use super::{label_search::SearchLabels, ops::Mem};
+use super::{
+    maps::{Change, MemMaps},
+    ops::Mem,
+    snapshot::StoredMaps,
+};
 
+/// A transaction of in-memory storage.
+///
+/// Transactions are made one at a time, each holding the writer lock of
+/// storage until it is committed or rolled back. Changes are kept until the
+/// transaction is committed and then made in place to the maps of storage,
+/// unless storage is truncated, in which case the transaction builds new maps
+/// that replace those of storage.
 pub struct MemTx {
     mem: Mem,
-    autnums: RangeMap<u32, Arc<RdapResponse>>,
-    ip4: PrefixMap<Ipv4Net, Arc<RdapResponse>>,
-    ip6: PrefixMap<Ipv6Net, Arc<RdapResponse>>,
-    domains: HashMap<String, Arc<RdapResponse>>,
-    domains_by_name: SearchLabels<Arc<RdapResponse>>,
-    idns: HashMap<String, Arc<RdapResponse>>,
-    nameservers: HashMap<String, Arc<RdapResponse>>,
-    entities: HashMap<String, Arc<RdapResponse>>,
-    srvhelps: HashMap<String, Arc<RdapResponse>>,
+    _writer: OwnedMutexGuard<()>,
+    staged: Staged,
+}
+
+/// The changes of a transaction that are yet to be committed.
+enum Staged {
+    /// The changes to make to the maps of storage.
+    Changes(Vec<Change>),
+    /// The maps replacing those of storage.
+    Maps(Box<MemMaps>),
 }
 
 impl MemTx {
     pub async fn new(mem: &Mem) -> Self {
-        let domains = Arc::clone(&mem.domains).read_owned().await.clone();
-        let mut domains_by_name = SearchLabels::builder().build();
-
-        // only do load up domain search labels if search by domain names is supported
-        if mem.config.common_config.domain_search_by_name_enable {
-            for (name, value) in domains.iter() {
-                domains_by_name.insert(name, value.clone());
-            }
+        Self {
+            mem: mem.clone(),
+            _writer: mem.writer.clone().lock_owned().await,
+            staged: Staged::Changes(vec![]),
         }
+    }
 
+    pub async fn new_truncate(mem: &Mem) -> Self {
         Self {
             mem: mem.clone(),
-            autnums: Arc::clone(&mem.autnums).read_owned().await.clone(),
-            ip4: Arc::clone(&mem.ip4).read_owned().await.clone(),
-            ip6: Arc::clone(&mem.ip6).read_owned().await.clone(),
-            domains,
-            domains_by_name,
-            idns: Arc::clone(&mem.idns).read_owned().await.clone(),
-            nameservers: Arc::clone(&mem.nameservers).read_owned().await.clone(),
-            entities: Arc::clone(&mem.entities).read_owned().await.clone(),
-            srvhelps: Arc::clone(&mem.srvhelps).read_owned().await.clone(),
+            _writer: mem.writer.clone().lock_owned().await,
+            staged: Staged::Maps(Box::new(MemMaps::new(mem.config.common_config))),
         }
     }
 
-    pub fn new_truncate(mem: &Mem) -> Self {
+    /// Creates a transaction replacing the maps of storage with those of a snapshot.
+    pub(crate) async fn new_restore(mem: &Mem, stored: StoredMaps) -> Self {
         Self {
             mem: mem.clone(),
-            autnums: RangeMap::new(),
-            ip4: PrefixMap::new(),
-            ip6: PrefixMap::new(),
-            domains: HashMap::new(),
-            domains_by_name: SearchLabels::builder().build(),
-            idns: HashMap::new(),
-            nameservers: HashMap::new(),
-            entities: HashMap::new(),
-            srvhelps: HashMap::new(),
+            _writer: mem.writer.clone().lock_owned().await,
+            staged: Staged::Maps(Box::new(MemMaps::restore(mem.config.common_config, stored))),
+        }
+    }
+
+    /// Stages a change, returning false if it is a deletion that finds nothing
+    /// to delete.
+    async fn stage(&mut self, change: Change) -> bool {
+        match &mut self.staged {
+            Staged::Changes(changes) => {
+                let found = change.finds(changes, &*self.mem.maps.read().await);
+                changes.push(change);
+                found
+            }
+            Staged::Maps(maps) => maps.apply(change),
         }
     }
 }
 
+/// Gets the CIDRs of IP subnets.
+fn nets(subnets: IpSubnets) -> Vec<IpNet> {
+    match subnets {
+        IpSubnets::V4(subnets) => subnets.map(IpNet::V4).collect(),
+        IpSubnets::V6(subnets) => subnets.map(IpNet::V6).collect(),
+    }
+}
+
 #[async_trait]
 impl TxHandle for MemTx {
     async fn add_entity(&mut self, entity: &Entity) -> Result<(), RdapServerError> {
@@ -84,8 +102,11 @@ impl TxHandle for MemTx {
             .handle
             .as_ref()
             .ok_or_else(|| RdapServerError::EmptyIndexData("handle".to_string()))?;
-        self.entities
-            .insert(handle.to_owned(), Arc::new(entity.clone().to_response()));
+        self.stage(Change::AddEntity(
+            handle.to_owned(),
+            Arc::new(entity.clone().to_response()),
+        ))
+        .await;
         Ok(())
     }
 
@@ -94,34 +115,26 @@ impl TxHandle for MemTx {
         entity_id: &EntityId,
         error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        self.entities.insert(
+        self.stage(Change::AddEntity(
             entity_id.handle.to_owned(),
             Arc::new(error.clone().to_response()),
-        );
+        ))
+        .await;
         Ok(())
     }
 
     async fn add_domain(&mut self, domain: &Domain) -> Result<(), RdapServerError> {
-        let domain_response = Arc::new(domain.clone().to_response());
-
         // add the domain as LDH, which is required.
         let ldh_name = domain
             .ldh_name
-            .as_ref()
+            .as_deref()
+            .map(ldh_name_key)
             .ok_or_else(|| RdapServerError::EmptyIndexData("ldhName".to_string()))?;
-        self.domains
-            .insert(ldh_name.to_owned(), domain_response.clone());
-
-        // add the domain by unicodeName
-        if let Some(unicode_name) = domain.unicode_name.as_ref() {
-            self.idns
-                .insert(unicode_name.to_owned(), domain_response.clone());
-        };
-
-        if self.mem.config.common_config.domain_search_by_name_enable {
-            self.domains_by_name.insert(ldh_name, domain_response);
-        }
-
+        self.stage(Change::AddDomain(
+            ldh_name,
+            Arc::new(domain.clone().to_response()),
+        ))
+        .await;
         Ok(())
     }
 
@@ -130,22 +143,25 @@ impl TxHandle for MemTx {
         domain_id: &DomainId,
         error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        self.domains.insert(
-            domain_id.ldh_name.to_owned(),
+        self.stage(Change::AddDomain(
+            ldh_name_key(&domain_id.ldh_name),
             Arc::new(error.clone().to_response()),
-        );
+        ))
+        .await;
         Ok(())
     }
 
     async fn add_nameserver(&mut self, nameserver: &Nameserver) -> Result<(), RdapServerError> {
         let ldh_name = nameserver
             .ldh_name
-            .as_ref()
+            .as_deref()
+            .map(ldh_name_key)
             .ok_or_else(|| RdapServerError::EmptyIndexData("ldhName".to_string()))?;
-        self.nameservers.insert(
-            ldh_name.to_owned(),
+        self.stage(Change::AddNameserver(
+            ldh_name,
             Arc::new(nameserver.clone().to_response()),
-        );
+        ))
+        .await;
         Ok(())
     }
 
@@ -154,10 +170,11 @@ impl TxHandle for MemTx {
         nameserver_id: &NameserverId,
         error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        self.nameservers.insert(
-            nameserver_id.ldh_name.to_owned(),
+        self.stage(Change::AddNameserver(
+            ldh_name_key(&nameserver_id.ldh_name),
             Arc::new(error.clone().to_response()),
-        );
+        ))
+        .await;
         Ok(())
     }
 
@@ -172,10 +189,12 @@ impl TxHandle for MemTx {
             .as_ref()
             .and_then(|n| n.as_u32())
             .ok_or_else(|| RdapServerError::EmptyIndexData("endNum".to_string()))?;
-        self.autnums.insert(
-            (start_num)..=(end_num),
+        self.stage(Change::AddAutnum(
+            start_num,
+            end_num,
             Arc::new(autnum.clone().to_response()),
-        );
+        ))
+        .await;
         Ok(())
     }
 
@@ -184,10 +203,12 @@ impl TxHandle for MemTx {
         autnum_id: &AutnumId,
         error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        self.autnums.insert(
-            (autnum_id.start_autnum)..=(autnum_id.end_autnum),
+        self.stage(Change::AddAutnum(
+            autnum_id.start_autnum,
+            autnum_id.end_autnum,
             Arc::new(error.clone().to_response()),
-        );
+        ))
+        .await;
         Ok(())
     }
 
@@ -205,19 +226,16 @@ impl TxHandle for MemTx {
             .as_ref()
             .ok_or_else(|| RdapServerError::EmptyIndexData("ipVersion".to_string()))?;
         let is_v4 = ip_type.eq_ignore_ascii_case("v4");
-        if is_v4 {
-            let subnets = Ipv4Subnets::new(start_addr.parse()?, end_addr.parse()?, 0);
-            for net in subnets {
-                self.ip4
-                    .insert(net, Arc::new(network.clone().to_response()));
-            }
+        let subnets = if is_v4 {
+            IpSubnets::from(Ipv4Subnets::new(start_addr.parse()?, end_addr.parse()?, 0))
         } else {
-            let subnets = Ipv6Subnets::new(start_addr.parse()?, end_addr.parse()?, 0);
-            for net in subnets {
-                self.ip6
-                    .insert(net, Arc::new(network.clone().to_response()));
-            }
+            IpSubnets::from(Ipv6Subnets::new(start_addr.parse()?, end_addr.parse()?, 0))
         };
+        self.stage(Change::AddNetwork(
+            nets(subnets),
+            Arc::new(network.clone().to_response()),
+        ))
+        .await;
         Ok(())
     }
 
@@ -226,49 +244,12 @@ impl TxHandle for MemTx {
         network_id: &NetworkId,
         error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        let subnets = match &network_id.network_id {
-            crate::storage::data::NetworkIdType::Cidr(cidr) => cidr.subnets(cidr.prefix_len())?,
-            crate::storage::data::NetworkIdType::Range {
-                start_address,
-                end_address,
-            } => {
-                let start_addr = IpAddr::from_str(start_address)?;
-                let end_addr = IpAddr::from_str(end_address)?;
-                if start_addr.is_ipv4() && end_addr.is_ipv4() {
-                    let IpAddr::V4(start_addr) = start_addr else {
-                        panic!("check failed")
-                    };
-                    let IpAddr::V4(end_addr) = end_addr else {
-                        panic!("check failed")
-                    };
-                    IpSubnets::from(Ipv4Subnets::new(start_addr, end_addr, 0))
-                } else if start_addr.is_ipv6() && end_addr.is_ipv6() {
-                    let IpAddr::V6(start_addr) = start_addr else {
-                        panic!("check failed")
-                    };
-                    let IpAddr::V6(end_addr) = end_addr else {
-                        panic!("check failed")
-                    };
-                    IpSubnets::from(Ipv6Subnets::new(start_addr, end_addr, 0))
-                } else {
-                    return Err(RdapServerError::EmptyIndexData(
-                        "mismatch ip version".to_string(),
-                    ));
-                }
-            }
-        };
-        match subnets {
-            IpSubnets::V4(subnets) => {
-                for net in subnets {
-                    self.ip4.insert(net, Arc::new(error.clone().to_response()));
-                }
-            }
-            IpSubnets::V6(subnets) => {
-                for net in subnets {
-                    self.ip6.insert(net, Arc::new(error.clone().to_response()));
-                }
-            }
-        }
+        let subnets = network_subnets(network_id)?;
+        self.stage(Change::AddNetwork(
+            nets(subnets),
+            Arc::new(error.clone().to_response()),
+        ))
+        .await;
         Ok(())
     }
 
@@ -278,48 +259,72 @@ impl TxHandle for MemTx {
         host: Option<&str>,
     ) -> Result<(), RdapServerError> {
         let host = host.unwrap_or("..default");
-        self.srvhelps
-            .insert(host.to_string(), Arc::new(help.clone().to_response()));
+        self.stage(Change::AddSrvHelp(
+            host.to_string(),
+            Arc::new(help.clone().to_response()),
+        ))
+        .await;
         Ok(())
     }
 
-    async fn commit(mut self: Box<Self>) -> Result<(), RdapServerError> {
-        // autnums
-        let mut autnum_g = self.mem.autnums.write().await;
-        std::mem::swap(&mut self.autnums, &mut autnum_g);
-
-        // ip4
-        let mut ip4_g = self.mem.ip4.write().await;
-        std::mem::swap(&mut self.ip4, &mut ip4_g);
-
-        // ip6
-        let mut ip6_g = self.mem.ip6.write().await;
-        std::mem::swap(&mut self.ip6, &mut ip6_g);
-
-        // domains
-        let mut domains_g = self.mem.domains.write().await;
-        std::mem::swap(&mut self.domains, &mut domains_g);
-
-        //domains by name
-        let mut domains_by_name_g = self.mem.domains_by_name.write().await;
-        std::mem::swap(&mut self.domains_by_name, &mut domains_by_name_g);
+    async fn delete_domain(&mut self, domain_id: &DomainId) -> Result<bool, RdapServerError> {
+        let domain_id = DomainId {
+            ldh_name: ldh_name_key(&domain_id.ldh_name),
+            unicode_name: domain_id.unicode_name.as_deref().map(unicode_name_key),
+        };
+        Ok(self.stage(Change::DeleteDomain(domain_id)).await)
+    }
 
-        //idns
-        let mut idns_g = self.mem.idns.write().await;
-        std::mem::swap(&mut self.idns, &mut idns_g);
+    async fn delete_entity(&mut self, entity_id: &EntityId) -> Result<bool, RdapServerError> {
+        Ok(self
+            .stage(Change::DeleteEntity(entity_id.handle.to_owned()))
+            .await)
+    }
 
-        // nameservers
-        let mut nameservers_g = self.mem.nameservers.write().await;
-        std::mem::swap(&mut self.nameservers, &mut nameservers_g);
+    async fn delete_nameserver(
+        &mut self,
+        nameserver_id: &NameserverId,
+    ) -> Result<bool, RdapServerError> {
+        Ok(self
+            .stage(Change::DeleteNameserver(ldh_name_key(
+                &nameserver_id.ldh_name,
+            )))
+            .await)
+    }
 
-        // entities
-        let mut entities_g = self.mem.entities.write().await;
-        std::mem::swap(&mut self.entities, &mut entities_g);
+    async fn delete_autnum(&mut self, autnum_id: &AutnumId) -> Result<bool, RdapServerError> {
+        Ok(self.stage(Change::DeleteAutnum(autnum_id.clone())).await)
+    }
 
-        //srvhelps
-        let mut srvhelps_g = self.mem.srvhelps.write().await;
-        std::mem::swap(&mut self.srvhelps, &mut srvhelps_g);
+    async fn delete_network(&mut self, network_id: &NetworkId) -> Result<bool, RdapServerError> {
+        let subnets = network_subnets(network_id)?;
+        Ok(self.stage(Change::DeleteNetwork(nets(subnets))).await)
+    }
 
+    async fn commit(self: Box<Self>) -> Result<(), RdapServerError> {
+        match self.staged {
+            Staged::Changes(changes) => {
+                let mut maps = self.mem.maps.write().await;
+                for change in changes {
+                    maps.apply(change);
+                }
+                if maps.has_stale_search_labels() {
+                    // the label searches are rebuilt while readers go on, as the
+                    // writer lock of the transaction keeps out other changes.
+                    let maps = maps.downgrade();
+                    let fresh = maps.fresh_search_labels();
+                    drop(maps);
+                    self.mem.maps.write().await.replace_search_labels(fresh);
+                }
+            }
+            Staged::Maps(mut maps) => {
+                if maps.has_stale_search_labels() {
+                    let fresh = maps.fresh_search_labels();
+                    maps.replace_search_labels(fresh);
+                }
+                *self.mem.maps.write().await = *maps;
+            }
+        }
         Ok(())
     }
 
@@ -1,18 +1,23 @@
+use std::net::IpAddr;
+
 use {
     async_trait::async_trait,
-    buildstructor::Builder,
-    icann_rdap_common::response::{
-        Autnum, Domain, Entity, Help, Nameserver, Network, RdapResponse, Rfc9083Error,
+    icann_rdap_common::{
+        response::{Autnum, Domain, Entity, Help, Nameserver, Network, RdapResponse, Rfc9083Error},
+        search_types::{RelatedEntityProperty, RirSearchRelation},
     },
 };
 
 use crate::error::RdapServerError;
 
-use self::data::{AutnumId, DomainId, EntityId, NameserverId, NetworkId};
+use self::data::{AutnumId, DataFiles, DomainId, EntityId, NameserverId, NetworkId};
 
 pub mod data;
+pub(crate) mod index;
 pub mod mem;
 pub mod pg;
+pub(crate) mod rir_search;
+pub(crate) mod search;
 
 pub type DynStoreOps = dyn StoreOps + Send + Sync;
 
@@ -34,6 +39,10 @@ pub trait StoreOps: Send + Sync {
     /// Get a domain from storage using the 'unicodeName' as the key.
     async fn get_domain_by_unicode(&self, unicode: &str) -> Result<RdapResponse, RdapServerError>;
 
+    /// Get the domain having a variant name from storage, using either the 'ldhName'
+    /// or the 'unicodeName' of the variant name as the key.
+    async fn get_domain_by_variant(&self, variant: &str) -> Result<RdapResponse, RdapServerError>;
+
     /// Get an entity from storage using the 'handle' of the entity as the key.
     async fn get_entity_by_handle(&self, handle: &str) -> Result<RdapResponse, RdapServerError>;
 
@@ -56,7 +65,172 @@ pub trait StoreOps: Send + Sync {
     async fn get_srv_help(&self, host: Option<&str>) -> Result<RdapResponse, RdapServerError>;
 
     /// Search for domains by name.
-    async fn search_domains_by_name(&self, name: &str) -> Result<RdapResponse, RdapServerError>;
+    async fn search_domains_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for domains by the name of their nameservers.
+    async fn search_domains_by_ns_ldh_name(
+        &self,
+        ns_ldh_name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for domains by the IP address of their nameservers.
+    async fn search_domains_by_ns_ip(
+        &self,
+        ip: IpAddr,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for nameservers by name.
+    async fn search_nameservers_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for nameservers by IP address.
+    async fn search_nameservers_by_ip(
+        &self,
+        ip: IpAddr,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for domains by the properties of their related entities (RFC 9536).
+    async fn reverse_search_domains(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for nameservers by the properties of their related entities (RFC 9536).
+    async fn reverse_search_nameservers(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for entities by the properties of their related entities (RFC 9536).
+    async fn reverse_search_entities(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for IP networks by handle (draft-ietf-regext-rdap-rir-search).
+    async fn search_networks_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for IP networks by name (draft-ietf-regext-rdap-rir-search).
+    async fn search_networks_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for autnums by handle (draft-ietf-regext-rdap-rir-search).
+    async fn search_autnums_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for autnums by name (draft-ietf-regext-rdap-rir-search).
+    async fn search_autnums_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for the IP networks having a relation to the network given in CIDR
+    /// notation (draft-ietf-regext-rdap-rir-search). The `rdap-up` and `rdap-top`
+    /// relations give a network, and `rdap-down` and `rdap-bottom` give network
+    /// search results.
+    async fn search_networks_by_relation(
+        &self,
+        relation: RirSearchRelation,
+        cidr: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Gets the configuration common to all storage back ends.
+    fn get_common_config(&self) -> &CommonConfig;
+
+    /// Search for entities by handle, ignoring case.
+    async fn search_entities_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Search for entities by the full name ('fn') of their vCard, ignoring case.
+    async fn search_entities_by_full_name(
+        &self,
+        full_name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError>;
+
+    /// Counts the objects in storage.
+    async fn get_object_counts(&self) -> Result<ObjectCounts, RdapServerError>;
+
+    /// Saves a snapshot of storage holding the data of the given data files.
+    /// Storage that persists on its own, or has no snapshot configured, does nothing.
+    async fn save_snapshot(&self, files: &DataFiles) -> Result<(), RdapServerError>;
+
+    /// Restores storage from its snapshot, returning the data files it holds the data of.
+    /// Returns none if there is no snapshot to restore.
+    async fn restore_snapshot(&self) -> Result<Option<DataFiles>, RdapServerError>;
+}
+
+/// The page of search results to get from storage.
+///
+/// Storage gives search results in the order of their default sort, and only
+/// builds those of the page. Paged search results have paging metadata with the
+/// count of all the results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchPage {
+    /// The number of results before the page.
+    pub offset: usize,
+
+    /// The most results in the page, if limited.
+    pub limit: Option<usize>,
+}
+
+impl SearchPage {
+    /// All the search results, without paging metadata.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// The page of results starting at the offset.
+    pub fn new(offset: usize, limit: usize) -> Self {
+        Self {
+            offset,
+            limit: Some(limit),
+        }
+    }
+}
+
+/// The number of objects of each type in storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectCounts {
+    pub domains: u64,
+    pub nameservers: u64,
+    pub entities: u64,
+    pub autnums: u64,
+    pub networks: u64,
 }
 
 /// Represents a handle to a transaction.
@@ -120,6 +294,30 @@ pub trait TxHandle: Send {
         host: Option<&str>,
     ) -> Result<(), RdapServerError>;
 
+    /// Delete a domain, or an error added as a domain, from storage.
+    /// Returns false if there was nothing to delete.
+    async fn delete_domain(&mut self, domain_id: &DomainId) -> Result<bool, RdapServerError>;
+
+    /// Delete an entity, or an error added as an entity, from storage.
+    /// Returns false if there was nothing to delete.
+    async fn delete_entity(&mut self, entity_id: &EntityId) -> Result<bool, RdapServerError>;
+
+    /// Delete a nameserver, or an error added as a nameserver, from storage.
+    /// Returns false if there was nothing to delete.
+    async fn delete_nameserver(
+        &mut self,
+        nameserver_id: &NameserverId,
+    ) -> Result<bool, RdapServerError>;
+
+    /// Delete an autnum, or an error added as an autnum, from storage.
+    /// Returns false if there was nothing to delete.
+    async fn delete_autnum(&mut self, autnum_id: &AutnumId) -> Result<bool, RdapServerError>;
+
+    /// Delete a network, or an error added as a network, from storage. Networks
+    /// more specific than the one deleted are kept. Returns false if there was
+    /// nothing to delete.
+    async fn delete_network(&mut self, network_id: &NetworkId) -> Result<bool, RdapServerError>;
+
     /// Commit the transaction.
     async fn commit(self: Box<Self>) -> Result<(), RdapServerError>;
 
@@ -128,15 +326,59 @@ pub trait TxHandle: Send {
 }
 
 /// Common configuration for storage back ends.
-#[derive(Debug, Clone, Copy, Builder)]
+#[derive(Debug, Clone, Copy)]
 pub struct CommonConfig {
     pub domain_search_by_name_enable: bool,
+    pub domain_search_by_ns_ldh_name_enable: bool,
+    pub domain_search_by_ns_ip_enable: bool,
+    pub nameserver_search_by_name_enable: bool,
+    pub nameserver_search_by_ip_enable: bool,
+    pub entity_search_by_handle_enable: bool,
+    pub entity_search_by_full_name_enable: bool,
+    pub reverse_search_enable: bool,
+    pub rir_search_enable: bool,
+    pub search_page_size: usize,
+}
+
+/// The number of search results in a page when it is not configured.
+pub const DEFAULT_SEARCH_PAGE_SIZE: usize = 100;
+
+#[buildstructor::buildstructor]
+impl CommonConfig {
+    /// Builds the common configuration. Searches that are not specified are enabled
+    /// and pages of search results default to [DEFAULT_SEARCH_PAGE_SIZE].
+    #[builder(visibility = "pub")]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        domain_search_by_name_enable: Option<bool>,
+        domain_search_by_ns_ldh_name_enable: Option<bool>,
+        domain_search_by_ns_ip_enable: Option<bool>,
+        nameserver_search_by_name_enable: Option<bool>,
+        nameserver_search_by_ip_enable: Option<bool>,
+        entity_search_by_handle_enable: Option<bool>,
+        entity_search_by_full_name_enable: Option<bool>,
+        reverse_search_enable: Option<bool>,
+        rir_search_enable: Option<bool>,
+        search_page_size: Option<usize>,
+    ) -> Self {
+        Self {
+            domain_search_by_name_enable: domain_search_by_name_enable.unwrap_or(true),
+            domain_search_by_ns_ldh_name_enable: domain_search_by_ns_ldh_name_enable
+                .unwrap_or(true),
+            domain_search_by_ns_ip_enable: domain_search_by_ns_ip_enable.unwrap_or(true),
+            nameserver_search_by_name_enable: nameserver_search_by_name_enable.unwrap_or(true),
+            nameserver_search_by_ip_enable: nameserver_search_by_ip_enable.unwrap_or(true),
+            entity_search_by_handle_enable: entity_search_by_handle_enable.unwrap_or(true),
+            entity_search_by_full_name_enable: entity_search_by_full_name_enable.unwrap_or(true),
+            reverse_search_enable: reverse_search_enable.unwrap_or(true),
+            rir_search_enable: rir_search_enable.unwrap_or(true),
+            search_page_size: search_page_size.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE),
+        }
+    }
 }
 
 impl Default for CommonConfig {
     fn default() -> Self {
-        CommonConfig {
-            domain_search_by_name_enable: true,
-        }
+        CommonConfig::builder().build()
     }
 }
@@ -1,14 +1,30 @@
-#![allow(clippy::diverging_sub_expression)]
+use std::{net::IpAddr, str::FromStr};
+
 use {
     async_trait::async_trait,
-    icann_rdap_common::response::RdapResponse,
-    sqlx::{query, PgPool},
+    icann_rdap_common::{
+        prelude::ToResponse,
+        response::{
+            AutnumSearchResults, DomainSearchResults, EntitySearchResults, NameserverSearchResults,
+            Network, NetworkSearchResults, PagingMetadata, RdapResponse,
+        },
+        search_types::{RelatedEntityProperty, RirSearchRelation},
+    },
+    ipnet::IpNet,
+    serde_json::Value,
+    sqlx::{query, query_as, query_scalar, PgPool},
     tracing::{debug, info},
 };
 
 use crate::{
     error::RdapServerError,
-    storage::{StoreOps, TxHandle},
+    rdap::response::{NOT_FOUND, NOT_IMPLEMENTED},
+    storage::{
+        data::DataFiles,
+        rir_search::{distinct_networks, network_relation},
+        search::{split_label_search, PartialSearch},
+        CommonConfig, ObjectCounts, SearchPage, StoreOps, TxHandle,
+    },
 };
 
 use super::{config::PgConfig, tx::PgTx};
@@ -16,12 +32,55 @@ use super::{config::PgConfig, tx::PgTx};
 #[derive(Clone)]
 pub struct Pg {
     pg_pool: PgPool,
+    config: PgConfig,
 }
 
 impl Pg {
     pub async fn new(config: PgConfig) -> Result<Self, RdapServerError> {
         let pg_pool = PgPool::connect(&config.db_url).await?;
-        Ok(Self { pg_pool })
+        Ok(Self { pg_pool, config })
+    }
+
+    /// Finds the keys of the objects of a searchable type by the properties of
+    /// their related entities.
+    ///
+    /// Handles are matched with case while full names and emails are not.
+    async fn reverse_search_keys(
+        &self,
+        searchable_type: &str,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+    ) -> Result<Vec<String>, RdapServerError> {
+        let pattern = partial_search_pattern(value)?;
+        let sql = match property {
+            RelatedEntityProperty::Handle => {
+                "select distinct object_key from reverse_search \
+                 where searchable_type = $1 and property = $2 and value like $3 \
+                 and ($4::text is null or role = $4)"
+            }
+            _ => {
+                "select distinct object_key from reverse_search \
+                 where searchable_type = $1 and property = $2 and lower(value) like lower($3) \
+                 and ($4::text is null or role = $4)"
+            }
+        };
+        let keys = query_scalar(sql)
+            .bind(searchable_type)
+            .bind(property.to_string())
+            .bind(pattern)
+            .bind(role.map(|role| role.to_lowercase()))
+            .fetch_all(&self.pg_pool)
+            .await?;
+        Ok(keys)
+    }
+
+    /// Fetches the RDAP JSON from an optional row and converts it into an [RdapResponse].
+    fn to_rdap(rdap: Option<Value>) -> Result<RdapResponse, RdapServerError> {
+        match rdap {
+            Some(rdap) => Ok(RdapResponse::try_from(rdap)?),
+            None => Ok(NOT_FOUND.clone()),
+        }
     }
 }
 
@@ -32,6 +91,9 @@ impl StoreOps for Pg {
         let mut conn = self.pg_pool.acquire().await?;
         query("select 1").fetch_one(&mut *conn).await?;
         info!("Database connection test is successful.");
+        debug!("Applying database schema migrations.");
+        sqlx::migrate!("./migrations").run(&self.pg_pool).await?;
+        info!("Database schema is current.");
         Ok(())
     }
 
@@ -43,36 +105,640 @@ impl StoreOps for Pg {
         Ok(Box::new(PgTx::new_truncate(&self.pg_pool).await?))
     }
 
-    async fn get_domain_by_ldh(&self, _ldh: &str) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+    async fn get_domain_by_ldh(&self, ldh: &str) -> Result<RdapResponse, RdapServerError> {
+        let rdap = query_scalar("select rdap from domain where ldh_name = $1")
+            .bind(ldh)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_domain_by_unicode(&self, unicode: &str) -> Result<RdapResponse, RdapServerError> {
+        let rdap = query_scalar("select rdap from domain where unicode_name = $1 limit 1")
+            .bind(unicode)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_domain_by_variant(&self, variant: &str) -> Result<RdapResponse, RdapServerError> {
+        let rdap = query_scalar(
+            "select d.rdap from domain d \
+             join domain_variant v on v.ldh_name = d.ldh_name \
+             where v.variant_name = $1 limit 1",
+        )
+        .bind(variant)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_entity_by_handle(&self, handle: &str) -> Result<RdapResponse, RdapServerError> {
+        let rdap = query_scalar("select rdap from entity where handle = $1")
+            .bind(handle)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_nameserver_by_ldh(&self, ldh: &str) -> Result<RdapResponse, RdapServerError> {
+        let rdap = query_scalar("select rdap from nameserver where ldh_name = $1")
+            .bind(ldh)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_autnum_by_num(&self, num: u32) -> Result<RdapResponse, RdapServerError> {
+        // the most specific (smallest) range containing the number wins.
+        let rdap = query_scalar(
+            "select rdap from autnum \
+             where start_autnum <= $1 and end_autnum >= $1 \
+             order by end_autnum - start_autnum asc limit 1",
+        )
+        .bind(i64::from(num))
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_network_by_ipaddr(&self, ipaddr: &str) -> Result<RdapResponse, RdapServerError> {
+        let addr = ipaddr.parse::<IpAddr>()?;
+        let rdap = query_scalar(
+            "select rdap from network \
+             where cidr >>= $1::inet \
+             order by masklen(cidr) desc limit 1",
+        )
+        .bind(addr.to_string())
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_network_by_cidr(&self, cidr: &str) -> Result<RdapResponse, RdapServerError> {
+        let net = IpNet::from_str(cidr)?.trunc();
+        let rdap = query_scalar(
+            "select rdap from network \
+             where cidr >>= $1::cidr \
+             order by masklen(cidr) desc limit 1",
+        )
+        .bind(net.to_string())
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn get_srv_help(&self, host: Option<&str>) -> Result<RdapResponse, RdapServerError> {
+        let host = host.unwrap_or("..default");
+        let rdap = query_scalar("select rdap from srvhelp where host = $1")
+            .bind(host)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+        Self::to_rdap(rdap)
+    }
+
+    async fn search_domains_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.domain_search_by_name_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            r#"select rdap, lower(ldh_name) collate "C" as sort_key from domain
+               where ldh_name like $1"#,
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(label_search_pattern(name)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        domain_search_results(rows, page)
+    }
+
+    async fn search_domains_by_ns_ldh_name(
+        &self,
+        ns_ldh_name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self
+            .config
+            .common_config
+            .domain_search_by_ns_ldh_name_enable
+        {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let pattern = if ns_ldh_name.contains('*') {
+            label_search_pattern(ns_ldh_name)?
+        } else {
+            escape_like(ns_ldh_name)
+        };
+        let sql = paged_sql(
+            r#"select d.rdap, lower(d.ldh_name) collate "C" as sort_key from domain d
+               where d.ldh_name in
+               (select n.ldh_name from domain_nameserver n where n.ns_ldh_name like $1)"#,
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(pattern)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        domain_search_results(rows, page)
+    }
+
+    async fn search_domains_by_ns_ip(
+        &self,
+        ip: IpAddr,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.domain_search_by_ns_ip_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            r#"select d.rdap, lower(d.ldh_name) collate "C" as sort_key from domain d
+               join domain_nameserver_ip i on i.ldh_name = d.ldh_name
+               where i.ip = $1::inet"#,
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(ip.to_string())
+            .fetch_all(&self.pg_pool)
+            .await?;
+        domain_search_results(rows, page)
+    }
+
+    async fn search_nameservers_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.nameserver_search_by_name_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            r#"select rdap, lower(ldh_name) collate "C" as sort_key from nameserver
+               where ldh_name like $1"#,
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(label_search_pattern(name)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        nameserver_search_results(rows, page)
+    }
+
+    async fn search_nameservers_by_ip(
+        &self,
+        ip: IpAddr,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.nameserver_search_by_ip_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            r#"select n.rdap, lower(n.ldh_name) collate "C" as sort_key from nameserver n
+               join nameserver_ip i on i.ldh_name = n.ldh_name
+               where i.ip = $1::inet"#,
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(ip.to_string())
+            .fetch_all(&self.pg_pool)
+            .await?;
+        nameserver_search_results(rows, page)
+    }
+
+    async fn reverse_search_domains(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.reverse_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let keys = self
+            .reverse_search_keys("domains", property, value, role)
+            .await?;
+        let sql = paged_sql(
+            r#"select rdap, lower(ldh_name) collate "C" as sort_key from domain
+               where ldh_name = any($1)"#,
+            page,
+        );
+        let rows = query_as(&sql).bind(keys).fetch_all(&self.pg_pool).await?;
+        domain_search_results(rows, page)
+    }
+
+    async fn reverse_search_nameservers(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.reverse_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let keys = self
+            .reverse_search_keys("nameservers", property, value, role)
+            .await?;
+        let sql = paged_sql(
+            r#"select rdap, lower(ldh_name) collate "C" as sort_key from nameserver
+               where ldh_name = any($1)"#,
+            page,
+        );
+        let rows = query_as(&sql).bind(keys).fetch_all(&self.pg_pool).await?;
+        nameserver_search_results(rows, page)
+    }
+
+    async fn reverse_search_entities(
+        &self,
+        property: RelatedEntityProperty,
+        value: &str,
+        role: Option<&str>,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.reverse_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let keys = self
+            .reverse_search_keys("entities", property, value, role)
+            .await?;
+        let sql = paged_sql(
+            r#"select rdap, handle collate "C" as sort_key from entity where handle = any($1)"#,
+            page,
+        );
+        let rows = query_as(&sql).bind(keys).fetch_all(&self.pg_pool).await?;
+        entity_search_results(rows, page)
+    }
+
+    async fn search_networks_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            "select rdap, min(cidr) as sort_key from network \
+             where rdap ->> 'handle' like $1 group by rdap",
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(partial_search_pattern(handle)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        network_search_results(rows, page)
+    }
+
+    async fn search_networks_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            "select rdap, min(cidr) as sort_key from network \
+             where lower(rdap ->> 'name') like lower($1) group by rdap",
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(partial_search_pattern(name)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        network_search_results(rows, page)
     }
 
-    async fn get_domain_by_unicode(&self, _unicode: &str) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+    async fn search_autnums_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            "select rdap, start_autnum as sort_key from autnum where rdap ->> 'handle' like $1",
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(partial_search_pattern(handle)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        autnum_search_results(rows, page)
     }
 
-    async fn get_entity_by_handle(&self, _handle: &str) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+    async fn search_autnums_by_name(
+        &self,
+        name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            "select rdap, start_autnum as sort_key from autnum \
+             where lower(rdap ->> 'name') like lower($1)",
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(partial_search_pattern(name)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        autnum_search_results(rows, page)
     }
 
-    async fn get_nameserver_by_ldh(&self, _ldh: &str) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+    async fn search_networks_by_relation(
+        &self,
+        relation: RirSearchRelation,
+        cidr: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.rir_search_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let net = IpNet::from_str(cidr)?.trunc();
+        let sql = match relation {
+            RirSearchRelation::Up | RirSearchRelation::Top => {
+                "select distinct rdap from network where cidr >>= $1::cidr"
+            }
+            RirSearchRelation::Down | RirSearchRelation::Bottom => {
+                "select distinct rdap from network where cidr <<= $1::cidr"
+            }
+        };
+        let rows: Vec<Value> = query_scalar(sql)
+            .bind(net.to_string())
+            .fetch_all(&self.pg_pool)
+            .await?;
+        Ok(network_relation(relation, &net, networks(rows)?, page))
     }
 
-    async fn get_autnum_by_num(&self, _num: u32) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+    fn get_common_config(&self) -> &CommonConfig {
+        &self.config.common_config
     }
 
-    async fn get_network_by_ipaddr(&self, _ipaddr: &str) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+    async fn search_entities_by_handle(
+        &self,
+        handle: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.entity_search_by_handle_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            r#"select rdap, handle collate "C" as sort_key from entity
+               where lower(handle) like lower($1)"#,
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(partial_search_pattern(handle)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        entity_search_results(rows, page)
     }
-    async fn get_network_by_cidr(&self, _cidr: &str) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+
+    async fn search_entities_by_full_name(
+        &self,
+        full_name: &str,
+        page: &SearchPage,
+    ) -> Result<RdapResponse, RdapServerError> {
+        if !self.config.common_config.entity_search_by_full_name_enable {
+            return Ok(NOT_IMPLEMENTED.clone());
+        }
+        //else
+        let sql = paged_sql(
+            r#"select rdap, handle collate "C" as sort_key from entity
+               where lower(full_name) like lower($1)"#,
+            page,
+        );
+        let rows = query_as(&sql)
+            .bind(partial_search_pattern(full_name)?)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        entity_search_results(rows, page)
+    }
+
+    async fn get_object_counts(&self) -> Result<ObjectCounts, RdapServerError> {
+        // networks are held once per CIDR they cover
+        let (domains, nameservers, entities, autnums, networks): (i64, i64, i64, i64, i64) =
+            query_as(
+                "select (select count(*) from domain), \
+                 (select count(*) from nameserver), \
+                 (select count(*) from entity), \
+                 (select count(*) from autnum), \
+                 (select count(distinct rdap) from network)",
+            )
+            .fetch_one(&self.pg_pool)
+            .await?;
+        Ok(ObjectCounts {
+            domains: domains as u64,
+            nameservers: nameservers as u64,
+            entities: entities as u64,
+            autnums: autnums as u64,
+            networks: networks as u64,
+        })
     }
-    async fn get_srv_help(&self, _host: Option<&str>) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+
+    async fn save_snapshot(&self, _files: &DataFiles) -> Result<(), RdapServerError> {
+        // the database persists on its own.
+        Ok(())
     }
-    async fn search_domains_by_name(&self, _name: &str) -> Result<RdapResponse, RdapServerError> {
-        todo!()
+
+    async fn restore_snapshot(&self) -> Result<Option<DataFiles>, RdapServerError> {
+        Ok(None)
+    }
+}
+
+/// Creates entity search results from the RDAP JSON of the matching rows.
+fn entity_search_results(
+    rows: Vec<(Value, i64)>,
+    page: &SearchPage,
+) -> Result<RdapResponse, RdapServerError> {
+    let paging_metadata = paging_metadata(&rows, page);
+    let mut results = vec![];
+    for (rdap, _) in rows {
+        if let RdapResponse::Entity(entity) = RdapResponse::try_from(rdap)? {
+            results.push(*entity);
+        }
+    }
+    let response = EntitySearchResults::builder()
+        .results(results)
+        .and_paging_metadata(paging_metadata)
+        .build()
+        .to_response();
+    Ok(response)
+}
+
+/// Creates domain search results from the RDAP JSON of the matching rows.
+fn domain_search_results(
+    rows: Vec<(Value, i64)>,
+    page: &SearchPage,
+) -> Result<RdapResponse, RdapServerError> {
+    let paging_metadata = paging_metadata(&rows, page);
+    let mut results = vec![];
+    for (rdap, _) in rows {
+        if let RdapResponse::Domain(domain) = RdapResponse::try_from(rdap)? {
+            results.push(*domain);
+        }
+    }
+    let response = DomainSearchResults::builder()
+        .results(results)
+        .and_paging_metadata(paging_metadata)
+        .build()
+        .to_response();
+    Ok(response)
+}
+
+/// Creates nameserver search results from the RDAP JSON of the matching rows.
+fn nameserver_search_results(
+    rows: Vec<(Value, i64)>,
+    page: &SearchPage,
+) -> Result<RdapResponse, RdapServerError> {
+    let paging_metadata = paging_metadata(&rows, page);
+    let mut results = vec![];
+    for (rdap, _) in rows {
+        if let RdapResponse::Nameserver(nameserver) = RdapResponse::try_from(rdap)? {
+            results.push(*nameserver);
+        }
+    }
+    let response = NameserverSearchResults::builder()
+        .results(results)
+        .and_paging_metadata(paging_metadata)
+        .build()
+        .to_response();
+    Ok(response)
+}
+
+/// Gets the networks of the RDAP JSON of the matching rows.
+fn networks(rows: Vec<Value>) -> Result<Vec<Network>, RdapServerError> {
+    let mut networks = vec![];
+    for rdap in rows {
+        if let RdapResponse::Network(network) = RdapResponse::try_from(rdap)? {
+            networks.push(*network);
+        }
+    }
+    Ok(networks)
+}
+
+/// Creates IP network search results from the RDAP JSON of the matching rows.
+fn network_search_results(
+    rows: Vec<(Value, i64)>,
+    page: &SearchPage,
+) -> Result<RdapResponse, RdapServerError> {
+    let paging_metadata = paging_metadata(&rows, page);
+    let rows = rows.into_iter().map(|(rdap, _)| rdap).collect();
+    let response = NetworkSearchResults::builder()
+        .results(distinct_networks(networks(rows)?))
+        .and_paging_metadata(paging_metadata)
+        .build()
+        .to_response();
+    Ok(response)
+}
+
+/// Creates autnum search results from the RDAP JSON of the matching rows.
+fn autnum_search_results(
+    rows: Vec<(Value, i64)>,
+    page: &SearchPage,
+) -> Result<RdapResponse, RdapServerError> {
+    let paging_metadata = paging_metadata(&rows, page);
+    let mut results = vec![];
+    for (rdap, _) in rows {
+        if let RdapResponse::Autnum(autnum) = RdapResponse::try_from(rdap)? {
+            results.push(*autnum);
+        }
+    }
+    let response = AutnumSearchResults::builder()
+        .results(results)
+        .and_paging_metadata(paging_metadata)
+        .build()
+        .to_response();
+    Ok(response)
+}
+
+/// Selects a page of the `rdap` of the rows of a query, in the order of their
+/// `sort_key`, along with the count of all the rows. Rows of errors, such as
+/// redirects, are not search results and so are neither paged nor counted.
+fn paged_sql(sql: &str, page: &SearchPage) -> String {
+    let limit = page
+        .limit
+        .map_or_else(|| "all".to_string(), |limit| limit.to_string());
+    format!(
+        "select rdap, count(*) over () from ({sql}) q \
+         where rdap->>'objectClassName' is not null \
+         order by sort_key offset {} limit {limit}",
+        page.offset
+    )
+}
+
+/// Gets the paging metadata of a page of rows selected with [paged_sql]. A
+/// page past the last row has no rows to give the count.
+fn paging_metadata(rows: &[(Value, i64)], page: &SearchPage) -> Option<PagingMetadata> {
+    page.limit.map(|_| {
+        let total_count = rows.first().map_or(0, |(_, count)| *count);
+        PagingMetadata::builder()
+            .total_count(total_count as u64)
+            .build()
+    })
+}
+
+/// Converts an RFC 9082 label search into a SQL `like` pattern.
+fn label_search_pattern(search: &str) -> Result<String, RdapServerError> {
+    let (prefix, suffix) = split_label_search(search)?;
+    if suffix.is_empty() {
+        Ok(format!("{}%", escape_like(prefix)))
+    } else {
+        Ok(format!("{}%.{}", escape_like(prefix), escape_like(suffix)))
+    }
+}
+
+/// Converts an RFC 9082 partial string search into a SQL `like` pattern.
+fn partial_search_pattern(search: &str) -> Result<String, RdapServerError> {
+    match PartialSearch::parse(search)? {
+        PartialSearch::Exact(s) => Ok(escape_like(s)),
+        PartialSearch::Prefix(s) => Ok(format!("{}%", escape_like(s))),
+    }
+}
+
+/// Escapes the special characters of a SQL `like` pattern.
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::escape_like;
+
+    #[test]
+    fn GIVEN_like_special_chars_WHEN_escape_like_THEN_chars_are_escaped() {
+        // GIVEN
+        let s = r"foo_bar%baz\";
+
+        // WHEN
+        let actual = escape_like(s);
+
+        // THEN
+        assert_eq!(actual, r"foo\_bar\%baz\\");
     }
 }
@@ -1,14 +1,23 @@
-#![allow(clippy::diverging_sub_expression)]
+use std::net::IpAddr;
+
 use {
     async_trait::async_trait,
-    icann_rdap_common::response::{Autnum, Domain, Entity, Nameserver, Network, Rfc9083Error},
-    sqlx::{PgPool, Postgres},
+    icann_rdap_common::response::{
+        Autnum, Domain, Entity, Help, Nameserver, Network, ObjectCommonFields, Rfc9083Error,
+    },
+    ipnet::{IpSubnets, Ipv4Subnets, Ipv6Subnets},
+    serde_json::Value,
+    sqlx::{query, PgPool, Postgres},
 };
 
 use crate::{
     error::RdapServerError,
     storage::{
         data::{AutnumId, DomainId, EntityId, NameserverId, NetworkId},
+        index::{
+            domain_variant_names, ldh_name_key, nameserver_ips, network_subnets, related_values,
+            unicode_name_key,
+        },
         TxHandle,
     },
 };
@@ -25,86 +34,445 @@ impl<'a> PgTx<'a> {
 
     pub async fn new_truncate(pg_pool: &PgPool) -> Result<Self, RdapServerError> {
         let mut db_tx = pg_pool.begin().await?;
-        // TODO actually complete this
-        // this is just here to make sure something will compile
-        sqlx::query("truncate domain").execute(&mut *db_tx).await?;
+        query(
+            "truncate domain, domain_nameserver, domain_nameserver_ip, domain_variant, entity, \
+             nameserver, nameserver_ip, autnum, network, srvhelp, reverse_search",
+        )
+        .execute(&mut *db_tx)
+        .await?;
         Ok(Self { db_tx })
     }
+
+    /// Replaces the reverse search rows of an object with the values of its related entities.
+    async fn upsert_reverse_search(
+        &mut self,
+        searchable_type: &str,
+        object_key: &str,
+        entities: &[Entity],
+    ) -> Result<(), RdapServerError> {
+        query("delete from reverse_search where searchable_type = $1 and object_key = $2")
+            .bind(searchable_type)
+            .bind(object_key)
+            .execute(&mut *self.db_tx)
+            .await?;
+        for related in related_values(entities) {
+            // an entity without roles is still found when no role is given.
+            let roles = if related.roles.is_empty() {
+                vec![String::default()]
+            } else {
+                related.roles
+            };
+            for role in roles {
+                query(
+                    "insert into reverse_search \
+                     (searchable_type, object_key, property, value, role) \
+                     values ($1, $2, $3, $4, $5) on conflict do nothing",
+                )
+                .bind(searchable_type)
+                .bind(object_key)
+                .bind(related.property.to_string())
+                .bind(&related.value)
+                .bind(role)
+                .execute(&mut *self.db_tx)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn upsert_domain(
+        &mut self,
+        ldh_name: &str,
+        unicode_name: Option<&str>,
+        nameservers: &[Nameserver],
+        variant_names: &[String],
+        rdap: Value,
+    ) -> Result<(), RdapServerError> {
+        query(
+            "insert into domain (ldh_name, unicode_name, rdap) values ($1, $2, $3) \
+             on conflict (ldh_name) do update \
+             set unicode_name = excluded.unicode_name, rdap = excluded.rdap",
+        )
+        .bind(ldh_name)
+        .bind(unicode_name)
+        .bind(rdap)
+        .execute(&mut *self.db_tx)
+        .await?;
+        query("delete from domain_nameserver where ldh_name = $1")
+            .bind(ldh_name)
+            .execute(&mut *self.db_tx)
+            .await?;
+        query("delete from domain_nameserver_ip where ldh_name = $1")
+            .bind(ldh_name)
+            .execute(&mut *self.db_tx)
+            .await?;
+        for nameserver in nameservers {
+            if let Some(ns_ldh_name) = nameserver.ldh_name.as_ref() {
+                query(
+                    "insert into domain_nameserver (ldh_name, ns_ldh_name) values ($1, $2) \
+                     on conflict do nothing",
+                )
+                .bind(ldh_name)
+                .bind(ns_ldh_name)
+                .execute(&mut *self.db_tx)
+                .await?;
+            }
+            for ip in nameserver_ips(nameserver) {
+                query(
+                    "insert into domain_nameserver_ip (ldh_name, ip) values ($1, $2::inet) \
+                     on conflict do nothing",
+                )
+                .bind(ldh_name)
+                .bind(ip.to_string())
+                .execute(&mut *self.db_tx)
+                .await?;
+            }
+        }
+        query("delete from domain_variant where ldh_name = $1")
+            .bind(ldh_name)
+            .execute(&mut *self.db_tx)
+            .await?;
+        for variant_name in variant_names {
+            query(
+                "insert into domain_variant (ldh_name, variant_name) values ($1, $2) \
+                 on conflict do nothing",
+            )
+            .bind(ldh_name)
+            .bind(variant_name)
+            .execute(&mut *self.db_tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn upsert_entity(
+        &mut self,
+        handle: &str,
+        full_name: Option<&str>,
+        rdap: Value,
+    ) -> Result<(), RdapServerError> {
+        query(
+            "insert into entity (handle, full_name, rdap) values ($1, $2, $3) \
+             on conflict (handle) do update \
+             set full_name = excluded.full_name, rdap = excluded.rdap",
+        )
+        .bind(handle)
+        .bind(full_name)
+        .bind(rdap)
+        .execute(&mut *self.db_tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn upsert_nameserver(
+        &mut self,
+        ldh_name: &str,
+        ips: &[IpAddr],
+        rdap: Value,
+    ) -> Result<(), RdapServerError> {
+        query(
+            "insert into nameserver (ldh_name, rdap) values ($1, $2) \
+             on conflict (ldh_name) do update set rdap = excluded.rdap",
+        )
+        .bind(ldh_name)
+        .bind(rdap)
+        .execute(&mut *self.db_tx)
+        .await?;
+        query("delete from nameserver_ip where ldh_name = $1")
+            .bind(ldh_name)
+            .execute(&mut *self.db_tx)
+            .await?;
+        for ip in ips {
+            query(
+                "insert into nameserver_ip (ldh_name, ip) values ($1, $2::inet) \
+                 on conflict do nothing",
+            )
+            .bind(ldh_name)
+            .bind(ip.to_string())
+            .execute(&mut *self.db_tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn upsert_autnum(
+        &mut self,
+        start_autnum: u32,
+        end_autnum: u32,
+        rdap: Value,
+    ) -> Result<(), RdapServerError> {
+        query(
+            "insert into autnum (start_autnum, end_autnum, rdap) values ($1, $2, $3) \
+             on conflict (start_autnum, end_autnum) do update set rdap = excluded.rdap",
+        )
+        .bind(i64::from(start_autnum))
+        .bind(i64::from(end_autnum))
+        .bind(rdap)
+        .execute(&mut *self.db_tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Networks are stored as the set of CIDRs covering their range, the same
+    /// as the in-memory prefix maps, so that a longest-prefix match finds them.
+    async fn upsert_network(
+        &mut self,
+        subnets: IpSubnets,
+        rdap: Value,
+    ) -> Result<(), RdapServerError> {
+        for cidr in cidrs(subnets) {
+            query(
+                "insert into network (cidr, rdap) values ($1::cidr, $2) \
+                 on conflict (cidr) do update set rdap = excluded.rdap",
+            )
+            .bind(cidr)
+            .bind(&rdap)
+            .execute(&mut *self.db_tx)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+fn cidrs(subnets: IpSubnets) -> Vec<String> {
+    match subnets {
+        IpSubnets::V4(subnets) => subnets.map(|net| net.to_string()).collect(),
+        IpSubnets::V6(subnets) => subnets.map(|net| net.to_string()).collect(),
+    }
 }
 
 #[async_trait]
 impl TxHandle for PgTx<'_> {
-    async fn add_entity(&mut self, _entity: &Entity) -> Result<(), RdapServerError> {
-        todo!()
+    async fn add_entity(&mut self, entity: &Entity) -> Result<(), RdapServerError> {
+        let handle = entity
+            .object_common
+            .handle
+            .as_ref()
+            .ok_or_else(|| RdapServerError::EmptyIndexData("handle".to_string()))?;
+        let full_name = entity.contact().and_then(|contact| contact.full_name);
+        self.upsert_entity(handle, full_name.as_deref(), serde_json::to_value(entity)?)
+            .await?;
+        self.upsert_reverse_search("entities", handle, entity.entities())
+            .await
     }
 
     async fn add_entity_err(
         &mut self,
-        _entity_id: &EntityId,
-        _error: &Rfc9083Error,
+        entity_id: &EntityId,
+        error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        todo!()
+        self.upsert_entity(&entity_id.handle, None, serde_json::to_value(error)?)
+            .await?;
+        self.upsert_reverse_search("entities", &entity_id.handle, &[])
+            .await
     }
 
-    async fn add_domain(&mut self, _domain: &Domain) -> Result<(), RdapServerError> {
-        // TODO actually complete this
-        // this is just here to make sure something will compile
-        sqlx::query("insert domain")
-            .execute(&mut *self.db_tx)
-            .await?;
-        Ok(())
+    async fn add_domain(&mut self, domain: &Domain) -> Result<(), RdapServerError> {
+        let ldh_name = domain
+            .ldh_name
+            .as_deref()
+            .map(ldh_name_key)
+            .ok_or_else(|| RdapServerError::EmptyIndexData("ldhName".to_string()))?;
+        self.upsert_domain(
+            &ldh_name,
+            domain
+                .unicode_name
+                .as_deref()
+                .map(unicode_name_key)
+                .as_deref(),
+            domain.nameservers.as_deref().unwrap_or_default(),
+            &domain_variant_names(domain),
+            serde_json::to_value(domain)?,
+        )
+        .await?;
+        self.upsert_reverse_search("domains", &ldh_name, domain.entities())
+            .await
     }
 
     async fn add_domain_err(
         &mut self,
-        _domain_id: &DomainId,
-        _error: &Rfc9083Error,
+        domain_id: &DomainId,
+        error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        todo!()
+        let ldh_name = ldh_name_key(&domain_id.ldh_name);
+        self.upsert_domain(
+            &ldh_name,
+            domain_id
+                .unicode_name
+                .as_deref()
+                .map(unicode_name_key)
+                .as_deref(),
+            &[],
+            &[],
+            serde_json::to_value(error)?,
+        )
+        .await?;
+        self.upsert_reverse_search("domains", &ldh_name, &[]).await
     }
 
-    async fn add_nameserver(&mut self, _nameserver: &Nameserver) -> Result<(), RdapServerError> {
-        todo!()
+    async fn add_nameserver(&mut self, nameserver: &Nameserver) -> Result<(), RdapServerError> {
+        let ldh_name = nameserver
+            .ldh_name
+            .as_deref()
+            .map(ldh_name_key)
+            .ok_or_else(|| RdapServerError::EmptyIndexData("ldhName".to_string()))?;
+        let ips = nameserver_ips(nameserver);
+        self.upsert_nameserver(&ldh_name, &ips, serde_json::to_value(nameserver)?)
+            .await?;
+        self.upsert_reverse_search("nameservers", &ldh_name, nameserver.entities())
+            .await
     }
 
     async fn add_nameserver_err(
         &mut self,
-        _nameserver_id: &NameserverId,
-        _error: &Rfc9083Error,
+        nameserver_id: &NameserverId,
+        error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        todo!()
+        let ldh_name = ldh_name_key(&nameserver_id.ldh_name);
+        self.upsert_nameserver(&ldh_name, &[], serde_json::to_value(error)?)
+            .await?;
+        self.upsert_reverse_search("nameservers", &ldh_name, &[])
+            .await
     }
 
-    async fn add_autnum(&mut self, _autnum: &Autnum) -> Result<(), RdapServerError> {
-        todo!()
+    async fn add_autnum(&mut self, autnum: &Autnum) -> Result<(), RdapServerError> {
+        let start_num = autnum
+            .start_autnum
+            .as_ref()
+            .and_then(|n| n.as_u32())
+            .ok_or_else(|| RdapServerError::EmptyIndexData("startNum".to_string()))?;
+        let end_num = autnum
+            .end_autnum
+            .as_ref()
+            .and_then(|n| n.as_u32())
+            .ok_or_else(|| RdapServerError::EmptyIndexData("endNum".to_string()))?;
+        self.upsert_autnum(start_num, end_num, serde_json::to_value(autnum)?)
+            .await
     }
 
     async fn add_autnum_err(
         &mut self,
-        _autnum_id: &AutnumId,
-        _error: &Rfc9083Error,
+        autnum_id: &AutnumId,
+        error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        todo!()
+        self.upsert_autnum(
+            autnum_id.start_autnum,
+            autnum_id.end_autnum,
+            serde_json::to_value(error)?,
+        )
+        .await
     }
 
-    async fn add_network(&mut self, _network: &Network) -> Result<(), RdapServerError> {
-        todo!()
+    async fn add_network(&mut self, network: &Network) -> Result<(), RdapServerError> {
+        let start_addr = network
+            .start_address
+            .as_ref()
+            .ok_or_else(|| RdapServerError::EmptyIndexData("startAddress".to_string()))?;
+        let end_addr = network
+            .end_address
+            .as_ref()
+            .ok_or_else(|| RdapServerError::EmptyIndexData("endAddress".to_string()))?;
+        let ip_type = network
+            .ip_version
+            .as_ref()
+            .ok_or_else(|| RdapServerError::EmptyIndexData("ipVersion".to_string()))?;
+        let is_v4 = ip_type.eq_ignore_ascii_case("v4");
+        let subnets = if is_v4 {
+            IpSubnets::from(Ipv4Subnets::new(start_addr.parse()?, end_addr.parse()?, 0))
+        } else {
+            IpSubnets::from(Ipv6Subnets::new(start_addr.parse()?, end_addr.parse()?, 0))
+        };
+        self.upsert_network(subnets, serde_json::to_value(network)?)
+            .await
     }
 
     async fn add_network_err(
         &mut self,
-        _network_id: &NetworkId,
-        _error: &Rfc9083Error,
+        network_id: &NetworkId,
+        error: &Rfc9083Error,
     ) -> Result<(), RdapServerError> {
-        todo!()
+        let subnets = network_subnets(network_id)?;
+        self.upsert_network(subnets, serde_json::to_value(error)?)
+            .await
     }
 
     async fn add_srv_help(
         &mut self,
-        _help: &icann_rdap_common::response::Help,
-        _host: Option<&str>,
+        help: &Help,
+        host: Option<&str>,
     ) -> Result<(), RdapServerError> {
-        todo!()
+        let host = host.unwrap_or("..default");
+        query(
+            "insert into srvhelp (host, rdap) values ($1, $2) \
+             on conflict (host) do update set rdap = excluded.rdap",
+        )
+        .bind(host)
+        .bind(serde_json::to_value(help)?)
+        .execute(&mut *self.db_tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_domain(&mut self, domain_id: &DomainId) -> Result<bool, RdapServerError> {
+        // the nameserver search and variant rows of the domain are deleted by cascade.
+        let ldh_name = ldh_name_key(&domain_id.ldh_name);
+        let deleted = query("delete from domain where ldh_name = $1")
+            .bind(&ldh_name)
+            .execute(&mut *self.db_tx)
+            .await?
+            .rows_affected();
+        self.upsert_reverse_search("domains", &ldh_name, &[])
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    async fn delete_entity(&mut self, entity_id: &EntityId) -> Result<bool, RdapServerError> {
+        let deleted = query("delete from entity where handle = $1")
+            .bind(&entity_id.handle)
+            .execute(&mut *self.db_tx)
+            .await?
+            .rows_affected();
+        self.upsert_reverse_search("entities", &entity_id.handle, &[])
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    async fn delete_nameserver(
+        &mut self,
+        nameserver_id: &NameserverId,
+    ) -> Result<bool, RdapServerError> {
+        // the IP address search rows of the nameserver are deleted by cascade.
+        let ldh_name = ldh_name_key(&nameserver_id.ldh_name);
+        let deleted = query("delete from nameserver where ldh_name = $1")
+            .bind(&ldh_name)
+            .execute(&mut *self.db_tx)
+            .await?
+            .rows_affected();
+        self.upsert_reverse_search("nameservers", &ldh_name, &[])
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    async fn delete_autnum(&mut self, autnum_id: &AutnumId) -> Result<bool, RdapServerError> {
+        let deleted = query("delete from autnum where start_autnum = $1 and end_autnum = $2")
+            .bind(i64::from(autnum_id.start_autnum))
+            .bind(i64::from(autnum_id.end_autnum))
+            .execute(&mut *self.db_tx)
+            .await?
+            .rows_affected();
+        Ok(deleted > 0)
+    }
+
+    async fn delete_network(&mut self, network_id: &NetworkId) -> Result<bool, RdapServerError> {
+        let mut deleted = 0;
+        for cidr in cidrs(network_subnets(network_id)?) {
+            deleted += query("delete from network where cidr = $1::cidr")
+                .bind(cidr)
+                .execute(&mut *self.db_tx)
+                .await?
+                .rows_affected();
+        }
+        Ok(deleted > 0)
     }
 
     async fn commit(self: Box<Self>) -> Result<(), RdapServerError> {
@@ -0,0 +1,212 @@
+use std::{borrow::Borrow, collections::BTreeMap, net::IpAddr};
+
+use {
+    icann_rdap_common::{
+        prelude::ToResponse,
+        response::{Network, NetworkSearchResults, RdapResponse},
+        search_types::RirSearchRelation,
+    },
+    ipnet::IpNet,
+};
+
+use crate::rdap::response::NOT_FOUND;
+
+use super::{search::page_results, SearchPage};
+
+/// The range of addresses of an IP network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct AddrRange {
+    start: IpAddr,
+    end: IpAddr,
+}
+
+impl AddrRange {
+    /// Gets the range of a network from its start and end addresses.
+    pub(crate) fn of_network(network: &Network) -> Option<Self> {
+        let start = network.start_address.as_deref()?.parse::<IpAddr>().ok()?;
+        let end = network.end_address.as_deref()?.parse::<IpAddr>().ok()?;
+        (start.is_ipv4() == end.is_ipv4() && start <= end).then_some(Self { start, end })
+    }
+
+    fn of_net(net: &IpNet) -> Self {
+        Self {
+            start: net.network(),
+            end: net.broadcast(),
+        }
+    }
+
+    /// True if this range holds all of the other range and more.
+    fn covers(&self, other: &Self) -> bool {
+        self != other && self.start <= other.start && other.end <= self.end
+    }
+
+    fn size(&self) -> u128 {
+        addr_value(self.end) - addr_value(self.start)
+    }
+}
+
+fn addr_value(addr: IpAddr) -> u128 {
+    match addr {
+        IpAddr::V4(v4) => u32::from(v4).into(),
+        IpAddr::V6(v6) => v6.into(),
+    }
+}
+
+/// Keeps one of each network, in the order of their ranges. Networks held by
+/// storage once per CIDR they cover are found more than once.
+pub(crate) fn distinct_networks<N: Borrow<Network>>(
+    networks: impl IntoIterator<Item = N>,
+) -> Vec<N> {
+    networks
+        .into_iter()
+        .filter_map(|network| AddrRange::of_network(network.borrow()).map(|range| (range, network)))
+        .collect::<BTreeMap<AddrRange, N>>()
+        .into_values()
+        .collect()
+}
+
+/// Gets the networks having a relation to a queried network, as specified in
+/// draft-ietf-regext-rdap-rir-search.
+///
+/// The candidates are the networks overlapping the queried network. A network
+/// with the same range as the queried network is not related to it. The
+/// `rdap-up` and `rdap-top` relations give the most and least specific network
+/// covering the queried network. The `rdap-down` relation gives the networks
+/// under the queried network that are not under any other of them, and
+/// `rdap-bottom` gives those that have no other of them under them, as a page
+/// of search results.
+pub(crate) fn network_relation<N: Borrow<Network>>(
+    relation: RirSearchRelation,
+    query: &IpNet,
+    candidates: impl IntoIterator<Item = N>,
+    page: &SearchPage,
+) -> RdapResponse {
+    let query = AddrRange::of_net(query);
+    let candidates = candidates
+        .into_iter()
+        .filter_map(|network| AddrRange::of_network(network.borrow()).map(|range| (range, network)))
+        .collect::<BTreeMap<AddrRange, N>>();
+    match relation {
+        RirSearchRelation::Up | RirSearchRelation::Top => {
+            let covering = candidates
+                .into_iter()
+                .filter(|(range, _)| range.covers(&query));
+            let network = if relation == RirSearchRelation::Up {
+                covering.min_by_key(|(range, _)| range.size())
+            } else {
+                covering.max_by_key(|(range, _)| range.size())
+            };
+            match network {
+                Some((_, network)) => network.borrow().clone().to_response(),
+                None => NOT_FOUND.clone(),
+            }
+        }
+        RirSearchRelation::Down | RirSearchRelation::Bottom => {
+            let under = candidates
+                .into_iter()
+                .filter(|(range, _)| query.covers(range))
+                .collect::<Vec<(AddrRange, N)>>();
+            let related = under
+                .iter()
+                .filter(|(range, _)| {
+                    if relation == RirSearchRelation::Down {
+                        !under.iter().any(|(other, _)| other.covers(range))
+                    } else {
+                        !under.iter().any(|(other, _)| range.covers(other))
+                    }
+                })
+                .map(|(_, network)| network.borrow());
+            let (results, paging_metadata) = page_results(related, page);
+            NetworkSearchResults::builder()
+                .results(results)
+                .and_paging_metadata(paging_metadata)
+                .build()
+                .to_response()
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {
+        icann_rdap_common::{
+            response::{Network, RdapResponse},
+            search_types::RirSearchRelation,
+        },
+        ipnet::IpNet,
+        rstest::rstest,
+    };
+
+    use crate::storage::SearchPage;
+
+    use super::{distinct_networks, network_relation};
+
+    fn networks() -> Vec<Network> {
+        [
+            "10.0.0.0/8",
+            "10.1.0.0/16",
+            "10.1.1.0/24",
+            "10.1.2.0/24",
+            "10.2.0.0/16",
+        ]
+        .into_iter()
+        .map(|cidr| {
+            Network::builder()
+                .cidr(cidr)
+                .handle(cidr)
+                .build()
+                .expect("network")
+        })
+        .collect()
+    }
+
+    fn handles(response: RdapResponse) -> Vec<String> {
+        match response {
+            RdapResponse::Network(network) => vec![network.object_common.handle.unwrap()],
+            RdapResponse::NetworkSearchResults(search) => search
+                .results
+                .into_iter()
+                .map(|network| network.object_common.handle.unwrap())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    #[rstest]
+    #[case(RirSearchRelation::Up, "10.1.1.0/24", &["10.1.0.0/16"])]
+    #[case(RirSearchRelation::Up, "10.1.1.1/32", &["10.1.1.0/24"])]
+    #[case(RirSearchRelation::Up, "10.0.0.0/8", &[])]
+    #[case(RirSearchRelation::Top, "10.1.1.0/24", &["10.0.0.0/8"])]
+    #[case(RirSearchRelation::Down, "10.0.0.0/8", &["10.1.0.0/16", "10.2.0.0/16"])]
+    #[case(RirSearchRelation::Down, "10.1.1.0/24", &[])]
+    #[case(RirSearchRelation::Bottom, "10.0.0.0/8", &["10.1.1.0/24", "10.1.2.0/24", "10.2.0.0/16"])]
+    #[case(RirSearchRelation::Bottom, "0.0.0.0/0", &["10.1.1.0/24", "10.1.2.0/24", "10.2.0.0/16"])]
+    fn GIVEN_networks_WHEN_network_relation_THEN_related_networks_found(
+        #[case] relation: RirSearchRelation,
+        #[case] query: &str,
+        #[case] expected: &[&str],
+    ) {
+        // GIVEN
+        let query = query.parse::<IpNet>().expect("cidr");
+
+        // WHEN
+        let actual = network_relation(relation, &query, networks(), &SearchPage::all());
+
+        // THEN
+        assert_eq!(handles(actual), expected);
+    }
+
+    #[test]
+    fn GIVEN_network_found_twice_WHEN_distinct_networks_THEN_network_kept_once() {
+        // GIVEN
+        let mut found = networks();
+        found.extend(networks());
+
+        // WHEN
+        let actual = distinct_networks(found);
+
+        // THEN
+        assert_eq!(actual, networks());
+    }
+}
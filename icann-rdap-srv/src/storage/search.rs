@@ -0,0 +1,282 @@
+use std::collections::BTreeSet;
+
+use icann_rdap_common::response::PagingMetadata;
+
+use crate::{error::RdapServerError, rdap::sorting::DefaultSort};
+
+use super::SearchPage;
+
+/// Validates a label search as specified in RFC 9082 and splits it into
+/// the text before the asterisk and the labels after the asterisk.
+///
+/// The returned suffix does not have a leading dot ('.'), so a search of
+/// "foo.*.com" yields `("foo.", "com")` and "foo.example.*" yields `("foo.example.", "")`.
+pub(crate) fn split_label_search(search: &str) -> Result<(&str, &str), RdapServerError> {
+    // search string is invalid if it doesn't have only one asterisk ('*')
+    if search.chars().filter(|c| *c == '*').count() != 1 {
+        return Err(RdapServerError::InvalidArg(
+            "Search string must contain one and only one asterisk ('*')".to_string(),
+        ));
+    }
+    // asterisk must not be followed by a character other than dot ('.')
+    let star = search
+        .find('*')
+        .expect("internal error. previous check should have caught this");
+    if star != search.chars().count() - 1
+        && search
+            .chars()
+            .nth(star + 1)
+            .expect("should have been short circuited")
+            != '.'
+    {
+        return Err(RdapServerError::InvalidArg(
+            "Search string asterisk ('*') must terminate domain label".to_string(),
+        ));
+    }
+
+    let parts = search
+        .split_once('*')
+        .expect("internal error. previous check should insure there is an asterisk");
+
+    // this is a limitation of the trie in that it requires a prefix
+    if parts.0.is_empty() {
+        return Err(RdapServerError::InvalidArg(
+            "Search string must have a prefix".to_string(),
+        ));
+    }
+
+    Ok((parts.0, parts.1.trim_start_matches('.')))
+}
+
+/// A search for a partial string as specified in RFC 9082, such as those
+/// used for entity handles and full names.
+///
+/// The search string may end with a single asterisk ('*'), in which case it
+/// matches any value beginning with the text before the asterisk. Otherwise it
+/// must match the value exactly.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum PartialSearch<'a> {
+    Exact(&'a str),
+    Prefix(&'a str),
+}
+
+impl<'a> PartialSearch<'a> {
+    /// Validates and parses a partial string search.
+    pub(crate) fn parse(search: &'a str) -> Result<Self, RdapServerError> {
+        match search.find('*') {
+            None if search.is_empty() => Err(RdapServerError::InvalidArg(
+                "Search string must not be empty".to_string(),
+            )),
+            None => Ok(Self::Exact(search)),
+            Some(star) if star != search.len() - 1 => Err(RdapServerError::InvalidArg(
+                "Search string asterisk ('*') must be the last character".to_string(),
+            )),
+            Some(0) => Err(RdapServerError::InvalidArg(
+                "Search string must have a prefix".to_string(),
+            )),
+            Some(star) => Ok(Self::Prefix(&search[..star])),
+        }
+    }
+
+    /// Returns true if the value matches this search.
+    pub(crate) fn is_match(&self, value: &str) -> bool {
+        match self {
+            Self::Exact(s) => value == *s,
+            Self::Prefix(s) => value.starts_with(s),
+        }
+    }
+
+    /// Returns true if the value matches this search, ignoring case.
+    pub(crate) fn is_match_ignore_case(&self, value: &str) -> bool {
+        let value = fold_case(value);
+        match self {
+            Self::Exact(s) => value == fold_case(s),
+            Self::Prefix(s) => value.starts_with(&fold_case(s)),
+        }
+    }
+
+    /// Finds the keys of the values matching this search, ignoring case, in an
+    /// index of `(case folded value, key)` pairs.
+    pub(crate) fn search_folded<'i>(
+        &self,
+        index: &'i BTreeSet<(String, String)>,
+    ) -> impl Iterator<Item = &'i String> {
+        let (text, exact) = match self {
+            Self::Exact(s) => (fold_case(s), true),
+            Self::Prefix(s) => (fold_case(s), false),
+        };
+        index
+            .range((text.clone(), String::new())..)
+            .take_while(move |(value, _)| {
+                if exact {
+                    *value == text
+                } else {
+                    value.starts_with(&text)
+                }
+            })
+            .map(|(_, key)| key)
+    }
+}
+
+/// Folds the case of a value for searches ignoring case, which is done the same
+/// way as the `lower()` of PostgreSQL.
+pub(crate) fn fold_case(value: &str) -> String {
+    value.to_lowercase()
+}
+
+/// Puts matching search results in the order of their default sort and takes
+/// a page of them, cloning only the results of the page.
+///
+/// Results without a default sort value are placed last, and ties keep the
+/// order of the matches. Paging metadata with the count of all the matches is
+/// given for a page with a limit.
+pub(crate) fn page_results<'a, T>(
+    matches: impl IntoIterator<Item = &'a T>,
+    page: &SearchPage,
+) -> (Vec<T>, Option<PagingMetadata>)
+where
+    T: DefaultSort + Clone + 'a,
+{
+    let mut matches = matches
+        .into_iter()
+        .map(|result| (result.default_sort_value(), result))
+        .collect::<Vec<(Option<String>, &T)>>();
+    matches.sort_by(|(a, _), (b, _)| (a.is_none(), a).cmp(&(b.is_none(), b)));
+    let total_count = matches.len();
+    let results = matches
+        .into_iter()
+        .skip(page.offset)
+        .take(page.limit.unwrap_or(usize::MAX))
+        .map(|(_, result)| result.clone())
+        .collect::<Vec<T>>();
+    let paging_metadata = page.limit.map(|_| {
+        PagingMetadata::builder()
+            .total_count(total_count as u64)
+            .build()
+    });
+    (results, paging_metadata)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use {icann_rdap_common::response::Domain, rstest::rstest};
+
+    use crate::storage::SearchPage;
+
+    use super::{fold_case, page_results, PartialSearch};
+
+    #[rstest]
+    #[case("FOO", PartialSearch::Exact("FOO"))]
+    #[case("FOO*", PartialSearch::Prefix("FOO"))]
+    #[case("Bob Sm*", PartialSearch::Prefix("Bob Sm"))]
+    fn GIVEN_valid_search_WHEN_parse_THEN_search_is_correct(
+        #[case] search: &str,
+        #[case] expected: PartialSearch,
+    ) {
+        // GIVEN in parameters
+
+        // WHEN
+        let actual = PartialSearch::parse(search).expect("invalid search");
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("*")]
+    #[case("F*O")]
+    #[case("FO**")]
+    fn GIVEN_invalid_search_WHEN_parse_THEN_error(#[case] search: &str) {
+        // GIVEN in parameters
+
+        // WHEN
+        let actual = PartialSearch::parse(search);
+
+        // THEN
+        assert!(actual.is_err());
+    }
+
+    #[rstest]
+    #[case("FOO*", "FOO-1", true)]
+    #[case("FOO*", "BAR-1", false)]
+    #[case("FOO", "FOO", true)]
+    #[case("FOO", "FOO-1", false)]
+    fn GIVEN_search_WHEN_is_match_THEN_result_is_correct(
+        #[case] search: &str,
+        #[case] value: &str,
+        #[case] expected: bool,
+    ) {
+        // GIVEN
+        let search = PartialSearch::parse(search).expect("invalid search");
+
+        // WHEN
+        let actual = search.is_match(value);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("bob sm*", "Bob Smurd", true)]
+    #[case("BOB SMURD", "Bob Smurd", true)]
+    #[case("bob sm*", "Alice Smurd", false)]
+    fn GIVEN_search_WHEN_is_match_ignore_case_THEN_result_is_correct(
+        #[case] search: &str,
+        #[case] value: &str,
+        #[case] expected: bool,
+    ) {
+        // GIVEN
+        let search = PartialSearch::parse(search).expect("invalid search");
+
+        // WHEN
+        let actual = search.is_match_ignore_case(value);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("foo*", &["FOO-1", "Foo-2"])]
+    #[case("FOO-1", &["FOO-1"])]
+    #[case("FOO", &[])]
+    fn GIVEN_folded_index_WHEN_search_folded_THEN_keys_found(
+        #[case] search: &str,
+        #[case] expected: &[&str],
+    ) {
+        // GIVEN
+        let index = ["FOO-1", "Foo-2", "BAR-1"]
+            .into_iter()
+            .map(|key| (fold_case(key), key.to_string()))
+            .collect::<BTreeSet<_>>();
+        let search = PartialSearch::parse(search).expect("invalid search");
+
+        // WHEN
+        let actual = search.search_folded(&index).collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn GIVEN_unsorted_matches_WHEN_page_results_THEN_page_of_sorted_results() {
+        // GIVEN
+        let matches = ["foo3.example", "FOO1.example", "foo2.example"]
+            .map(|name| Domain::builder().ldh_name(name).build());
+
+        // WHEN
+        let (actual, paging_metadata) = page_results(&matches, &SearchPage::new(1, 1));
+
+        // THEN
+        let names = actual
+            .into_iter()
+            .filter_map(|d| d.ldh_name)
+            .collect::<Vec<String>>();
+        assert_eq!(names, ["foo2.example"]);
+        let paging_metadata = paging_metadata.expect("no paging metadata");
+        assert_eq!(paging_metadata.total_count, Some(3));
+    }
+}
@@ -0,0 +1,71 @@
+use std::{collections::HashMap, sync::Arc};
+
+use {
+    axum::{
+        extract::{Request, State},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        Router,
+    },
+    http::{header::HOST, uri::Authority},
+    tower::ServiceExt,
+};
+
+/// The routers of the tenants served by host name, keyed by lowercase host name.
+pub(crate) type TenantHosts = Arc<HashMap<String, Router>>;
+
+/// Middleware handing requests for the hosts of tenants to the routers of the tenants.
+/// Requests for other hosts are served by the default storage.
+pub(crate) async fn tenant_by_host(
+    State(hosts): State<TenantHosts>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let router = request_host(&request).and_then(|host| hosts.get(&host));
+    match router {
+        Some(router) => router.clone().oneshot(request).await.into_response(),
+        None => next.run(request).await,
+    }
+}
+
+/// Gets the lowercase host name of a request, without its port, from the URI
+/// of HTTP/2 requests or else the `Host` header.
+fn request_host(request: &Request) -> Option<String> {
+    if let Some(host) = request.uri().host() {
+        return Some(host.to_ascii_lowercase());
+    }
+    let host = request.headers().get(HOST)?.to_str().ok()?;
+    let authority = host.parse::<Authority>().ok()?;
+    Some(authority.host().to_ascii_lowercase())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use {axum::extract::Request, http::header::HOST, rstest::rstest};
+
+    use super::request_host;
+
+    #[rstest]
+    #[case("rdap.example.com", Some("rdap.example.com"))]
+    #[case("RDAP.Example.COM:8080", Some("rdap.example.com"))]
+    #[case("[::1]:3000", Some("[::1]"))]
+    #[case("not a host", None)]
+    fn GIVEN_host_header_WHEN_request_host_THEN_host_name_without_port(
+        #[case] host: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        // GIVEN
+        let request = Request::builder()
+            .uri("/rdap/help")
+            .header(HOST, host)
+            .body(axum::body::Body::empty())
+            .expect("request");
+
+        // WHEN
+        let actual = request_host(&request);
+
+        // THEN
+        assert_eq!(actual.as_deref(), expected);
+    }
+}
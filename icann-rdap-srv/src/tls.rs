@@ -0,0 +1,393 @@
+use std::{
+    io::BufReader,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use {
+    axum::{extract::ConnectInfo, Router},
+    http::Request,
+    hyper::body::Incoming,
+    hyper_util::{
+        rt::{TokioExecutor, TokioIo},
+        server::conn::auto,
+        service::TowerToHyperService,
+    },
+    rustls::{
+        crypto::ring::{default_provider, sign::any_supported_type},
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ServerConfig,
+    },
+    tokio::{net::TcpListener, task::JoinSet, time::timeout},
+    tokio_rustls::TlsAcceptor,
+    tower::ServiceExt,
+    tracing::{debug, info, warn},
+};
+
+use crate::{config::TlsConfig, error::RdapServerError, shutdown::Shutdown};
+
+/// How often the certificate and key files are checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a client has to complete the TLS handshake before it is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Resolves the certificate of the server, which is reloaded when its files change.
+#[derive(Debug)]
+pub(crate) struct ReloadingCertResolver {
+    cert_file: String,
+    key_file: String,
+    loaded: RwLock<LoadedCert>,
+}
+
+#[derive(Debug)]
+struct LoadedCert {
+    cert_pem: Vec<u8>,
+    key_pem: Vec<u8>,
+    certified_key: Arc<CertifiedKey>,
+}
+
+impl ReloadingCertResolver {
+    pub(crate) fn new(config: &TlsConfig) -> Result<Self, RdapServerError> {
+        let loaded = load_cert(&config.cert_file, &config.key_file)?;
+        Ok(Self {
+            cert_file: config.cert_file.clone(),
+            key_file: config.key_file.clone(),
+            loaded: RwLock::new(loaded),
+        })
+    }
+
+    /// Reloads the certificate if its files have changed, returning true if it did.
+    ///
+    /// If the files cannot be loaded, or the certificate does not match the key,
+    /// such as while they are being replaced, the current certificate is kept.
+    pub(crate) fn reload(&self) -> Result<bool, RdapServerError> {
+        let cert_pem = read_file(&self.cert_file)?;
+        let key_pem = read_file(&self.key_file)?;
+        {
+            let loaded = self.loaded.read().expect("certificate lock poisoned");
+            if loaded.cert_pem == cert_pem && loaded.key_pem == key_pem {
+                return Ok(false);
+            }
+        }
+        let certified_key = certified_key(&cert_pem, &key_pem, &self.cert_file, &self.key_file)?;
+        *self.loaded.write().expect("certificate lock poisoned") = LoadedCert {
+            cert_pem,
+            key_pem,
+            certified_key: Arc::new(certified_key),
+        };
+        Ok(true)
+    }
+
+    /// Periodically reloads the certificate.
+    pub(crate) async fn reload_periodically(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match self.reload() {
+                Ok(true) => info!("reloaded TLS certificate from {}", self.cert_file),
+                Ok(false) => {}
+                Err(e) => warn!("unable to reload TLS certificate: {e}"),
+            }
+        }
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let loaded = self.loaded.read().expect("certificate lock poisoned");
+        Some(loaded.certified_key.clone())
+    }
+}
+
+/// Checks that the certificate and key files of a TLS configuration can be
+/// read and that the certificate matches the key.
+pub fn check_cert(config: &TlsConfig) -> Result<(), RdapServerError> {
+    load_cert(&config.cert_file, &config.key_file).map(|_| ())
+}
+
+fn load_cert(cert_file: &str, key_file: &str) -> Result<LoadedCert, RdapServerError> {
+    let cert_pem = read_file(cert_file)?;
+    let key_pem = read_file(key_file)?;
+    let certified_key = certified_key(&cert_pem, &key_pem, cert_file, key_file)?;
+    Ok(LoadedCert {
+        cert_pem,
+        key_pem,
+        certified_key: Arc::new(certified_key),
+    })
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, RdapServerError> {
+    std::fs::read(path).map_err(|e| RdapServerError::Config(format!("cannot read {path}: {e}")))
+}
+
+fn certified_key(
+    cert_pem: &[u8],
+    key_pem: &[u8],
+    cert_file: &str,
+    key_file: &str,
+) -> Result<CertifiedKey, RdapServerError> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(cert_pem))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| RdapServerError::Config(format!("cannot parse {cert_file}: {e}")))?;
+    if certs.is_empty() {
+        return Err(RdapServerError::Config(format!(
+            "no certificates found in {cert_file}"
+        )));
+    }
+    let key = rustls_pemfile::private_key(&mut BufReader::new(key_pem))
+        .map_err(|e| RdapServerError::Config(format!("cannot parse {key_file}: {e}")))?
+        .ok_or_else(|| RdapServerError::Config(format!("no private key found in {key_file}")))?;
+    let signing_key = any_supported_type(&key)?;
+    let certified_key = CertifiedKey::new(certs, signing_key);
+    certified_key.keys_match().map_err(|e| {
+        RdapServerError::Config(format!(
+            "certificate in {cert_file} does not match the key in {key_file}: {e}"
+        ))
+    })?;
+    Ok(certified_key)
+}
+
+/// Creates the acceptor of TLS connections, offering HTTP/2 and HTTP/1.1.
+pub(crate) fn tls_acceptor(
+    resolver: Arc<ReloadingCertResolver>,
+) -> Result<TlsAcceptor, RdapServerError> {
+    let mut config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_cert_resolver(resolver);
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Serves the router over TLS connections accepted from the listener.
+///
+/// The address of the client is given to the router as [ConnectInfo], as it is
+/// by [axum::serve] for plain HTTP. Once shut down, no more connections are
+/// accepted and those open are closed when their requests in flight finish.
+pub(crate) async fn serve_tls(
+    tcp_listener: TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+    shutdown: Shutdown,
+) -> Result<(), RdapServerError> {
+    serve_tls_with_timeout(tcp_listener, acceptor, app, shutdown, HANDSHAKE_TIMEOUT).await
+}
+
+async fn serve_tls_with_timeout(
+    tcp_listener: TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+    shutdown: Shutdown,
+    handshake_timeout: Duration,
+) -> Result<(), RdapServerError> {
+    let mut connections = JoinSet::new();
+    loop {
+        let accepted = tokio::select! {
+            accepted = tcp_listener.accept() => accepted,
+            _ = shutdown.signaled() => break,
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+        };
+        let (stream, remote_addr) = match accepted {
+            Ok(connection) => connection,
+            Err(e) => {
+                // errors such as running out of file descriptors pass with time
+                debug!("unable to accept connection: {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        let shutdown = shutdown.clone();
+        connections.spawn(async move {
+            // clients stalling the handshake are dropped, also when shutting down.
+            let handshake = tokio::select! {
+                handshake = timeout(handshake_timeout, acceptor.accept(stream)) => handshake,
+                _ = shutdown.signaled() => return,
+            };
+            let stream = match handshake {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    debug!("TLS handshake with {remote_addr} failed: {e}");
+                    return;
+                }
+                Err(_) => {
+                    debug!("TLS handshake with {remote_addr} timed out");
+                    return;
+                }
+            };
+            let service = app.map_request(move |mut request: Request<Incoming>| {
+                request
+                    .extensions_mut()
+                    .insert(ConnectInfo::<SocketAddr>(remote_addr));
+                request
+            });
+            let builder = auto::Builder::new(TokioExecutor::new());
+            let connection =
+                builder.serve_connection(TokioIo::new(stream), TowerToHyperService::new(service));
+            tokio::pin!(connection);
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = shutdown.signaled() => {
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+            if let Err(e) = result {
+                debug!("connection with {remote_addr} failed: {e}");
+            }
+        });
+    }
+    drop(tcp_listener);
+    while connections.join_next().await.is_some() {}
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use {
+        axum::Router,
+        test_dir::{DirBuilder, TestDir},
+        tokio::{
+            io::AsyncReadExt,
+            net::{TcpListener, TcpStream},
+        },
+    };
+
+    use crate::{config::TlsConfig, shutdown::Shutdown};
+
+    use super::{serve_tls_with_timeout, tls_acceptor, ReloadingCertResolver};
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    fn copy_fixture(dir: &TestDir, name: &str, to: &str) {
+        std::fs::copy(format!("{FIXTURES}/{name}"), dir.path(to)).expect("copying fixture");
+    }
+
+    fn resolver(dir: &TestDir) -> ReloadingCertResolver {
+        let config = TlsConfig::builder()
+            .cert_file(dir.path("cert.pem").to_string_lossy())
+            .key_file(dir.path("key.pem").to_string_lossy())
+            .with_http(false)
+            .build();
+        ReloadingCertResolver::new(&config).expect("loading certificate")
+    }
+
+    fn end_entity(resolver: &ReloadingCertResolver) -> Vec<u8> {
+        let loaded = resolver.loaded.read().expect("certificate lock");
+        loaded
+            .certified_key
+            .end_entity_cert()
+            .expect("certificate")
+            .to_vec()
+    }
+
+    #[test]
+    fn GIVEN_unchanged_files_WHEN_reload_THEN_not_reloaded() {
+        // GIVEN
+        let dir = TestDir::temp();
+        copy_fixture(&dir, "localhost.cert.pem", "cert.pem");
+        copy_fixture(&dir, "localhost.key.pem", "key.pem");
+        let resolver = resolver(&dir);
+
+        // WHEN
+        let actual = resolver.reload().expect("reloading");
+
+        // THEN
+        assert!(!actual);
+    }
+
+    #[test]
+    fn GIVEN_renewed_files_WHEN_reload_THEN_new_certificate() {
+        // GIVEN
+        let dir = TestDir::temp();
+        copy_fixture(&dir, "localhost.cert.pem", "cert.pem");
+        copy_fixture(&dir, "localhost.key.pem", "key.pem");
+        let resolver = resolver(&dir);
+        let before = end_entity(&resolver);
+        copy_fixture(&dir, "localhost-renewed.cert.pem", "cert.pem");
+        copy_fixture(&dir, "localhost-renewed.key.pem", "key.pem");
+
+        // WHEN
+        let actual = resolver.reload().expect("reloading");
+
+        // THEN
+        assert!(actual);
+        assert_ne!(end_entity(&resolver), before);
+    }
+
+    #[test]
+    fn GIVEN_invalid_key_file_WHEN_reload_THEN_error_and_certificate_kept() {
+        // GIVEN
+        let dir = TestDir::temp();
+        copy_fixture(&dir, "localhost.cert.pem", "cert.pem");
+        copy_fixture(&dir, "localhost.key.pem", "key.pem");
+        let resolver = resolver(&dir);
+        let before = end_entity(&resolver);
+        std::fs::write(dir.path("key.pem"), "not a key").expect("writing key");
+
+        // WHEN
+        let actual = resolver.reload();
+
+        // THEN
+        assert!(actual.is_err());
+        assert_eq!(end_entity(&resolver), before);
+    }
+
+    #[test]
+    fn GIVEN_half_rotated_files_WHEN_reload_THEN_error_and_certificate_kept() {
+        // GIVEN
+        let dir = TestDir::temp();
+        copy_fixture(&dir, "localhost.cert.pem", "cert.pem");
+        copy_fixture(&dir, "localhost.key.pem", "key.pem");
+        let resolver = resolver(&dir);
+        let before = end_entity(&resolver);
+        copy_fixture(&dir, "localhost-renewed.cert.pem", "cert.pem");
+
+        // WHEN
+        let actual = resolver.reload();
+
+        // THEN
+        assert!(actual.is_err());
+        assert_eq!(end_entity(&resolver), before);
+    }
+
+    #[tokio::test]
+    async fn GIVEN_client_stalling_handshake_WHEN_serve_tls_THEN_connection_dropped() {
+        // GIVEN
+        let dir = TestDir::temp();
+        copy_fixture(&dir, "localhost.cert.pem", "cert.pem");
+        copy_fixture(&dir, "localhost.key.pem", "key.pem");
+        let acceptor = tls_acceptor(Arc::new(resolver(&dir))).expect("tls acceptor");
+        let tcp_listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("binding listener");
+        let local_addr = tcp_listener.local_addr().expect("local address");
+        let shutdown = Shutdown::new(Duration::from_secs(1));
+        tokio::spawn(serve_tls_with_timeout(
+            tcp_listener,
+            acceptor,
+            Router::new(),
+            shutdown.clone(),
+            Duration::from_millis(100),
+        ));
+
+        // WHEN
+        let mut stream = TcpStream::connect(local_addr).await.expect("connecting");
+
+        // THEN
+        let mut buf = [0u8; 1];
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .expect("connection dropped before timeout");
+        assert!(matches!(read, Ok(0) | Err(_)));
+        shutdown.shutdown();
+    }
+}
@@ -1 +1,45 @@
+use {
+    sha2::{Digest, Sha256},
+    subtle::ConstantTimeEq,
+};
+
 pub mod bin;
+
+/// True if a secret given by a caller is one of the configured secrets.
+///
+/// The digests of the secrets are compared in constant time, and every
+/// configured secret is compared, so the time taken does not reveal how much
+/// of a secret was guessed or which secret matched.
+pub(crate) fn is_secret<'a>(secrets: impl IntoIterator<Item = &'a String>, given: &str) -> bool {
+    let given = Sha256::digest(given);
+    secrets.into_iter().fold(false, |found, secret| {
+        found | bool::from(Sha256::digest(secret).ct_eq(&given))
+    })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use rstest::rstest;
+
+    use super::is_secret;
+
+    #[rstest]
+    #[case("secret-key", true)]
+    #[case("other-key", true)]
+    #[case("secret-ke", false)]
+    #[case("", false)]
+    fn GIVEN_secrets_WHEN_is_secret_THEN_only_exact_match(
+        #[case] given: &str,
+        #[case] expected: bool,
+    ) {
+        // GIVEN
+        let secrets = vec!["secret-key".to_string(), "other-key".to_string()];
+
+        // WHEN
+        let actual = is_secret(&secrets, given);
+
+        // THEN
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,2 +1,3 @@
+mod rdap_srv;
 mod rdap_srv_data;
 mod rdap_srv_store;
@@ -0,0 +1,210 @@
+#![allow(non_snake_case)]
+
+use {rstest::rstest, test_dir::DirBuilder};
+
+use crate::test_jig::RdapSrvTestJig;
+
+#[test]
+fn GIVEN_valid_config_file_WHEN_check_config_THEN_success() {
+    // GIVEN
+    let mut test_jig = RdapSrvTestJig::new();
+    let config_file = test_jig.config_dir.path("rdap-srv.toml");
+    std::fs::write(
+        &config_file,
+        r#"
+            [listen]
+            port = 3000
+
+            [storage]
+            type = "memory"
+
+            [search]
+            domain_search_by_name = true
+        "#,
+    )
+    .expect("writing config file");
+
+    // WHEN
+    test_jig
+        .cmd
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--check-config");
+
+    // THEN
+    let assert = test_jig.cmd.assert();
+    assert.success();
+}
+
+#[test]
+fn GIVEN_unknown_value_in_config_file_WHEN_check_config_THEN_error() {
+    // GIVEN
+    let mut test_jig = RdapSrvTestJig::new();
+    let config_file = test_jig.config_dir.path("rdap-srv.toml");
+    std::fs::write(
+        &config_file,
+        r#"
+            [listen]
+            prot = 3000
+        "#,
+    )
+    .expect("writing config file");
+
+    // WHEN
+    test_jig
+        .cmd
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--check-config");
+
+    // THEN
+    let assert = test_jig.cmd.assert();
+    assert.failure();
+}
+
+#[test]
+fn GIVEN_invalid_value_in_config_file_WHEN_check_config_THEN_error() {
+    // GIVEN
+    let mut test_jig = RdapSrvTestJig::new();
+    let config_file = test_jig.config_dir.path("rdap-srv.toml");
+    std::fs::write(
+        &config_file,
+        r#"
+            [storage]
+            type = "floppy"
+        "#,
+    )
+    .expect("writing config file");
+
+    // WHEN
+    test_jig
+        .cmd
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--check-config");
+
+    // THEN
+    let assert = test_jig.cmd.assert();
+    assert.failure();
+}
+
+#[test]
+fn GIVEN_env_var_WHEN_check_config_THEN_env_var_overrides_config_file() {
+    // GIVEN
+    let mut test_jig = RdapSrvTestJig::new();
+    let config_file = test_jig.config_dir.path("rdap-srv.toml");
+    std::fs::write(
+        &config_file,
+        r#"
+            [search]
+            page_size = 0
+        "#,
+    )
+    .expect("writing config file");
+
+    // WHEN
+    test_jig
+        .cmd
+        .env("RDAP_SRV_CONFIG", &config_file)
+        .env("RDAP_SRV_SEARCH_PAGE_SIZE", "10")
+        .arg("--check-config");
+
+    // THEN
+    let assert = test_jig.cmd.assert();
+    assert.success();
+}
+
+#[test]
+fn GIVEN_inline_tenants_in_config_file_WHEN_check_config_THEN_success() {
+    // GIVEN
+    let mut test_jig = RdapSrvTestJig::new();
+    let config_file = test_jig.config_dir.path("rdap-srv.toml");
+    std::fs::write(
+        &config_file,
+        r#"
+            [[tenants]]
+            name = "foo"
+            hosts = ["rdap.foo.example"]
+
+            [[tenants]]
+            name = "bar"
+            path_prefix = "bar"
+        "#,
+    )
+    .expect("writing config file");
+
+    // WHEN
+    test_jig
+        .cmd
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--check-config");
+
+    // THEN
+    let assert = test_jig.cmd.assert();
+    assert.success();
+}
+
+#[test]
+fn GIVEN_tenant_with_db_url_and_memory_storage_WHEN_check_config_THEN_error() {
+    // GIVEN
+    let mut test_jig = RdapSrvTestJig::new();
+    let config_file = test_jig.config_dir.path("rdap-srv.toml");
+    std::fs::write(
+        &config_file,
+        r#"
+            [storage]
+            type = "memory"
+
+            [[tenants]]
+            name = "foo"
+            path_prefix = "foo"
+            db_url = "postgresql://127.0.0.1/foo"
+        "#,
+    )
+    .expect("writing config file");
+
+    // WHEN
+    test_jig
+        .cmd
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--check-config");
+
+    // THEN
+    let assert = test_jig.cmd.assert();
+    assert.failure();
+}
+
+#[rstest]
+#[case(
+    r#"
+        [redaction]
+        policy_file = "no-such-policy.json"
+    "#
+)]
+#[case(
+    r#"
+        [listen.tls]
+        cert_file = "no-such-cert.pem"
+        key_file = "no-such-key.pem"
+    "#
+)]
+fn GIVEN_unreadable_file_in_config_file_WHEN_check_config_THEN_error(#[case] toml: &str) {
+    // GIVEN
+    let mut test_jig = RdapSrvTestJig::new();
+    let config_file = test_jig.config_dir.path("rdap-srv.toml");
+    std::fs::write(&config_file, toml).expect("writing config file");
+
+    // WHEN
+    test_jig
+        .cmd
+        .current_dir(test_jig.config_dir.root())
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--check-config");
+
+    // THEN
+    let assert = test_jig.cmd.assert();
+    assert.failure();
+}
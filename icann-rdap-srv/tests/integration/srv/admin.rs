@@ -0,0 +1,262 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::response::{Domain, Network, RdapResponse},
+    icann_rdap_srv::storage::StoreOps,
+    rstest::rstest,
+    serde_json::json,
+};
+
+use crate::test_jig::SrvTestJig;
+
+const API_KEY: &str = "admin-secret";
+
+#[tokio::test]
+async fn GIVEN_domain_WHEN_put_object_THEN_domain_is_served() {
+    // GIVEN
+    let (test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let domain = Domain::builder().ldh_name("foo.example").build();
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .put(format!("{admin_url}/object"))
+        .bearer_auth(API_KEY)
+        .json(&domain)
+        .send()
+        .await
+        .expect("put object");
+
+    // THEN
+    assert_eq!(response.status(), 204);
+    let actual = test_srv
+        .mem
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain");
+    let RdapResponse::Domain(domain) = actual else {
+        panic!("domain not found")
+    };
+    assert_eq!(domain.ldh_name.as_deref(), Some("foo.example"));
+}
+
+#[tokio::test]
+async fn GIVEN_wrong_api_key_WHEN_put_object_THEN_status_code_401() {
+    // GIVEN
+    let (test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let domain = Domain::builder().ldh_name("foo.example").build();
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .put(format!("{admin_url}/object"))
+        .bearer_auth("wrong-key")
+        .json(&domain)
+        .send()
+        .await
+        .expect("put object");
+
+    // THEN
+    assert_eq!(response.status(), 401);
+    let actual = test_srv
+        .mem
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain");
+    assert!(matches!(actual, RdapResponse::ErrorResponse(_)));
+}
+
+#[tokio::test]
+async fn GIVEN_non_rdap_json_WHEN_put_object_THEN_status_code_400_with_description() {
+    // GIVEN
+    let (_test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .put(format!("{admin_url}/object"))
+        .bearer_auth(API_KEY)
+        .json(&json!({"foo": "bar"}))
+        .send()
+        .await
+        .expect("put object");
+
+    // THEN
+    assert_eq!(response.status(), 400);
+    let json = response
+        .json::<serde_json::Value>()
+        .await
+        .expect("error body");
+    let RdapResponse::ErrorResponse(error) = RdapResponse::try_from(json).expect("parsing rdap")
+    else {
+        panic!("response is not an rdap error")
+    };
+    assert_eq!(error.error_code, 400);
+    assert!(error.description.is_some_and(|d| !d.is_empty()));
+}
+
+#[tokio::test]
+async fn GIVEN_domain_template_WHEN_post_template_THEN_domains_are_served() {
+    // GIVEN
+    let (test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let template = json!({
+        "domain": {
+            "object": {
+                "rdapConformance": ["rdap_level_0"],
+                "objectClassName": "domain",
+                "ldhName": "example"
+            }
+        },
+        "ids": [
+            {"ldhName": "foo.example"},
+            {"ldhName": "bar.example"}
+        ]
+    });
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .post(format!("{admin_url}/template"))
+        .bearer_auth(API_KEY)
+        .json(&template)
+        .send()
+        .await
+        .expect("post template");
+
+    // THEN
+    assert_eq!(response.status(), 204);
+    for ldh_name in ["foo.example", "bar.example"] {
+        let actual = test_srv
+            .mem
+            .get_domain_by_ldh(ldh_name)
+            .await
+            .expect("getting domain");
+        assert!(matches!(actual, RdapResponse::Domain(_)));
+    }
+}
+
+#[tokio::test]
+async fn GIVEN_template_with_invalid_id_WHEN_post_template_THEN_status_code_400_and_nothing_served()
+{
+    // GIVEN
+    let (test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let template = json!({
+        "domain": {
+            "object": {
+                "rdapConformance": ["rdap_level_0"],
+                "objectClassName": "domain",
+                "ldhName": "example"
+            }
+        },
+        "ids": [
+            {"ldhName": "foo.example"},
+            {"ldhName": "-bar..example"}
+        ]
+    });
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .post(format!("{admin_url}/template"))
+        .bearer_auth(API_KEY)
+        .json(&template)
+        .send()
+        .await
+        .expect("post template");
+
+    // THEN
+    assert_eq!(response.status(), 400);
+    let actual = test_srv
+        .mem
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain");
+    assert!(matches!(actual, RdapResponse::ErrorResponse(_)));
+}
+
+#[tokio::test]
+async fn GIVEN_domain_WHEN_delete_domain_THEN_domain_is_not_served() {
+    // GIVEN
+    let (test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .delete(format!("{admin_url}/domain/foo.example"))
+        .bearer_auth(API_KEY)
+        .send()
+        .await
+        .expect("delete object");
+
+    // THEN
+    assert_eq!(response.status(), 204);
+    let actual = test_srv
+        .mem
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain");
+    assert!(matches!(actual, RdapResponse::ErrorResponse(_)));
+}
+
+#[tokio::test]
+async fn GIVEN_network_WHEN_delete_ip_cidr_THEN_network_is_not_served() {
+    // GIVEN
+    let (test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_network(
+        &Network::builder()
+            .cidr("10.0.0.0/8")
+            .build()
+            .expect("cidr parsing"),
+    )
+    .await
+    .expect("add network in tx");
+    tx.commit().await.expect("tx commit");
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .delete(format!("{admin_url}/ip/10.0.0.0/8"))
+        .bearer_auth(API_KEY)
+        .send()
+        .await
+        .expect("delete object");
+
+    // THEN
+    assert_eq!(response.status(), 204);
+    let actual = test_srv
+        .mem
+        .get_network_by_ipaddr("10.0.0.1")
+        .await
+        .expect("getting network");
+    assert!(matches!(actual, RdapResponse::ErrorResponse(_)));
+}
+
+#[rstest]
+#[case("domain/foo.example", 404)]
+#[case("autnum/64496-64511", 404)]
+#[case("autnum/foo", 400)]
+#[case("ip/10.0.0.0", 400)]
+#[case("help/foo", 400)]
+#[tokio::test]
+async fn GIVEN_no_objects_WHEN_delete_THEN_status_code(#[case] path: &str, #[case] expected: u16) {
+    // GIVEN
+    let (_test_srv, admin_url) = SrvTestJig::new_admin(API_KEY).await;
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let response = client
+        .delete(format!("{admin_url}/{path}"))
+        .bearer_auth(API_KEY)
+        .send()
+        .await
+        .expect("delete object");
+
+    // THEN
+    assert_eq!(response.status(), expected);
+}
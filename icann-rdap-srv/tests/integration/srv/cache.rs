@@ -0,0 +1,97 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::response::Domain,
+    icann_rdap_srv::{config::CacheConfig, storage::StoreOps},
+    reqwest::{header, StatusCode},
+    rstest::rstest,
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[rstest]
+#[case("domain/foo.example", StatusCode::OK, Some("max-age=3600"))]
+#[case("domain/bar.example", StatusCode::NOT_FOUND, Some("max-age=60"))]
+#[case("domains?name=foo.*", StatusCode::OK, Some("no-store"))]
+#[case("entity/foo", StatusCode::NOT_FOUND, Some("max-age=60"))]
+#[case("autnum/700", StatusCode::NOT_FOUND, Some("max-age=60"))]
+#[tokio::test]
+async fn GIVEN_cache_config_WHEN_get_THEN_cache_control_of_class(
+    #[case] path: &str,
+    #[case] expected_status: StatusCode,
+    #[case] expected_cache_control: Option<&str>,
+) {
+    // GIVEN
+    let test_srv = SrvTestJig::new_cache(
+        CacheConfig::builder()
+            .domain(3600)
+            .search(0)
+            .error(60)
+            .build(),
+    )
+    .await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let response = reqwest::get(format!("{}/{path}", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), expected_status);
+    let cache_control = response
+        .headers()
+        .get(header::CACHE_CONTROL)
+        .map(|value| value.to_str().expect("cache control text"));
+    assert_eq!(cache_control, expected_cache_control);
+    assert!(response.headers().contains_key(header::EXPIRES));
+}
+
+#[tokio::test]
+async fn GIVEN_no_cache_config_WHEN_get_THEN_no_cache_headers() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/foo.example", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert!(!response.headers().contains_key(header::CACHE_CONTROL));
+    assert!(!response.headers().contains_key(header::EXPIRES));
+}
+
+#[tokio::test]
+async fn GIVEN_cache_config_WHEN_not_modified_THEN_cache_control_sent() {
+    // GIVEN
+    let test_srv = SrvTestJig::new_cache(CacheConfig::builder().domain(3600).build()).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    let client = reqwest::Client::new();
+    let url = format!("{}/domain/foo.example", test_srv.rdap_base);
+    let response = client.get(&url).send().await.expect("rdap request");
+    let etag = response.headers().get(header::ETAG).expect("etag").clone();
+
+    // WHEN
+    let response = client
+        .get(&url)
+        .header(header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .expect("conditional request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(
+        response.headers().get(header::CACHE_CONTROL),
+        Some(&header::HeaderValue::from_static("max-age=3600"))
+    );
+}
@@ -0,0 +1,155 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::response::{Domain, Event},
+    icann_rdap_srv::storage::StoreOps,
+    reqwest::{header, StatusCode},
+    rstest::rstest,
+};
+
+use crate::test_jig::SrvTestJig;
+
+async fn new_with_domain() -> SrvTestJig {
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .events(vec![Event::builder()
+                .event_action("last changed")
+                .event_date("2024-01-01T00:00:00Z")
+                .build()])
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    test_srv
+}
+
+#[tokio::test]
+async fn GIVEN_domain_WHEN_get_THEN_validators_returned() {
+    // GIVEN
+    let test_srv = new_with_domain().await;
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/foo.example", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers().get(header::ETAG).expect("etag");
+    assert!(etag.to_str().expect("etag text").starts_with('"'));
+    assert_eq!(
+        response
+            .headers()
+            .get(header::LAST_MODIFIED)
+            .expect("last modified"),
+        "Mon, 01 Jan 2024 00:00:00 GMT"
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_etag_of_domain_WHEN_get_with_if_none_match_THEN_not_modified() {
+    // GIVEN
+    let test_srv = new_with_domain().await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/domain/foo.example", test_srv.rdap_base);
+    let response = client.get(&url).send().await.expect("rdap request");
+    let etag = response.headers().get(header::ETAG).expect("etag").clone();
+
+    // WHEN
+    let response = client
+        .get(&url)
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .expect("conditional request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers().get(header::ETAG), Some(&etag));
+    assert!(response.bytes().await.expect("body").is_empty());
+}
+
+#[tokio::test]
+async fn GIVEN_domain_changed_WHEN_get_with_old_etag_THEN_domain_returned() {
+    // GIVEN
+    let test_srv = new_with_domain().await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/domain/foo.example", test_srv.rdap_base);
+    let response = client.get(&url).send().await.expect("rdap request");
+    let etag = response.headers().get(header::ETAG).expect("etag").clone();
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .status("active")
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let response = client
+        .get(&url)
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .expect("conditional request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers().get(header::ETAG), Some(&etag));
+}
+
+#[rstest]
+#[case("Mon, 01 Jan 2024 00:00:00 GMT", StatusCode::NOT_MODIFIED)]
+#[case("Tue, 02 Jan 2024 00:00:00 GMT", StatusCode::NOT_MODIFIED)]
+#[case("Sun, 31 Dec 2023 00:00:00 GMT", StatusCode::OK)]
+#[tokio::test]
+async fn GIVEN_last_changed_domain_WHEN_get_with_if_modified_since_THEN_status(
+    #[case] if_modified_since: &str,
+    #[case] expected: StatusCode,
+) {
+    // GIVEN
+    let test_srv = new_with_domain().await;
+
+    // WHEN
+    let response = reqwest::Client::new()
+        .get(format!("{}/domain/foo.example", test_srv.rdap_base))
+        .header(header::IF_MODIFIED_SINCE, if_modified_since)
+        .send()
+        .await
+        .expect("conditional request");
+
+    // THEN
+    assert_eq!(response.status(), expected);
+}
+
+#[rstest]
+#[case("domain/foo.example", StatusCode::OK)]
+#[case("domain/bar.example", StatusCode::NOT_FOUND)]
+#[case("domains?name=foo.*", StatusCode::OK)]
+#[case("nameservers?ip=192.0.2.1", StatusCode::OK)]
+#[tokio::test]
+async fn GIVEN_server_WHEN_head_THEN_status_without_body(
+    #[case] path: &str,
+    #[case] expected: StatusCode,
+) {
+    // GIVEN
+    let test_srv = new_with_domain().await;
+
+    // WHEN
+    let response = reqwest::Client::new()
+        .head(format!("{}/{path}", test_srv.rdap_base))
+        .send()
+        .await
+        .expect("head request");
+
+    // THEN
+    assert_eq!(response.status(), expected);
+    assert!(response.bytes().await.expect("body").is_empty());
+}
@@ -6,8 +6,10 @@ use {
         rdap::{rdap_request, QueryType},
         RdapClientError,
     },
-    icann_rdap_common::response::Domain,
+    icann_rdap_common::response::{Domain, Nameserver, RdapResponse, Variant, VariantName},
     icann_rdap_srv::storage::{CommonConfig, StoreOps},
+    reqwest::StatusCode,
+    rstest::rstest,
 };
 
 use crate::test_jig::SrvTestJig;
@@ -123,3 +125,267 @@ async fn GIVEN_server_with_domain_and_search_enabled_WHEN_query_domain_THEN_stat
     // THEN
     assert_eq!(response.http_data.status_code, 200);
 }
+
+#[tokio::test]
+async fn GIVEN_server_with_domain_WHEN_query_domain_search_by_ns_ldh_name_THEN_status_code_200() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .nameserver(
+                Nameserver::builder()
+                    .ldh_name("ns1.foo.example")
+                    .build()
+                    .expect("building nameserver"),
+            )
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::DomainNsNameSearch("ns1.foo.*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::DomainSearchResults(results) = response.rdap else {
+        panic!("response is not domain search results")
+    };
+    assert_eq!(results.results.len(), 1);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_domain_WHEN_query_domain_search_by_ns_ip_THEN_status_code_200() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .nameserver(
+                Nameserver::builder()
+                    .ldh_name("ns1.foo.example")
+                    .address("192.0.2.1")
+                    .build()
+                    .expect("building nameserver"),
+            )
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::domain_ns_ip_search("192.0.2.1").expect("invalid ip address");
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::DomainSearchResults(results) = response.rdap else {
+        panic!("response is not domain search results")
+    };
+    assert_eq!(results.results.len(), 1);
+}
+
+#[rstest]
+#[case("café.example")]
+#[case("CAFÉ.Example")]
+#[case("cafe\u{0301}.example")]
+#[case("xn--caf-dma.example")]
+#[case("XN--CAF-DMA.EXAMPLE.")]
+#[tokio::test]
+async fn GIVEN_server_with_idn_WHEN_query_domain_by_any_form_THEN_same_domain(#[case] name: &str) {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .unicode_name("café.example")
+            .ldh_name("xn--caf-dma.example")
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/{name}", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::OK);
+    let RdapResponse::Domain(domain) = response
+        .json::<RdapResponse>()
+        .await
+        .expect("parsing response")
+    else {
+        panic!("response is not a domain")
+    };
+    assert_eq!(domain.ldh_name.as_deref(), Some("xn--caf-dma.example"));
+}
+
+#[rstest]
+#[case("café.example")]
+#[case("xn--caf-dma.example")]
+#[case("Cafe\u{0301}.EXAMPLE")]
+#[tokio::test]
+async fn GIVEN_server_with_unnormalized_idn_WHEN_query_domain_by_any_form_THEN_same_domain(
+    #[case] name: &str,
+) {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .unicode_name("CAFÉ.Example")
+            .ldh_name("Café.Example.")
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/{name}", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn GIVEN_invalid_idn_WHEN_query_domain_THEN_status_code_400() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/xn--a.example", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[case("registered", StatusCode::OK)]
+#[case("conjoined", StatusCode::OK)]
+#[case("unregistered", StatusCode::NOT_FOUND)]
+#[case("registration restricted", StatusCode::NOT_FOUND)]
+#[tokio::test]
+async fn GIVEN_domain_with_variant_WHEN_query_variant_THEN_as_relation_dictates(
+    #[case] relation: &str,
+    #[case] expected_status: StatusCode,
+) {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .ldh_name("xn--caf-dma.example")
+            .unicode_name("café.example")
+            .variant(
+                Variant::builder()
+                    .relation(relation)
+                    .variant_name(
+                        VariantName::builder()
+                            .ldh_name("xn--caf-fma.example")
+                            .unicode_name("cafè.example")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/CAFÈ.example", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), expected_status);
+    match response
+        .json::<RdapResponse>()
+        .await
+        .expect("parsing response")
+    {
+        RdapResponse::Domain(domain) => {
+            assert_eq!(domain.ldh_name.as_deref(), Some("xn--caf-dma.example"))
+        }
+        RdapResponse::ErrorResponse(error) => {
+            let notices = error.common.notices.expect("notices");
+            let link = &notices[0].links.as_ref().expect("links")[0];
+            assert_eq!(link.rel.as_deref(), Some("related"));
+            assert!(link
+                .href
+                .as_deref()
+                .expect("href")
+                .ends_with("/domain/xn--caf-dma.example"));
+        }
+        _ => panic!("response is not a domain or error"),
+    }
+}
+
+#[rstest]
+#[case("cafè.example")]
+#[case("xn--caf-fma.example")]
+#[tokio::test]
+async fn GIVEN_domain_with_unnormalized_variant_WHEN_query_variant_THEN_status_code_200(
+    #[case] name: &str,
+) {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .ldh_name("xn--caf-dma.example")
+            .unicode_name("café.example")
+            .variant(
+                Variant::builder()
+                    .relation("registered")
+                    .variant_name(
+                        VariantName::builder()
+                            .ldh_name("XN--CAF-FMA.Example.")
+                            .unicode_name("CAFÈ.Example")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/{name}", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,125 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::{rdap_request, QueryType},
+        RdapClientError,
+    },
+    icann_rdap_common::{
+        contact::Contact,
+        response::{Entity, RdapResponse},
+    },
+    icann_rdap_srv::storage::{CommonConfig, StoreOps},
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[tokio::test]
+async fn GIVEN_server_with_entity_WHEN_query_entity_search_by_handle_THEN_status_code_200() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_entity(&Entity::builder().handle("FOO-1").build())
+        .await
+        .expect("add entity in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::EntityHandleSearch("FOO*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::EntitySearchResults(results) = response.rdap else {
+        panic!("response is not entity search results")
+    };
+    assert_eq!(results.results.len(), 1);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_entity_WHEN_query_entity_search_by_name_THEN_status_code_200() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_entity(
+        &Entity::builder()
+            .handle("FOO-1")
+            .contact(Contact::builder().full_name("Bob Smurd").build())
+            .build(),
+    )
+    .await
+    .expect("add entity in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::EntityNameSearch("Bob*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::EntitySearchResults(results) = response.rdap else {
+        panic!("response is not entity search results")
+    };
+    assert_eq!(results.results.len(), 1);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_entity_WHEN_query_entity_search_with_bad_search_THEN_status_code_400() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::EntityHandleSearch("F*O".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 400);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_entity_and_search_disabled_WHEN_query_entity_search_by_name_THEN_status_code_501(
+) {
+    // GIVEN
+    let common_config = CommonConfig::builder()
+        .entity_search_by_full_name_enable(false)
+        .build();
+    let test_srv = SrvTestJig::new_common_config(common_config).await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::EntityNameSearch("Bob*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 501);
+}
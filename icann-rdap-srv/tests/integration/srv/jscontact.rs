@@ -0,0 +1,81 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::{
+        contact::{Contact, Email},
+        response::{Domain, Entity, ExtensionId, RdapResponse},
+    },
+    icann_rdap_srv::{config::ContactFormat, storage::StoreOps},
+    rstest::rstest,
+};
+
+use crate::test_jig::SrvTestJig;
+
+async fn add_registrant_domain(test_srv: &SrvTestJig) {
+    let registrant = Entity::builder()
+        .handle("foo")
+        .role("registrant")
+        .contact(
+            Contact::builder()
+                .full_name("Joe User")
+                .emails(vec![Email::builder().email("joe@example.com").build()])
+                .build(),
+        )
+        .build();
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .entity(registrant)
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+}
+
+#[rstest]
+#[case(ContactFormat::JCard, "domain/foo.example", true, false)]
+#[case(ContactFormat::JsContact, "domain/foo.example", false, true)]
+#[case(ContactFormat::Both, "domain/foo.example", true, true)]
+#[case(ContactFormat::Both, "domain/foo.example?jscard=1", false, true)]
+#[case(ContactFormat::Both, "domains?name=foo.*&jscard=1", false, true)]
+#[tokio::test]
+async fn GIVEN_contact_format_WHEN_get_THEN_registrant_has_formats(
+    #[case] contact_format: ContactFormat,
+    #[case] path: &str,
+    #[case] expect_jcard: bool,
+    #[case] expect_jscontact: bool,
+) {
+    // GIVEN
+    let test_srv = SrvTestJig::new_contact_format(contact_format).await;
+    add_registrant_domain(&test_srv).await;
+
+    // WHEN
+    let response = reqwest::get(format!("{}/{path}", test_srv.rdap_base))
+        .await
+        .expect("rdap request")
+        .json::<RdapResponse>()
+        .await
+        .expect("parsing response");
+
+    // THEN
+    assert_eq!(
+        response.has_extension_id(ExtensionId::JsContactLevel0),
+        expect_jscontact
+    );
+    let domain = match response {
+        RdapResponse::Domain(domain) => *domain,
+        RdapResponse::DomainSearchResults(search) => search.results[0].clone(),
+        _ => panic!("not a domain"),
+    };
+    let registrant = &domain.object_common.entities.expect("entities")[0];
+    assert_eq!(registrant.vcard_array.is_some(), expect_jcard);
+    assert_eq!(registrant.jscard.is_some(), expect_jscontact);
+    let contact = registrant.contact().expect("registrant contact");
+    assert_eq!(contact.full_name.as_deref(), Some("Joe User"));
+    assert_eq!(
+        contact.emails.expect("emails")[0].email.as_str(),
+        "joe@example.com"
+    );
+}
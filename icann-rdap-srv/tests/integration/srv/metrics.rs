@@ -0,0 +1,106 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::response::{Domain, Entity, Nameserver, RdapResponse},
+    icann_rdap_srv::storage::StoreOps,
+};
+
+use crate::test_jig::SrvTestJig;
+
+const API_KEY: &str = "admin-secret";
+
+#[tokio::test]
+async fn GIVEN_requests_WHEN_get_metrics_THEN_counts_by_route_and_status() {
+    // GIVEN
+    let (test_srv, metrics_url) = SrvTestJig::new_metrics().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    let client = reqwest::Client::new();
+    for path in ["domain/foo.example", "domain/bar.example", "help"] {
+        client
+            .get(format!("{}/{path}", test_srv.rdap_base))
+            .send()
+            .await
+            .expect("rdap request");
+    }
+
+    // WHEN
+    let response = client.get(&metrics_url).send().await.expect("metrics");
+
+    // THEN
+    assert_eq!(response.status(), 200);
+    let text = response.text().await.expect("metrics text");
+    assert!(text.contains(r#"rdap_requests_total{route="domain",status="200"} 1"#));
+    assert!(text.contains(r#"rdap_requests_total{route="domain",status="404"} 1"#));
+    assert!(text.contains(r#"rdap_request_duration_seconds_count{route="domain",status="200"} 1"#));
+    assert!(text.contains(r#"rdap_requests_total{route="help","#));
+}
+
+#[tokio::test]
+async fn GIVEN_objects_put_by_admin_WHEN_get_metrics_THEN_object_counts() {
+    // GIVEN
+    let (_test_srv, admin_url, metrics_url) = SrvTestJig::new_admin_metrics(API_KEY).await;
+    let client = reqwest::Client::new();
+    for object in [
+        RdapResponse::Domain(Domain::builder().ldh_name("foo.example").build().into()),
+        RdapResponse::Domain(Domain::builder().ldh_name("bar.example").build().into()),
+        RdapResponse::Nameserver(
+            Nameserver::builder()
+                .ldh_name("ns.foo.example")
+                .build()
+                .expect("building nameserver")
+                .into(),
+        ),
+        RdapResponse::Entity(Entity::builder().handle("foo").build().into()),
+    ] {
+        let response = client
+            .put(format!("{admin_url}/object"))
+            .bearer_auth(API_KEY)
+            .json(&object)
+            .send()
+            .await
+            .expect("put object");
+        assert_eq!(response.status(), 204);
+    }
+
+    // WHEN
+    let text = client
+        .get(&metrics_url)
+        .send()
+        .await
+        .expect("metrics")
+        .text()
+        .await
+        .expect("metrics text");
+
+    // THEN
+    assert!(text.contains(r#"rdap_objects{object_type="domain"} 2"#));
+    assert!(text.contains(r#"rdap_objects{object_type="nameserver"} 1"#));
+    assert!(text.contains(r#"rdap_objects{object_type="entity"} 1"#));
+    assert!(text.contains(r#"rdap_objects{object_type="autnum"} 0"#));
+}
+
+#[tokio::test]
+async fn GIVEN_objects_committed_outside_admin_WHEN_get_metrics_THEN_objects_not_counted() {
+    // GIVEN
+    let (test_srv, metrics_url) = SrvTestJig::new_metrics().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let text = reqwest::get(&metrics_url)
+        .await
+        .expect("metrics")
+        .text()
+        .await
+        .expect("metrics text");
+
+    // THEN
+    assert!(!text.contains(r#"rdap_objects{object_type="domain"} 1"#));
+}
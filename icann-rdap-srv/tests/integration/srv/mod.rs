@@ -1,4 +1,21 @@
+mod admin;
 mod bootstrap;
+mod cache;
+mod conditional;
 mod domain;
+mod entity;
+mod jscontact;
+mod metrics;
+mod nameserver;
+mod paging;
+mod rate_limit;
+mod redaction;
 mod redirect;
+mod reverse_search;
+mod rir_search;
+mod shutdown;
+mod sorting;
 mod srvhelp;
+mod subsetting;
+mod tenant;
+mod tls;
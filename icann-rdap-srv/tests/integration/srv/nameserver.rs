@@ -0,0 +1,151 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::{rdap_request, QueryType},
+        RdapClientError,
+    },
+    icann_rdap_common::response::{Nameserver, RdapResponse},
+    icann_rdap_srv::storage::{CommonConfig, StoreOps},
+    reqwest::StatusCode,
+    rstest::rstest,
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[tokio::test]
+async fn GIVEN_server_with_nameserver_WHEN_query_nameserver_search_by_name_THEN_status_code_200() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .build()
+            .expect("building nameserver"),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::NameserverNameSearch("ns.foo.*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::NameserverSearchResults(results) = response.rdap else {
+        panic!("response is not nameserver search results")
+    };
+    assert_eq!(results.results.len(), 1);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_nameserver_WHEN_query_nameserver_search_by_ip_THEN_status_code_200() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .address("192.0.2.1")
+            .build()
+            .expect("building nameserver"),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::ns_ip_search("192.0.2.1").expect("invalid ip address");
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::NameserverSearchResults(results) = response.rdap else {
+        panic!("response is not nameserver search results")
+    };
+    assert_eq!(results.results.len(), 1);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_nameserver_and_search_disabled_WHEN_query_nameserver_search_by_ip_THEN_status_code_501(
+) {
+    // GIVEN
+    let common_config = CommonConfig::builder()
+        .nameserver_search_by_ip_enable(false)
+        .build();
+    let test_srv = SrvTestJig::new_common_config(common_config).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .address("192.0.2.1")
+            .build()
+            .expect("building nameserver"),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::ns_ip_search("192.0.2.1").expect("invalid ip address");
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 501);
+}
+
+#[rstest]
+#[case("ns.café.example", StatusCode::OK)]
+#[case("NS.xn--caf-dma.example", StatusCode::OK)]
+#[case("ns.xn--a.example", StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn GIVEN_server_with_idn_nameserver_WHEN_query_nameserver_THEN_normalized(
+    #[case] name: &str,
+    #[case] expected_status: StatusCode,
+) {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.xn--caf-dma.example")
+            .build()
+            .expect("building nameserver"),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let response = reqwest::get(format!("{}/nameserver/{name}", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(response.status(), expected_status);
+}
@@ -0,0 +1,91 @@
+#![allow(non_snake_case)]
+
+use {
+    futures_util::StreamExt,
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::{rdap_paged_request, rdap_url_request, QueryType},
+        RdapClientError,
+    },
+    icann_rdap_common::response::{Domain, ExtensionId, RdapResponse},
+    icann_rdap_srv::storage::{CommonConfig, StoreOps},
+    std::pin::pin,
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[tokio::test]
+async fn GIVEN_server_with_more_domains_than_page_size_WHEN_paged_request_THEN_all_pages_returned()
+{
+    // GIVEN
+    let common_config = CommonConfig::builder().search_page_size(2).build();
+    let test_srv = SrvTestJig::new_common_config(common_config).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    for i in 0..5 {
+        tx.add_domain(
+            &Domain::builder()
+                .ldh_name(format!("foo{i}.example"))
+                .build(),
+        )
+        .await
+        .expect("add domain in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::DomainNameSearch("foo*".to_string());
+    let mut pages = pin!(rdap_paged_request(&test_srv.rdap_base, &query, &client));
+    let mut names = vec![];
+    let mut page_count = 0;
+    while let Some(page) = pages.next().await {
+        let page = page.expect("quering server");
+        assert!(page.rdap.has_extension_id(ExtensionId::Paging));
+        let RdapResponse::DomainSearchResults(results) = page.rdap else {
+            panic!("response is not domain search results")
+        };
+        let paging = results.paging_metadata.expect("no paging metadata");
+        assert_eq!(paging.total_count, Some(5));
+        assert_eq!(paging.page_size, Some(2));
+        names.extend(results.results.into_iter().filter_map(|d| d.ldh_name));
+        page_count += 1;
+    }
+
+    // THEN
+    assert_eq!(page_count, 3);
+    assert_eq!(
+        names,
+        vec![
+            "foo0.example",
+            "foo1.example",
+            "foo2.example",
+            "foo3.example",
+            "foo4.example"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_server_WHEN_search_with_invalid_cursor_THEN_status_code_400() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let url = format!("{}/domains?name=foo*&cursor=zz", test_srv.rdap_base);
+    let response = rdap_url_request(&url, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 400);
+}
@@ -0,0 +1,92 @@
+#![allow(non_snake_case)]
+
+use {
+    axum_client_ip::SecureClientIpSource,
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::{rdap_request, QueryType},
+    },
+    icann_rdap_common::response::{Domain, RdapResponse},
+    icann_rdap_srv::{
+        config::{RateLimit, RateLimitConfig},
+        storage::StoreOps,
+    },
+};
+
+use crate::test_jig::SrvTestJig;
+
+fn lookup_rate_limit() -> RateLimitConfig {
+    RateLimitConfig::builder()
+        .client_ip_source(SecureClientIpSource::ConnectInfo)
+        .lookup(
+            RateLimit::builder()
+                .per_minute(60)
+                .burst(1)
+                .build()
+                .expect("rate limit"),
+        )
+        .build()
+}
+
+#[tokio::test]
+async fn GIVEN_lookup_rate_limit_exceeded_WHEN_request_THEN_status_code_429_with_retry_after() {
+    // GIVEN
+    let test_srv = SrvTestJig::new_rate_limit(lookup_rate_limit()).await;
+    let url = format!("{}/domain/foo.example", test_srv.rdap_base);
+    let client = reqwest::Client::new();
+    client.get(&url).send().await.expect("first request");
+
+    // WHEN
+    let response = client.get(&url).send().await.expect("second request");
+
+    // THEN
+    assert_eq!(response.status(), 429);
+    assert_eq!(
+        response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok()),
+        Some("1")
+    );
+    let json = response
+        .json::<serde_json::Value>()
+        .await
+        .expect("rdap error body");
+    let rdap = RdapResponse::try_from(json).expect("parsing rdap");
+    let RdapResponse::ErrorResponse(error) = rdap else {
+        panic!("response is not an rdap error")
+    };
+    assert_eq!(error.error_code, 429);
+}
+
+#[tokio::test]
+async fn GIVEN_lookup_rate_limit_exceeded_WHEN_client_retries_THEN_lookup_succeeds() {
+    // GIVEN
+    let test_srv = SrvTestJig::new_rate_limit(lookup_rate_limit()).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .max_retries(1)
+        .max_retry_secs(2)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::domain("foo.example").expect("invalid domain name");
+    rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("first query");
+
+    // WHEN
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    let response = response.expect("retried query");
+    let RdapResponse::Domain(domain) = response.rdap else {
+        panic!("response is not a domain")
+    };
+    assert_eq!(domain.ldh_name.as_deref(), Some("foo.example"));
+}
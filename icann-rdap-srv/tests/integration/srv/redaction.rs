@@ -0,0 +1,233 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::{
+        contact::{Contact, Email},
+        response::{Domain, Entity, ExtensionId, RdapResponse},
+    },
+    icann_rdap_srv::{
+        config::RedactionConfig, rdap::redaction::RedactionPolicy, storage::StoreOps,
+    },
+    rstest::rstest,
+    std::sync::Arc,
+};
+
+use crate::test_jig::SrvTestJig;
+
+const POLICY: &str = r#"
+  {
+    "rules": [
+      {
+        "objectClassName": "entity",
+        "role": "registrant",
+        "field": "email",
+        "method": "removal",
+        "name": "Registrant Email",
+        "reason": "Server policy"
+      }
+    ]
+  }
+"#;
+
+async fn redaction_srv() -> SrvTestJig {
+    let redaction = RedactionConfig::builder()
+        .policy(Arc::new(
+            RedactionPolicy::from_json(POLICY).expect("parsing policy"),
+        ))
+        .api_key("secret-key")
+        .basic_credential("bob:password")
+        .build();
+    let test_srv = SrvTestJig::new_redaction(redaction).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .entity(
+                Entity::builder()
+                    .handle("REG-1")
+                    .contact(
+                        Contact::builder()
+                            .full_name("Bob Smurd")
+                            .emails(vec![Email::builder().email("bob@example.com").build()])
+                            .build(),
+                    )
+                    .role("registrant")
+                    .build(),
+            )
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    test_srv
+}
+
+async fn get_domain(request: reqwest::RequestBuilder) -> Domain {
+    let json = request
+        .send()
+        .await
+        .expect("sending request")
+        .json::<serde_json::Value>()
+        .await
+        .expect("json body");
+    let RdapResponse::Domain(domain) = RdapResponse::try_from(json).expect("parsing rdap") else {
+        panic!("response is not a domain")
+    };
+    *domain
+}
+
+fn registrant_emails(domain: &Domain) -> Option<Vec<Email>> {
+    domain.object_common.entities.as_ref()?[0].contact()?.emails
+}
+
+#[tokio::test]
+async fn GIVEN_redaction_policy_WHEN_anonymous_lookup_THEN_redacted() {
+    // GIVEN
+    let test_srv = redaction_srv().await;
+    let url = format!("{}/domain/foo.example", test_srv.rdap_base);
+
+    // WHEN
+    let domain = get_domain(reqwest::Client::new().get(&url)).await;
+
+    // THEN
+    assert!(registrant_emails(&domain).is_none());
+    assert!(domain
+        .common
+        .rdap_conformance
+        .as_ref()
+        .expect("no conformance")
+        .contains(&ExtensionId::Redacted.to_extension()));
+    let redacted = domain.object_common.redacted.expect("no redacted");
+    assert_eq!(
+        redacted[0].pre_path.as_deref(),
+        Some("$.entities[0].vcardArray[1][?(@[0]=='email')]")
+    );
+}
+
+#[rstest]
+#[case(Some("secret-key"), None, false)]
+#[case(Some("wrong-key"), None, true)]
+#[case(None, Some(("bob", "password")), false)]
+#[case(None, Some(("bob", "wrong")), true)]
+#[tokio::test]
+async fn GIVEN_redaction_policy_WHEN_authenticated_lookup_THEN_unredacted(
+    #[case] api_key: Option<&str>,
+    #[case] basic: Option<(&str, &str)>,
+    #[case] expected_redacted: bool,
+) {
+    // GIVEN
+    let test_srv = redaction_srv().await;
+    let url = format!("{}/domain/foo.example", test_srv.rdap_base);
+    let mut request = reqwest::Client::new().get(&url);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    if let Some((user, password)) = basic {
+        request = request.basic_auth(user, Some(password));
+    }
+
+    // WHEN
+    let domain = get_domain(request).await;
+
+    // THEN
+    assert_eq!(registrant_emails(&domain).is_none(), expected_redacted);
+    assert_eq!(domain.object_common.redacted.is_some(), expected_redacted);
+}
+
+#[tokio::test]
+async fn GIVEN_redaction_policy_WHEN_lookup_THEN_varies_by_authorization() {
+    // GIVEN
+    let test_srv = redaction_srv().await;
+
+    // WHEN
+    let response = reqwest::get(format!("{}/domain/foo.example", test_srv.rdap_base))
+        .await
+        .expect("sending request");
+
+    // THEN
+    assert_eq!(
+        response.headers().get(reqwest::header::VARY),
+        Some(&reqwest::header::HeaderValue::from_static("authorization"))
+    );
+}
+
+#[rstest]
+#[case("domains/reverse_search/entity?email=bob@example.com", None, 403)]
+#[case(
+    "domains/reverse_search/entity?email=bob@example.com&role=registrant",
+    None,
+    403
+)]
+#[case(
+    "domains/reverse_search/entity?email=bob@example.com&role=technical",
+    None,
+    200
+)]
+#[case("domains/reverse_search/entity?fn=Bob*", None, 200)]
+#[case(
+    "domains/reverse_search/entity?email=bob@example.com",
+    Some("secret-key"),
+    200
+)]
+#[tokio::test]
+async fn GIVEN_redaction_policy_WHEN_reverse_search_THEN_search_by_redacted_field_refused(
+    #[case] path: &str,
+    #[case] api_key: Option<&str>,
+    #[case] expected: u16,
+) {
+    // GIVEN
+    let test_srv = redaction_srv().await;
+    let url = format!("{}/{path}", test_srv.rdap_base);
+
+    // WHEN
+    let mut request = reqwest::Client::new().get(&url);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request.send().await.expect("sending request");
+
+    // THEN
+    assert_eq!(response.status(), expected);
+}
+
+#[tokio::test]
+async fn GIVEN_redaction_policy_WHEN_anonymous_entity_lookup_THEN_role_rules_redact_entity() {
+    // GIVEN
+    let test_srv = redaction_srv().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_entity(
+        &Entity::builder()
+            .handle("REG-2")
+            .contact(
+                Contact::builder()
+                    .full_name("Bob Smurd")
+                    .emails(vec![Email::builder().email("bob@example.com").build()])
+                    .build(),
+            )
+            .build(),
+    )
+    .await
+    .expect("add entity in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let json = reqwest::get(format!("{}/entity/REG-2", test_srv.rdap_base))
+        .await
+        .expect("sending request")
+        .json::<serde_json::Value>()
+        .await
+        .expect("json body");
+
+    // THEN
+    let RdapResponse::Entity(entity) = RdapResponse::try_from(json).expect("parsing rdap") else {
+        panic!("response is not an entity")
+    };
+    let contact = entity.contact().expect("no contact");
+    assert_eq!(contact.full_name.as_deref(), Some("Bob Smurd"));
+    assert!(contact.emails.is_none());
+    let redacted = entity.object_common.redacted.expect("no redacted");
+    assert_eq!(
+        redacted[0].pre_path.as_deref(),
+        Some("$.vcardArray[1][?(@[0]=='email')]")
+    );
+}
@@ -0,0 +1,161 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::{rdap_request, QueryType, ReverseSearch},
+        RdapClientError,
+    },
+    icann_rdap_common::{
+        contact::Contact,
+        response::{Domain, Entity, Help, Notice, NoticeOrRemark, RdapResponse},
+        search_types::RelatedEntityProperty,
+    },
+    icann_rdap_srv::storage::{CommonConfig, StoreOps},
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[tokio::test]
+async fn GIVEN_server_with_domain_WHEN_query_domain_reverse_search_THEN_status_code_200() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .entity(
+                Entity::builder()
+                    .handle("REG-1")
+                    .contact(Contact::builder().full_name("Bob Smurd").build())
+                    .role("registrant")
+                    .build(),
+            )
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::DomainReverseSearch(
+        ReverseSearch::builder()
+            .property(RelatedEntityProperty::FullName)
+            .value("Bob*")
+            .role("registrant")
+            .build(),
+    );
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::DomainSearchResults(results) = response.rdap else {
+        panic!("response is not domain search results")
+    };
+    assert_eq!(results.results.len(), 1);
+}
+
+#[tokio::test]
+async fn GIVEN_server_WHEN_query_entity_reverse_search_with_bad_search_THEN_status_code_400() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::EntityReverseSearch(
+        ReverseSearch::builder()
+            .property(RelatedEntityProperty::Handle)
+            .value("F*O")
+            .build(),
+    );
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 400);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_reverse_search_disabled_WHEN_query_nameserver_reverse_search_THEN_status_code_501(
+) {
+    // GIVEN
+    let common_config = CommonConfig::builder().reverse_search_enable(false).build();
+    let test_srv = SrvTestJig::new_common_config(common_config).await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::NameserverReverseSearch(
+        ReverseSearch::builder()
+            .property(RelatedEntityProperty::Email)
+            .value("bob@example.com")
+            .build(),
+    );
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 501);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_help_WHEN_query_help_THEN_reverse_search_properties_advertised() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    let srvhelp = Help::builder()
+        .notice(Notice(
+            NoticeOrRemark::builder()
+                .description_entry("foo".to_string())
+                .build(),
+        ))
+        .build();
+    tx.add_srv_help(&srvhelp, None)
+        .await
+        .expect("adding srv help");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let response = rdap_request(&test_srv.rdap_base, &QueryType::Help, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    let RdapResponse::Help(help) = response.rdap else {
+        panic!("response is not help")
+    };
+    assert!(help.reverse_search_properties().iter().any(|p| {
+        p.searchable_resource_type.as_deref() == Some("domains")
+            && p.property.as_deref() == Some("fn")
+    }));
+    assert!(help
+        .common
+        .rdap_conformance
+        .as_ref()
+        .expect("no rdapConformance")
+        .iter()
+        .any(|e| e.as_str() == "reverse_search"));
+}
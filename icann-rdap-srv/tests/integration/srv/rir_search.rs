@@ -0,0 +1,210 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::{rdap_request, QueryType},
+        RdapClientError,
+    },
+    icann_rdap_common::{
+        response::{Autnum, ExtensionId, Network, RdapResponse},
+        search_types::RirSearchRelation,
+    },
+    icann_rdap_srv::storage::{CommonConfig, StoreOps},
+};
+
+use crate::test_jig::SrvTestJig;
+
+async fn add_networks(test_srv: &SrvTestJig) {
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    for (cidr, handle) in [
+        ("10.0.0.0/8", "NET-10-0"),
+        ("10.1.0.0/16", "NET-10-1"),
+        ("10.1.1.0/24", "NET-10-1-1"),
+    ] {
+        tx.add_network(
+            &Network::builder()
+                .cidr(cidr)
+                .handle(handle)
+                .name("Example Net")
+                .build()
+                .expect("cidr parsing"),
+        )
+        .await
+        .expect("add network in tx");
+    }
+    tx.commit().await.expect("tx commit");
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_networks_WHEN_query_ips_by_handle_THEN_network_search_results() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    add_networks(&test_srv).await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::NetworkHandleSearch("NET-10-1*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    assert!(response.rdap.has_extension_id(ExtensionId::RirSearch1));
+    assert!(response.rdap.has_extension_id(ExtensionId::IpSearchResults));
+    let RdapResponse::NetworkSearchResults(results) = response.rdap else {
+        panic!("response is not network search results")
+    };
+    assert_eq!(results.results.len(), 2);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_autnums_WHEN_query_autnums_by_name_THEN_autnum_search_results() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    for (range, name) in [(700..710, "EXAMPLE-AS"), (800..800, "OTHER-AS")] {
+        tx.add_autnum(&Autnum::builder().autnum_range(range).name(name).build())
+            .await
+            .expect("add autnum in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::AutnumNameSearch("example*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::AutnumSearchResults(results) = response.rdap else {
+        panic!("response is not autnum search results")
+    };
+    assert_eq!(results.results.len(), 1);
+    assert_eq!(results.results[0].name.as_deref(), Some("EXAMPLE-AS"));
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_networks_WHEN_query_up_relation_THEN_parent_network_returned() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    add_networks(&test_srv).await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::network_relation_search(RirSearchRelation::Up, "10.1.1.0/24")
+        .expect("relation query");
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::Network(network) = response.rdap else {
+        panic!("response is not a network")
+    };
+    assert_eq!(network.object_common.handle.as_deref(), Some("NET-10-1"));
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_networks_WHEN_query_down_relation_THEN_child_networks_returned() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    add_networks(&test_srv).await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::network_relation_search(RirSearchRelation::Down, "10.0.0.0/8")
+        .expect("relation query");
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert_eq!(response.http_data.status_code, 200);
+    let RdapResponse::NetworkSearchResults(results) = response.rdap else {
+        panic!("response is not network search results")
+    };
+    let handles = results
+        .results
+        .iter()
+        .map(|network| network.object_common.handle.as_deref().expect("handle"))
+        .collect::<Vec<&str>>();
+    assert_eq!(handles, ["NET-10-1"]);
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_networks_WHEN_query_network_THEN_relation_links_given() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    add_networks(&test_srv).await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::ipv4cidr("10.1.0.0/16").expect("cidr query");
+    let response = rdap_request(&test_srv.rdap_base, &query, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert!(response.rdap.has_extension_id(ExtensionId::RirSearch1));
+    let RdapResponse::Network(network) = response.rdap else {
+        panic!("response is not a network")
+    };
+    let links = network.object_common.links.expect("links");
+    for relation in ["rdap-up", "rdap-down", "rdap-top", "rdap-bottom"] {
+        let link = links
+            .iter()
+            .find(|link| link.rel.as_deref() == Some(relation))
+            .expect("relation link");
+        assert_eq!(
+            link.href.as_deref(),
+            Some(format!("/rdap/ips/rirSearch1/{relation}/10.1.0.0/16").as_str())
+        );
+    }
+}
+
+#[tokio::test]
+async fn GIVEN_server_with_rir_search_disabled_WHEN_query_ips_by_name_THEN_status_code_501() {
+    // GIVEN
+    let common_config = CommonConfig::builder().rir_search_enable(false).build();
+    let test_srv = SrvTestJig::new_common_config(common_config).await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::NetworkNameSearch("Example*".to_string());
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 501);
+}
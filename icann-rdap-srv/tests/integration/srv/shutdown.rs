@@ -0,0 +1,81 @@
+#![allow(non_snake_case)]
+
+use std::time::Duration;
+
+use {
+    icann_rdap_common::response::Domain,
+    icann_rdap_srv::storage::StoreOps,
+    reqwest::StatusCode,
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    },
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[tokio::test]
+async fn GIVEN_running_server_WHEN_shutdown_THEN_server_stops_and_refuses_connections() {
+    // GIVEN
+    let (test_srv, shutdown, server) = SrvTestJig::new_shutdown(Duration::from_secs(5)).await;
+    let rdap_base = test_srv.rdap_base;
+    let before = reqwest::get(format!("{rdap_base}/domain/foo.example"))
+        .await
+        .expect("rdap request");
+    assert_eq!(before.status(), StatusCode::NOT_FOUND);
+
+    // WHEN
+    shutdown.shutdown();
+
+    // THEN
+    let stopped = tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server stopped before timeout")
+        .expect("server task");
+    assert!(stopped.is_ok());
+    let after = reqwest::get(format!("{rdap_base}/domain/foo.example")).await;
+    assert!(after.is_err());
+}
+
+#[tokio::test]
+async fn GIVEN_request_in_flight_WHEN_shutdown_THEN_request_completes_before_server_stops() {
+    // GIVEN
+    let (test_srv, shutdown, server) = SrvTestJig::new_shutdown(Duration::from_secs(5)).await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    let authority = test_srv
+        .rdap_base
+        .trim_start_matches("http://")
+        .trim_end_matches("/rdap")
+        .to_string();
+    let mut stream = TcpStream::connect(&authority).await.expect("connecting");
+    stream
+        .write_all(b"GET /rdap/domain/foo.example HTTP/1.1\r\n")
+        .await
+        .expect("writing request line");
+    // give the server time to accept the connection and read the request line.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // WHEN
+    shutdown.shutdown();
+    stream
+        .write_all(format!("Host: {authority}\r\n\r\n").as_bytes())
+        .await
+        .expect("writing headers");
+
+    // THEN
+    let mut response = String::new();
+    tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+        .await
+        .expect("response before timeout")
+        .expect("reading response");
+    assert!(response.starts_with("HTTP/1.1 200"));
+    let stopped = tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server stopped before timeout")
+        .expect("server task");
+    assert!(stopped.is_ok());
+}
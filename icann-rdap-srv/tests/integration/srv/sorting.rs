@@ -0,0 +1,94 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::rdap_url_request,
+        RdapClientError,
+    },
+    icann_rdap_common::response::{Domain, Event, ExtensionId, RdapResponse},
+    icann_rdap_srv::storage::StoreOps,
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[tokio::test]
+async fn GIVEN_server_with_domains_WHEN_search_sorted_by_registration_date_THEN_domains_in_date_order(
+) {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    for (name, date) in [
+        ("foo1.example", "2022-01-01T00:00:00Z"),
+        ("foo2.example", "2020-01-01T00:00:00Z"),
+        ("foo3.example", "2021-01-01T00:00:00Z"),
+    ] {
+        tx.add_domain(
+            &Domain::builder()
+                .ldh_name(name)
+                .event(
+                    Event::builder()
+                        .event_action("registration")
+                        .event_date(date)
+                        .build(),
+                )
+                .build(),
+        )
+        .await
+        .expect("add domain in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let url = format!(
+        "{}/domains?name=foo*&sort=registrationDate:d",
+        test_srv.rdap_base
+    );
+    let response = rdap_url_request(&url, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert!(response.rdap.has_extension_id(ExtensionId::Sorting));
+    let RdapResponse::DomainSearchResults(results) = response.rdap else {
+        panic!("response is not domain search results")
+    };
+    let names = results
+        .results
+        .iter()
+        .filter_map(|d| d.ldh_name.as_deref())
+        .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["foo1.example", "foo3.example", "foo2.example"]);
+    let sorting = results.sorting_metadata.expect("no sorting metadata");
+    assert_eq!(sorting.current_sort.as_deref(), Some("registrationDate:d"));
+    assert!(sorting
+        .available_sorts()
+        .iter()
+        .any(|s| s.property.as_deref() == Some("expirationDate")));
+}
+
+#[tokio::test]
+async fn GIVEN_server_WHEN_search_with_unknown_sort_THEN_status_code_400() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let url = format!("{}/domains?name=foo*&sort=registrar", test_srv.rdap_base);
+    let response = rdap_url_request(&url, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 400);
+}
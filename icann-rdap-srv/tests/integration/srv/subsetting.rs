@@ -0,0 +1,84 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::rdap_url_request,
+        RdapClientError,
+    },
+    icann_rdap_common::response::{Domain, Event, ExtensionId, Nameserver, RdapResponse},
+    icann_rdap_srv::storage::StoreOps,
+};
+
+use crate::test_jig::SrvTestJig;
+
+#[tokio::test]
+async fn GIVEN_server_with_domains_WHEN_search_with_id_field_set_THEN_only_names_returned() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .handle("FOO-1")
+            .event(
+                Event::builder()
+                    .event_action("registration")
+                    .event_date("2020-01-01T00:00:00Z")
+                    .build(),
+            )
+            .nameservers(vec![Nameserver::builder()
+                .ldh_name("ns.foo.example")
+                .build()
+                .expect("building nameserver")])
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let url = format!("{}/domains?name=foo*&fieldSet=id", test_srv.rdap_base);
+    let response = rdap_url_request(&url, &client)
+        .await
+        .expect("quering server");
+
+    // THEN
+    assert!(response.rdap.has_extension_id(ExtensionId::Subsetting));
+    let RdapResponse::DomainSearchResults(results) = response.rdap else {
+        panic!("response is not domain search results")
+    };
+    let domain = results.results.first().expect("no domains");
+    assert_eq!(domain.ldh_name.as_deref(), Some("foo.example"));
+    assert!(domain.object_common.handle.is_none());
+    assert!(domain.object_common.events.is_none());
+    assert!(domain.nameservers.is_none());
+    let subsetting = results.subsetting_metadata.expect("no subsetting metadata");
+    assert_eq!(subsetting.current_field_set.as_deref(), Some("id"));
+}
+
+#[tokio::test]
+async fn GIVEN_server_WHEN_search_with_unknown_field_set_THEN_status_code_400() {
+    // GIVEN
+    let test_srv = SrvTestJig::new().await;
+
+    // WHEN
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .follow_redirects(false)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let url = format!("{}/domains?name=foo*&fieldSet=tiny", test_srv.rdap_base);
+    let response = rdap_url_request(&url, &client).await;
+
+    // THEN
+    let RdapClientError::Client(error) = response.expect_err("not an error response") else {
+        panic!("the error was not an HTTP error")
+    };
+    assert_eq!(error.status().expect("no status code"), 400);
+}
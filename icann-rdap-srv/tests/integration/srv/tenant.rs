@@ -0,0 +1,95 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::response::Domain,
+    icann_rdap_srv::{
+        config::TenantConfig,
+        storage::{mem::ops::Mem, StoreOps},
+    },
+    reqwest::{header, StatusCode},
+};
+
+use crate::test_jig::SrvTestJig;
+
+async fn add_domain(mem: &Mem, ldh_name: &str) {
+    let mut tx = mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name(ldh_name).build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+}
+
+async fn new_with_tenant() -> SrvTestJig {
+    let tenant = TenantConfig::builder()
+        .name("example")
+        .host("rdap.example.net")
+        .path_prefix("example")
+        .bootstrap(false)
+        .build();
+    let (test_srv, tenant_mems) = SrvTestJig::new_tenants(vec![tenant]).await;
+    add_domain(&test_srv.mem, "default.example").await;
+    add_domain(&tenant_mems[0], "tenant.example").await;
+    test_srv
+}
+
+#[tokio::test]
+async fn GIVEN_tenant_by_path_prefix_WHEN_get_domains_THEN_only_tenant_domains_found() {
+    // GIVEN
+    let test_srv = new_with_tenant().await;
+    let tenant_base = test_srv.rdap_base.replace("/rdap", "/example/rdap");
+
+    // WHEN
+    let tenant_domain = reqwest::get(format!("{tenant_base}/domain/tenant.example"))
+        .await
+        .expect("rdap request");
+    let default_domain = reqwest::get(format!("{tenant_base}/domain/default.example"))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(tenant_domain.status(), StatusCode::OK);
+    assert_eq!(default_domain.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn GIVEN_tenant_by_host_WHEN_get_domains_THEN_only_tenant_domains_found() {
+    // GIVEN
+    let test_srv = new_with_tenant().await;
+    let client = reqwest::Client::new();
+
+    // WHEN
+    let tenant_domain = client
+        .get(format!("{}/domain/tenant.example", test_srv.rdap_base))
+        .header(header::HOST, "RDAP.example.net:443")
+        .send()
+        .await
+        .expect("rdap request");
+    let default_domain = client
+        .get(format!("{}/domain/default.example", test_srv.rdap_base))
+        .header(header::HOST, "rdap.example.net")
+        .send()
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(tenant_domain.status(), StatusCode::OK);
+    assert_eq!(default_domain.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn GIVEN_tenant_WHEN_get_domains_of_default_storage_THEN_tenant_domains_not_found() {
+    // GIVEN
+    let test_srv = new_with_tenant().await;
+
+    // WHEN
+    let default_domain = reqwest::get(format!("{}/domain/default.example", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+    let tenant_domain = reqwest::get(format!("{}/domain/tenant.example", test_srv.rdap_base))
+        .await
+        .expect("rdap request");
+
+    // THEN
+    assert_eq!(default_domain.status(), StatusCode::OK);
+    assert_eq!(tenant_domain.status(), StatusCode::NOT_FOUND);
+}
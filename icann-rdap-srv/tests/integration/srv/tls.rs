@@ -0,0 +1,100 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_client::{
+        http::{create_client, ClientConfig},
+        rdap::{rdap_request, QueryType},
+    },
+    icann_rdap_common::response::{Domain, RdapResponse},
+    icann_rdap_srv::storage::StoreOps,
+};
+
+use crate::test_jig::SrvTestJig;
+
+async fn add_domain(test_srv: &SrvTestJig) {
+    let mut tx = test_srv.mem.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+}
+
+#[tokio::test]
+async fn GIVEN_tls_server_WHEN_query_domain_over_https_THEN_domain_returned() {
+    // GIVEN
+    let (test_srv, http_rdap_base) = SrvTestJig::new_tls(false).await;
+    add_domain(&test_srv).await;
+    let client_config = ClientConfig::builder()
+        .accept_invalid_certificates(true)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+
+    // WHEN
+    let query = QueryType::domain("foo.example").expect("invalid domain name");
+    let response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+
+    // THEN
+    assert!(test_srv.rdap_base.starts_with("https://"));
+    assert!(http_rdap_base.is_none());
+    let response = response.expect("query domain");
+    let RdapResponse::Domain(domain) = response.rdap else {
+        panic!("response is not a domain")
+    };
+    assert_eq!(domain.ldh_name.as_deref(), Some("foo.example"));
+}
+
+#[tokio::test]
+async fn GIVEN_tls_server_with_http_WHEN_query_domain_over_http_THEN_domain_returned() {
+    // GIVEN
+    let (test_srv, http_rdap_base) = SrvTestJig::new_tls(true).await;
+    add_domain(&test_srv).await;
+    let http_rdap_base = http_rdap_base.expect("no plain HTTP listener");
+    let client_config = ClientConfig::builder()
+        .https_only(false)
+        .accept_invalid_certificates(true)
+        .build();
+    let client = create_client(&client_config).expect("creating client");
+    let query = QueryType::domain("foo.example").expect("invalid domain name");
+
+    // WHEN
+    let https_response = rdap_request(&test_srv.rdap_base, &query, &client).await;
+    let http_response = rdap_request(&http_rdap_base, &query, &client).await;
+
+    // THEN
+    assert!(http_rdap_base.starts_with("http://"));
+    assert!(https_response.is_ok());
+    let http_response = http_response.expect("query domain over http");
+    let RdapResponse::Domain(domain) = http_response.rdap else {
+        panic!("response is not a domain")
+    };
+    assert_eq!(domain.ldh_name.as_deref(), Some("foo.example"));
+}
+
+#[tokio::test]
+async fn GIVEN_tls_server_with_admin_api_WHEN_put_object_over_https_THEN_domain_is_served() {
+    // GIVEN
+    let (test_srv, admin_url) = SrvTestJig::new_tls_admin("admin-secret").await;
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .expect("creating client");
+
+    // WHEN
+    let response = client
+        .put(format!("{admin_url}/object"))
+        .bearer_auth("admin-secret")
+        .json(&Domain::builder().ldh_name("foo.example").build())
+        .send()
+        .await
+        .expect("put object");
+
+    // THEN
+    assert!(admin_url.starts_with("https://"));
+    assert_eq!(response.status(), 204);
+    let actual = test_srv
+        .mem
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain");
+    assert!(matches!(actual, RdapResponse::Domain(_)));
+}
@@ -1,5 +1,10 @@
 #![allow(non_snake_case)]
 
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
 use {
     icann_rdap_common::{
         prelude::Numberish,
@@ -8,11 +13,11 @@ use {
         },
     },
     icann_rdap_srv::{
-        config::{ServiceConfig, StorageType},
+        config::{ServiceConfig, StorageType, TenantConfig},
         storage::{
             data::{
-                load_data, AutnumId, AutnumOrError::AutnumObject, DomainId, DomainOrError,
-                EntityId, EntityOrError::EntityObject, NameserverId,
+                load_data, load_data_since, AutnumId, AutnumOrError::AutnumObject, DomainId,
+                DomainOrError, EntityId, EntityOrError::EntityObject, NameserverId,
                 NameserverOrError::NameserverObject, NetworkId, NetworkIdType,
                 NetworkOrError::NetworkObject, Template,
             },
@@ -486,6 +491,171 @@ async fn GIVEN_data_dir_with_network_template_with_range_WHEN_mem_init_THEN_netw
     }
 }
 
+#[tokio::test]
+async fn GIVEN_domains_in_mem_and_delete_template_WHEN_update_THEN_domain_is_deleted() {
+    // GIVEN
+    let temp = TestDir::temp();
+    for ldh in ["foo.example", "bar.example"] {
+        std::fs::write(
+            temp.path(&format!("{ldh}.json")),
+            serde_json::to_string(&Domain::builder().ldh_name(ldh).build())
+                .expect("serializing domain"),
+        )
+        .expect("writing file");
+    }
+    let data_dir = temp.root().to_string_lossy().to_string();
+    let mem = new_and_init_mem(data_dir.clone()).await;
+    std::fs::remove_file(temp.path("foo.example.json")).expect("removing file");
+    std::fs::write(
+        temp.path("retired.template"),
+        r#"{"domain":"delete","ids":[{"ldhName":"foo.example"}]}"#,
+    )
+    .expect("writing file");
+
+    // WHEN
+    load_data(
+        &ServiceConfig::non_server()
+            .data_dir(data_dir)
+            .storage_type(StorageType::Memory(
+                MemConfig::builder()
+                    .common_config(CommonConfig::default())
+                    .build(),
+            ))
+            .build()
+            .expect("building service config"),
+        &mem,
+        false,
+    )
+    .await
+    .expect("updating data");
+
+    // THEN
+    let actual = mem
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain by ldh");
+    assert!(matches!(actual, RdapResponse::ErrorResponse(_)));
+    let actual = mem
+        .get_domain_by_ldh("bar.example")
+        .await
+        .expect("getting domain by ldh");
+    assert!(matches!(actual, RdapResponse::Domain(_)));
+}
+
+/// Writes a domain to a file in a data directory, modified at the given time.
+fn write_domain_file(data_dir: &Path, ldh_name: &str, modified: SystemTime) {
+    let domain = Domain::builder().ldh_name(ldh_name).build();
+    let domain_file = data_dir.join(format!("{ldh_name}.json"));
+    std::fs::write(
+        &domain_file,
+        serde_json::to_string(&domain).expect("serializing domain"),
+    )
+    .expect("writing file");
+    std::fs::File::options()
+        .write(true)
+        .open(&domain_file)
+        .and_then(|file| file.set_modified(modified))
+        .expect("setting modified time");
+}
+
+/// Creates the config of memory with a snapshot, along with a service using it.
+fn snapshot_configs(temp: &TestDir, data_dir: &Path) -> (MemConfig, ServiceConfig) {
+    let mem_config = MemConfig::builder()
+        .common_config(CommonConfig::default())
+        .snapshot_file(temp.path("snapshot").to_string_lossy())
+        .build();
+    let config = ServiceConfig::non_server()
+        .data_dir(data_dir.to_string_lossy())
+        .storage_type(StorageType::Memory(mem_config.clone()))
+        .build()
+        .expect("building service config");
+    (mem_config, config)
+}
+
+async fn assert_domains_loaded(mem: &Mem, expected: &[(&str, bool)]) {
+    for (ldh_name, loaded) in expected {
+        let actual = mem
+            .get_domain_by_ldh(ldh_name)
+            .await
+            .expect("getting domain by ldh");
+        assert_eq!(
+            matches!(actual, RdapResponse::Domain(_)),
+            *loaded,
+            "{ldh_name}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn GIVEN_restored_snapshot_WHEN_load_data_since_THEN_new_and_modified_files_are_loaded() {
+    // GIVEN
+    let temp = TestDir::temp();
+    let data_dir = temp.path("data");
+    std::fs::create_dir(&data_dir).expect("creating data dir");
+    let (mem_config, config) = snapshot_configs(&temp, &data_dir);
+    let as_of = SystemTime::now();
+    write_domain_file(&data_dir, "foo.example", as_of - Duration::from_secs(60));
+    let mem = Mem::new(mem_config.clone());
+    let files = load_data(&config, &mem, false).await.expect("loading data");
+    mem.save_snapshot(&files).await.expect("saving snapshot");
+    write_domain_file(&data_dir, "bar.example", as_of + Duration::from_secs(60));
+    // copied into the data directory with the time it was modified elsewhere.
+    write_domain_file(&data_dir, "baz.example", as_of - Duration::from_secs(30));
+    let mem = Mem::new(mem_config);
+    let restored = mem.restore_snapshot().await.expect("restoring snapshot");
+    // emptied, so only the files loaded since the snapshot are found.
+    mem.new_truncate_tx()
+        .await
+        .expect("new truncate transaction")
+        .commit()
+        .await
+        .expect("tx commit");
+
+    // WHEN
+    let actual = load_data_since(&config, &mem, &restored.expect("snapshot restored"))
+        .await
+        .expect("loading data since snapshot");
+
+    // THEN
+    assert_ne!(actual, files);
+    assert_domains_loaded(
+        &mem,
+        &[
+            ("foo.example", false),
+            ("bar.example", true),
+            ("baz.example", true),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn GIVEN_restored_snapshot_and_removed_file_WHEN_load_data_since_THEN_data_is_reloaded() {
+    // GIVEN
+    let temp = TestDir::temp();
+    let data_dir = temp.path("data");
+    std::fs::create_dir(&data_dir).expect("creating data dir");
+    let (mem_config, config) = snapshot_configs(&temp, &data_dir);
+    let modified = SystemTime::now() - Duration::from_secs(60);
+    write_domain_file(&data_dir, "foo.example", modified);
+    write_domain_file(&data_dir, "bar.example", modified);
+    let mem = Mem::new(mem_config.clone());
+    let files = load_data(&config, &mem, false).await.expect("loading data");
+    mem.save_snapshot(&files).await.expect("saving snapshot");
+    std::fs::remove_file(data_dir.join("bar.example.json")).expect("removing file");
+    let mem = Mem::new(mem_config);
+    let restored = mem.restore_snapshot().await.expect("restoring snapshot");
+
+    // WHEN
+    let actual = load_data_since(&config, &mem, &restored.expect("snapshot restored"))
+        .await
+        .expect("loading data since snapshot");
+
+    // THEN
+    assert_ne!(actual, files);
+    assert_domains_loaded(&mem, &[("foo.example", true), ("bar.example", false)]).await;
+}
+
 #[tokio::test]
 async fn GIVEN_data_dir_with_default_help_WHEN_mem_init_THEN_default_help_is_loaded() {
     // GIVEN
@@ -583,3 +753,65 @@ async fn GIVEN_data_dir_with_host_help_WHEN_mem_init_THEN_host_help_is_loaded()
         "bar"
     );
 }
+
+#[tokio::test]
+async fn GIVEN_templates_declaring_tenants_WHEN_load_data_for_tenant_THEN_only_its_templates_are_loaded(
+) {
+    // GIVEN
+    let temp = TestDir::temp();
+    let tenant_dir = temp.path("example");
+    std::fs::create_dir(&tenant_dir).expect("creating tenant dir");
+    for (file_name, ldh_name, tenant) in [
+        ("mine.template", "mine.example", Some("example")),
+        ("theirs.template", "theirs.example", Some("other")),
+        ("shared.template", "shared.example", None),
+    ] {
+        let template = Template::Domain {
+            domain: DomainOrError::DomainObject(Box::new(
+                Domain::builder().ldh_name("template.example").build(),
+            )),
+            ids: vec![DomainId::builder().ldh_name(ldh_name).build()],
+        };
+        let mut json = serde_json::to_value(&template).expect("serializing template");
+        if let Some(tenant) = tenant {
+            json["tenant"] = tenant.into();
+        }
+        std::fs::write(tenant_dir.join(file_name), json.to_string()).expect("writing file");
+    }
+    let mem_config = MemConfig::builder()
+        .common_config(CommonConfig::default())
+        .build();
+    let tenant = TenantConfig::builder()
+        .name("example")
+        .path_prefix("example")
+        .bootstrap(false)
+        .build();
+    let config = ServiceConfig::non_server()
+        .data_dir(temp.root().to_string_lossy())
+        .storage_type(StorageType::Memory(mem_config.clone()))
+        .build()
+        .expect("building service config")
+        .for_tenant(&tenant)
+        .expect("tenant service config");
+    let mem = Mem::new(mem_config);
+
+    // WHEN
+    load_data(&config, &mem, false).await.expect("loading data");
+
+    // THEN
+    for (ldh_name, loaded) in [
+        ("mine.example", true),
+        ("theirs.example", false),
+        ("shared.example", true),
+    ] {
+        let actual = mem
+            .get_domain_by_ldh(ldh_name)
+            .await
+            .expect("getting domain by ldh");
+        assert_eq!(
+            matches!(actual, RdapResponse::Domain(_)),
+            loaded,
+            "{ldh_name}"
+        );
+    }
+}
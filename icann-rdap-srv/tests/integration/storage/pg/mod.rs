@@ -0,0 +1,2200 @@
+#![allow(non_snake_case)]
+
+use {
+    icann_rdap_common::{
+        contact::{Contact, Email},
+        prelude::Numberish,
+        response::{
+            Autnum, Common, Domain, Entity, Help, Nameserver, Network, Notice, NoticeOrRemark,
+            ObjectCommon, RdapResponse, Rfc9083Error, Variant, VariantName,
+        },
+        search_types::{RelatedEntityProperty, RirSearchRelation},
+    },
+    icann_rdap_srv::{
+        error::RdapServerError,
+        storage::{
+            data::{AutnumId, DomainId, EntityId, NameserverId, NetworkId, NetworkIdType},
+            CommonConfig, SearchPage, StoreOps, TxHandle,
+        },
+    },
+    rstest::rstest,
+};
+
+use crate::test_jig::PgStoreTestJig;
+
+#[tokio::test]
+async fn GIVEN_domain_in_pg_WHEN_new_truncate_tx_THEN_no_domain_in_pg() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let tx = pg.new_truncate_tx().await.expect("new truncate tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    let actual = pg
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain by ldh");
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+#[tokio::test]
+async fn GIVEN_domain_in_pg_WHEN_lookup_domain_by_ldh_THEN_domain_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain by ldh");
+
+    // THEN
+    let RdapResponse::Domain(domain) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        domain.ldh_name.as_ref().expect("ldhName is none"),
+        "foo.example"
+    )
+}
+
+#[tokio::test]
+async fn GIVEN_domain_in_pg_WHEN_lookup_domain_by_unicode_THEN_domain_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .unicode_name("foo.example")
+            .ldh_name("foo.example")
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_domain_by_unicode("foo.example")
+        .await
+        .expect("getting domain by unicode");
+
+    // THEN
+    let RdapResponse::Domain(domain) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        domain.unicode_name.as_ref().expect("unicodeName is none"),
+        "foo.example"
+    )
+}
+
+#[tokio::test]
+async fn GIVEN_unnormalized_idn_in_pg_WHEN_lookup_by_normalized_names_THEN_domain_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .unicode_name("CAFÉ.Example")
+            .ldh_name("Café.Example.")
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let by_ldh = pg
+        .get_domain_by_ldh("xn--caf-dma.example")
+        .await
+        .expect("getting domain by ldh");
+    let by_unicode = pg
+        .get_domain_by_unicode("café.example")
+        .await
+        .expect("getting domain by unicode");
+
+    // THEN
+    assert!(matches!(by_ldh, RdapResponse::Domain(_)));
+    assert!(matches!(by_unicode, RdapResponse::Domain(_)));
+}
+
+#[tokio::test]
+async fn GIVEN_domain_in_pg_WHEN_search_domain_by_name_THEN_domain_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .unicode_name("foo.example.com")
+            .ldh_name("foo.example.com")
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_domains_by_name("foo.example.*", &SearchPage::all())
+        .await
+        .expect("getting domain by unicode");
+
+    // THEN
+    let RdapResponse::DomainSearchResults(domains) = actual else {
+        panic!()
+    };
+    assert_eq!(domains.clone().results.len(), 1);
+    assert_eq!(
+        domains
+            .results
+            .first()
+            .expect("at least one")
+            .unicode_name
+            .as_ref()
+            .expect("unicodeName is none"),
+        "foo.example.com"
+    )
+}
+
+#[tokio::test]
+async fn GIVEN_domains_in_pg_WHEN_search_domain_by_name_with_page_THEN_page_of_sorted_domains_returned(
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    for name in [
+        "foo3.example",
+        "foo1.example",
+        "foo4.example",
+        "foo2.example",
+    ] {
+        tx.add_domain(&Domain::builder().ldh_name(name).build())
+            .await
+            .expect("add domain in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_domains_by_name("foo*", &SearchPage::new(1, 2))
+        .await
+        .expect("searching domains by name");
+
+    // THEN
+    let RdapResponse::DomainSearchResults(domains) = actual else {
+        panic!("response is not domain search results")
+    };
+    let names = domains
+        .results
+        .iter()
+        .map(|d| d.ldh_name.as_deref().expect("ldhName is none"))
+        .collect::<Vec<&str>>();
+    assert_eq!(names, ["foo2.example", "foo3.example"]);
+    let paging_metadata = domains.paging_metadata.expect("no paging metadata");
+    assert_eq!(paging_metadata.total_count, Some(4));
+}
+
+#[tokio::test]
+async fn GIVEN_domains_and_redirect_in_pg_WHEN_search_domain_by_name_with_page_THEN_redirect_not_counted(
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    for name in ["foo1.example", "foo3.example"] {
+        tx.add_domain(&Domain::builder().ldh_name(name).build())
+            .await
+            .expect("add domain in tx");
+    }
+    tx.add_domain_err(
+        &DomainId::builder().ldh_name("foo2.example").build(),
+        &Rfc9083Error::redirect().url("https://example.net/").build(),
+    )
+    .await
+    .expect("add domain redirect");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_domains_by_name("foo*", &SearchPage::new(0, 2))
+        .await
+        .expect("searching domains by name");
+
+    // THEN
+    let RdapResponse::DomainSearchResults(domains) = actual else {
+        panic!("response is not domain search results")
+    };
+    let names = domains
+        .results
+        .iter()
+        .map(|d| d.ldh_name.as_deref().expect("ldhName is none"))
+        .collect::<Vec<&str>>();
+    assert_eq!(names, ["foo1.example", "foo3.example"]);
+    let paging_metadata = domains.paging_metadata.expect("no paging metadata");
+    assert_eq!(paging_metadata.total_count, Some(2));
+}
+
+#[rstest]
+#[case("foo.example")]
+#[case("foo.*example")]
+#[case("*.example")]
+#[tokio::test]
+async fn GIVEN_invalid_search_WHEN_search_domain_by_name_in_pg_THEN_invalid_arg(
+    #[case] name: &str,
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+
+    // WHEN
+    let actual = jig
+        .pg
+        .search_domains_by_name(name, &SearchPage::all())
+        .await;
+
+    // THEN
+    assert!(matches!(actual, Err(RdapServerError::InvalidArg(_))));
+}
+
+#[tokio::test]
+async fn GIVEN_domain_in_pg_but_search_not_enabled_WHEN_search_domain_by_name_THEN_not_implemented()
+{
+    // GIVEN
+    let common_config = CommonConfig::builder()
+        .domain_search_by_name_enable(false)
+        .build();
+    let Some(jig) = PgStoreTestJig::new_common_config(common_config).await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain(
+        &Domain::idn()
+            .unicode_name("foo.example.com")
+            .ldh_name("foo.example.com")
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_domains_by_name("foo.example.*", &SearchPage::all())
+        .await
+        .expect("getting domain by unicode");
+
+    // THEN
+    let RdapResponse::ErrorResponse(_e) = actual else {
+        panic!()
+    };
+}
+
+#[tokio::test]
+async fn GIVEN_domain_redirect_in_pg_WHEN_lookup_domain_by_ldh_THEN_redirect_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain_err(
+        &DomainId::builder().ldh_name("foo.example").build(),
+        &Rfc9083Error::redirect()
+            .url("https://other.example/domain/foo.example")
+            .build(),
+    )
+    .await
+    .expect("add domain error in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain by ldh");
+
+    // THEN
+    assert!(actual.is_redirect());
+}
+
+#[rstest]
+#[case("ns1.foo.example", &["bar.example", "foo.example"])]
+#[case("ns2.foo.example", &["foo.example"])]
+#[case("ns*.foo.example", &["bar.example", "foo.example"])]
+#[case("ns1.foo.*", &["bar.example", "foo.example"])]
+#[case("ns3.foo.example", &[])]
+#[tokio::test]
+async fn GIVEN_domains_in_pg_WHEN_search_domains_by_ns_ldh_name_THEN_domains_returned(
+    #[case] ns_ldh_name: &str,
+    #[case] expected: &[&str],
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_nameservers(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_domains_by_ns_ldh_name(ns_ldh_name, &SearchPage::all())
+        .await
+        .expect("searching domains by nameserver name");
+
+    // THEN
+    assert_domain_search_results(actual, expected);
+}
+
+#[rstest]
+#[case("192.0.2.1", &["bar.example", "foo.example"])]
+#[case("2001:db8::2", &["foo.example"])]
+#[case("192.0.2.3", &[])]
+#[tokio::test]
+async fn GIVEN_domains_in_pg_WHEN_search_domains_by_ns_ip_THEN_domains_returned(
+    #[case] ip: &str,
+    #[case] expected: &[&str],
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_nameservers(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_domains_by_ns_ip(ip.parse().expect("parsing ip"), &SearchPage::all())
+        .await
+        .expect("searching domains by nameserver ip");
+
+    // THEN
+    assert_domain_search_results(actual, expected);
+}
+
+#[tokio::test]
+async fn GIVEN_domain_replaced_in_pg_WHEN_search_domains_by_old_ns_THEN_domain_not_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_nameservers(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("foo.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let by_name = pg
+        .search_domains_by_ns_ldh_name("ns2.foo.example", &SearchPage::all())
+        .await
+        .expect("searching domains by nameserver name");
+    let by_ip = pg
+        .search_domains_by_ns_ip("192.0.2.1".parse().expect("parsing ip"), &SearchPage::all())
+        .await
+        .expect("searching domains by nameserver ip");
+
+    // THEN
+    assert_domain_search_results(by_name, &[]);
+    assert_domain_search_results(by_ip, &["bar.example"]);
+}
+
+#[tokio::test]
+async fn GIVEN_domain_in_pg_but_search_not_enabled_WHEN_search_domains_by_ns_ldh_name_THEN_not_implemented(
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new_common_config(
+        CommonConfig::builder()
+            .domain_search_by_ns_ldh_name_enable(false)
+            .build(),
+    )
+    .await
+    else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_nameservers(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_domains_by_ns_ldh_name("ns1.foo.example", &SearchPage::all())
+        .await
+        .expect("searching domains by nameserver name");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 501)
+}
+
+async fn add_domains_with_nameservers(tx: &mut dyn TxHandle) {
+    let ns1 = Nameserver::builder()
+        .ldh_name("ns1.foo.example")
+        .address("192.0.2.1")
+        .build()
+        .expect("building nameserver");
+    let ns2 = Nameserver::builder()
+        .ldh_name("ns2.foo.example")
+        .address("2001:db8::2")
+        .build()
+        .expect("building nameserver");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .nameserver(ns1.clone())
+            .nameserver(ns2)
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("bar.example")
+            .nameserver(ns1)
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+}
+
+fn assert_domain_search_results(actual: RdapResponse, expected: &[&str]) {
+    let RdapResponse::DomainSearchResults(domains) = actual else {
+        panic!("response is not domain search results")
+    };
+    let mut names = domains
+        .results
+        .iter()
+        .map(|d| d.ldh_name.as_deref().expect("ldhName is none"))
+        .collect::<Vec<&str>>();
+    names.sort();
+    assert_eq!(names, expected);
+}
+
+#[tokio::test]
+async fn GIVEN_no_domain_in_pg_WHEN_lookup_domain_by_ldh_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+
+    // WHEN
+    let actual = pg
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain by ldh");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+#[tokio::test]
+async fn GIVEN_entity_in_pg_WHEN_lookup_entity_by_handle_THEN_entity_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_entity(&Entity::builder().handle("foo").build())
+        .await
+        .expect("add entity in tx");
+    tx.commit().await.expect("entity tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_entity_by_handle("foo")
+        .await
+        .expect("getting entity by handle");
+
+    // THEN
+    let RdapResponse::Entity(entity) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        entity
+            .object_common
+            .handle
+            .as_ref()
+            .expect("handle is none"),
+        "foo"
+    )
+}
+
+#[rstest]
+#[case("FOO*", &["FOO-1", "FOO-2"])]
+#[case("FOO-1", &["FOO-1"])]
+#[case("FOO", &[])]
+#[case("BAR*", &["BAR-1"])]
+#[case("foo*", &["FOO-1", "FOO-2"])]
+#[case("bar-1", &["BAR-1"])]
+#[tokio::test]
+async fn GIVEN_entities_in_pg_WHEN_search_entities_by_handle_THEN_entities_returned(
+    #[case] handle: &str,
+    #[case] expected: &[&str],
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    for handle in ["FOO-1", "FOO-2", "BAR-1"] {
+        tx.add_entity(&Entity::builder().handle(handle).build())
+            .await
+            .expect("add entity in tx");
+    }
+    tx.commit().await.expect("entity tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_entities_by_handle(handle, &SearchPage::all())
+        .await
+        .expect("searching entities by handle");
+
+    // THEN
+    let RdapResponse::EntitySearchResults(entities) = actual else {
+        panic!()
+    };
+    let mut handles = entities
+        .results
+        .iter()
+        .map(|e| e.object_common.handle.as_deref().expect("handle is none"))
+        .collect::<Vec<&str>>();
+    handles.sort();
+    assert_eq!(handles, expected);
+}
+
+#[rstest]
+#[case("Bob*", &["FOO-1", "FOO-2"])]
+#[case("bob smurd", &["FOO-1"])]
+#[case("Alice*", &[])]
+#[tokio::test]
+async fn GIVEN_entities_in_pg_WHEN_search_entities_by_full_name_THEN_entities_returned(
+    #[case] full_name: &str,
+    #[case] expected: &[&str],
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    for (handle, full_name) in [("FOO-1", "Bob Smurd"), ("FOO-2", "Bobby Smurd")] {
+        tx.add_entity(
+            &Entity::builder()
+                .handle(handle)
+                .contact(Contact::builder().full_name(full_name).build())
+                .build(),
+        )
+        .await
+        .expect("add entity in tx");
+    }
+    tx.add_entity(&Entity::builder().handle("BAR-1").build())
+        .await
+        .expect("add entity in tx");
+    tx.commit().await.expect("entity tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_entities_by_full_name(full_name, &SearchPage::all())
+        .await
+        .expect("searching entities by full name");
+
+    // THEN
+    let RdapResponse::EntitySearchResults(entities) = actual else {
+        panic!()
+    };
+    let mut handles = entities
+        .results
+        .iter()
+        .map(|e| e.object_common.handle.as_deref().expect("handle is none"))
+        .collect::<Vec<&str>>();
+    handles.sort();
+    assert_eq!(handles, expected);
+}
+
+#[tokio::test]
+async fn GIVEN_entity_in_pg_but_search_not_enabled_WHEN_search_entities_by_handle_THEN_not_implemented(
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new_common_config(
+        CommonConfig::builder()
+            .entity_search_by_handle_enable(false)
+            .build(),
+    )
+    .await
+    else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_entity(&Entity::builder().handle("FOO-1").build())
+        .await
+        .expect("add entity in tx");
+    tx.commit().await.expect("entity tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_entities_by_handle("FOO*", &SearchPage::all())
+        .await
+        .expect("searching entities by handle");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 501)
+}
+
+#[tokio::test]
+async fn GIVEN_no_entity_in_pg_WHEN_lookup_entity_by_handle_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+
+    // WHEN
+    let actual = pg
+        .get_entity_by_handle("foo")
+        .await
+        .expect("getting entity by handle");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+#[tokio::test]
+async fn GIVEN_nameserver_in_pg_WHEN_lookup_nameserver_by_ldh_THEN_nameserver_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_nameserver_by_ldh("ns.foo.example")
+        .await
+        .expect("getting nameserver by ldh");
+
+    // THEN
+    let RdapResponse::Nameserver(nameserver) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        nameserver.ldh_name.as_ref().expect("ldhName is none"),
+        "ns.foo.example"
+    )
+}
+
+#[tokio::test]
+async fn GIVEN_no_nameserver_in_pg_WHEN_lookup_nameserver_by_ldh_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+
+    // WHEN
+    let actual = pg
+        .get_nameserver_by_ldh("ns.foo.example")
+        .await
+        .expect("getting nameserver by ldh");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+#[tokio::test]
+async fn GIVEN_nameserver_in_pg_WHEN_search_nameserver_by_name_THEN_nameserver_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.bar.example")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_nameservers_by_name("ns.foo.*", &SearchPage::all())
+        .await
+        .expect("searching nameservers by name");
+
+    // THEN
+    let RdapResponse::NameserverSearchResults(nameservers) = actual else {
+        panic!()
+    };
+    assert_eq!(nameservers.results.len(), 1);
+    assert_eq!(
+        nameservers
+            .results
+            .first()
+            .expect("at least one")
+            .ldh_name
+            .as_ref()
+            .expect("ldhName is none"),
+        "ns.foo.example"
+    )
+}
+
+#[rstest]
+#[case("10.0.0.1", &["ns1.foo.example", "ns2.foo.example"])]
+#[case("10.0.0.2", &["ns2.foo.example"])]
+#[case("2001:db8::1", &["ns1.foo.example"])]
+#[case("10.0.0.3", &[])]
+#[tokio::test]
+async fn GIVEN_nameservers_in_pg_WHEN_search_nameserver_by_ip_THEN_nameservers_returned(
+    #[case] ip: &str,
+    #[case] expected: &[&str],
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns1.foo.example")
+            .address("10.0.0.1")
+            .address("2001:db8::1")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns2.foo.example")
+            .address("10.0.0.1")
+            .address("10.0.0.2")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_nameservers_by_ip(ip.parse().expect("parsing ip"), &SearchPage::all())
+        .await
+        .expect("searching nameservers by ip");
+
+    // THEN
+    let RdapResponse::NameserverSearchResults(nameservers) = actual else {
+        panic!()
+    };
+    let mut names = nameservers
+        .results
+        .iter()
+        .map(|ns| ns.ldh_name.as_deref().expect("ldhName is none"))
+        .collect::<Vec<&str>>();
+    names.sort();
+    assert_eq!(names, expected);
+}
+
+#[tokio::test]
+async fn GIVEN_nameserver_replaced_in_pg_WHEN_search_nameserver_by_old_ip_THEN_no_nameservers_returned(
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .address("10.0.0.1")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .address("10.0.0.2")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_nameservers_by_ip("10.0.0.1".parse().expect("parsing ip"), &SearchPage::all())
+        .await
+        .expect("searching nameservers by ip");
+
+    // THEN
+    let RdapResponse::NameserverSearchResults(nameservers) = actual else {
+        panic!()
+    };
+    assert!(nameservers.results.is_empty());
+}
+
+#[tokio::test]
+async fn GIVEN_nameserver_in_pg_but_search_not_enabled_WHEN_search_nameserver_by_ip_THEN_not_implemented(
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new_common_config(
+        CommonConfig::builder()
+            .nameserver_search_by_ip_enable(false)
+            .build(),
+    )
+    .await
+    else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .address("10.0.0.1")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_nameservers_by_ip("10.0.0.1".parse().expect("parsing ip"), &SearchPage::all())
+        .await
+        .expect("searching nameservers by ip");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 501)
+}
+
+#[tokio::test]
+async fn GIVEN_autnum_in_pg_WHEN_lookup_autnum_by_start_autnum_THEN_autnum_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_autnum(&Autnum::builder().autnum_range(700..710).build())
+        .await
+        .expect("add autnum in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_autnum_by_num(700)
+        .await
+        .expect("getting autnum by num");
+
+    // THEN
+    let RdapResponse::Autnum(autnum) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        *autnum.start_autnum.as_ref().expect("startNum is none"),
+        Numberish::<u32>::from(700)
+    );
+    assert_eq!(
+        *autnum.end_autnum.as_ref().expect("startNum is none"),
+        Numberish::<u32>::from(710)
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_autnum_in_pg_WHEN_lookup_autnum_by_end_autnum_THEN_autnum_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_autnum(&Autnum::builder().autnum_range(700..710).build())
+        .await
+        .expect("add autnum in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_autnum_by_num(710)
+        .await
+        .expect("getting autnum by num");
+
+    // THEN
+    let RdapResponse::Autnum(autnum) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        *autnum.start_autnum.as_ref().expect("startNum is none"),
+        Numberish::<u32>::from(700)
+    );
+    assert_eq!(
+        *autnum.end_autnum.as_ref().expect("startNum is none"),
+        Numberish::<u32>::from(710)
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_no_autnum_in_pg_WHEN_lookup_autnum_by_num_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+
+    // WHEN
+    let actual = pg
+        .get_autnum_by_num(700)
+        .await
+        .expect("getting autnum by num");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+#[rstest]
+#[case("192.168.0.0/24", "192.168.0.1", "192.168.0.0", "192.168.0.255")]
+#[case("192.168.0.0/24", "192.168.0.0", "192.168.0.0", "192.168.0.255")]
+#[case("192.168.0.0/24", "192.168.0.254", "192.168.0.0", "192.168.0.255")]
+#[case("192.168.0.0/24", "192.168.0.255", "192.168.0.0", "192.168.0.255")]
+#[tokio::test]
+async fn GIVEN_network_in_pg_WHEN_lookup_network_by_address_THEN_network_returned(
+    #[case] cidr: &str,
+    #[case] addr: &str,
+    #[case] start: &str,
+    #[case] end: &str,
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_network(&Network::builder().cidr(cidr).build().expect("cidr parsing"))
+        .await
+        .expect("add network in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_network_by_ipaddr(addr)
+        .await
+        .expect("getting network by num");
+
+    // THEN
+    let RdapResponse::Network(network) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        *network
+            .start_address
+            .as_ref()
+            .expect("startAddress is none"),
+        start
+    );
+    assert_eq!(
+        *network.end_address.as_ref().expect("endAddress is none"),
+        end
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_no_network_in_pg_WHEN_lookup_network_by_address_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+
+    // WHEN
+    let actual = pg
+        .get_network_by_ipaddr("192.168.0.1")
+        .await
+        .expect("getting network by address");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+#[rstest]
+#[case(&["192.168.0.0/16", "192.168.0.0/8", "192.168.0.0/24"], "192.168.0.1", "192.168.0.0", "192.168.0.255")]
+#[case(&["2001::/64", "2001::/56", "2001::/20"], "2001::1", "2001::", "2001::ffff:ffff:ffff:ffff")]
+#[tokio::test]
+async fn GIVEN_contained_networks_in_pg_WHEN_lookup_network_by_address_THEN_most_specific_network_returned(
+    #[case] cidrs: &[&str],
+    #[case] addr: &str,
+    #[case] start: &str,
+    #[case] end: &str,
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    for cidr in cidrs {
+        tx.add_network(
+            &Network::builder()
+                .cidr(*cidr)
+                .build()
+                .expect("cidr parsing"),
+        )
+        .await
+        .expect("add network in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_network_by_ipaddr(addr)
+        .await
+        .expect("getting network by num");
+
+    // THEN
+    let RdapResponse::Network(network) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        *network
+            .start_address
+            .as_ref()
+            .expect("startAddress is none"),
+        start
+    );
+    assert_eq!(
+        *network.end_address.as_ref().expect("endAddress is none"),
+        end
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_offbit_network_in_pg_WHEN_lookup_network_by_first_address_THEN_network_returned() {
+    // GIVEN
+    let start = "10.0.0.0";
+    let end = "10.0.1.255";
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_network(&Network {
+        common: Common {
+            rdap_conformance: None,
+            notices: None,
+        },
+        object_common: ObjectCommon {
+            object_class_name: "ip network".to_string(),
+            handle: None,
+            remarks: None,
+            links: None,
+            events: None,
+            status: None,
+            port_43: None,
+            entities: None,
+            redacted: None,
+        },
+        start_address: Some(start.to_string()),
+        end_address: Some(end.to_string()),
+        ip_version: Some("v4".to_string()),
+        name: None,
+        network_type: None,
+        parent_handle: None,
+        country: None,
+        cidr0_cidrs: None,
+    })
+    .await
+    .expect("add network in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_network_by_ipaddr(start)
+        .await
+        .expect("getting network by num");
+
+    // THEN
+    let RdapResponse::Network(network) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        *network
+            .start_address
+            .as_ref()
+            .expect("startAddress is none"),
+        start
+    );
+    assert_eq!(
+        *network.end_address.as_ref().expect("endAddress is none"),
+        end
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_offbit_network_in_pg_WHEN_lookup_network_by_last_address_THEN_network_returned() {
+    // GIVEN
+    let start = "10.0.0.0";
+    let end = "10.0.1.255";
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_network(&Network {
+        common: Common {
+            rdap_conformance: None,
+            notices: None,
+        },
+        object_common: ObjectCommon {
+            object_class_name: "ip network".to_string(),
+            handle: None,
+            remarks: None,
+            links: None,
+            events: None,
+            status: None,
+            port_43: None,
+            entities: None,
+            redacted: None,
+        },
+        start_address: Some(start.to_string()),
+        end_address: Some(end.to_string()),
+        ip_version: Some("v4".to_string()),
+        name: None,
+        network_type: None,
+        parent_handle: None,
+        country: None,
+        cidr0_cidrs: None,
+    })
+    .await
+    .expect("add network in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_network_by_ipaddr(end)
+        .await
+        .expect("getting network by num");
+
+    // THEN
+    let RdapResponse::Network(network) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        *network
+            .start_address
+            .as_ref()
+            .expect("startAddress is none"),
+        start
+    );
+    assert_eq!(
+        *network.end_address.as_ref().expect("endAddress is none"),
+        end
+    );
+}
+
+#[rstest]
+#[case("192.168.0.0/16", "192.168.0.0/24", "192.168.0.0", "192.168.255.255")]
+#[case("192.168.0.0/16", "192.168.0.0/16", "192.168.0.0", "192.168.255.255")]
+#[tokio::test]
+async fn GIVEN_network_in_pg_WHEN_lookup_network_by_cidr_THEN_network_returned(
+    #[case] cidr: &str,
+    #[case] lookup: &str,
+    #[case] start: &str,
+    #[case] end: &str,
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_network(&Network::builder().cidr(cidr).build().expect("cidr parsing"))
+        .await
+        .expect("add network in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_network_by_cidr(lookup)
+        .await
+        .expect("getting network by cidr");
+
+    // THEN
+    let RdapResponse::Network(network) = actual else {
+        panic!()
+    };
+    assert_eq!(
+        *network
+            .start_address
+            .as_ref()
+            .expect("startAddress is none"),
+        start
+    );
+    assert_eq!(
+        *network.end_address.as_ref().expect("endAddress is none"),
+        end
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_no_network_in_pg_WHEN_lookup_network_by_cidr_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+
+    // WHEN
+    let actual = pg
+        .get_network_by_cidr("192.168.0.0/24")
+        .await
+        .expect("getting network by address");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+#[tokio::test]
+async fn GIVEN_default_help_in_pg_WHEN_lookup_help_with_no_host_THEN_get_default_help() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_srv_help(
+        &Help::builder()
+            .notice(Notice(
+                NoticeOrRemark::builder()
+                    .description_entry("foo".to_string())
+                    .build(),
+            ))
+            .build(),
+        None,
+    )
+    .await
+    .expect("adding srv help");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg.get_srv_help(None).await.expect("getting srv helf");
+
+    // THEN
+    let RdapResponse::Help(srvhelp) = actual else {
+        panic!()
+    };
+    let notice = srvhelp
+        .common
+        .notices
+        .expect("no notices in srvhelp")
+        .first()
+        .expect("notices empty")
+        .to_owned();
+    assert_eq!(
+        notice
+            .description
+            .as_ref()
+            .expect("no description!")
+            .vec()
+            .first()
+            .expect("no description in notice"),
+        "foo"
+    );
+}
+
+#[tokio::test]
+async fn GIVEN_help_in_pg_WHEN_lookup_help_with_host_THEN_get_host_help() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_srv_help(
+        &Help::builder()
+            .notice(Notice(
+                NoticeOrRemark::builder()
+                    .description_entry("bar".to_string())
+                    .build(),
+            ))
+            .build(),
+        Some("bar.example.com"),
+    )
+    .await
+    .expect("adding srv help");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .get_srv_help(Some("bar.example.com"))
+        .await
+        .expect("getting srv helf");
+
+    // THEN
+    let RdapResponse::Help(srvhelp) = actual else {
+        panic!()
+    };
+    let notice = srvhelp
+        .common
+        .notices
+        .expect("no notices in srvhelp")
+        .first()
+        .expect("notices empty")
+        .to_owned();
+    assert_eq!(
+        notice
+            .description
+            .as_ref()
+            .expect("no description")
+            .vec()
+            .first()
+            .expect("no description in notice"),
+        "bar"
+    );
+}
+
+#[rstest]
+#[case(RelatedEntityProperty::FullName, "bob*", None, &["bar.example", "foo.example"])]
+#[case(RelatedEntityProperty::FullName, "Bob Smurd", Some("registrant"), &["foo.example"])]
+#[case(RelatedEntityProperty::Handle, "REG-1", None, &["foo.example"])]
+#[case(RelatedEntityProperty::Handle, "reg-1", None, &[])]
+#[case(RelatedEntityProperty::Email, "BOB@example.com", Some("technical"), &["bar.example"])]
+#[tokio::test]
+async fn GIVEN_domains_in_pg_WHEN_reverse_search_domains_THEN_domains_returned(
+    #[case] property: RelatedEntityProperty,
+    #[case] value: &str,
+    #[case] role: Option<&str>,
+    #[case] expected: &[&str],
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_entities(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .reverse_search_domains(property, value, role, &SearchPage::all())
+        .await
+        .expect("reverse searching domains");
+
+    // THEN
+    assert_domain_search_results(actual, expected);
+}
+
+#[tokio::test]
+async fn GIVEN_nameserver_and_entity_in_pg_WHEN_reverse_search_THEN_objects_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns1.foo.example")
+            .entity(bob_smurd("REG-1", "registrant"))
+            .build()
+            .expect("building nameserver"),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.add_entity(
+        &Entity::builder()
+            .handle("FOO-1")
+            .entity(bob_smurd("REG-1", "registrant"))
+            .build(),
+    )
+    .await
+    .expect("add entity in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let nameservers = pg
+        .reverse_search_nameservers(
+            RelatedEntityProperty::Handle,
+            "REG*",
+            None,
+            &SearchPage::all(),
+        )
+        .await
+        .expect("reverse searching nameservers");
+    let entities = pg
+        .reverse_search_entities(
+            RelatedEntityProperty::FullName,
+            "bob*",
+            Some("registrant"),
+            &SearchPage::all(),
+        )
+        .await
+        .expect("reverse searching entities");
+
+    // THEN
+    let RdapResponse::NameserverSearchResults(nameservers) = nameservers else {
+        panic!("response is not nameserver search results")
+    };
+    assert_eq!(nameservers.results.len(), 1);
+    let RdapResponse::EntitySearchResults(entities) = entities else {
+        panic!("response is not entity search results")
+    };
+    assert_eq!(entities.results.len(), 1);
+}
+
+#[tokio::test]
+async fn GIVEN_domain_in_pg_but_reverse_search_not_enabled_WHEN_reverse_search_domains_THEN_not_implemented(
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new_common_config(
+        CommonConfig::builder().reverse_search_enable(false).build(),
+    )
+    .await
+    else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_entities(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .reverse_search_domains(
+            RelatedEntityProperty::FullName,
+            "bob*",
+            None,
+            &SearchPage::all(),
+        )
+        .await
+        .expect("reverse searching domains");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 501)
+}
+
+#[tokio::test]
+async fn GIVEN_objects_in_pg_WHEN_get_object_counts_THEN_objects_counted() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_entities(&mut *tx).await;
+    tx.add_entity(&bob_smurd("BOB-1", "registrant"))
+        .await
+        .expect("add entity in tx");
+    tx.add_autnum(&Autnum::builder().autnum_range(700..710).build())
+        .await
+        .expect("add autnum in tx");
+    for cidr in ["10.0.0.0/8", "2001::/20"] {
+        tx.add_network(&Network::builder().cidr(cidr).build().expect("cidr parsing"))
+            .await
+            .expect("add network in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg.get_object_counts().await.expect("counting objects");
+
+    // THEN
+    assert_eq!(actual.domains, 3);
+    assert_eq!(actual.entities, 1);
+    assert_eq!(actual.nameservers, 0);
+    assert_eq!(actual.autnums, 1);
+    assert_eq!(actual.networks, 2);
+}
+
+#[tokio::test]
+async fn GIVEN_domains_in_pg_WHEN_delete_domain_THEN_domain_not_found_or_searched() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_nameservers(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let deleted = tx
+        .delete_domain(&DomainId::builder().ldh_name("foo.example").build())
+        .await
+        .expect("delete domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(deleted);
+    let actual = pg
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain by ldh");
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404);
+    let by_name = pg
+        .search_domains_by_name("foo.*", &SearchPage::all())
+        .await
+        .expect("searching domains by name");
+    assert_domain_search_results(by_name, &[]);
+    let by_ns_name = pg
+        .search_domains_by_ns_ldh_name("ns2.foo.example", &SearchPage::all())
+        .await
+        .expect("searching domains by nameserver name");
+    assert_domain_search_results(by_ns_name, &[]);
+    let by_ns_ip = pg
+        .search_domains_by_ns_ip("192.0.2.1".parse().expect("parsing ip"), &SearchPage::all())
+        .await
+        .expect("searching domains by nameserver ip");
+    assert_domain_search_results(by_ns_ip, &["bar.example"]);
+}
+
+#[tokio::test]
+async fn GIVEN_domains_in_pg_WHEN_delete_domain_THEN_domain_not_reverse_searched() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_domains_with_entities(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.delete_domain(&DomainId::builder().ldh_name("foo.example").build())
+        .await
+        .expect("delete domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    let actual = pg
+        .reverse_search_domains(
+            RelatedEntityProperty::FullName,
+            "Bob*",
+            None,
+            &SearchPage::all(),
+        )
+        .await
+        .expect("reverse searching domains");
+    assert_domain_search_results(actual, &["bar.example"]);
+}
+
+#[tokio::test]
+async fn GIVEN_domain_redirect_in_pg_WHEN_delete_domain_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_domain_err(
+        &DomainId::builder().ldh_name("foo.example").build(),
+        &Rfc9083Error::redirect()
+            .url("https://other.example/domain/foo.example")
+            .build(),
+    )
+    .await
+    .expect("add domain error in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let deleted = tx
+        .delete_domain(&DomainId::builder().ldh_name("foo.example").build())
+        .await
+        .expect("delete domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(deleted);
+    let actual = pg
+        .get_domain_by_ldh("foo.example")
+        .await
+        .expect("getting domain by ldh");
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404);
+}
+
+#[tokio::test]
+async fn GIVEN_no_domain_in_pg_WHEN_delete_domain_THEN_nothing_deleted() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let actual = tx
+        .delete_domain(&DomainId::builder().ldh_name("foo.example").build())
+        .await
+        .expect("delete domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(!actual);
+}
+
+#[tokio::test]
+async fn GIVEN_entity_in_pg_WHEN_delete_entity_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_entity(&bob_smurd("BOB-1", "registrant"))
+        .await
+        .expect("add entity in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let deleted = tx
+        .delete_entity(&EntityId::builder().handle("BOB-1").build())
+        .await
+        .expect("delete entity in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(deleted);
+    let actual = pg
+        .get_entity_by_handle("BOB-1")
+        .await
+        .expect("getting entity by handle");
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404);
+}
+
+#[tokio::test]
+async fn GIVEN_nameserver_in_pg_WHEN_delete_nameserver_THEN_not_found_or_searched() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_nameserver(
+        &Nameserver::builder()
+            .ldh_name("ns.foo.example")
+            .address("10.0.0.1")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .expect("add nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let deleted = tx
+        .delete_nameserver(&NameserverId::builder().ldh_name("ns.foo.example").build())
+        .await
+        .expect("delete nameserver in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(deleted);
+    let actual = pg
+        .get_nameserver_by_ldh("ns.foo.example")
+        .await
+        .expect("getting nameserver by ldh");
+    assert!(matches!(actual, RdapResponse::ErrorResponse(_)));
+    for actual in [
+        pg.search_nameservers_by_name("ns.foo.*", &SearchPage::all())
+            .await
+            .expect("searching nameservers by name"),
+        pg.search_nameservers_by_ip("10.0.0.1".parse().expect("parsing ip"), &SearchPage::all())
+            .await
+            .expect("searching nameservers by ip"),
+    ] {
+        let RdapResponse::NameserverSearchResults(nameservers) = actual else {
+            panic!()
+        };
+        assert!(nameservers.results.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn GIVEN_autnum_in_pg_WHEN_delete_autnum_THEN_404_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_autnum(&Autnum::builder().autnum_range(700..710).build())
+        .await
+        .expect("add autnum in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let deleted = tx
+        .delete_autnum(
+            &AutnumId::builder()
+                .start_autnum(700)
+                .end_autnum(710)
+                .build(),
+        )
+        .await
+        .expect("delete autnum in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(deleted);
+    let actual = pg
+        .get_autnum_by_num(705)
+        .await
+        .expect("getting autnum by num");
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!()
+    };
+    assert_eq!(error.error_code, 404);
+}
+
+#[rstest]
+#[case(700, 709)]
+#[case(701, 710)]
+#[case(705, 705)]
+#[tokio::test]
+async fn GIVEN_autnum_in_pg_WHEN_delete_autnum_with_other_range_THEN_nothing_deleted(
+    #[case] start_autnum: u32,
+    #[case] end_autnum: u32,
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    tx.add_autnum(&Autnum::builder().autnum_range(700..710).build())
+        .await
+        .expect("add autnum in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let deleted = tx
+        .delete_autnum(
+            &AutnumId::builder()
+                .start_autnum(start_autnum)
+                .end_autnum(end_autnum)
+                .build(),
+        )
+        .await
+        .expect("delete autnum in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(!deleted);
+    let actual = pg
+        .get_autnum_by_num(705)
+        .await
+        .expect("getting autnum by num");
+    assert!(matches!(actual, RdapResponse::Autnum(_)));
+}
+
+#[rstest]
+#[case("10.0.0.0/8", "10.1.0.0/16", "10.1.0.1", "10.2.0.1")]
+#[case("2001::/20", "2001::/64", "2001::1", "2001:0:0:1::1")]
+#[tokio::test]
+async fn GIVEN_contained_networks_in_pg_WHEN_delete_network_THEN_more_specific_network_kept(
+    #[case] deleted_cidr: &str,
+    #[case] kept_cidr: &str,
+    #[case] kept_addr: &str,
+    #[case] deleted_addr: &str,
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    for cidr in [deleted_cidr, kept_cidr] {
+        tx.add_network(&Network::builder().cidr(cidr).build().expect("cidr parsing"))
+            .await
+            .expect("add network in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    let deleted = tx
+        .delete_network(
+            &NetworkId::builder()
+                .network_id(NetworkIdType::Cidr(
+                    deleted_cidr.parse().expect("parsing cidr"),
+                ))
+                .build(),
+        )
+        .await
+        .expect("delete network in tx");
+    tx.commit().await.expect("tx commit");
+
+    // THEN
+    assert!(deleted);
+    let actual = pg
+        .get_network_by_ipaddr(kept_addr)
+        .await
+        .expect("getting network by address");
+    assert!(matches!(actual, RdapResponse::Network(_)));
+    let actual = pg
+        .get_network_by_ipaddr(deleted_addr)
+        .await
+        .expect("getting network by address");
+    assert!(matches!(actual, RdapResponse::ErrorResponse(_)));
+}
+
+fn bob_smurd(handle: &str, role: &str) -> Entity {
+    Entity::builder()
+        .handle(handle)
+        .contact(
+            Contact::builder()
+                .full_name("Bob Smurd")
+                .email(Email::builder().email("bob@example.com").build())
+                .build(),
+        )
+        .role(role)
+        .build()
+}
+
+async fn add_domains_with_entities(tx: &mut dyn TxHandle) {
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("foo.example")
+            .entity(bob_smurd("REG-1", "registrant"))
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.add_domain(
+        &Domain::builder()
+            .ldh_name("bar.example")
+            .entity(bob_smurd("TECH-1", "technical"))
+            .build(),
+    )
+    .await
+    .expect("add domain in tx");
+    tx.add_domain(&Domain::builder().ldh_name("baz.example").build())
+        .await
+        .expect("add domain in tx");
+}
+
+fn domain_with_variant() -> Domain {
+    Domain::builder()
+        .ldh_name("xn--caf-dma.example")
+        .unicode_name("café.example")
+        .variant(
+            Variant::builder()
+                .relation("unregistered")
+                .variant_name(
+                    VariantName::builder()
+                        .ldh_name("xn--caf-fma.example")
+                        .unicode_name("cafè.example")
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+#[rstest]
+#[case("xn--caf-fma.example")]
+#[case("cafè.example")]
+#[tokio::test]
+async fn GIVEN_domain_with_variant_in_pg_WHEN_lookup_domain_by_variant_THEN_domain_returned(
+    #[case] variant: &str,
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let store = &jig.pg;
+    let mut tx = store.new_tx().await.expect("new transaction");
+    tx.add_domain(&domain_with_variant())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = store
+        .get_domain_by_variant(variant)
+        .await
+        .expect("getting domain by variant");
+
+    // THEN
+    let RdapResponse::Domain(domain) = actual else {
+        panic!("domain not found by variant")
+    };
+    assert_eq!(domain.ldh_name.as_deref(), Some("xn--caf-dma.example"));
+}
+
+#[tokio::test]
+async fn GIVEN_domain_with_variant_replaced_in_pg_WHEN_lookup_domain_by_variant_THEN_404_returned()
+{
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let store = &jig.pg;
+    let mut tx = store.new_tx().await.expect("new transaction");
+    tx.add_domain(&domain_with_variant())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+    let mut tx = store.new_tx().await.expect("new transaction");
+    tx.add_domain(&Domain::builder().ldh_name("xn--caf-dma.example").build())
+        .await
+        .expect("add domain in tx");
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = store
+        .get_domain_by_variant("xn--caf-fma.example")
+        .await
+        .expect("getting domain by variant");
+
+    // THEN
+    let RdapResponse::ErrorResponse(error) = actual else {
+        panic!("domain found by variant")
+    };
+    assert_eq!(error.error_code, 404)
+}
+
+async fn add_rir_networks(tx: &mut dyn TxHandle) {
+    for (cidr, handle, name) in [
+        ("10.0.0.0/8", "NET-10-0", "Ten Net"),
+        ("10.1.0.0/16", "NET-10-1", "Ten One Net"),
+        ("10.1.1.0/24", "NET-10-1-1", "Customer Net"),
+        ("10.1.2.0/24", "NET-10-1-2", "Customer Net"),
+    ] {
+        tx.add_network(
+            &Network::builder()
+                .cidr(cidr)
+                .handle(handle)
+                .name(name)
+                .build()
+                .expect("cidr parsing"),
+        )
+        .await
+        .expect("add network in tx");
+    }
+}
+
+fn network_handles(actual: RdapResponse) -> Vec<String> {
+    match actual {
+        RdapResponse::Network(network) => {
+            vec![network.object_common.handle.expect("handle is none")]
+        }
+        RdapResponse::NetworkSearchResults(search) => search
+            .results
+            .into_iter()
+            .map(|network| network.object_common.handle.expect("handle is none"))
+            .collect(),
+        _ => panic!("not a network or network search results"),
+    }
+}
+
+#[tokio::test]
+async fn GIVEN_networks_in_pg_WHEN_search_networks_by_name_THEN_networks_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_rir_networks(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_networks_by_name("customer*", &SearchPage::all())
+        .await
+        .expect("searching networks by name");
+
+    // THEN
+    assert_eq!(network_handles(actual), ["NET-10-1-1", "NET-10-1-2"]);
+}
+
+#[rstest]
+#[case(RirSearchRelation::Up, "10.1.1.0/24", &["NET-10-1"])]
+#[case(RirSearchRelation::Top, "10.1.1.0/24", &["NET-10-0"])]
+#[case(RirSearchRelation::Down, "10.0.0.0/8", &["NET-10-1"])]
+#[case(RirSearchRelation::Bottom, "10.0.0.0/8", &["NET-10-1-1", "NET-10-1-2"])]
+#[tokio::test]
+async fn GIVEN_networks_in_pg_WHEN_search_networks_by_relation_THEN_related_networks_returned(
+    #[case] relation: RirSearchRelation,
+    #[case] cidr: &str,
+    #[case] expected: &[&str],
+) {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    add_rir_networks(tx.as_mut()).await;
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_networks_by_relation(relation, cidr, &SearchPage::all())
+        .await
+        .expect("searching networks by relation");
+
+    // THEN
+    assert_eq!(network_handles(actual), expected);
+}
+
+#[tokio::test]
+async fn GIVEN_autnums_in_pg_WHEN_search_autnums_by_handle_THEN_autnums_returned() {
+    // GIVEN
+    let Some(jig) = PgStoreTestJig::new().await else {
+        return;
+    };
+    let pg = &jig.pg;
+    let mut tx = pg.new_tx().await.expect("new transaction");
+    for (range, handle) in [
+        (700..710, "AS-700"),
+        (800..800, "AS-800"),
+        (900..900, "OTHER"),
+    ] {
+        tx.add_autnum(&Autnum::builder().autnum_range(range).handle(handle).build())
+            .await
+            .expect("add autnum in tx");
+    }
+    tx.commit().await.expect("tx commit");
+
+    // WHEN
+    let actual = pg
+        .search_autnums_by_handle("AS-*", &SearchPage::all())
+        .await
+        .expect("searching autnums by handle");
+
+    // THEN
+    let RdapResponse::AutnumSearchResults(autnums) = actual else {
+        panic!()
+    };
+    let handles = autnums
+        .results
+        .iter()
+        .map(|a| a.object_common.handle.as_deref().expect("handle is none"))
+        .collect::<Vec<&str>>();
+    assert_eq!(handles, ["AS-700", "AS-800"]);
+}
@@ -1,17 +1,35 @@
 use {
     assert_cmd::Command,
     icann_rdap_srv::{
-        config::ListenConfig,
-        server::{AppState, Listener},
+        config::{
+            AdminConfig, CacheConfig, ContactFormat, ListenConfig, RateLimitConfig,
+            RedactionConfig, TenantConfig, TlsConfig,
+        },
+        error::RdapServerError,
+        metrics::Metrics,
+        server::{AppState, Listener, Tenant},
+        shutdown::Shutdown,
         storage::{
             mem::{config::MemConfig, ops::Mem},
-            CommonConfig,
+            pg::{config::PgConfig, ops::Pg},
+            CommonConfig, StoreOps,
         },
     },
-    std::time::Duration,
+    std::{
+        sync::{Arc, LazyLock},
+        time::Duration,
+    },
     test_dir::{DirBuilder, TestDir},
+    tokio::{
+        sync::{Mutex, MutexGuard},
+        task::JoinHandle,
+    },
 };
 
+/// Environment variable giving the URL of a PostgreSQL database used for testing.
+/// If not set, the PostgreSQL storage tests are skipped.
+pub const TEST_DB_URL: &str = "RDAP_SRV_TEST_DB_URL";
+
 pub struct RdapSrvStoreTestJig {
     pub cmd: Command,
     #[allow(dead_code)]
@@ -37,6 +55,22 @@ impl RdapSrvStoreTestJig {
     }
 }
 
+pub struct RdapSrvTestJig {
+    pub cmd: Command,
+    pub config_dir: TestDir,
+}
+
+impl RdapSrvTestJig {
+    pub fn new() -> Self {
+        let config_dir = TestDir::temp();
+        let mut cmd = Command::cargo_bin("rdap-srv").expect("cannot find rdap-srv cmd");
+        cmd.env_clear()
+            .timeout(Duration::from_secs(2))
+            .env("RDAP_SRV_LOG", "debug");
+        Self { cmd, config_dir }
+    }
+}
+
 pub struct RdapSrvDataTestJig {
     pub cmd: Command,
     pub source_dir: TestDir,
@@ -86,6 +120,11 @@ impl SrvTestJig {
         let app_state = AppState {
             storage: mem.clone(),
             bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
         };
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
         let listener = Listener::listen(&ListenConfig::default())
@@ -107,6 +146,61 @@ impl SrvTestJig {
         let app_state = AppState {
             storage: mem.clone(),
             bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(&ListenConfig::default())
+            .await
+            .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        Self { mem, rdap_base }
+    }
+
+    pub async fn new_rate_limit(rate_limit: RateLimitConfig) -> Self {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit,
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(&ListenConfig::default())
+            .await
+            .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        Self { mem, rdap_base }
+    }
+
+    pub async fn new_cache(cache: CacheConfig) -> Self {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache,
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
         };
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
         let listener = Listener::listen(&ListenConfig::default())
@@ -122,11 +216,298 @@ impl SrvTestJig {
         Self { mem, rdap_base }
     }
 
+    pub async fn new_contact_format(contact_format: ContactFormat) -> Self {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format,
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(&ListenConfig::default())
+            .await
+            .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        Self { mem, rdap_base }
+    }
+
+    pub async fn new_redaction(redaction: RedactionConfig) -> Self {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction,
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(&ListenConfig::default())
+            .await
+            .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        Self { mem, rdap_base }
+    }
+
+    /// Creates a server terminating TLS with the certificate of the test fixtures.
+    /// If plain HTTP is served alongside, its RDAP base URL is also returned.
+    pub async fn new_tls(with_http: bool) -> (Self, Option<String>) {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(
+            &ListenConfig::builder()
+                .ip_addr("127.0.0.1")
+                .tls(fixture_tls_config(with_http))
+                .build(),
+        )
+        .await
+        .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        let http_rdap_base = listener.http_rdap_base();
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        (Self { mem, rdap_base }, http_rdap_base)
+    }
+
+    /// Creates a server with tenants, returning the storage of each tenant.
+    pub async fn new_tenants(tenants: Vec<TenantConfig>) -> (Self, Vec<Mem>) {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let tenant_mems = tenants.iter().map(|_| Mem::default()).collect::<Vec<Mem>>();
+        let tenants = tenants
+            .into_iter()
+            .zip(tenant_mems.iter())
+            .map(|(config, tenant_mem)| {
+                let state = AppState {
+                    storage: tenant_mem.clone(),
+                    bootstrap: config.bootstrap,
+                    ..app_state.clone()
+                };
+                Tenant::new(config, state)
+            })
+            .collect();
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(&ListenConfig::default())
+            .await
+            .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        tokio::spawn(async move {
+            listener
+                .start_with_tenants(app_state, tenants)
+                .await
+                .expect("starting server");
+        });
+        (Self { mem, rdap_base }, tenant_mems)
+    }
+
+    /// Creates a server serving metrics, returning the URL of the metrics endpoint.
+    pub async fn new_metrics() -> (Self, String) {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(&ListenConfig::builder().metrics_port(0).build())
+            .await
+            .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        let metrics_url = listener.metrics_url().expect("metrics url");
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        (Self { mem, rdap_base }, metrics_url)
+    }
+
+    /// Creates a server serving metrics that drains for the shutdown timeout,
+    /// returning its shutdown handle and the task running it.
+    pub async fn new_shutdown(
+        shutdown_timeout: Duration,
+    ) -> (Self, Shutdown, JoinHandle<Result<(), RdapServerError>>) {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let listener = Listener::listen(
+            &ListenConfig::builder()
+                .metrics_port(0)
+                .shutdown_timeout(shutdown_timeout)
+                .build(),
+        )
+        .await
+        .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        let shutdown = listener.shutdown_handle();
+        let server = tokio::spawn(listener.start_with_state(app_state));
+        (Self { mem, rdap_base }, shutdown, server)
+    }
+
+    /// Creates a server with the admin API, returning the base URL of the admin API.
+    pub async fn new_admin(api_key: &str) -> (Self, String) {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let admin = AdminConfig::builder()
+            .port(0)
+            .api_key(api_key.to_string())
+            .build();
+        let listener = Listener::listen(&ListenConfig::builder().admin(admin).build())
+            .await
+            .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        let admin_url = listener.admin_url().expect("admin url");
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        (Self { mem, rdap_base }, admin_url)
+    }
+
+    /// Creates a server with the admin API and serving metrics, returning the base
+    /// URLs of the admin API and the metrics.
+    pub async fn new_admin_metrics(api_key: &str) -> (Self, String, String) {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let admin = AdminConfig::builder()
+            .port(0)
+            .api_key(api_key.to_string())
+            .build();
+        let listener =
+            Listener::listen(&ListenConfig::builder().admin(admin).metrics_port(0).build())
+                .await
+                .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        let admin_url = listener.admin_url().expect("admin url");
+        let metrics_url = listener.metrics_url().expect("metrics url");
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        (Self { mem, rdap_base }, admin_url, metrics_url)
+    }
+
+    /// Creates a server terminating TLS with the admin API, returning the base URL
+    /// of the admin API.
+    pub async fn new_tls_admin(api_key: &str) -> (Self, String) {
+        let mem = Mem::default();
+        let app_state = AppState {
+            storage: mem.clone(),
+            bootstrap: false,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
+        };
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+        let admin = AdminConfig::builder()
+            .port(0)
+            .api_key(api_key.to_string())
+            .build();
+        let listener = Listener::listen(
+            &ListenConfig::builder()
+                .ip_addr("127.0.0.1")
+                .tls(fixture_tls_config(false))
+                .admin(admin)
+                .build(),
+        )
+        .await
+        .expect("listening on interface");
+        let rdap_base = listener.rdap_base();
+        let admin_url = listener.admin_url().expect("admin url");
+        tokio::spawn(async move {
+            listener
+                .start_with_state(app_state)
+                .await
+                .expect("starting server");
+        });
+        (Self { mem, rdap_base }, admin_url)
+    }
+
     pub async fn new_bootstrap() -> Self {
         let mem = Mem::default();
         let app_state = AppState {
             storage: mem.clone(),
             bootstrap: true,
+            rate_limit: RateLimitConfig::default(),
+            redaction: RedactionConfig::default(),
+            cache: CacheConfig::default(),
+            contact_format: ContactFormat::default(),
+            metrics: Arc::new(Metrics::new()),
         };
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
         let listener = Listener::listen(&ListenConfig::default())
@@ -142,3 +523,45 @@ impl SrvTestJig {
         Self { mem, rdap_base }
     }
 }
+
+/// The TLS configuration with the certificate of the test fixtures.
+fn fixture_tls_config(with_http: bool) -> TlsConfig {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    TlsConfig::builder()
+        .cert_file(format!("{fixtures}/localhost.cert.pem"))
+        .key_file(format!("{fixtures}/localhost.key.pem"))
+        .with_http(with_http)
+        .build()
+}
+
+/// Tests against PostgreSQL share one database, so they are run one at a time.
+static PG_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+pub struct PgStoreTestJig {
+    pub pg: Pg,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl PgStoreTestJig {
+    /// Creates a [Pg] store with all data truncated, or `None` if no test database is configured.
+    pub async fn new() -> Option<Self> {
+        Self::new_common_config(CommonConfig::default()).await
+    }
+
+    pub async fn new_common_config(common_config: CommonConfig) -> Option<Self> {
+        let Ok(db_url) = std::env::var(TEST_DB_URL) else {
+            eprintln!("{TEST_DB_URL} is not set. Skipping PostgreSQL test.");
+            return None;
+        };
+        let guard = PG_LOCK.lock().await;
+        let pg_config = PgConfig::builder()
+            .db_url(db_url)
+            .common_config(common_config)
+            .build();
+        let pg = Pg::new(pg_config).await.expect("connecting to database");
+        pg.init().await.expect("initializing database");
+        let tx = pg.new_truncate_tx().await.expect("new truncate tx");
+        tx.commit().await.expect("truncate tx commit");
+        Some(Self { pg, _guard: guard })
+    }
+}